@@ -1,69 +1,464 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use common::{
+	analytics::AnalyticsQuery,
+	codec::{ self, RawValue, Row, RowCodec },
 	benchmark::{
 		measure_execution,
 		DatabaseBenchmark,
-		generate_random_order,
+		files_size,
+		generate_order_chunks,
+		generate_orders_with_items,
 		generate_random_product,
 		generate_random_user,
+		generate_test_data_chunks,
+		GENERATION_CHUNK_SIZE,
+		PROBE_BLOB,
+		PROBE_TABLE,
 	},
-	models::{ BenchmarkResult, Order, OrderWithDetails, Product, User },
+	connect,
+	consistency::measure_consistency,
+	contention::{ is_contended_write, measure_contention, CONTENDED_USERS },
+	context::RunContext,
+	distribution,
+	fixtures::FIXTURE_TABLES,
+	import::{ self, ImportColumn, ImportColumnType, DROP_IMPORT_TABLE_QUERY, READ_IMPORTED_RECORD_QUERY },
+	plan::{ BenchmarkTest, IndexMode, KeyEncoding, TextMode, TimestampEncoding },
+	projection::{ measure_projection, READ_PROJECTION_QUERY },
+	models::{
+		BenchmarkResult,
+		Capabilities,
+		Order,
+		OrderWithDetails,
+		OrderWithItems,
+		Product,
+		Record,
+		RecordCounts,
+		User,
+		WideRecord,
+		WideValue,
+		ORDER_ITEM_RECORD_BYTES,
+		ORDER_RECORD_BYTES,
+		WIDE_RECORD_BYTES,
+	},
+	nullable::{
+		users_to_soft_delete,
+		LIVE_USER_IDS_QUERY,
+		READ_DISCOUNTED_PRODUCTS_QUERY,
+		READ_LIVE_USERS_QUERY,
+		READ_USERS_NOT_DELETED_QUERY,
+		SOFT_DELETE_USERS_QUERY,
+		SOFT_DELETE_USER_QUERY,
+	},
+	retry::RetryPolicy,
+	search::search_pattern,
+	statement_cache::PreparedStatements,
+	tenants::{ AGGREGATE_ORDERS_BY_TENANT_QUERY, READ_TENANT_ORDERS_QUERY, TENANT_PAGE_LEN },
+	text::holds_text,
+	trace::{ TraceChunk, TraceOp },
+	writers::measure_concurrent_inserts,
+	wide::{
+		self,
+		generate_wide_record,
+		WideColumnType,
+		INSERT_WIDE_RECORD_QUERY,
+		READ_WIDE_COLUMN_SUBSET_QUERY,
+		READ_WIDE_RECORD_QUERY,
+		WIDE_COLUMN_COUNT,
+	},
+};
+use duckdb::{
+	appender_params_from_iter,
+	params,
+	params_from_iter,
+	types::{ TimeUnit, Value },
+	AccessMode,
+	Config,
+	Connection,
+	OptionalExt,
 };
-use duckdb::{ Connection, params };
 use std::path::Path;
+use std::sync::atomic::{ AtomicU64, Ordering };
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 use uuid::Uuid;
+use chrono::{ DateTime, Utc };
+use tracing::{ debug, info };
+
+// Environment variable selecting the bulk insert path
+const BULK_INSERT_ENV: &str = "DUCKDB_BULK_INSERT";
+
+// File of the snapshot of the dataset in its fixture directory
+const SNAPSHOT_FILE: &str = "duckdb.db";
+
+// Directory of the export written by a backup in the backup directory of the run
+const BACKUP_DIR: &str = "duckdb-export";
+
+// Looks users up by joining them with a list of ids bound as a single comma-separated parameter,
+// so every batch reuses the same statement instead of formatting one placeholder per id
+const READ_MANY_BY_IDS_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE id IN (SELECT unnest(string_split(?, ',')))";
+
+// Same lookup for binary ids, sent as hexadecimal strings and decoded back to their bytes
+const READ_MANY_BY_BINARY_IDS_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE id IN (SELECT unhex(unnest(string_split(?, ','))))";
+
+// Other queries of the tests, also planned by `explain`
+const INSERT_USER_QUERY: &str =
+	"INSERT INTO users (id, tenant_id, name, email, created_at, active, deleted_at) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const INSERT_PRODUCT_QUERY: &str =
+	"INSERT INTO products (id, name, description, price, discount, stock, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const READ_BY_ID_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE id = ?";
+const READ_BY_COLUMN_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE email LIKE ? LIMIT ? OFFSET ?";
+const ONE_JOIN_QUERY: &str =
+	"
+	SELECT o.id, o.user_id, o.quantity, o.total_price, o.created_at,
+		p.id, p.name, p.description, p.price, p.discount, p.stock, p.created_at
+	FROM orders o
+	JOIN products p ON o.product_id = p.id
+	WHERE o.product_id = ?
+	LIMIT 100
+";
+const TWO_JOINS_QUERY: &str =
+	"
+	SELECT
+		o.id, o.quantity, o.total_price, o.created_at,
+		u.id, u.tenant_id, u.name, u.email, u.created_at, u.active, u.deleted_at,
+		p.id, p.name, p.description, p.price, p.discount, p.stock, p.created_at
+	FROM orders o
+	JOIN users u ON o.user_id = u.id
+	JOIN products p ON o.product_id = p.id
+	WHERE o.id = ?
+";
+const INSERT_ORDER_QUERY: &str =
+	"INSERT INTO orders (id, tenant_id, user_id, product_id, quantity, total_price, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const INSERT_ORDER_ITEM_QUERY: &str =
+	"INSERT INTO order_items (id, order_id, product_id, quantity, unit_price) VALUES (?, ?, ?, ?, ?)";
+const READ_ORDER_WITH_ITEMS_QUERY: &str =
+	"
+	SELECT o.id, o.user_id, o.total_price, o.created_at,
+		   i.id, i.product_id, i.quantity, i.unit_price, p.name
+	FROM orders o
+	JOIN order_items i ON i.order_id = o.id
+	JOIN products p ON i.product_id = p.id
+	WHERE o.id = ?
+";
+const UPDATE_PRODUCT_STOCK_QUERY: &str = "UPDATE products SET stock = ? WHERE id = ?";
+const READ_PRODUCT_STOCK_QUERY: &str = "SELECT stock FROM products WHERE id = ?";
+const UPDATE_USER_QUERY: &str = "UPDATE users SET name = ?, email = ?, active = ? WHERE id = ?";
+const UPDATE_USER_ACTIVE_QUERY: &str = "UPDATE users SET active = ? WHERE id = ?";
+const UPDATE_ORDER_QUERY: &str = "UPDATE orders SET quantity = ?, total_price = ? WHERE id = ?";
+
+// Errors of a write conflicting with another transaction, which go away once it commits. A
+// conflict aborts the transaction it happens in, so only statements running on their own retry
+fn is_transient(e: &duckdb::Error) -> bool {
+	e.to_string().to_lowercase().contains("conflict")
+}
+
+// Value an id is stored as: VARCHAR holding its string form or BLOB holding its 16 bytes
+fn key_value(encoding: KeyEncoding, id: &Uuid) -> Value {
+	match encoding {
+		KeyEncoding::String => Value::Text(id.to_string()),
+		KeyEncoding::Binary => Value::Blob(id.as_bytes().to_vec()),
+	}
+}
+
+// Ids joined into the single parameter of the read_many_by_ids query
+fn id_batch(encoding: KeyEncoding, ids: &[Value]) -> Result<String> {
+	let ids = ids
+		.iter()
+		.map(|id| {
+			let id = parse_key(id.clone())?;
+			Ok(match encoding {
+				KeyEncoding::String => id.to_string(),
+				KeyEncoding::Binary => id.simple().to_string(),
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+	Ok(ids.join(","))
+}
+
+// Id read back from a key column of either encoding
+fn parse_key(value: Value) -> Result<Uuid> {
+	codec::decode_key(&raw_value(value))
+}
+
+// Value a timestamp is stored as: VARCHAR holding its RFC 3339 form or a native TIMESTAMP
+fn timestamp_value(encoding: TimestampEncoding, timestamp: &DateTime<Utc>) -> Value {
+	match encoding {
+		TimestampEncoding::String => Value::Text(timestamp.to_rfc3339()),
+		TimestampEncoding::Native => Value::Timestamp(TimeUnit::Microsecond, timestamp.timestamp_micros()),
+	}
+}
+
+// Value as the codec decodes it, a native timestamp in microseconds
+fn raw_value(value: Value) -> RawValue {
+	match value {
+		Value::Null => RawValue::Null,
+		Value::Boolean(value) => RawValue::Boolean(value),
+		Value::TinyInt(value) => RawValue::Integer(value.into()),
+		Value::SmallInt(value) => RawValue::Integer(value.into()),
+		Value::Int(value) => RawValue::Integer(value.into()),
+		Value::BigInt(value) => RawValue::Integer(value),
+		Value::UTinyInt(value) => RawValue::Integer(value.into()),
+		Value::USmallInt(value) => RawValue::Integer(value.into()),
+		Value::UInt(value) => RawValue::Integer(value.into()),
+		Value::Float(value) => RawValue::Real(value.into()),
+		Value::Double(value) => RawValue::Real(value),
+		Value::Text(text) => RawValue::Text(text),
+		Value::Blob(bytes) => RawValue::Blob(bytes),
+		Value::Timestamp(unit, time) => RawValue::Timestamp(match unit {
+			TimeUnit::Second => time * 1_000_000,
+			TimeUnit::Millisecond => time * 1_000,
+			TimeUnit::Microsecond => time,
+			TimeUnit::Nanosecond => time / 1_000,
+		}),
+		other => RawValue::Text(format!("{:?}", other)),
+	}
+}
+
+// Values of a row fetched by a read test, left for the codec to decode
+fn raw_row(row: &duckdb::Row) -> duckdb::Result<Row> {
+	(0..row.as_ref().column_count())
+		.map(|column| row.get::<_, Value>(column).map(raw_value))
+		.collect::<duckdb::Result<_>>()
+		.map(Row)
+}
+
+fn wide_value(value: &WideValue) -> Value {
+	match value {
+		WideValue::Integer(value) => Value::BigInt(*value),
+		WideValue::Real(value) => Value::Double(*value),
+		WideValue::Text(value) => Value::Text(value.clone()),
+		WideValue::Boolean(value) => Value::Boolean(*value),
+	}
+}
+
+// Value of a column of an imported record, NULL for an empty cell
+fn imported_value(value: &Option<WideValue>) -> Value {
+	value.as_ref().map_or(Value::Null, wide_value)
+}
+
+// Values of a wide record in column order: the id, then every column
+fn wide_record_values(encoding: KeyEncoding, record: &WideRecord) -> Vec<Value> {
+	std::iter::once(key_value(encoding, &record.id)).chain(record.values.iter().map(wide_value)).collect()
+}
+
+// How bulk inserts write their rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkInsertMode {
+	// DuckDB's Appender, writing rows straight into the table storage
+	Appender,
+	// One prepared INSERT executed per row inside a transaction
+	Statements,
+}
+
+impl BulkInsertMode {
+	// Read the mode from DUCKDB_BULK_INSERT, `appender` when unset
+	pub fn from_env() -> Result<Self> {
+		match std::env::var(BULK_INSERT_ENV).as_deref() {
+			Err(_) | Ok("appender") => Ok(BulkInsertMode::Appender),
+			Ok("statements") => Ok(BulkInsertMode::Statements),
+			Ok(other) =>
+				Err(
+					anyhow::anyhow!(
+						"Invalid {} value {:?}, expected appender or statements",
+						BULK_INSERT_ENV,
+						other
+					)
+				),
+		}
+	}
+}
+
+// Connection handed to the test loops, preparing their hot queries through the statement cache
+// of the connection so they aren't prepared again on every call
+struct CachingConnection<'c> {
+	conn: &'c Connection,
+	statements: &'c PreparedStatements,
+}
+
+impl<'c> CachingConnection<'c> {
+	fn prepare_cached(&self, sql: &'static str) -> duckdb::Result<duckdb::CachedStatement<'c>> {
+		self.statements.record_lookup(sql);
+		self.conn.prepare_cached(sql)
+	}
+}
+
+impl std::ops::Deref for CachingConnection<'_> {
+	type Target = Connection;
+
+	fn deref(&self) -> &Self::Target {
+		self.conn
+	}
+}
+
+// Reader connection with the statements it prepared
+type Reader = (Arc<Mutex<Connection>>, Arc<PreparedStatements>);
+
+// Data a test needs, prepared before it is timed
+#[derive(Default)]
+pub struct Fixture {
+	// Records written by the insert tests
+	users: Vec<User>,
+	products: Vec<Product>,
+	orders: Vec<OrderWithItems>,
+	wide_records: Vec<WideRecord>,
+	// Ids of the records read or updated
+	ids: Arc<Vec<Value>>,
+	// Batches of ids bound by read_many_by_ids
+	terms: Arc<Vec<String>>,
+	// Clone of the connection of each writer of concurrent_inserts and consistency_probe, or
+	// contender of long_transaction_contention
+	connections: Vec<Arc<Mutex<Connection>>>,
+}
 
 pub struct DuckdbBenchmark {
 	pub db_path: String,
+	// Whether `db_path` is an existing database attached with `--connect`, opened read-only
+	connected: bool,
 	cpu_count: usize,
 	// We need a mutex to safely share the connection across async functions
 	conn: Arc<Mutex<Connection>>,
+	statements: Arc<PreparedStatements>,
+	// Clones of the connection to the same database, used by the read tests to query concurrently
+	readers: Vec<Reader>,
+	bulk_insert: BulkInsertMode,
+	indexes: IndexMode,
+	key_encoding: KeyEncoding,
+	timestamp_encoding: TimestampEncoding,
+	retry: RetryPolicy,
+	context: RunContext,
 }
 
 impl DuckdbBenchmark {
-	pub async fn new(cpu_count: usize) -> Result<Self> {
-		let db_path = std::env
-			::current_dir()?
-			.join("data/duckdb-benchmark.db")
-			.to_string_lossy()
-			.to_string();
+	pub async fn new(cpu_count: usize, data_dir: &Path, bulk_insert: BulkInsertMode) -> Result<Self> {
+		let db_path = data_dir.join("duckdb-benchmark.db").to_string_lossy().to_string();
 
-		println!("Database path: {}", db_path);
+		debug!("Database path: {}", db_path);
 
 		// Create data directory if it doesn't exist
-		let data_dir = Path::new("./data");
 		if !data_dir.exists() {
 			std::fs::create_dir_all(data_dir)?;
 		}
 
+		Self::start(db_path, false, cpu_count, bulk_insert).await
+	}
+
+	// Attach to an existing database file, which the runs only read
+	pub async fn connect(cpu_count: usize, dsn: &str, bulk_insert: BulkInsertMode) -> Result<Self> {
+		Self::start(dsn.to_string(), true, cpu_count, bulk_insert).await
+	}
+
+	async fn start(db_path: String, connected: bool, cpu_count: usize, bulk_insert: BulkInsertMode) -> Result<Self> {
+		let context = RunContext::default();
+		let (conn, readers) = Self::open(&db_path, connected, cpu_count, &context)?;
+
+		// Wrap the connection in Arc<Mutex> for safe sharing
+		let conn = Arc::new(Mutex::new(conn));
+
+		// Create a new instance
+		let benchmark = Self {
+			db_path,
+			connected,
+			cpu_count,
+			conn,
+			statements: Arc::new(PreparedStatements::new(context.clone())),
+			readers,
+			bulk_insert,
+			indexes: IndexMode::default(),
+			key_encoding: KeyEncoding::default(),
+			timestamp_encoding: TimestampEncoding::default(),
+			retry: RetryPolicy::default(),
+			context,
+		};
+
+		// Initialize database
+		benchmark.init().await?;
+
+		Ok(benchmark)
+	}
+
+	// Open the database along with its reader connections, read-only when it is a connected one
+	fn open(
+		db_path: &str,
+		read_only: bool,
+		cpu_count: usize,
+		context: &RunContext
+	) -> Result<(Connection, Vec<Reader>)> {
 		// Create the connection to DuckDB
-		let conn = Connection::open(&db_path)?;
+		let conn = if read_only {
+			Connection::open_with_flags(db_path, Config::default().access_mode(AccessMode::ReadOnly)?)?
+		} else {
+			Connection::open(db_path)?
+		};
 
 		// Configure DuckDB
-		println!("Setting threads to {}", cpu_count);
+		debug!("Setting threads to {}", cpu_count);
 		conn.execute(&format!("SET threads TO {}", cpu_count), [])?;
 
 		// Enable parallel execution
-		println!("Enabling object cache");
+		debug!("Enabling object cache");
 		conn.execute("PRAGMA enable_object_cache", [])?;
 
 		// Set memory limit
-		println!("Setting memory limit to 4GB");
+		debug!("Setting memory limit to 4GB");
 		conn.execute("PRAGMA memory_limit='4GB'", [])?;
 
-		// Wrap the connection in Arc<Mutex> for safe sharing
-		let conn = Arc::new(Mutex::new(conn));
+		// One reader per available core; the read tests use as many of them as the CPU count allows
+		let reader_count = std::thread::available_parallelism().map_or(4, |count| count.get());
+		let readers = (0..reader_count)
+			.map(|_| {
+				let statements = Arc::new(PreparedStatements::new(context.clone()));
+				Ok((Arc::new(Mutex::new(conn.try_clone()?)), statements))
+			})
+			.collect::<Result<Vec<_>>>()?;
 
-		// Create a new instance
-		let benchmark = Self { db_path, cpu_count, conn };
+		Ok((conn, readers))
+	}
 
-		// Initialize database
-		benchmark.init().await?;
+	// Check that the connected database holds the tables of the suite, its users keyed and
+	// timestamped as the plan encodes them
+	async fn check_schema(&self, id_type: &'static str, timestamp_type: &'static str) -> Result<()> {
+		let (tables, id_column, created_at_column) = self.run_blocking(|conn| {
+			let tables = conn
+				.prepare("SELECT table_name FROM information_schema.tables")?
+				.query_map([], |row| row.get(0))?
+				.collect::<Result<Vec<String>, _>>()?;
+			let column_type = |column: &str| {
+				conn
+					.query_row(
+						"SELECT data_type FROM information_schema.columns WHERE table_name = 'users' AND column_name = ?",
+						[column],
+						|row| row.get::<_, String>(0)
+					)
+					.optional()
+			};
+			Ok((tables, column_type("id")?, column_type("created_at")?))
+		}).await?;
 
-		Ok(benchmark)
+		connect::check_tables(&tables)?;
+		for (column, declared, expected, setting) in [
+			("id", id_column, id_type, "key_encoding"),
+			("created_at", created_at_column, timestamp_type, "timestamp_encoding"),
+		] {
+			let Some(declared) = declared else {
+				anyhow::bail!("The users of the connected database have no {} column", column);
+			};
+			anyhow::ensure!(
+				declared == expected,
+				"The users of the connected database have a {} column of type {}, the {} of the plan expects {}",
+				column,
+				declared,
+				setting,
+				expected
+			);
+		}
+
+		Ok(())
 	}
 
 	// Helper to run blocking database operations in a way that works with async/await
@@ -78,137 +473,434 @@ impl DuckdbBenchmark {
 
 		Ok(result)
 	}
+
+	// Split `count` operations into one contiguous range per reader and run them on `cpu_count`
+	// connections at once; `work` receives the offset and length of its range
+	async fn run_parallel<F>(&self, count: usize, work: F) -> Result<()>
+		where F: Fn(&CachingConnection, usize, usize) -> Result<()> + Send + Sync + 'static
+	{
+		let work = Arc::new(work);
+		let workers = self.cpu_count.clamp(1, self.readers.len());
+		let chunk = count.div_ceil(workers);
+
+		let tasks = self.readers[..workers]
+			.iter()
+			.enumerate()
+			.map(|(i, reader)| (reader.clone(), i * chunk))
+			.filter(|(_, offset)| *offset < count)
+			.map(|((conn, statements), offset)| {
+				let work = work.clone();
+				let len = chunk.min(count - offset);
+				tokio::task::spawn_blocking(move || {
+					let conn = conn.blocking_lock();
+					work(&CachingConnection { conn: &conn, statements: &statements }, offset, len)
+				})
+			});
+
+		for result in futures::future::try_join_all(tasks).await? {
+			result?;
+		}
+
+		Ok(())
+	}
+
+	// Physical plan DuckDB picks for a query, as printed by EXPLAIN
+	fn query_plan<P: duckdb::Params>(conn: &Connection, query: &str, params: P) -> Result<String> {
+		let mut stmt = conn.prepare(&format!("EXPLAIN {}", query))?;
+		let plan = stmt
+			.query_map(params, |row| row.get::<_, String>(1))?
+			.collect::<Result<Vec<_>, _>>()?
+			.join("\n");
+		Ok(plan)
+	}
+
+	// Query of read_many_by_ids for the key encoding in use
+	fn read_many_by_ids_query(&self) -> &'static str {
+		match self.key_encoding {
+			KeyEncoding::String => READ_MANY_BY_IDS_QUERY,
+			KeyEncoding::Binary => READ_MANY_BY_BINARY_IDS_QUERY,
+		}
+	}
+
+	// First id of a table, NULL when it's empty
+	async fn first_id(&self, table: &'static str) -> Result<Value> {
+		Ok(self.first_ids(table, 1).await?.pop().unwrap_or(Value::Null))
+	}
+
+	// First `limit` ids of a table
+	async fn first_ids(&self, table: &'static str, limit: usize) -> Result<Vec<Value>> {
+		self.run_blocking(move |conn| {
+			let mut stmt = conn.prepare(&format!("SELECT id FROM {} LIMIT ?", table))?;
+			let ids: Vec<Value> = stmt
+				.query_map([limit as i64], |row| row.get(0))?
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|e| anyhow::anyhow!(e))?;
+			Ok(ids)
+		}).await
+	}
+
+	// Number of the first `limit` rows of a table whose integer `column` doesn't hold the value
+	// `expected` gives for the row's position, as written by the update tests
+	fn count_stale_rows(
+		conn: &Connection,
+		table: &str,
+		column: &str,
+		limit: usize,
+		expected: impl Fn(usize) -> i32
+	) -> Result<usize> {
+		let mut ids = conn.prepare(&format!("SELECT id FROM {} LIMIT ?", table))?;
+		let ids: Vec<Value> = ids
+			.query_map([limit as i64], |row| row.get(0))?
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let mut stmt = conn.prepare(&format!("SELECT {} FROM {} WHERE id = ?", column, table))?;
+		let mut stale = 0;
+		for (i, id) in ids.iter().enumerate() {
+			let value: i32 = stmt.query_row([id], |row| row.get(0))?;
+			if value != expected(i) {
+				stale += 1;
+			}
+		}
+
+		Ok(stale)
+	}
 }
 
 #[async_trait]
 impl DatabaseBenchmark for DuckdbBenchmark {
+	type Fixture = Fixture;
+
 	async fn init(&self) -> Result<()> {
-		println!("Initializing database");
-		let result = self.run_blocking(|conn| {
+		info!("Initializing database");
+		let indexes = self.indexes;
+		let id_type = match self.key_encoding {
+			KeyEncoding::String => "VARCHAR",
+			KeyEncoding::Binary => "BLOB",
+		};
+		let timestamp_type = match self.timestamp_encoding {
+			TimestampEncoding::String => "VARCHAR",
+			TimestampEncoding::Native => "TIMESTAMP",
+		};
+
+		// A connected database keeps its schema and indexes, which only have to suit the plan
+		if self.connected {
+			return self.check_schema(id_type, timestamp_type).await;
+		}
+
+		let result = self.run_blocking(move |conn| {
+			// Tables created with other key or timestamp encodings, or before the tenants and the
+			// optional fields were added, are dropped; their data is regenerated anyway
+			let column_type = |column: &str| {
+				conn
+					.query_row(
+						"SELECT data_type FROM information_schema.columns WHERE table_name = 'users' AND column_name = ?",
+						[column],
+						|row| row.get::<_, String>(0)
+					)
+					.optional()
+			};
+			let outdated =
+				column_type("id")?.is_some_and(|current| current != id_type) ||
+				column_type("created_at")?.is_some_and(|current| current != timestamp_type) ||
+				(column_type("id")?.is_some() &&
+					(column_type("tenant_id")?.is_none() || column_type("deleted_at")?.is_none()));
+			if outdated {
+				conn.execute_batch(
+					"DROP TABLE IF EXISTS wide_records;
+					DROP TABLE IF EXISTS order_items;
+					DROP TABLE IF EXISTS orders;
+					DROP TABLE IF EXISTS products;
+					DROP TABLE IF EXISTS users;"
+				)?;
+			}
+
 			// Create users table
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS users (
-                    id VARCHAR,
+				&format!("CREATE TABLE IF NOT EXISTS users (
+                    id {id_type},
+                    tenant_id UINTEGER NOT NULL,
                     name VARCHAR NOT NULL,
                     email VARCHAR NOT NULL,
-                    created_at VARCHAR NOT NULL,
-                    active BOOLEAN NOT NULL
-                )",
+                    created_at {timestamp_type} NOT NULL,
+                    active BOOLEAN NOT NULL,
+                    deleted_at {timestamp_type}
+                )"),
 				[]
 			)?;
 
 			// Create products table
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS products (
-                    id VARCHAR,
+				&format!("CREATE TABLE IF NOT EXISTS products (
+                    id {id_type},
                     name VARCHAR NOT NULL,
                     description VARCHAR NOT NULL,
                     price DOUBLE NOT NULL,
+                    discount DOUBLE,
                     stock INTEGER NOT NULL,
-                    created_at VARCHAR NOT NULL
-                )",
+                    created_at {timestamp_type} NOT NULL
+                )"),
 				[]
 			)?;
 
 			// Create orders table with foreign keys
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS orders (
-                    id VARCHAR,
-                    user_id VARCHAR NOT NULL,
-                    product_id VARCHAR NOT NULL,
+				&format!("CREATE TABLE IF NOT EXISTS orders (
+                    id {id_type},
+                    tenant_id UINTEGER NOT NULL,
+                    user_id {id_type} NOT NULL,
+                    product_id {id_type} NOT NULL,
                     quantity INTEGER NOT NULL,
                     total_price DOUBLE NOT NULL,
-                    created_at VARCHAR NOT NULL,
-                )",
+                    created_at {timestamp_type} NOT NULL,
+                )"),
+				[]
+			)?;
+
+			// Create order items table, one row per product of an order
+			conn.execute(
+				&format!("CREATE TABLE IF NOT EXISTS order_items (
+                    id {id_type},
+                    order_id {id_type} NOT NULL,
+                    product_id {id_type} NOT NULL,
+                    quantity INTEGER NOT NULL,
+                    unit_price DOUBLE NOT NULL
+                )"),
+				[]
+			)?;
+
+			// Create wide records table, of many columns of mixed types
+			conn.execute(
+				&wide::create_table_query(id_type, |column_type| match column_type {
+					WideColumnType::Integer => "BIGINT",
+					WideColumnType::Real => "DOUBLE",
+					WideColumnType::Text => "VARCHAR",
+					WideColumnType::Boolean => "BOOLEAN",
+				}),
 				[]
 			)?;
 
+			// DuckDB can't add a primary key to an existing table, so the ids get unique ART
+			// indexes instead, enforcing the same constraint and serving the point reads
+			conn.execute_batch(
+				"CREATE UNIQUE INDEX IF NOT EXISTS idx_users_id ON users (id);
+				CREATE UNIQUE INDEX IF NOT EXISTS idx_products_id ON products (id);
+				CREATE UNIQUE INDEX IF NOT EXISTS idx_orders_id ON orders (id);
+				CREATE UNIQUE INDEX IF NOT EXISTS idx_order_items_id ON order_items (id);
+				CREATE UNIQUE INDEX IF NOT EXISTS idx_wide_records_id ON wide_records (id);"
+			)?;
+
+			// Create or drop the secondary indexes. The user emails and deletion times aren't
+			// indexed: DuckDB turns updates of indexed columns into a delete and an insert, which
+			// trips the unique id index when the update and soft-delete tests change them. DuckDB
+			// has no partial indexes, so the product discounts are indexed whole, NULLs included
+			if indexes.is_on() {
+				conn.execute_batch(
+					"CREATE INDEX IF NOT EXISTS idx_products_name ON products (name);
+					CREATE INDEX IF NOT EXISTS idx_orders_user_id ON orders (user_id);
+					CREATE INDEX IF NOT EXISTS idx_orders_product_id ON orders (product_id);
+					CREATE INDEX IF NOT EXISTS idx_order_items_order_id ON order_items (order_id);
+					CREATE INDEX IF NOT EXISTS idx_orders_tenant_id ON orders (tenant_id);
+					CREATE INDEX IF NOT EXISTS idx_products_discount ON products (discount);
+					DROP INDEX IF EXISTS idx_users_deleted_at;"
+				)?;
+			} else {
+				conn.execute_batch(
+					"DROP INDEX IF EXISTS idx_products_name;
+					DROP INDEX IF EXISTS idx_orders_user_id;
+					DROP INDEX IF EXISTS idx_orders_product_id;
+					DROP INDEX IF EXISTS idx_order_items_order_id;
+					DROP INDEX IF EXISTS idx_orders_tenant_id;
+					DROP INDEX IF EXISTS idx_users_deleted_at;
+					DROP INDEX IF EXISTS idx_products_discount;"
+				)?;
+			}
+
 			Ok(())
 		}).await;
 
-		println!("Database initialized");
+		info!("Database initialized");
 		result
 	}
 
 	async fn generate_test_data(&self, count: usize) -> Result<()> {
-		println!("Generating test data for {} users", count);
-		// Generate users
-		let users: Vec<User> = (0..count).map(|_| generate_random_user()).collect();
-
-		// Generate products
-		let products: Vec<Product> = (0..count)
-			.map(|_| generate_random_product())
-			.collect();
-
-		// Generate orders (one per user/product pair for simplicity)
-		let mut orders = Vec::with_capacity(count);
-		for i in 0..count {
-			let user_id = users[i % users.len()].id;
-			let product_id = products[i % products.len()].id;
-			orders.push(generate_random_order(user_id, product_id));
-		}
+		info!("Generating test data for {} users", count);
+		let bulk_insert = self.bulk_insert;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		// Generate and insert the data chunk by chunk, so only one chunk is held in memory
+		let chunks = generate_test_data_chunks(
+			count,
+			self.context.payload(),
+			self.context.tenants(),
+			self.context.key_strategy()
+		);
+		for chunk in chunks {
+			self.run_blocking(move |conn| {
+				match bulk_insert {
+					BulkInsertMode::Appender => {
+						// Each appender flushes its rows when dropped at the end of its block
+						{
+							let mut appender = conn.appender("users")?;
+							for user in &chunk.users {
+								appender.append_row(
+									params![
+										key_value(encoding, &user.id),
+										user.tenant_id,
+										user.name,
+										user.email,
+										timestamp_value(timestamps, &user.created_at),
+										user.active,
+										user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+									]
+								)?;
+							}
+						}
 
-		// Clone the collections for the async closure
-		let users_clone = users.clone();
-		let products_clone = products.clone();
-		let orders_clone = orders.clone();
+						{
+							let mut appender = conn.appender("products")?;
+							for product in &chunk.products {
+								appender.append_row(
+									params![
+										key_value(encoding, &product.id),
+										product.name,
+										product.description,
+										product.price,
+										product.discount,
+										product.stock,
+										timestamp_value(timestamps, &product.created_at)
+									]
+								)?;
+							}
+						}
 
-		self.run_blocking(move |conn| {
-			// Use a transaction for better performance
-			let tx = conn.transaction()?;
+						{
+							let mut appender = conn.appender("orders")?;
+							for order in &chunk.orders {
+								appender.append_row(
+									params![
+										key_value(encoding, &order.id),
+										order.tenant_id,
+										key_value(encoding, &order.user_id),
+										key_value(encoding, &order.product_id),
+										order.quantity,
+										order.total_price,
+										timestamp_value(timestamps, &order.created_at)
+									]
+								)?;
+							}
+						}
 
-			// Insert users
-			for user in &users_clone {
-				tx.execute(
-					"INSERT INTO users (id, name, email, created_at, active) VALUES (?, ?, ?, ?, ?)",
-					params![
-						user.id.to_string(),
-						user.name,
-						user.email,
-						user.created_at.to_rfc3339(),
-						user.active
-					]
-				)?;
-			}
+						{
+							let mut appender = conn.appender("order_items")?;
+							for item in &chunk.order_items {
+								appender.append_row(
+									params![
+										key_value(encoding, &item.id),
+										key_value(encoding, &item.order_id),
+										key_value(encoding, &item.product_id),
+										item.quantity,
+										item.unit_price
+									]
+								)?;
+							}
+						}
 
-			// Insert products
-			for product in &products_clone {
-				tx.execute(
-					"INSERT INTO products (id, name, description, price, stock, created_at) VALUES (?, ?, ?, ?, ?, ?)",
-					params![
-						product.id.to_string(),
-						product.name,
-						product.description,
-						product.price,
-						product.stock,
-						product.created_at.to_rfc3339()
-					]
-				)?;
-			}
+						let mut appender = conn.appender("wide_records")?;
+						for record in &chunk.wide_records {
+							appender.append_row(appender_params_from_iter(wide_record_values(encoding, record)))?;
+						}
+						appender.flush();
+					}
+					BulkInsertMode::Statements => {
+						// Use a transaction for better performance
+						let tx = conn.transaction()?;
+
+						// Insert users
+						for user in &chunk.users {
+							tx.execute(
+								INSERT_USER_QUERY,
+								params![
+									key_value(encoding, &user.id),
+									user.tenant_id,
+									user.name,
+									user.email,
+									timestamp_value(timestamps, &user.created_at),
+									user.active,
+									user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+								]
+							)?;
+						}
 
-			// Insert orders
-			for order in &orders_clone {
-				tx.execute(
-					"INSERT INTO orders (id, user_id, product_id, quantity, total_price, created_at) VALUES (?, ?, ?, ?, ?, ?)",
-					params![
-						order.id.to_string(),
-						order.user_id.to_string(),
-						order.product_id.to_string(),
-						order.quantity,
-						order.total_price,
-						order.created_at.to_rfc3339()
-					]
-				)?;
-			}
+						// Insert products
+						for product in &chunk.products {
+							tx.execute(
+								INSERT_PRODUCT_QUERY,
+								params![
+									key_value(encoding, &product.id),
+									product.name,
+									product.description,
+									product.price,
+									product.discount,
+									product.stock,
+									timestamp_value(timestamps, &product.created_at)
+								]
+							)?;
+						}
 
-			tx.commit()?;
-			Ok(())
-		}).await
+						// Insert orders
+						for order in &chunk.orders {
+							tx.execute(
+								INSERT_ORDER_QUERY,
+								params![
+									key_value(encoding, &order.id),
+									order.tenant_id,
+									key_value(encoding, &order.user_id),
+									key_value(encoding, &order.product_id),
+									order.quantity,
+									order.total_price,
+									timestamp_value(timestamps, &order.created_at)
+								]
+							)?;
+						}
+
+						// Insert order items
+						for item in &chunk.order_items {
+							tx.execute(
+								INSERT_ORDER_ITEM_QUERY,
+								params![
+									key_value(encoding, &item.id),
+									key_value(encoding, &item.order_id),
+									key_value(encoding, &item.product_id),
+									item.quantity,
+									item.unit_price
+								]
+							)?;
+						}
+
+						// Insert wide records
+						for record in &chunk.wide_records {
+							tx.execute(
+								INSERT_WIDE_RECORD_QUERY.as_str(),
+								params_from_iter(wide_record_values(encoding, record))
+							)?;
+						}
+
+						tx.commit()?;
+					}
+				}
+
+				Ok(())
+			}).await?;
+		}
+
+		Ok(())
 	}
 
 	async fn cleanup(&self) -> Result<()> {
 		self.run_blocking(|conn| {
 			// Empty all tables first
+			conn.execute("DELETE FROM wide_records", [])?;
+			conn.execute("DELETE FROM order_items", [])?;
 			conn.execute("DELETE FROM orders", [])?;
 			conn.execute("DELETE FROM products", [])?;
 			conn.execute("DELETE FROM users", [])?;
@@ -222,172 +914,1330 @@ impl DatabaseBenchmark for DuckdbBenchmark {
 		}).await
 	}
 
-	fn database_name(&self) -> String {
-		"DuckDB".to_string()
+	async fn snapshot(&self, dir: &Path) -> Result<()> {
+		std::fs::create_dir_all(dir)?;
+		let db_path = self.db_path.clone();
+		let target = dir.join(SNAPSHOT_FILE);
+
+		// The connection stays locked while the file is copied so no write lands in between
+		self.run_blocking(move |conn| {
+			conn.execute_batch("CHECKPOINT")?;
+			std::fs::copy(&db_path, &target)?;
+			Ok(())
+		}).await
 	}
 
-	fn set_cpu_count(&mut self, count: usize) {
-		println!("Setting CPU count to {}", count);
-		self.cpu_count = count;
-		let conn = self.conn.clone();
-		// Update the thread count in DuckDB
-		tokio::spawn(async move {
-			let _ = tokio::task::spawn_blocking(move || {
-				let conn = conn.blocking_lock();
-				let _ = conn.execute(&format!("SET threads TO {}", count), []);
-			}).await;
-		});
+	async fn restore(&self, dir: &Path) -> Result<()> {
+		let path = dir.join(SNAPSHOT_FILE);
+		if !path.exists() {
+			anyhow::bail!("No snapshot at {}", path.display());
+		}
+
+		let path = path.to_string_lossy().replace('\'', "''");
+		self.run_blocking(move |conn| {
+			conn.execute_batch(&format!("ATTACH '{}' AS fixture (READ_ONLY)", path))?;
+			let copied = (|| -> Result<()> {
+				let tx = conn.transaction()?;
+				for table in FIXTURE_TABLES.iter().rev() {
+					tx.execute(&format!("DELETE FROM {}", table), [])?;
+				}
+				for table in FIXTURE_TABLES {
+					tx.execute(&format!("INSERT INTO {} SELECT * FROM fixture.{}", table, table), [])?;
+				}
+				tx.commit()?;
+				Ok(())
+			})();
+			// Detach even when the copy failed, the next restore attaches again
+			conn.execute_batch("DETACH fixture")?;
+			copied
+		}).await
 	}
 
-	fn get_cpu_count(&self) -> usize {
-		self.cpu_count
+	// EXPORT DATABASE writes the schema, the indexes and a Parquet file per table
+	async fn backup(&self, dir: &Path) -> Result<()> {
+		let target = dir.join(BACKUP_DIR);
+		self.run_blocking(move |conn| {
+			if target.exists() {
+				std::fs::remove_dir_all(&target)?;
+			}
+			let path = target.to_string_lossy().replace('\'', "''");
+			conn.execute_batch(&format!("EXPORT DATABASE '{}' (FORMAT PARQUET)", path))?;
+			Ok(())
+		}).await
 	}
 
-	async fn insert_single_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		println!("Inserting {} users", count);
-		measure_execution(
-			&self.database_name(),
-			"insert_single_many_times",
-			count,
-			self.cpu_count,
-			|| async {
-				let conn = self.conn.clone();
+	// IMPORT DATABASE creates the tables it loads, so the current ones are dropped first
+	async fn restore_backup(&self, dir: &Path) -> Result<()> {
+		let path = dir.join(BACKUP_DIR);
+		if !path.exists() {
+			anyhow::bail!("No backup at {}", path.display());
+		}
 
-				tokio::task::spawn_blocking(move || {
-					let mut conn = conn.blocking_lock();
-					let tx = conn.transaction()?;
+		let path = path.to_string_lossy().replace('\'', "''");
+		self.run_blocking(move |conn| {
+			for table in FIXTURE_TABLES.iter().rev() {
+				conn.execute_batch(&format!("DROP TABLE IF EXISTS {}", table))?;
+			}
+			conn.execute_batch(&format!("IMPORT DATABASE '{}'", path))?;
+			Ok(())
+		}).await
+	}
+
+	async fn reset(&mut self) -> Result<()> {
+		anyhow::ensure!(!self.connected, "A connected database isn't deleted");
+
+		// DuckDB closes the database once its last connection is dropped, which must happen
+		// before the files are deleted; an in-memory connection stands in meanwhile
+		self.readers.clear();
+		self.conn = Arc::new(Mutex::new(Connection::open_in_memory()?));
+
+		for suffix in ["", ".wal"] {
+			match std::fs::remove_file(format!("{}{}", self.db_path, suffix)) {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+					return Err(e.into());
+				}
+				_ => {}
+			}
+		}
+
+		// Reopen every connection on the new file, with empty statement caches
+		let (conn, readers) = Self::open(&self.db_path, false, self.cpu_count, &self.context)?;
+		self.conn = Arc::new(Mutex::new(conn));
+		self.statements = Arc::new(PreparedStatements::new(self.context.clone()));
+		self.readers = readers;
+
+		self.init().await
+	}
+
+	async fn ping(&self) -> Result<()> {
+		self.run_blocking(|conn| {
+			conn.query_row("SELECT 1", [], |row| row.get::<_, i32>(0))?;
+			Ok(())
+		}).await
+	}
+
+	async fn probe(&self) -> Result<Capabilities> {
+		self.run_blocking(|conn| {
+			conn.execute_batch(
+				&format!(
+					"DROP TABLE IF EXISTS {table}; CREATE TABLE {table} (id INTEGER PRIMARY KEY, data BLOB NOT NULL);",
+					table = PROBE_TABLE
+				)
+			)?;
+			conn.execute(
+				&format!("INSERT INTO {} (id, data) VALUES (1, ?)", PROBE_TABLE),
+				params![Value::Blob(PROBE_BLOB.to_vec())]
+			)?;
+			let blob: Value = conn.query_row(&format!("SELECT data FROM {}", PROBE_TABLE), [], |row| row.get(0))?;
+
+			let window_functions = conn
+				.query_row(
+					&format!("SELECT ROW_NUMBER() OVER (ORDER BY id) FROM {}", PROBE_TABLE),
+					[],
+					|row| row.get::<_, i64>(0)
+				)
+				.is_ok();
+			// The fts extension isn't bundled, it only loads once installed
+			let full_text_search = conn.execute_batch("LOAD fts").is_ok();
+			conn.execute_batch(&format!("DROP TABLE {}", PROBE_TABLE))?;
+
+			Ok(Capabilities {
+				window_functions,
+				full_text_search,
+				blobs: matches!(blob, Value::Blob(bytes) if bytes == PROBE_BLOB),
+				analytics: false,
+			})
+		}).await
+	}
+
+	// Size of the database file and of its WAL, which the checkpoint before each test empties
+	async fn bytes_written(&self) -> Result<Option<u64>> {
+		let wal_path = format!("{}.wal", self.db_path);
+		Ok(Some(files_size(&[&self.db_path, &wal_path])))
+	}
+
+	// The file keeps the blocks freed by updates and deletes for later writes, so it never shrinks
+	async fn database_size(&self) -> Result<Option<u64>> {
+		self.bytes_written().await
+	}
+
+	// The blocks of the database, used and free, the size of its WAL and the memory of its buffer
+	// manager, as PRAGMA database_size reports them
+	async fn engine_stats(&self) -> Result<Option<serde_json::Value>> {
+		self.run_blocking(|conn| {
+			let mut stmt = conn.prepare("PRAGMA database_size")?;
+			let mut rows = stmt.query([])?;
+			let mut stats = serde_json::Map::new();
+			if let Some(row) = rows.next()? {
+				let columns = row.as_ref().column_names();
+				for (i, column) in columns.into_iter().enumerate() {
+					let value = match row.get::<_, Value>(i)? {
+						Value::BigInt(value) => value.into(),
+						Value::UBigInt(value) => value.into(),
+						Value::Text(value) => value.into(),
+						Value::Null => serde_json::Value::Null,
+						value => format!("{:?}", value).into(),
+					};
+					stats.insert(column, value);
+				}
+			}
+			Ok(Some(serde_json::Value::Object(stats)))
+		}).await
+	}
+
+	async fn explain(&self, test: BenchmarkTest) -> Result<Option<String>> {
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		let key_strategy = self.context.key_strategy();
+
+		// DuckDB plans the query for the values bound to it, so they are taken from the data
+		let (query, params): (&'static str, Vec<Value>) = match test {
+			BenchmarkTest::InsertSingleManyTimes | BenchmarkTest::ConcurrentInserts => {
+				let user = generate_random_user(self.context.payload(), self.context.tenants(), key_strategy);
+				(
+					INSERT_USER_QUERY,
+					vec![
+						key_value(encoding, &user.id),
+						Value::UInt(user.tenant_id),
+						Value::Text(user.name),
+						Value::Text(user.email),
+						timestamp_value(timestamps, &user.created_at),
+						Value::Boolean(user.active),
+						user.deleted_at.as_ref().map_or(Value::Null, |deleted_at| timestamp_value(timestamps, deleted_at))
+					],
+				)
+			}
+			BenchmarkTest::InsertManyAtOnce => {
+				// The appender writes straight into the table storage, without a query
+				if self.bulk_insert == BulkInsertMode::Appender {
+					return Ok(None);
+				}
+				let product = generate_random_product(self.context.payload(), key_strategy);
+				(
+					INSERT_PRODUCT_QUERY,
+					vec![
+						key_value(encoding, &product.id),
+						Value::Text(product.name),
+						Value::Text(product.description),
+						Value::Double(product.price),
+						product.discount.map_or(Value::Null, Value::Double),
+						Value::Int(product.stock),
+						timestamp_value(timestamps, &product.created_at)
+					],
+				)
+			}
+			BenchmarkTest::ReadByIdManyTimes => (READ_BY_ID_QUERY, vec![self.first_id("users").await?]),
+			BenchmarkTest::ReadManyByIds => {
+				let ids = self.first_ids("users", 3).await?;
+				(self.read_many_by_ids_query(), vec![Value::Text(id_batch(encoding, &ids)?)])
+			}
+			BenchmarkTest::ReadByColumnSearch => {
+				let pattern = Value::Text(search_pattern(self.context.selectivity()));
+				(READ_BY_COLUMN_QUERY, vec![pattern, Value::BigInt(100), Value::BigInt(0)])
+			}
+			BenchmarkTest::ReadWithOneJoin => (ONE_JOIN_QUERY, vec![self.first_id("products").await?]),
+			BenchmarkTest::ReadWithTwoJoins => (TWO_JOINS_QUERY, vec![self.first_id("orders").await?]),
+			BenchmarkTest::UpdateSingleFieldOneEntry | BenchmarkTest::UpdateSingleFieldManyEntries => {
+				(UPDATE_PRODUCT_STOCK_QUERY, vec![Value::Int(1), self.first_id("products").await?])
+			}
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+				(
+					UPDATE_USER_QUERY,
+					vec![
+						Value::Text("Updated User 0".to_string()),
+						Value::Text("updated0@example.com".to_string()),
+						Value::Boolean(true),
+						self.first_id("users").await?
+					],
+				)
+			}
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+				(
+					UPDATE_ORDER_QUERY,
+					vec![Value::Int(1), Value::Double(9.99), self.first_id("orders").await?],
+				)
+			}
+			BenchmarkTest::InsertOrdersWithItems => {
+				(
+					INSERT_ORDER_ITEM_QUERY,
+					vec![
+						key_value(encoding, &Uuid::new_v4()),
+						self.first_id("orders").await?,
+						self.first_id("products").await?,
+						Value::Int(1),
+						Value::Double(9.99)
+					],
+				)
+			}
+			BenchmarkTest::ReadOrdersWithItems => {
+				(READ_ORDER_WITH_ITEMS_QUERY, vec![self.first_id("orders").await?])
+			}
+			BenchmarkTest::InsertWideRecords => {
+				let record = generate_wide_record(key_strategy);
+				(INSERT_WIDE_RECORD_QUERY.as_str(), wide_record_values(encoding, &record))
+			}
+			BenchmarkTest::ReadWideRecordsById => {
+				(READ_WIDE_RECORD_QUERY, vec![self.first_id("wide_records").await?])
+			}
+			BenchmarkTest::ReadWideColumnSubset => {
+				(READ_WIDE_COLUMN_SUBSET_QUERY.as_str(), vec![Value::BigInt(100), Value::BigInt(0)])
+			}
+			BenchmarkTest::ReadTenantOrders => {
+				(READ_TENANT_ORDERS_QUERY, vec![Value::UInt(0), Value::BigInt(TENANT_PAGE_LEN as i64)])
+			}
+			BenchmarkTest::AggregateOrdersByTenant => (AGGREGATE_ORDERS_BY_TENANT_QUERY, vec![]),
+			BenchmarkTest::ReadUsersNotDeleted => {
+				(READ_USERS_NOT_DELETED_QUERY, vec![Value::BigInt(100), Value::BigInt(0)])
+			}
+			BenchmarkTest::ReadDiscountedProducts => {
+				(READ_DISCOUNTED_PRODUCTS_QUERY, vec![Value::BigInt(100), Value::BigInt(0)])
+			}
+			BenchmarkTest::SoftDeleteUsers => {
+				(
+					SOFT_DELETE_USER_QUERY,
+					vec![timestamp_value(timestamps, &Utc::now()), self.first_id("users").await?],
+				)
+			}
+			BenchmarkTest::ReadLiveUsers => {
+				(READ_LIVE_USERS_QUERY, vec![Value::BigInt(100), Value::BigInt(0)])
+			}
+			BenchmarkTest::LongTransactionContention => {
+				(UPDATE_USER_ACTIVE_QUERY, vec![Value::Boolean(true), self.first_id("users").await?])
+			}
+			BenchmarkTest::ConsistencyProbe => {
+				(UPDATE_PRODUCT_STOCK_QUERY, vec![Value::Int(1), self.first_id("products").await?])
+			}
+			BenchmarkTest::ReadProjection => {
+				(READ_PROJECTION_QUERY, vec![Value::BigInt(100), Value::BigInt(0)])
+			}
+		};
+
+		let plan = self.run_blocking(move |conn| {
+			Self::query_plan(conn, query, params_from_iter(params))
+		}).await?;
+		Ok(Some(plan))
+	}
+
+	// The point reads go through the unique ART index of the ids. The emails aren't indexed and
+	// the column search matches their end, which an ART index can't seek anyway
+	fn expected_index(&self, test: BenchmarkTest) -> Option<&'static str> {
+		match test {
+			BenchmarkTest::ReadByIdManyTimes => Some("INDEX_SCAN"),
+			_ => None,
+		}
+	}
+
+	fn supports_analytics(&self) -> bool {
+		true
+	}
+
+	fn connected(&self) -> bool {
+		self.connected
+	}
+
+	async fn generate_orders(&self, count: usize) -> Result<()> {
+		// Orders go to the first users and products, enough of them to spread the revenue around
+		let user_ids: Vec<Uuid> = self.first_ids("users", GENERATION_CHUNK_SIZE).await?
+			.into_iter()
+			.map(parse_key)
+			.collect::<Result<_>>()?;
+		let product_ids: Vec<Uuid> = self.first_ids("products", GENERATION_CHUNK_SIZE).await?
+			.into_iter()
+			.map(parse_key)
+			.collect::<Result<_>>()?;
+		anyhow::ensure!(
+			!user_ids.is_empty() && !product_ids.is_empty(),
+			"No users or products found to order"
+		);
+
+		let bulk_insert = self.bulk_insert;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		let chunks = generate_order_chunks(
+			count,
+			self.context.payload(),
+			user_ids,
+			product_ids,
+			self.context.tenants(),
+			self.context.key_strategy()
+		);
+		for orders in chunks {
+			self.run_blocking(move |conn| {
+				match bulk_insert {
+					BulkInsertMode::Appender => {
+						let mut appender = conn.appender("orders")?;
+						for order in &orders {
+							appender.append_row(
+								params![
+									key_value(encoding, &order.id),
+									order.tenant_id,
+									key_value(encoding, &order.user_id),
+									key_value(encoding, &order.product_id),
+									order.quantity,
+									order.total_price,
+									timestamp_value(timestamps, &order.created_at)
+								]
+							)?;
+						}
+						appender.flush();
+					}
+					BulkInsertMode::Statements => {
+						let tx = conn.transaction()?;
+						for order in &orders {
+							tx.execute(
+								INSERT_ORDER_QUERY,
+								params![
+									key_value(encoding, &order.id),
+									order.tenant_id,
+									key_value(encoding, &order.user_id),
+									key_value(encoding, &order.product_id),
+									order.quantity,
+									order.total_price,
+									timestamp_value(timestamps, &order.created_at)
+								]
+							)?;
+						}
+						tx.commit()?;
+					}
+				}
+				Ok(())
+			}).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn run_analytics_query(&self, query: AnalyticsQuery) -> Result<usize> {
+		self.run_blocking(move |conn| {
+			let mut stmt = conn.prepare_cached(query.sql())?;
+			// Reading every row makes sure the whole result is computed
+			let rows = stmt.query_map([], |_| Ok(()))?.collect::<Result<Vec<_>, _>>()?;
+			Ok(rows.len())
+		}).await
+	}
+
+	async fn create_import_table(&self, columns: &[ImportColumn]) -> Result<()> {
+		let id_type = match self.key_encoding {
+			KeyEncoding::String => "VARCHAR",
+			KeyEncoding::Binary => "BLOB",
+		};
+		let query = import::create_table_query(id_type, columns, |column_type| match column_type {
+			ImportColumnType::Integer => "BIGINT",
+			ImportColumnType::Real => "DOUBLE",
+			ImportColumnType::Text => "VARCHAR",
+			ImportColumnType::Boolean => "BOOLEAN",
+		});
+		self.run_blocking(move |conn| {
+			conn.execute(DROP_IMPORT_TABLE_QUERY, [])?;
+			conn.execute(&query, [])?;
+			Ok(())
+		}).await
+	}
+
+	async fn insert_imported_records(&self, records: Arc<Vec<Record>>) -> Result<()> {
+		let Some(first) = records.first() else {
+			return Ok(());
+		};
+		let query = import::insert_query(first.values.len());
+		let context = self.context.clone();
+		let encoding = self.key_encoding;
+
+		self.run_blocking(move |conn| {
+			// All the records are written in a single transaction, like insert_wide_records
+			let tx = conn.transaction()?;
+			let mut stmt = tx.prepare(&query)?;
+
+			for record in records.iter() {
+				context.check_cancelled()?;
+				let params = std::iter::once(key_value(encoding, &record.id))
+					.chain(record.values.iter().map(imported_value));
+				stmt.execute(params_from_iter(params))?;
+				context.record_operation();
+			}
+			drop(stmt);
+
+			tx.commit()?;
+			Ok(())
+		}).await
+	}
+
+	async fn read_imported_records(&self, ids: Arc<Vec<Uuid>>) -> Result<()> {
+		let context = self.context.clone();
+		let encoding = self.key_encoding;
+
+		self.run_parallel(ids.len(), move |conn, offset, len| {
+			let mut stmt = conn.prepare_cached(READ_IMPORTED_RECORD_QUERY)?;
+
+			for id in &ids[offset..offset + len] {
+				context.check_cancelled()?;
+				// The id, then every column
+				let _record: Option<Vec<Value>> = stmt
+					.query_row([key_value(encoding, id)], |row| {
+						(0..row.as_ref().column_count()).map(|column| row.get::<_, Value>(column)).collect()
+					})
+					.optional()?;
+				context.record_operation();
+			}
+
+			Ok(())
+		}).await
+	}
+
+	async fn update_imported_records(
+		&self,
+		column: usize,
+		updates: Arc<Vec<(Uuid, Option<WideValue>)>>
+	) -> Result<()> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let query = import::update_query(column);
+
+		self.run_blocking(move |conn| {
+			let mut stmt = conn.prepare(&query)?;
+
+			for (id, value) in updates.iter() {
+				context.check_cancelled()?;
+				retry.run(&context, is_transient, || {
+					stmt.execute(params![imported_value(value), key_value(encoding, id)])
+				})?;
+				context.record_operation();
+			}
+
+			Ok(())
+		}).await
+	}
+
+	async fn drop_import_table(&self) -> Result<()> {
+		self.run_blocking(|conn| {
+			conn.execute(DROP_IMPORT_TABLE_QUERY, [])?;
+			Ok(())
+		}).await
+	}
+
+	fn written_record_bytes(&self, test: BenchmarkTest) -> Option<u64> {
+		let payload = self.context.payload();
+		match test {
+			| BenchmarkTest::InsertSingleManyTimes
+			| BenchmarkTest::ConcurrentInserts
+			| BenchmarkTest::UpdateMultipleFieldsOneEntry => Some(payload.user_record_bytes()),
+			| BenchmarkTest::InsertManyAtOnce
+			| BenchmarkTest::UpdateSingleFieldOneEntry
+			| BenchmarkTest::UpdateSingleFieldManyEntries => Some(payload.product_record_bytes()),
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => Some(ORDER_RECORD_BYTES),
+			// Orders have two items on average
+			BenchmarkTest::InsertOrdersWithItems => Some(ORDER_RECORD_BYTES + 2 * ORDER_ITEM_RECORD_BYTES),
+			BenchmarkTest::InsertWideRecords => Some(WIDE_RECORD_BYTES),
+			_ => None,
+		}
+	}
+
+	async fn checkpoint(&self) -> Result<()> {
+		// The runs don't write to a connected database, whose WAL is left to its owner
+		if self.connected {
+			return Ok(());
+		}
+
+		// Write the WAL into the database file
+		self.run_blocking(|conn| {
+			conn.execute_batch("CHECKPOINT")?;
+			Ok(())
+		}).await
+	}
+
+	fn database_name(&self) -> String {
+		"DuckDB".to_string()
+	}
+
+	async fn set_cpu_count(&mut self, count: usize) -> Result<()> {
+		debug!("Setting CPU count to {}", count);
+		// Update the thread count in DuckDB before any test runs with it; the setting is global
+		// to the database, so it also applies to the reader connections
+		self.run_blocking(move |conn| {
+			conn.execute(&format!("SET threads TO {}", count), [])?;
+			Ok(())
+		}).await?;
+		self.cpu_count = count;
+		Ok(())
+	}
+
+	fn get_cpu_count(&self) -> usize {
+		self.cpu_count
+	}
+
+	fn set_index_mode(&mut self, indexes: IndexMode) {
+		self.indexes = indexes;
+	}
+
+	fn set_key_encoding(&mut self, encoding: KeyEncoding) {
+		self.key_encoding = encoding;
+	}
+
+	fn set_timestamp_encoding(&mut self, encoding: TimestampEncoding) {
+		self.timestamp_encoding = encoding;
+	}
+
+	fn run_context(&self) -> &RunContext {
+		&self.context
+	}
+
+	async fn count_records(&self) -> Result<RecordCounts> {
+		self.run_blocking(|conn| {
+			let count = |table: &str| {
+				conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+			};
+
+			Ok(RecordCounts {
+				users: count("users")? as usize,
+				products: count("products")? as usize,
+				orders: count("orders")? as usize,
+				order_items: count("order_items")? as usize,
+				wide_records: count("wide_records")? as usize,
+			})
+		}).await
+	}
+
+	async fn verify_test(&self, test: BenchmarkTest, count: usize) -> Result<()> {
+		// Values written by the last iteration of the single entry update tests
+		let last = count.saturating_sub(1);
+		// Under a distribution, the entry updated last by update_single_field_one_entry is random
+		let fixed_access = self.context.distribution().is_none();
+		let adversarial = self.context.payload().text == TextMode::Adversarial;
+
+		self.run_blocking(move |conn| {
+			// Records the test updated that don't hold the values it wrote
+			let stale = match test {
+				BenchmarkTest::UpdateSingleFieldOneEntry if count > 0 && fixed_access => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM products WHERE id = (SELECT id FROM products LIMIT 1) AND stock != ?",
+						params![(last % 1000) as i32],
+						|row| row.get::<_, i64>(0)
+					)? as usize
+				}
+				BenchmarkTest::UpdateSingleFieldManyEntries => {
+					Self::count_stale_rows(conn, "products", "stock", count, |i| ((i as i32) % 1000) + 1)?
+				}
+				BenchmarkTest::UpdateMultipleFieldsOneEntry if count > 0 => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM users WHERE id = (SELECT id FROM users LIMIT 1) AND (name != ? OR email != ? OR active != ?)",
+						params![
+							format!("Updated User {}", last),
+							format!("updated{}@example.com", last),
+							last.is_multiple_of(2)
+						],
+						|row| row.get::<_, i64>(0)
+					)? as usize
+				}
+				BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+					Self::count_stale_rows(conn, "orders", "quantity", count, |i| ((i as i32) % 10) + 1)?
+				}
+				_ => 0,
+			};
+			anyhow::ensure!(stale == 0, "{} updated records don't hold the values written by the test", stale);
+
+			// Orders and order items referencing a record that no longer exists
+			let dangling: i64 = conn.query_row(
+				"SELECT
+					(SELECT COUNT(*) FROM orders o
+					WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = o.user_id)
+					OR NOT EXISTS (SELECT 1 FROM products p WHERE p.id = o.product_id))
+					+ (SELECT COUNT(*) FROM order_items i
+					WHERE NOT EXISTS (SELECT 1 FROM orders o WHERE o.id = i.order_id)
+					OR NOT EXISTS (SELECT 1 FROM products p WHERE p.id = i.product_id))",
+				[],
+				|row| row.get(0)
+			)?;
+			anyhow::ensure!(dangling == 0, "{} orders or order items reference a missing record", dangling);
+
+			// Ids are kept unique by their indexes
+			let duplicates: i64 = conn.query_row(
+				"SELECT (SELECT COUNT(*) - COUNT(DISTINCT id) FROM users)
+				+ (SELECT COUNT(*) - COUNT(DISTINCT id) FROM products)
+				+ (SELECT COUNT(*) - COUNT(DISTINCT id) FROM orders)
+				+ (SELECT COUNT(*) - COUNT(DISTINCT id) FROM order_items)
+				+ (SELECT COUNT(*) - COUNT(DISTINCT id) FROM wide_records)",
+				[],
+				|row| row.get(0)
+			)?;
+			anyhow::ensure!(duplicates == 0, "{} records share their id with another record", duplicates);
+
+			// Names not holding the adversarial text they were generated with, leaving out the
+			// users whose name the update tests rewrote
+			if adversarial {
+				let mut stmt = conn.prepare(
+					"SELECT id, name FROM users WHERE name NOT LIKE 'Updated User %'
+					UNION ALL SELECT id, name FROM products"
+				)?;
+				let names: Vec<(Value, String)> = stmt
+					.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+					.collect::<Result<Vec<_>, _>>()?;
+				let mut garbled = 0;
+				for (id, name) in names {
+					if !holds_text(&parse_key(id)?, &name) {
+						garbled += 1;
+					}
+				}
+				anyhow::ensure!(garbled == 0, "{} names don't read back as they were written", garbled);
+			}
+
+			Ok(())
+		}).await
+	}
+
+	async fn prepare(&self, test: BenchmarkTest, count: usize) -> Result<Fixture> {
+		let mut fixture = Fixture::default();
+		let payload = self.context.payload();
+		let tenants = self.context.tenants();
+		let key_strategy = self.context.key_strategy();
+
+		match test {
+			BenchmarkTest::InsertSingleManyTimes => {
+				fixture.users = (0..count).map(|_| generate_random_user(payload, tenants, key_strategy)).collect();
+			}
+			BenchmarkTest::ConcurrentInserts => {
+				fixture.users = (0..count).map(|_| generate_random_user(payload, tenants, key_strategy)).collect();
+				let writers = self.context.writers();
+				fixture.connections = self.run_blocking(move |conn| {
+					(0..writers)
+						.map(|_| Ok(Arc::new(Mutex::new(conn.try_clone()?))))
+						.collect::<Result<Vec<_>>>()
+				}).await?;
+			}
+			BenchmarkTest::InsertManyAtOnce => {
+				fixture.products = (0..count).map(|_| generate_random_product(payload, key_strategy)).collect();
+			}
+			BenchmarkTest::LongTransactionContention => {
+				let ids = self.first_ids("users", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found to contend for");
+				fixture.ids = Arc::new(ids);
+				let contenders = self.context.writers();
+				fixture.connections = self.run_blocking(move |conn| {
+					(0..contenders)
+						.map(|_| Ok(Arc::new(Mutex::new(conn.try_clone()?))))
+						.collect::<Result<Vec<_>>>()
+				}).await?;
+			}
+			BenchmarkTest::ConsistencyProbe => {
+				let ids = self.first_ids("products", 1).await?;
+				anyhow::ensure!(!ids.is_empty(), "No product found to count with");
+				fixture.ids = Arc::new(ids);
+				let writers = self.context.writers();
+				fixture.connections = self.run_blocking(move |conn| {
+					(0..writers)
+						.map(|_| Ok(Arc::new(Mutex::new(conn.try_clone()?))))
+						.collect::<Result<Vec<_>>>()
+				}).await?;
+			}
+			BenchmarkTest::ReadByIdManyTimes => {
+				let mut user_ids = self.first_ids("users", count).await?;
+
+				// If we don't have enough users, generate some test data
+				if user_ids.len() < 10 {
+					self.generate_test_data(100).await?;
+					user_ids = self.first_ids("users", count).await?;
+				}
+				if let Some(distribution) = self.context.distribution() {
+					user_ids = distribution::sample(distribution, &user_ids, count);
+				}
+				fixture.ids = Arc::new(user_ids);
+			}
+			BenchmarkTest::ReadManyByIds => {
+				let mut user_ids = self.first_ids("users", count).await?;
+
+				// If we don't have enough users, generate some test data
+				if user_ids.len() < 100 {
+					self.generate_test_data(500).await?;
+					user_ids = self.first_ids("users", count).await?;
+				}
+
+				// Create batches of IDs, each bound as a single parameter
+				let batch_size = 200;
+				let encoding = self.key_encoding;
+				let batches: Vec<String> = user_ids
+					.chunks(batch_size)
+					.map(|batch| id_batch(encoding, batch))
+					.collect::<Result<_>>()?;
+
+				// Make sure the ids are looked up with a hash semi-join rather than repeated scans
+				if let Some(batch) = batches.first().cloned() {
+					let query = self.read_many_by_ids_query();
+					let plan = self.run_blocking(move |conn| {
+						Self::query_plan(conn, query, [batch])
+					}).await?;
+					if !plan.contains("HASH_JOIN") {
+						return Err(anyhow::anyhow!("Unexpected plan for read_many_by_ids:\n{}", plan));
+					}
+				}
+				fixture.terms = Arc::new(batches);
+			}
+			BenchmarkTest::ReadWithOneJoin => {
+				fixture.ids = Arc::new(self.first_ids("products", 100).await?);
+			}
+			BenchmarkTest::ReadWithTwoJoins => {
+				fixture.ids = Arc::new(self.first_ids("orders", 100).await?);
+			}
+			BenchmarkTest::UpdateSingleFieldOneEntry => {
+				// Under a distribution, each update goes to an entry drawn from the first `count` products
+				let distribution = self.context.distribution();
+				let limit = if distribution.is_some() { count } else { 1 };
+				let mut ids = self.first_ids("products", limit).await?;
+				anyhow::ensure!(!ids.is_empty(), "No products found for update");
+				if let Some(distribution) = distribution {
+					ids = distribution::sample(distribution, &ids, count);
+				}
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+				let ids = self.first_ids("users", 1).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found for update");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::UpdateSingleFieldManyEntries => {
+				fixture.ids = Arc::new(self.first_ids("products", count).await?);
+			}
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+				fixture.ids = Arc::new(self.first_ids("orders", count).await?);
+			}
+			BenchmarkTest::InsertOrdersWithItems => {
+				let user_ids: Vec<Uuid> = self.first_ids("users", GENERATION_CHUNK_SIZE).await?
+					.into_iter()
+					.map(parse_key)
+					.collect::<Result<_>>()?;
+				let product_ids: Vec<Uuid> = self.first_ids("products", GENERATION_CHUNK_SIZE).await?
+					.into_iter()
+					.map(parse_key)
+					.collect::<Result<_>>()?;
+				anyhow::ensure!(
+					!user_ids.is_empty() && !product_ids.is_empty(),
+					"No users or products found to order"
+				);
+				fixture.orders = generate_orders_with_items(
+					count,
+					payload,
+					&user_ids,
+					&product_ids,
+					tenants,
+					key_strategy
+				);
+			}
+			BenchmarkTest::ReadOrdersWithItems => {
+				let ids = self.first_ids("orders", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No orders found to read");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::InsertWideRecords => {
+				fixture.wide_records = (0..count).map(|_| generate_wide_record(key_strategy)).collect();
+			}
+			BenchmarkTest::ReadWideRecordsById => {
+				let ids = self.first_ids("wide_records", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No wide records found to read");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::SoftDeleteUsers => {
+				let ids = self.run_blocking(move |conn| {
+					let mut stmt = conn.prepare(LIVE_USER_IDS_QUERY)?;
+					let ids: Vec<Value> = stmt
+						.query_map([count as i64], |row| row.get(0))?
+						.collect::<Result<Vec<_>, _>>()
+						.map_err(|e| anyhow::anyhow!(e))?;
+					Ok(ids)
+				}).await?;
+				anyhow::ensure!(!ids.is_empty(), "No live users found to soft-delete");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::ReadLiveUsers => {
+				// Soft-delete users until half of them are
+				let deleted_at = timestamp_value(self.timestamp_encoding, &Utc::now());
+				self.run_blocking(move |conn| {
+					let (users, deleted): (i64, i64) = conn.query_row(
+						"SELECT COUNT(*), COUNT(deleted_at) FROM users",
+						[],
+						|row| Ok((row.get(0)?, row.get(1)?))
+					)?;
+					let soft_deleted = users_to_soft_delete(users as usize, deleted as usize);
+					conn.execute(SOFT_DELETE_USERS_QUERY, params![deleted_at, soft_deleted as i64])?;
+					Ok(())
+				}).await?;
+			}
+			// These tests select their rows in the statements they time
+			| BenchmarkTest::ReadByColumnSearch
+			| BenchmarkTest::ReadWideColumnSubset
+			| BenchmarkTest::ReadTenantOrders
+			| BenchmarkTest::AggregateOrdersByTenant
+			| BenchmarkTest::ReadUsersNotDeleted
+			| BenchmarkTest::ReadDiscountedProducts
+			| BenchmarkTest::ReadProjection => {}
+		}
+
+		Ok(fixture)
+	}
+
+	fn traced_fixture(&self, step: TraceOp, chunk: &TraceChunk) -> Result<(BenchmarkTest, Fixture)> {
+		let mut fixture = Fixture::default();
+		let ids: Vec<Value> = chunk.keys.iter().map(|id| key_value(self.key_encoding, id)).collect();
+
+		// Updates of products don't apply to the traced users, so they update users instead
+		let test = match step {
+			TraceOp::Load | TraceOp::Insert => {
+				fixture.users = chunk.users.clone();
+				BenchmarkTest::InsertSingleManyTimes
+			}
+			TraceOp::Scan => {
+				fixture.terms = Arc::new(
+					ids.chunks(200).map(|batch| id_batch(self.key_encoding, batch)).collect::<Result<_>>()?
+				);
+				BenchmarkTest::ReadManyByIds
+			}
+			TraceOp::Update | TraceOp::ReadModifyWrite => {
+				fixture.ids = Arc::new(ids);
+				BenchmarkTest::UpdateMultipleFieldsOneEntry
+			}
+			TraceOp::Read => {
+				fixture.ids = Arc::new(ids);
+				BenchmarkTest::ReadByIdManyTimes
+			}
+		};
+		Ok((test, fixture))
+	}
+
+	async fn insert_single_many_times(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Inserting {} users", count);
+		let users = fixture.users;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::InsertSingleManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				let conn = self.conn.clone();
+				let context = self.context.clone();
+				let encoding = self.key_encoding;
+				let timestamps = self.timestamp_encoding;
+
+				tokio::task::spawn_blocking(move || {
+					let mut conn = conn.blocking_lock();
+					let tx = conn.transaction()?;
+
+					for user in &users {
+						context.check_cancelled()?;
+						tx.execute(
+							INSERT_USER_QUERY,
+							params![
+								key_value(encoding, &user.id),
+								user.tenant_id,
+								user.name,
+								user.email,
+								timestamp_value(timestamps, &user.created_at),
+								user.active,
+								user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+							]
+						)?;
+						context.record_operation();
+					}
+
+					tx.commit()?;
+					Ok(())
+				}).await?
+			}
+		).await
+	}
+
+	async fn insert_many_at_once(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Inserting {} products", count);
+		// The statements path is reported under its own name so both paths can be compared
+		let test_name = match self.bulk_insert {
+			BulkInsertMode::Appender => BenchmarkTest::InsertManyAtOnce.name().to_string(),
+			BulkInsertMode::Statements => format!("{}_statements", BenchmarkTest::InsertManyAtOnce.name()),
+		};
+		let bulk_insert = self.bulk_insert;
+		let batch_len = self.context.batch_len(count);
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		let products = fixture.products;
+
+		measure_execution(
+			&self.database_name(),
+			&test_name,
+			count,
+			self.cpu_count,
+			|| async {
+				let conn = self.conn.clone();
+				let context = self.context.clone();
+				tokio::task::spawn_blocking(move || {
+					let mut conn = conn.blocking_lock();
+
+					match bulk_insert {
+						BulkInsertMode::Appender => {
+							let mut appender = conn.appender("products")?;
+
+							for batch in products.chunks(batch_len) {
+								let start = Instant::now();
+								for product in batch {
+									context.check_cancelled()?;
+									appender.append_row(
+										params![
+											key_value(encoding, &product.id),
+											product.name,
+											product.description,
+											product.price,
+											product.discount,
+											product.stock,
+											timestamp_value(timestamps, &product.created_at)
+										]
+									)?;
+								}
+
+								// Write each batch of buffered rows, the last one before the timer stops
+								appender.flush();
+								context.record_batch(batch.len(), start.elapsed());
+							}
+						}
+						BulkInsertMode::Statements => {
+							// Each batch of products is inserted in its own transaction
+							for batch in products.chunks(batch_len) {
+								let start = Instant::now();
+								let tx = conn.transaction()?;
+
+								// Prepare the statement outside the loop for better performance
+								let mut stmt = tx.prepare(INSERT_PRODUCT_QUERY)?;
+
+								for product in batch {
+									context.check_cancelled()?;
+									stmt.execute(
+										params![
+											key_value(encoding, &product.id),
+											product.name,
+											product.description,
+											product.price,
+											product.discount,
+											product.stock,
+											timestamp_value(timestamps, &product.created_at)
+										]
+									)?;
+								}
+								drop(stmt);
+
+								tx.commit()?;
+								context.record_batch(batch.len(), start.elapsed());
+							}
+						}
+					}
+
+					Ok(())
+				}).await?
+			}
+		).await
+	}
+
+	async fn read_by_id_many_times(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} users", count);
+		let user_ids = fixture.ids;
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadByIdManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_BY_ID_QUERY)?;
+
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						// Cycle through the available IDs
+						let user_id = &user_ids[i % user_ids.len()];
+
+						// Query the user
+						let user = stmt.query_row([user_id], raw_row)?;
+						codec.decode([user], User::try_from)?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_many_by_ids(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let query = self.read_many_by_ids_query();
+		let batches = fixture.terms;
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadManyByIds.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader queries its own share of the batches
+				self.run_parallel(batches.len(), move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(query)?;
+
+					for batch in &batches[offset..offset + len] {
+						context.check_cancelled()?;
+						let start = Instant::now();
+						let users = stmt
+							.query_map([batch], raw_row)?
+							.collect::<Result<Vec<_>, _>>()?;
+						let count = users.len();
+						codec.decode(users, User::try_from)?;
+						context.record_batch(count, start.elapsed());
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_by_column_search(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+		let pattern = search_pattern(self.context.selectivity());
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadByColumnSearch.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader scans its own page of the matching users
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare(READ_BY_COLUMN_QUERY)?;
+
+					let users = stmt
+						.query_map(params![pattern, len as i64, offset as i64], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = users.len() as u64;
+					codec.decode(users, User::try_from)?;
+					context.record_operations(count);
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_with_one_join(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let product_ids = fixture.ids;
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWithOneJoin.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				if product_ids.is_empty() {
+					return Ok(()); // No products to query
+				}
+
+				let queries = count / product_ids.len() + 1;
+				self.run_parallel(queries, move |conn, offset, len| {
+					// Query with join between orders and products
+					let mut stmt = conn.prepare(ONE_JOIN_QUERY)?;
+
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let product_id = &product_ids[i % product_ids.len()];
+						let results = stmt
+							.query_map([product_id], raw_row)?
+							.collect::<Result<Vec<_>, _>>()?;
+						codec.decode(results, |Row(mut values)| {
+							// The order, then its product
+							let product = Product::try_from(Row(values.split_off(5)))?;
+							let order = Row(values);
+							Ok((
+								order.key(0)?,
+								order.key(1)?,
+								order.integer::<i32>(2)?,
+								order.real(3)?,
+								order.timestamp(4)?,
+								product,
+							))
+						})?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_with_two_joins(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let order_ids = fixture.ids;
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWithTwoJoins.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				if order_ids.is_empty() {
+					return Ok(()); // No orders to query
+				}
+
+				let queries = count / order_ids.len() + 1;
+				self.run_parallel(queries, move |conn, offset, len| {
+					// Query with two joins: orders → users and orders → products
+					let mut stmt = conn.prepare(TWO_JOINS_QUERY)?;
+
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let order_id = &order_ids[i % order_ids.len()];
+						let results = stmt
+							.query_map([order_id], raw_row)?
+							.collect::<Result<Vec<_>, _>>()?;
+						codec.decode(results, OrderWithDetails::try_from)?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn update_single_field_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let product_ids = fixture.ids;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateSingleFieldOneEntry.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				let conn = self.conn.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+				let retry = self.retry;
+				let product_ids = product_ids.clone();
+
+				tokio::task::spawn_blocking(move || {
+					let conn = conn.blocking_lock();
+					let conn = CachingConnection { conn: &conn, statements: &statements };
+
+					for i in 0..count {
+						context.check_cancelled()?;
+						// Update the same product many times, unless drawn from a distribution, changing its stock
+						let product_id = &product_ids[i % product_ids.len()];
+						let new_stock = (i as i32) % 1000;
 
-					for _ in 0..count {
-						let user = generate_random_user();
-						tx.execute(
-							"INSERT INTO users (id, name, email, created_at, active) VALUES (?, ?, ?, ?, ?)",
-							params![
-								user.id.to_string(),
-								user.name,
-								user.email,
-								user.created_at.to_rfc3339(),
-								user.active
-							]
-						)?;
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(UPDATE_PRODUCT_STOCK_QUERY)?.execute(
+								params![new_stock, product_id]
+							)
+						})?;
+						context.record_operation();
 					}
 
-					tx.commit()?;
 					Ok(())
 				}).await?
 			}
 		).await
 	}
 
-	async fn insert_many_at_once(&self, count: usize) -> Result<BenchmarkResult> {
-		println!("Inserting {} products", count);
+	async fn update_single_field_many_entries(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let products = fixture.ids;
+		let batch_len = self.context.batch_len(count);
+
 		measure_execution(
 			&self.database_name(),
-			"insert_many_at_once",
+			BenchmarkTest::UpdateSingleFieldManyEntries.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				// Generate products for insertion
-				let products: Vec<Product> = (0..count)
-					.map(|_| generate_random_product())
-					.collect();
-
-				let products_clone = products.clone();
-
 				let conn = self.conn.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+
 				tokio::task::spawn_blocking(move || {
 					let mut conn = conn.blocking_lock();
 
-					// Start a transaction
-					let tx = conn.transaction()?;
+					if products.is_empty() {
+						return Ok(()); // No products to update
+					}
 
-					// Prepare the statement outside the loop for better performance
-					let mut stmt = tx.prepare(
-						"INSERT INTO products (id, name, description, price, stock, created_at) VALUES (?, ?, ?, ?, ?, ?)"
-					)?;
+					// Each batch of products is updated in its own transaction
+					for (batch_index, batch) in products.chunks(batch_len).enumerate() {
+						let tx = conn.transaction()?;
+						let cached = CachingConnection { conn: &tx, statements: &statements };
 
-					// Insert all products
-					for product in &products_clone {
-						stmt.execute(
-							params![
-								product.id.to_string(),
-								product.name,
-								product.description,
-								product.price,
-								product.stock,
-								product.created_at.to_rfc3339()
-							]
-						)?;
-					}
+						for (j, product_id) in batch.iter().enumerate() {
+							context.check_cancelled()?;
+							// Update each product's stock
+							let i = batch_index * batch_len + j;
+							let new_stock = ((i as i32) % 1000) + 1;
 
-					// Commit the transaction
-					tx.commit()?;
+							cached.prepare_cached(UPDATE_PRODUCT_STOCK_QUERY)?.execute(
+								params![new_stock, product_id]
+							)?;
+						}
+
+						tx.commit()?;
+						context.record_operations(batch.len() as u64);
+					}
 					Ok(())
 				}).await?
 			}
 		).await
 	}
 
-	async fn read_by_id_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		println!("Reading {} users", count);
-		// First, get a list of user IDs to query
-		let user_ids = self.run_blocking(move |conn| {
-			let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-			let user_ids: Vec<String> = stmt
-				.query_map([count as i64], |row| row.get(0))?
-				.collect::<Result<Vec<_>, _>>()
-				.map_err(|e| anyhow::anyhow!(e))?;
-			Ok(user_ids)
-		}).await?;
-
-		// If we don't have enough users, generate some test data
-		if user_ids.len() < 10 {
-			self.generate_test_data(100).await?;
-		}
-
-		// Get the user_ids again if needed
-		let user_ids = if user_ids.is_empty() {
-			self.run_blocking(move |conn| {
-				let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-				let user_ids: Vec<String> = stmt
-					.query_map([count as i64], |row| row.get(0))?
-					.collect::<Result<Vec<_>, _>>()
-					.map_err(|e| anyhow::anyhow!(e))?;
-				Ok(user_ids)
-			}).await?
-		} else {
-			user_ids
-		};
+	async fn update_multiple_fields_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		// A single user, unless a trace gives the users its updates go to
+		let user_ids = fixture.ids;
 
 		measure_execution(
 			&self.database_name(),
-			"read_by_id_many_times",
+			BenchmarkTest::UpdateMultipleFieldsOneEntry.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				let conn = self.conn.clone();
-				let user_ids = user_ids.clone(); // Clone for the closure
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+				let retry = self.retry;
+				let user_ids = user_ids.clone();
 
 				tokio::task::spawn_blocking(move || {
 					let conn = conn.blocking_lock();
-					let mut stmt = conn.prepare("SELECT * FROM users WHERE id = ?")?;
+					let conn = CachingConnection { conn: &conn, statements: &statements };
 
 					for i in 0..count {
-						// Cycle through the available IDs
+						context.check_cancelled()?;
+						// Update multiple fields of the same user
 						let user_id = &user_ids[i % user_ids.len()];
+						let new_name = format!("Updated User {}", i);
+						let new_email = format!("updated{}@example.com", i);
+						let new_active = i % 2 == 0;
 
-						// Query the user
-						let _user = stmt.query_row([user_id], |row| {
-							Ok(User {
-								id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-								name: row.get(1)?,
-								email: row.get(2)?,
-								created_at: chrono::DateTime
-									::parse_from_rfc3339(&row.get::<_, String>(3)?)
-									.unwrap()
-									.with_timezone(&chrono::Utc),
-								active: row.get(4)?,
-							})
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(UPDATE_USER_QUERY)?.execute(
+								params![new_name, new_email, new_active, user_id]
+							)
 						})?;
+						context.record_operation();
 					}
 
 					Ok(())
@@ -396,511 +2246,427 @@ impl DatabaseBenchmark for DuckdbBenchmark {
 		).await
 	}
 
-	async fn read_many_by_ids(&self, count: usize) -> Result<BenchmarkResult> {
-		// First, get a batch of user IDs
-		let mut user_ids = self.run_blocking(move |conn| {
-			let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-			let user_ids: Vec<String> = stmt
-				.query_map([count as i64], |row| row.get(0))?
-				.collect::<Result<Vec<_>, _>>()
-				.map_err(|e| anyhow::anyhow!(e))?;
-			Ok(user_ids)
-		}).await?;
-
-		// If we don't have enough users, generate some test data
-		if user_ids.len() < 100 {
-			self.generate_test_data(500).await?;
-
-			// Get user_ids again
-			user_ids = self.run_blocking(move |conn| {
-				let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-				let user_ids: Vec<String> = stmt
-					.query_map([count as i64], |row| row.get(0))?
-					.collect::<Result<Vec<_>, _>>()
-					.map_err(|e| anyhow::anyhow!(e))?;
-				Ok(user_ids)
-			}).await?;
-		}
-
-		// Create batches of IDs
-		let batch_size = 200;
-		let num_batches = (count + batch_size - 1) / batch_size; // Ceiling division
-		let batches: Vec<Vec<String>> = (0..num_batches)
-			.map(|i| {
-				let start = i * batch_size;
-				let end = std::cmp::min(start + batch_size, user_ids.len());
-				user_ids[start..end].to_vec()
-			})
-			.collect();
+	async fn update_multiple_fields_many_entries(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let orders = fixture.ids;
+		let batch_len = self.context.batch_len(count);
 
 		measure_execution(
 			&self.database_name(),
-			"read_many_by_ids",
+			BenchmarkTest::UpdateMultipleFieldsManyEntries.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				let conn = self.conn.clone();
-				let batches = batches.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
 
 				tokio::task::spawn_blocking(move || {
-					let conn = conn.blocking_lock();
+					let mut conn = conn.blocking_lock();
 
-					for batch in batches {
-						// Create placeholders for the IN clause
-						let placeholders = batch
-							.iter()
-							.enumerate()
-							.map(|(i, _)| format!("?{}", i + 1))
-							.collect::<Vec<String>>()
-							.join(",");
-
-						let query =
-							format!("SELECT * FROM users WHERE id IN ({})", placeholders);
-
-						// Convert batch to params format
-						let param_values: Vec<_> = batch
-							.iter()
-							.map(|id| id.as_str())
-							.collect();
-
-						// Execute the query using named parameters
-						let mut stmt = conn.prepare(&query)?;
-						let _users = stmt
-							.query_map(
-								duckdb::params_from_iter(param_values.iter()),
-								|row| {
-									Ok(User {
-										id: Uuid::parse_str(
-											&row.get::<_, String>(0)?
-										).unwrap(),
-										name: row.get(1)?,
-										email: row.get(2)?,
-										created_at: chrono::DateTime
-											::parse_from_rfc3339(
-												&row.get::<_, String>(3)?
-											)
-											.unwrap()
-											.with_timezone(&chrono::Utc),
-										active: row.get(4)?,
-									})
-								}
-							)?
-							.collect::<Result<Vec<_>, _>>()?;
+					if orders.is_empty() {
+						return Ok(()); // No orders to update
 					}
 
+					// Each batch of orders is updated in its own transaction
+					for (batch_index, batch) in orders.chunks(batch_len).enumerate() {
+						let tx = conn.transaction()?;
+						let cached = CachingConnection { conn: &tx, statements: &statements };
+
+						for (j, order_id) in batch.iter().enumerate() {
+							context.check_cancelled()?;
+							// Update quantity and total_price
+							let i = batch_index * batch_len + j;
+							let new_quantity = ((i as i32) % 10) + 1;
+							let new_total_price =
+								(new_quantity as f64) * 9.99 + ((i as f64) % 10.0);
+
+							cached.prepare_cached(UPDATE_ORDER_QUERY)?.execute(
+								params![new_quantity, new_total_price, order_id]
+							)?;
+						}
+
+						tx.commit()?;
+						context.record_operations(batch.len() as u64);
+					}
 					Ok(())
 				}).await?
 			}
 		).await
 	}
 
-	async fn read_by_column_search(&self, count: usize) -> Result<BenchmarkResult> {
+	async fn insert_orders_with_items(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Inserting {} orders with their items", count);
+		let orders = fixture.orders;
+
 		measure_execution(
 			&self.database_name(),
-			"read_by_column_search",
+			BenchmarkTest::InsertOrdersWithItems.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				let conn = self.conn.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+				let retry = self.retry;
+				let encoding = self.key_encoding;
+				let timestamps = self.timestamp_encoding;
 
 				tokio::task::spawn_blocking(move || {
-					let conn = conn.blocking_lock();
+					let mut conn = conn.blocking_lock();
 
-					// Get a list of different email domains to search for
-					let mut stmt = conn.prepare(
-						"SELECT DISTINCT substring(email FROM position('@' IN email) + 1) as domain FROM users LIMIT 50"
-					)?;
-					let domains: Vec<String> = stmt
-						.query_map([], |row| row.get(0))?
-						.collect::<Result<Vec<_>, _>>()
-						.map_err(|e| anyhow::anyhow!(e))?;
+					// Each order is written with its items in its own transaction; a conflict
+					// aborts the transaction, so it is retried as a whole
+					for order in &orders {
+						context.check_cancelled()?;
+						retry.run(&context, is_transient, || {
+							let tx = conn.transaction()?;
+							let cached = CachingConnection { conn: &tx, statements: &statements };
+
+							cached.prepare_cached(INSERT_ORDER_QUERY)?.execute(
+								params![
+									key_value(encoding, &order.order.id),
+									order.order.tenant_id,
+									key_value(encoding, &order.order.user_id),
+									key_value(encoding, &order.order.product_id),
+									order.order.quantity,
+									order.order.total_price,
+									timestamp_value(timestamps, &order.order.created_at)
+								]
+							)?;
+							let mut stmt = cached.prepare_cached(INSERT_ORDER_ITEM_QUERY)?;
+							for item in &order.items {
+								stmt.execute(
+									params![
+										key_value(encoding, &item.id),
+										key_value(encoding, &item.order_id),
+										key_value(encoding, &item.product_id),
+										item.quantity,
+										item.unit_price
+									]
+								)?;
+							}
+							drop(stmt);
 
-					if domains.is_empty() {
-						return Ok(()); // No data to search
+							tx.commit()
+						})?;
+						context.record_operation();
 					}
+					Ok(())
+				}).await?
+			}
+		).await
+	}
 
-					// Perform searches for each domain
-					let mut stmt = conn.prepare(
-						"SELECT * FROM users WHERE email LIKE ?"
-					)?;
+	async fn read_orders_with_items(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} orders with their items", count);
+		let order_ids = fixture.ids;
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
 
-					let iterations = count / domains.len() + 1; // Ensure we run enough iterations
-
-					for _ in 0..iterations {
-						for domain in &domains {
-							let pattern = format!("%@{}", domain);
-							let _users = stmt
-								.query_map([pattern], |row| {
-									Ok(User {
-										id: Uuid::parse_str(
-											&row.get::<_, String>(0)?
-										).unwrap(),
-										name: row.get(1)?,
-										email: row.get(2)?,
-										created_at: chrono::DateTime
-											::parse_from_rfc3339(
-												&row.get::<_, String>(3)?
-											)
-											.unwrap()
-											.with_timezone(&chrono::Utc),
-										active: row.get(4)?,
-									})
-								})?
-								.collect::<Result<Vec<_>, _>>()?;
-
-							if count <= iterations * domains.len() {
-								break;
-							}
-						}
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadOrdersWithItems.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_ORDER_WITH_ITEMS_QUERY)?;
+
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let order_id = &order_ids[i % order_ids.len()];
+
+						let items = stmt
+							.query_map([order_id], raw_row)?
+							.collect::<Result<Vec<_>, _>>()?;
+						codec.decode(items, |row| {
+							Ok((
+								// Order data
+								row.key(0)?,
+								row.key(1)?,
+								row.real(2)?,
+								row.timestamp(3)?,
+								// Item data
+								row.key(4)?,
+								row.key(5)?,
+								row.integer::<i32>(6)?,
+								row.real(7)?,
+								row.text(8)?,
+							))
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
-				}).await?
+				}).await
 			}
 		).await
 	}
 
-	async fn read_with_one_join(&self, count: usize) -> Result<BenchmarkResult> {
+	async fn insert_wide_records(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Inserting {} wide records", count);
+		let records = fixture.wide_records;
+
 		measure_execution(
 			&self.database_name(),
-			"read_with_one_join",
+			BenchmarkTest::InsertWideRecords.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				let conn = self.conn.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+				let encoding = self.key_encoding;
 
 				tokio::task::spawn_blocking(move || {
-					let conn = conn.blocking_lock();
-
-					// Get a batch of product IDs to query
-					let mut stmt = conn.prepare("SELECT id FROM products LIMIT 100")?;
-					let product_ids: Vec<String> = stmt
-						.query_map([], |row| row.get(0))?
-						.collect::<Result<Vec<_>, _>>()
-						.map_err(|e| anyhow::anyhow!(e))?;
+					let mut conn = conn.blocking_lock();
 
-					if product_ids.is_empty() {
-						return Ok(()); // No products to query
-					}
+					// All the records are written in a single transaction, like in SQLite
+					let tx = conn.transaction()?;
+					let cached = CachingConnection { conn: &tx, statements: &statements };
+					let mut stmt = cached.prepare_cached(INSERT_WIDE_RECORD_QUERY.as_str())?;
 
-					// Query with join between orders and products
-					let query =
-						"
-                        SELECT o.*, p.name, p.description, p.price, p.stock, p.created_at 
-                        FROM orders o
-                        JOIN products p ON o.product_id = p.id
-                        WHERE o.product_id = ?
-                        LIMIT 100
-                    ";
-
-					let mut stmt = conn.prepare(query)?;
-
-					let iterations = count / product_ids.len() + 1;
-
-					for _ in 0..iterations {
-						for product_id in &product_ids {
-							let _results = stmt
-								.query_map([product_id], |row| {
-									let order_id = Uuid::parse_str(
-										&row.get::<_, String>(0)?
-									).unwrap();
-									let user_id = Uuid::parse_str(
-										&row.get::<_, String>(1)?
-									).unwrap();
-									let product_id = Uuid::parse_str(
-										&row.get::<_, String>(2)?
-									).unwrap();
-									let quantity: i32 = row.get(3)?;
-									let total_price: f64 = row.get(4)?;
-									let order_created_at = chrono::DateTime
-										::parse_from_rfc3339(&row.get::<_, String>(5)?)
-										.unwrap()
-										.with_timezone(&chrono::Utc);
-
-									let product_name: String = row.get(6)?;
-									let product_description: String = row.get(7)?;
-									let product_price: f64 = row.get(8)?;
-									let product_stock: i32 = row.get(9)?;
-									let product_created_at = chrono::DateTime
-										::parse_from_rfc3339(&row.get::<_, String>(10)?)
-										.unwrap()
-										.with_timezone(&chrono::Utc);
-
-									let product = Product {
-										id: product_id,
-										name: product_name,
-										description: product_description,
-										price: product_price,
-										stock: product_stock,
-										created_at: product_created_at,
-									};
-
-									Ok((
-										order_id,
-										user_id,
-										quantity,
-										total_price,
-										order_created_at,
-										product,
-									))
-								})?
-								.collect::<Result<Vec<_>, _>>()?;
-
-							if count <= iterations * product_ids.len() {
-								break;
-							}
-						}
+					for record in &records {
+						context.check_cancelled()?;
+						stmt.execute(params_from_iter(wide_record_values(encoding, record)))?;
+						context.record_operation();
 					}
+					drop(stmt);
 
+					tx.commit()?;
 					Ok(())
 				}).await?
 			}
 		).await
 	}
 
-	async fn read_with_two_joins(&self, count: usize) -> Result<BenchmarkResult> {
+	async fn read_wide_records_by_id(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} wide records", count);
+		let record_ids = fixture.ids;
+		let context = self.context.clone();
+
 		measure_execution(
 			&self.database_name(),
-			"read_with_two_joins",
+			BenchmarkTest::ReadWideRecordsById.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let conn = self.conn.clone();
-
-				tokio::task::spawn_blocking(move || {
-					let conn = conn.blocking_lock();
-
-					// Get a batch of order IDs to query
-					let mut stmt = conn.prepare("SELECT id FROM orders LIMIT 100")?;
-					let order_ids: Vec<String> = stmt
-						.query_map([], |row| row.get(0))?
-						.collect::<Result<Vec<_>, _>>()
-						.map_err(|e| anyhow::anyhow!(e))?;
+				self.run_parallel(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_WIDE_RECORD_QUERY)?;
 
-					if order_ids.is_empty() {
-						return Ok(()); // No orders to query
-					}
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let record_id = &record_ids[i % record_ids.len()];
 
-					// Query with two joins: orders → users and orders → products
-					let query =
-						"
-                        SELECT 
-                            o.id, o.quantity, o.total_price, o.created_at,
-                            u.id, u.name, u.email, u.created_at, u.active,
-                            p.id, p.name, p.description, p.price, p.stock, p.created_at
-                        FROM orders o
-                        JOIN users u ON o.user_id = u.id
-                        JOIN products p ON o.product_id = p.id
-                        WHERE o.id = ?
-                    ";
-
-					let mut stmt = conn.prepare(query)?;
-
-					let iterations = count / order_ids.len() + 1;
-
-					for _ in 0..iterations {
-						for order_id in &order_ids {
-							let _results = stmt
-								.query_map([order_id], |row| {
-									let order_id = Uuid::parse_str(
-										&row.get::<_, String>(0)?
-									).unwrap();
-									let quantity: i32 = row.get(1)?;
-									let total_price: f64 = row.get(2)?;
-									let order_created_at = chrono::DateTime
-										::parse_from_rfc3339(&row.get::<_, String>(3)?)
-										.unwrap()
-										.with_timezone(&chrono::Utc);
-
-									let user_id = Uuid::parse_str(
-										&row.get::<_, String>(4)?
-									).unwrap();
-									let user_name: String = row.get(5)?;
-									let user_email: String = row.get(6)?;
-									let user_created_at = chrono::DateTime
-										::parse_from_rfc3339(&row.get::<_, String>(7)?)
-										.unwrap()
-										.with_timezone(&chrono::Utc);
-									let user_active: bool = row.get(8)?;
-
-									let product_id = Uuid::parse_str(
-										&row.get::<_, String>(9)?
-									).unwrap();
-									let product_name: String = row.get(10)?;
-									let product_description: String = row.get(11)?;
-									let product_price: f64 = row.get(12)?;
-									let product_stock: i32 = row.get(13)?;
-									let product_created_at = chrono::DateTime
-										::parse_from_rfc3339(&row.get::<_, String>(14)?)
-										.unwrap()
-										.with_timezone(&chrono::Utc);
-
-									let user = User {
-										id: user_id,
-										name: user_name,
-										email: user_email,
-										created_at: user_created_at,
-										active: user_active,
-									};
-
-									let product = Product {
-										id: product_id,
-										name: product_name,
-										description: product_description,
-										price: product_price,
-										stock: product_stock,
-										created_at: product_created_at,
-									};
-
-									let order_with_details = OrderWithDetails {
-										id: order_id,
-										quantity,
-										total_price,
-										created_at: order_created_at,
-										user,
-										product,
-									};
-
-									Ok(order_with_details)
-								})?
-								.collect::<Result<Vec<_>, _>>()?;
-
-							if count <= iterations * order_ids.len() {
-								break;
-							}
-						}
+						// The id, then every column
+						let _record: Vec<Value> = stmt.query_row([record_id], |row| {
+							(0..=WIDE_COLUMN_COUNT).map(|column| row.get::<_, Value>(column)).collect()
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
-				}).await?
+				}).await
 			}
 		).await
 	}
 
-	async fn update_single_field_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		// First, get a product ID to update
-		let product_id = self.run_blocking(|conn| {
-			let id = conn
-				.query_row("SELECT id FROM products LIMIT 1", [], |row|
-					row.get::<_, String>(0)
-				)
-				.map_err(|e| anyhow::anyhow!(e))?;
-			Ok(id)
-		}).await?;
+	async fn read_wide_column_subset(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} rows of a few wide record columns", count);
+		let context = self.context.clone();
 
 		measure_execution(
 			&self.database_name(),
-			"update_single_field_one_entry",
+			BenchmarkTest::ReadWideColumnSubset.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let conn = self.conn.clone();
-				let product_id = product_id.clone();
+				// Each reader scans its own page of the table
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare_cached(READ_WIDE_COLUMN_SUBSET_QUERY.as_str())?;
+
+					let _rows: Vec<_> = stmt
+						.query_map(params![len as i64, offset as i64], |row| {
+							Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
+						})?
+						.collect::<Result<Vec<_>, _>>()?;
+					context.record_operations(_rows.len() as u64);
 
-				tokio::task::spawn_blocking(move || {
-					let conn = conn.blocking_lock();
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					for i in 0..count {
-						// Update the same product many times, changing its stock
-						let new_stock = (i as i32) % 1000;
+	async fn read_tenant_orders(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} pages of the orders of a tenant", count);
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+		let tenants = self.context.tenants();
 
-						conn.execute(
-							"UPDATE products SET stock = ? WHERE id = ?",
-							params![new_stock, product_id]
-						)?;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadTenantOrders.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_TENANT_ORDERS_QUERY)?;
+
+					// Each query reads a page of the orders of the next tenant
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let tenant_id = (i % tenants) as u32;
+
+						let orders = stmt
+							.query_map(params![tenant_id, TENANT_PAGE_LEN as i64], raw_row)?
+							.collect::<Result<Vec<_>, _>>()?;
+						codec.decode(orders, |row| {
+							Ok((
+								row.key(0)?,
+								row.key(1)?,
+								row.key(2)?,
+								row.integer::<i32>(3)?,
+								row.real(4)?,
+								row.timestamp(5)?,
+							))
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
-				}).await?
+				}).await
 			}
 		).await
 	}
 
-	async fn update_single_field_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
+	async fn aggregate_orders_by_tenant(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Aggregating the orders of every tenant {} times", count);
+		let context = self.context.clone();
+
 		measure_execution(
 			&self.database_name(),
-			"update_single_field_many_entries",
+			BenchmarkTest::AggregateOrdersByTenant.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let conn = self.conn.clone();
+				self.run_parallel(count, move |conn, _offset, len| {
+					let mut stmt = conn.prepare_cached(AGGREGATE_ORDERS_BY_TENANT_QUERY)?;
+
+					for _ in 0..len {
+						context.check_cancelled()?;
+						let _tenants: Vec<_> = stmt
+							.query_map([], |row| {
+								Ok((
+									row.get::<_, u32>(0)?,
+									row.get::<_, i64>(1)?,
+									row.get::<_, i64>(2)?,
+									row.get::<_, f64>(3)?,
+								))
+							})?
+							.collect::<Result<Vec<_>, _>>()?;
+						context.record_operation();
+					}
 
-				tokio::task::spawn_blocking(move || {
-					let mut conn = conn.blocking_lock();
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					// Get product IDs for updating
-					let mut stmt = conn.prepare("SELECT id FROM products LIMIT ?")?;
-					let products: Vec<String> = stmt
-						.query_map([count as i64], |row| row.get(0))?
-						.collect::<Result<Vec<_>, _>>()
-						.map_err(|e| anyhow::anyhow!(e))?;
+	async fn read_users_not_deleted(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} users not deleted", count);
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
 
-					if products.is_empty() {
-						return Ok(()); // No products to update
-					}
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadUsersNotDeleted.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader reads its own page of the users not deleted
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare_cached(READ_USERS_NOT_DELETED_QUERY)?;
+
+					let rows = stmt
+						.query_map(params![len as i64, offset as i64], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = rows.len() as u64;
+					codec.decode(rows, |row| Ok((row.key(0)?, row.text(1)?, row.text(2)?, row.timestamp(3)?)))?;
+					context.record_operations(count);
 
-					// Start a transaction for better performance
-					let tx = conn.transaction()?;
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					for (i, product_id) in products.iter().enumerate() {
-						// Update each product's stock
-						let new_stock = ((i as i32) % 1000) + 1;
+	async fn read_discounted_products(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} products on discount", count);
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
 
-						tx.execute(
-							"UPDATE products SET stock = ? WHERE id = ?",
-							params![new_stock, product_id]
-						)?;
-					}
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadDiscountedProducts.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader reads its own page of the products on discount
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare_cached(READ_DISCOUNTED_PRODUCTS_QUERY)?;
+
+					let rows = stmt
+						.query_map(params![len as i64, offset as i64], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = rows.len() as u64;
+					codec.decode(rows, |row| Ok((row.key(0)?, row.text(1)?, row.real(2)?, row.real(3)?)))?;
+					context.record_operations(count);
 
-					tx.commit()?;
 					Ok(())
-				}).await?
+				}).await
 			}
 		).await
 	}
 
-	async fn update_multiple_fields_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		// Get a user to update
-		let user_id = self.run_blocking(|conn| {
-			let id = conn
-				.query_row("SELECT id FROM users LIMIT 1", [], |row|
-					row.get::<_, String>(0)
-				)
-				.map_err(|e| anyhow::anyhow!(e))?;
-			Ok(id)
-		}).await?;
+	async fn soft_delete_users(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Soft-deleting {} users", count);
+		let user_ids = fixture.ids;
 
 		measure_execution(
 			&self.database_name(),
-			"update_multiple_fields_one_entry",
+			BenchmarkTest::SoftDeleteUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				let conn = self.conn.clone();
-				let user_id = user_id.clone();
+				let statements = self.statements.clone();
+				let context = self.context.clone();
+				let retry = self.retry;
+				let timestamps = self.timestamp_encoding;
+				let user_ids = user_ids.clone();
 
 				tokio::task::spawn_blocking(move || {
 					let conn = conn.blocking_lock();
+					let conn = CachingConnection { conn: &conn, statements: &statements };
 
 					for i in 0..count {
-						// Update multiple fields of the same user
-						let new_name = format!("Updated User {}", i);
-						let new_email = format!("updated{}@example.com", i);
-						let new_active = i % 2 == 0;
+						context.check_cancelled()?;
+						let user_id = &user_ids[i % user_ids.len()];
+						let deleted_at = timestamp_value(timestamps, &Utc::now());
 
-						conn.execute(
-							"UPDATE users SET name = ?, email = ?, active = ? WHERE id = ?",
-							params![new_name, new_email, new_active, user_id]
-						)?;
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(SOFT_DELETE_USER_QUERY)?.execute(params![deleted_at, user_id])
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
@@ -909,51 +2675,232 @@ impl DatabaseBenchmark for DuckdbBenchmark {
 		).await
 	}
 
-	async fn update_multiple_fields_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
+	async fn read_live_users(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} live users", count);
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
 		measure_execution(
 			&self.database_name(),
-			"update_multiple_fields_many_entries",
+			BenchmarkTest::ReadLiveUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let conn = self.conn.clone();
+				// Each reader reads its own page of the live users
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare_cached(READ_LIVE_USERS_QUERY)?;
+
+					let rows = stmt
+						.query_map(params![len as i64, offset as i64], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = rows.len() as u64;
+					codec.decode(rows, |row| Ok((row.key(0)?, row.text(1)?, row.text(2)?, row.timestamp(3)?)))?;
+					context.record_operations(count);
 
-				tokio::task::spawn_blocking(move || {
-					let mut conn = conn.blocking_lock();
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					// Get a batch of order IDs to update
-					let mut stmt = conn.prepare("SELECT id FROM orders LIMIT ?")?;
-					let orders: Vec<String> = stmt
-						.query_map([count as i64], |row| row.get(0))?
-						.collect::<Result<Vec<_>, _>>()
-						.map_err(|e| anyhow::anyhow!(e))?;
+	async fn concurrent_inserts(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = Arc::new(fixture.users);
+		let connections = fixture.connections;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
 
-					if orders.is_empty() {
-						return Ok(()); // No orders to update
+		measure_concurrent_inserts(
+			&self.database_name(),
+			count,
+			writers,
+			self.cpu_count,
+			|writer, range| {
+				let conn = connections[writer].clone();
+				let users = users.clone();
+				let context = context.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						let conn = conn.blocking_lock();
+						// Each insert commits on its own, so a conflict with another writer is retried
+						for user in &users[range] {
+							context.check_cancelled()?;
+							retry.run(&context, is_transient, || {
+								conn.execute(
+									INSERT_USER_QUERY,
+									params![
+										key_value(encoding, &user.id),
+										user.tenant_id,
+										user.name,
+										user.email,
+										timestamp_value(timestamps, &user.created_at),
+										user.active,
+										user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+									]
+								)
+							})?;
+							context.record_operation();
+						}
+						Ok(())
+					}).await?
+				}
+			}
+		).await
+	}
+
+	async fn long_transaction_contention(
+		&self,
+		count: usize,
+		contenders: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let ids = fixture.ids;
+		let connections = fixture.connections;
+		let context = self.context.clone();
+		let holder = self.conn.clone();
+		let held_ids = ids.clone();
+
+		// DuckDB keeps versions of the rows: the contenders read the last committed ones, and
+		// their writes only fail, at once, on the users the transaction changed
+		measure_contention(
+			&self.database_name(),
+			count,
+			contenders,
+			self.cpu_count,
+			|hold| async move {
+				tokio::task::spawn_blocking(move || {
+					let mut conn = holder.blocking_lock();
+					// Rolls back if dropped before committing
+					let transaction = conn.transaction()?;
+					for (i, user_id) in held_ids.iter().take(CONTENDED_USERS).enumerate() {
+						transaction.execute(UPDATE_USER_ACTIVE_QUERY, params![i % 2 == 0, user_id])?;
 					}
+					hold.hold();
+					transaction.commit()?;
+					Ok(())
+				}).await?
+			},
+			|contender, range, counters| {
+				let conn = connections[contender].clone();
+				let ids = ids.clone();
+				let context = context.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						let conn = conn.blocking_lock();
+						for i in range {
+							context.check_cancelled()?;
+							let user_id = &ids[i % ids.len()];
+							let write = is_contended_write(i);
+							let start = Instant::now();
+							let outcome = if write {
+								conn.execute(UPDATE_USER_ACTIVE_QUERY, params![i % 2 == 0, user_id]).map(|_| ())
+							} else {
+								conn.query_row(READ_BY_ID_QUERY, [user_id], |_| Ok(())).optional().map(|_| ())
+							};
+							match outcome {
+								Ok(()) => counters.record(write, start.elapsed(), false),
+								Err(e) if is_transient(&e) => counters.record(write, start.elapsed(), true),
+								Err(e) => return Err(e.into()),
+							}
+							context.record_operation();
+						}
+						Ok(())
+					}).await?
+				}
+			}
+		).await
+	}
 
-					// Start a transaction for better performance
-					let tx = conn.transaction()?;
+	async fn consistency_probe(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let product_id = fixture.ids[0].clone();
+		let connections = fixture.connections;
+		let context = self.context.clone();
 
-					for (i, order_id) in orders.iter().enumerate() {
-						// Update quantity and total_price
-						let new_quantity = ((i as i32) % 10) + 1;
-						let new_total_price =
-							(new_quantity as f64) * 9.99 + ((i as f64) % 10.0);
+		measure_consistency(
+			&self.database_name(),
+			count,
+			writers,
+			self.cpu_count,
+			|| {
+				let product_id = product_id.clone();
+				self.run_blocking(move |conn| {
+					let stock: i32 = conn.query_row(READ_PRODUCT_STOCK_QUERY, [product_id], |row| row.get(0))?;
+					Ok(stock as i64)
+				})
+			},
+			|writer, range, counters| {
+				let conn = connections[writer].clone();
+				let product_id = product_id.clone();
+				let context = context.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						let mut conn = conn.blocking_lock();
+						for _ in range {
+							context.check_cancelled()?;
+							// Each transaction reads its own version of the product; writing it
+							// after another transaction changed it fails on a conflict
+							let outcome = (|| {
+								let transaction = conn.transaction()?;
+								let stock: i32 = transaction.query_row(
+									READ_PRODUCT_STOCK_QUERY,
+									[&product_id],
+									|row| row.get(0)
+								)?;
+								transaction.execute(UPDATE_PRODUCT_STOCK_QUERY, params![stock + 1, product_id])?;
+								transaction.commit()
+							})();
+							match outcome {
+								Ok(()) => counters.record(true),
+								Err(e) if is_transient(&e) => counters.record(false),
+								Err(e) => return Err(e.into()),
+							}
+							context.record_operation();
+						}
+						Ok(())
+					}).await?
+				}
+			}
+		).await
+	}
 
-						tx.execute(
-							"UPDATE orders SET quantity = ?, total_price = ? WHERE id = ?",
-							params![new_quantity, new_total_price, order_id]
-						)?;
+	async fn read_projection(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		info!("Reading {} products by projection and with every column", count);
+
+		measure_projection(&self.database_name(), count, self.cpu_count, |projection| {
+			let context = self.context.clone();
+			let rows = Arc::new(AtomicU64::new(0));
+			let read_rows = rows.clone();
+			async move {
+				// Each reader reads its own page of the products, getting every column it selected;
+				// only the vectors of the selected columns are scanned
+				self.run_parallel(count, move |conn, offset, len| {
+					context.check_cancelled()?;
+					let mut stmt = conn.prepare_cached(projection.query())?;
+
+					let mut page = stmt.query(params![len as i64, offset as i64])?;
+					let mut read = 0;
+					while let Some(row) = page.next()? {
+						let _values = (0..row.as_ref().column_count())
+							.map(|column| row.get::<_, Value>(column))
+							.collect::<Result<Vec<_>, _>>()?;
+						read += 1;
 					}
+					projection.record(&context, read);
+					read_rows.fetch_add(read, Ordering::Relaxed);
 
-					tx.commit()?;
 					Ok(())
-				}).await?
+				}).await?;
+				Ok(rows.load(Ordering::Relaxed))
 			}
-		).await
+		}).await
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{ BulkInsertMode, DuckdbBenchmark };
+
+	common::conformance_tests!(dir => DuckdbBenchmark::new(1, &dir, BulkInsertMode::Appender));
+}
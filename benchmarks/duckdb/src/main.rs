@@ -1,18 +1,30 @@
 use anyhow::Result;
+use tracing::info;
 mod duckdb_benchmark;
 
-use crate::duckdb_benchmark::DuckdbBenchmark;
-use common::server::run_server;
+use crate::duckdb_benchmark::{ BulkInsertMode, DuckdbBenchmark };
+use common::{ config::ServerConfig, server::run_server };
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	println!("Starting DuckDB benchmark");
-	// Create a new DuckDB benchmark with 1 CPU core initially
-	let benchmark = DuckdbBenchmark::new(4).await?;
-	println!("Benchmark created");
+	let config = ServerConfig::load();
+	config.init_logging();
+	info!("Starting DuckDB benchmark");
 
-	// Run the server on port 3002
-	run_server(benchmark, 3002).await?;
+	// Bulk inserts use the Appender unless DUCKDB_BULK_INSERT=statements
+	let bulk_insert = BulkInsertMode::from_env()?;
+	info!("Bulk insert mode: {:?}", bulk_insert);
+
+	// Create a new DuckDB benchmark with 1 CPU core initially, on an existing database when one
+	// is given
+	let benchmark = match &config.connect {
+		Some(dsn) => DuckdbBenchmark::connect(4, dsn, bulk_insert).await?,
+		None => DuckdbBenchmark::new(4, &config.data_dir, bulk_insert).await?,
+	};
+	info!("Benchmark created");
+
+	// Run the server, on port 3002 by default
+	run_server(benchmark, &config, 3002).await?;
 
 	Ok(())
 }
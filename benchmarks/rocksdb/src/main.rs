@@ -1,16 +1,20 @@
 use anyhow::Result;
+use tracing::info;
 mod rocksdb_benchmark;
 
 use crate::rocksdb_benchmark::RocksDBBenchmark;
-use common::server::run_server;
+use common::{ config::ServerConfig, server::run_server };
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	println!("Starting RocksDB benchmark");
-	let benchmark = RocksDBBenchmark::new(4).await?;
-	println!("Benchmark created");
+	let config = ServerConfig::load();
+	config.init_logging();
+	info!("Starting RocksDB benchmark");
 
-	run_server(benchmark, 3003).await?;
+	let benchmark = RocksDBBenchmark::new(4, &config.data_dir).await?;
+	info!("Benchmark created");
+
+	run_server(benchmark, &config, 3003).await?;
 
 	Ok(())
 }
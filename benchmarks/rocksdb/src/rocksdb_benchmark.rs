@@ -1,43 +1,397 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use common::{
+	backup::dir_size,
 	benchmark::{
 		measure_execution,
 		DatabaseBenchmark,
-		generate_random_order,
-		generate_random_product,
+		generate_orders_with_items,
 		generate_random_user,
+		generate_test_data_chunks,
+		GENERATION_CHUNK_SIZE,
+		PROBE_BLOB,
+		PROBE_TABLE,
 	},
-	models::{ BenchmarkResult, Order, Product, User, OrderWithDetails },
+	consistency::measure_consistency,
+	context::RunContext,
+	distribution,
+	plan::{ BenchmarkTest, IndexMode, KeyEncoding, TextMode, TimestampEncoding },
+	projection::measure_projection,
+	models::{
+		BackendCapabilities,
+		BenchmarkResult,
+		Capabilities,
+		Order,
+		OrderItem,
+		Product,
+		RecordCounts,
+		User,
+		OrderWithItems,
+		Record as ImportedRecord,
+		WideRecord,
+		WideValue,
+		ORDER_ITEM_RECORD_BYTES,
+		ORDER_RECORD_BYTES,
+		WIDE_RECORD_BYTES,
+	},
+	import::{ ImportColumn, IMPORT_TABLE },
+	nullable::users_to_soft_delete,
+	retry::RetryPolicy,
+	search::search_suffix,
+	tenants::TENANT_PAGE_LEN,
+	text::holds_text,
+	trace::{ TraceChunk, TraceOp },
+	writers::measure_concurrent_inserts,
+	wide::{ generate_wide_record, WIDE_SUBSET_COLUMNS },
+};
+use rocksdb::{
+	backup::{ BackupEngine, BackupEngineOptions, RestoreOptions },
+	checkpoint::Checkpoint,
+	DB,
+	Env,
+	BlockBasedOptions,
+	BoundColumnFamily,
+	ColumnFamilyDescriptor,
+	ErrorKind,
+	Options,
+	IteratorMode,
+	SliceTransform,
+	WriteBatch,
 };
-use rocksdb::{ DB, ColumnFamilyDescriptor, Options, IteratorMode, WriteBatch };
-use std::path::Path;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use serde::{ Serialize, Deserialize };
+use std::cmp::Reverse;
+use std::collections::{ BTreeMap, HashSet };
+use std::fs;
+use std::path::{ Path, PathBuf };
+use std::sync::{ Arc, RwLock };
+use std::time::Instant;
+use serde::{ de::DeserializeOwned, Serialize };
+use chrono::{ DateTime, Utc };
 use uuid::Uuid;
 use bincode;
 
 const USERS_CF: &str = "users";
 const PRODUCTS_CF: &str = "products";
 const ORDERS_CF: &str = "orders";
+// Keyed by the order key followed by the item key, so the items of an order are read by prefix
+const ORDER_ITEMS_CF: &str = "order_items";
+const WIDE_RECORDS_CF: &str = "wide_records";
+const IMPORTED_RECORDS_CF: &str = IMPORT_TABLE;
 const USERS_EMAIL_INDEX_CF: &str = "users_email_index";
 const PRODUCTS_NAME_INDEX_CF: &str = "products_name_index";
 const ORDERS_USER_ID_INDEX_CF: &str = "orders_user_id_index";
 const ORDERS_PRODUCT_ID_INDEX_CF: &str = "orders_product_id_index";
+const ORDERS_TENANT_ID_INDEX_CF: &str = "orders_tenant_id_index";
+
+// Directory of the checkpoint of the dataset in its fixture directory
+const SNAPSHOT_DIR: &str = "rocksdb";
+
+// Directories of the BackupEngine and of the database it restores, in the backup directory of the run
+const BACKUP_DIR: &str = "rocksdb-backups";
+const RESTORED_DIR: &str = "rocksdb-restored";
+
+// Every column family, flushed when the server shuts down
+const COLUMN_FAMILIES: [&str; 11] = [
+	USERS_CF,
+	PRODUCTS_CF,
+	ORDERS_CF,
+	ORDER_ITEMS_CF,
+	WIDE_RECORDS_CF,
+	IMPORTED_RECORDS_CF,
+	USERS_EMAIL_INDEX_CF,
+	PRODUCTS_NAME_INDEX_CF,
+	ORDERS_USER_ID_INDEX_CF,
+	ORDERS_PRODUCT_ID_INDEX_CF,
+	ORDERS_TENANT_ID_INDEX_CF,
+];
+
+// Prefix extracted from the keys of each index column family: the start of the emails and
+// product names, which the searches seek, and the start of the referenced ids, as long as a
+// binary id so the same length works for both key encodings; the order items are prefixed the
+// same way by the key of their order. Tenants are indexed by their id in big-endian bytes.
+const EMAIL_INDEX_PREFIX_LEN: usize = 8;
+const NAME_INDEX_PREFIX_LEN: usize = 12;
+const UUID_INDEX_PREFIX_LEN: usize = 16;
+const TENANT_INDEX_PREFIX_LEN: usize = 4;
+
+// Tickers of the statistics read into the engine statistics after each test
+const ENGINE_STATS_TICKERS: [&str; 6] = [
+	"rocksdb.block.cache.hit",
+	"rocksdb.block.cache.miss",
+	"rocksdb.memtable.hit",
+	"rocksdb.memtable.miss",
+	"rocksdb.bloom.filter.useful",
+	"rocksdb.stall.micros",
+];
+
+// Properties of the whole database, and those summed over the column families
+const ENGINE_STATS_DB_PROPERTIES: [&str; 4] = [
+	"rocksdb.num-running-flushes",
+	"rocksdb.num-running-compactions",
+	"rocksdb.actual-delayed-write-rate",
+	"rocksdb.is-write-stopped",
+];
+const ENGINE_STATS_CF_PROPERTIES: [&str; 4] = [
+	"rocksdb.cur-size-all-mem-tables",
+	"rocksdb.block-cache-usage",
+	"rocksdb.estimate-pending-compaction-bytes",
+	"rocksdb.estimate-live-data-size",
+];
+
+// Sentinel key of the default column family read by health checks
+const PING_KEY: &[u8] = b"__ping__";
+
+// Errors of a write RocksDB can't take right now, e.g. while it stalls writes, and may accept later
+fn is_transient(e: &rocksdb::Error) -> bool {
+	matches!(e.kind(), ErrorKind::Busy | ErrorKind::TryAgain | ErrorKind::TimedOut)
+}
+
+// Count of a ticker in the statistics dump of RocksDB, from its `rocksdb.wal.bytes COUNT : 1024` line
+fn ticker_count(stats: &str, ticker: &str) -> u64 {
+	stats
+		.lines()
+		.find_map(|line| line.strip_prefix(ticker)?.trim_start().strip_prefix("COUNT :")?.trim().parse().ok())
+		.unwrap_or(0)
+}
+
+// Copy the files of a snapshot to `target`, replacing what it held. Table files are never changed
+// once written, so they are hard-linked when the file system allows it, like in a checkpoint
+fn copy_snapshot(snapshot: &Path, target: &Path) -> Result<()> {
+	if target.exists() {
+		fs::remove_dir_all(target)?;
+	}
+	fs::create_dir_all(target)?;
+
+	for entry in fs::read_dir(snapshot)? {
+		let entry = entry?;
+		let from = entry.path();
+		let to = target.join(entry.file_name());
+		let is_table = from.extension().is_some_and(|ext| ext == "sst");
+		if !is_table || fs::hard_link(&from, &to).is_err() {
+			fs::copy(&from, &to)?;
+		}
+	}
+
+	Ok(())
+}
+
+
+// Records whose `created_at` can be serialized as microseconds since the epoch, instead of the
+// RFC 3339 string chrono serializes it as
+trait Record: Serialize + DeserializeOwned {
+	fn serialize_native(&self) -> Result<Vec<u8>>;
+	fn deserialize_native(bytes: &[u8]) -> Result<Self>;
+}
+
+fn timestamp_from_micros(micros: i64) -> Result<DateTime<Utc>> {
+	DateTime::from_timestamp_micros(micros).ok_or_else(|| anyhow::anyhow!("Invalid timestamp: {}", micros))
+}
+
+impl Record for User {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		let created_at = self.created_at.timestamp_micros();
+		let deleted_at = self.deleted_at.map(|deleted_at| deleted_at.timestamp_micros());
+		Ok(
+			bincode::serialize(
+				&(&self.id, self.tenant_id, &self.name, &self.email, created_at, self.active, deleted_at)
+			)?
+		)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		let (id, tenant_id, name, email, created_at, active, deleted_at): (_, _, _, _, _, _, Option<i64>) =
+			bincode::deserialize(bytes)?;
+		Ok(User {
+			id,
+			tenant_id,
+			name,
+			email,
+			created_at: timestamp_from_micros(created_at)?,
+			active,
+			deleted_at: deleted_at.map(timestamp_from_micros).transpose()?,
+		})
+	}
+}
+
+impl Record for Product {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		let created_at = self.created_at.timestamp_micros();
+		Ok(
+			bincode::serialize(
+				&(&self.id, &self.name, &self.description, self.price, self.discount, self.stock, created_at)
+			)?
+		)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		let (id, name, description, price, discount, stock, created_at) = bincode::deserialize(bytes)?;
+		Ok(Product {
+			id,
+			name,
+			description,
+			price,
+			discount,
+			stock,
+			created_at: timestamp_from_micros(created_at)?,
+		})
+	}
+}
+
+impl Record for Order {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		let created_at = self.created_at.timestamp_micros();
+		Ok(
+			bincode::serialize(
+				&(
+					&self.id,
+					self.tenant_id,
+					&self.user_id,
+					&self.product_id,
+					self.quantity,
+					self.total_price,
+					created_at,
+				)
+			)?
+		)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		let (id, tenant_id, user_id, product_id, quantity, total_price, created_at) =
+			bincode::deserialize(bytes)?;
+		Ok(Order {
+			id,
+			tenant_id,
+			user_id,
+			product_id,
+			quantity,
+			total_price,
+			created_at: timestamp_from_micros(created_at)?,
+		})
+	}
+}
+
+// Order items have no timestamp, so both encodings serialize them the same way
+impl Record for OrderItem {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		Ok(bincode::serialize(self)?)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		Ok(bincode::deserialize(bytes)?)
+	}
+}
+
+// Wide records have no timestamp either; a row is stored whole, every column in one value
+impl Record for WideRecord {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		Ok(bincode::serialize(self)?)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		Ok(bincode::deserialize(bytes)?)
+	}
+}
+
+// Imported records have no timestamp either, and are stored whole like the wide records
+impl Record for ImportedRecord {
+	fn serialize_native(&self) -> Result<Vec<u8>> {
+		Ok(bincode::serialize(self)?)
+	}
+
+	fn deserialize_native(bytes: &[u8]) -> Result<Self> {
+		Ok(bincode::deserialize(bytes)?)
+	}
+}
+
+// How keys and records are laid out in the column families, following the plan
+#[derive(Debug, Clone, Copy, Default)]
+struct Codec {
+	keys: KeyEncoding,
+	timestamps: TimestampEncoding,
+}
+
+impl Codec {
+	// Key of a record: its id as a string or as its 16 bytes, depending on the key encoding
+	fn record_key(&self, id: &Uuid) -> Vec<u8> {
+		match self.keys {
+			KeyEncoding::String => id.to_string().into_bytes(),
+			KeyEncoding::Binary => id.as_bytes().to_vec(),
+		}
+	}
+
+	// Id a record key holds
+	fn parse_key(&self, key: &[u8]) -> Result<Uuid> {
+		match self.keys {
+			KeyEncoding::String => Ok(Uuid::parse_str(std::str::from_utf8(key)?)?),
+			KeyEncoding::Binary => Ok(Uuid::from_slice(key)?),
+		}
+	}
+
+	// Key of an order item: the key of its order followed by its own
+	fn item_key(&self, item: &OrderItem) -> Vec<u8> {
+		let mut key = self.record_key(&item.order_id);
+		key.extend_from_slice(&self.record_key(&item.id));
+		key
+	}
+
+	// Key of an index entry: the indexed value, a colon and the key of the record
+	fn index_key(&self, value: impl AsRef<[u8]>, id: &Uuid) -> Vec<u8> {
+		let mut key = value.as_ref().to_vec();
+		key.push(b':');
+		key.extend_from_slice(&self.record_key(id));
+		key
+	}
+
+	fn serialize<T: Record>(&self, value: &T) -> Result<Vec<u8>> {
+		match self.timestamps {
+			TimestampEncoding::String => Ok(bincode::serialize(value)?),
+			TimestampEncoding::Native => value.serialize_native(),
+		}
+	}
+
+	fn deserialize<T: Record>(&self, bytes: &[u8]) -> Result<T> {
+		match self.timestamps {
+			TimestampEncoding::String => Ok(bincode::deserialize(bytes)?),
+			TimestampEncoding::Native => T::deserialize_native(bytes),
+		}
+	}
+}
+
+// Data a test needs, prepared before it is timed
+#[derive(Default)]
+pub struct Fixture {
+	// Users written by the insert tests
+	users: Arc<Vec<User>>,
+	// Orders written by insert_orders_with_items
+	orders: Arc<Vec<OrderWithItems>>,
+	// Records written by insert_wide_records
+	wide_records: Arc<Vec<WideRecord>>,
+	// Keys of the records read or updated
+	keys: Arc<Vec<Box<[u8]>>>,
+}
 
 pub struct RocksDBBenchmark {
-	db: Arc<Mutex<DB>>,
+	// RocksDB is thread-safe: the handle is shared by every blocking task without a lock. The lock
+	// only guards replacing the handle when the database is reset from a snapshot
+	db: Arc<RwLock<Arc<DB>>>,
 	db_path: String,
+	// Empty database copied over the database by `cleanup` and `reset`
+	empty_path: PathBuf,
+	// Options the database was opened with, holding its statistics
+	options: Options,
 	cpu_count: usize,
+	// Whether the index column families are maintained and used by the tests
+	indexes: IndexMode,
+	codec: Codec,
+	retry: RetryPolicy,
+	context: RunContext,
 }
 
 impl RocksDBBenchmark {
-	pub async fn new(cpu_count: usize) -> Result<Self> {
-		let db_path = "./data/rocksdb-benchmark";
+	pub async fn new(cpu_count: usize, data_dir: &Path) -> Result<Self> {
+		let db_path = data_dir.join("rocksdb-benchmark").to_string_lossy().to_string();
+		let empty_path = data_dir.join("rocksdb-empty");
 
 		// Create data directory if it doesn't exist
-		let data_dir = Path::new("./data");
 		if !data_dir.exists() {
 			std::fs::create_dir_all(data_dir)?;
 		}
@@ -45,72 +399,455 @@ impl RocksDBBenchmark {
 		// Create DB options
 		let mut opts = Options::default();
 		opts.create_if_missing(true);
+		// Databases created before a column family was added get it on open
+		opts.create_missing_column_families(true);
 		opts.increase_parallelism(cpu_count as i32);
 		opts.set_max_background_jobs(4);
 		opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+		// Counts the bytes written to the WAL, flushes and compactions
+		opts.enable_statistics();
 
-		// Define column families
-		let cf_names = vec![
-			USERS_CF,
-			PRODUCTS_CF,
-			ORDERS_CF,
-			USERS_EMAIL_INDEX_CF,
-			PRODUCTS_NAME_INDEX_CF,
-			ORDERS_USER_ID_INDEX_CF,
-			ORDERS_PRODUCT_ID_INDEX_CF
-		];
-
-		let cf_descriptors: Vec<ColumnFamilyDescriptor> = cf_names
-			.iter()
-			.map(|name| {
-				let mut cf_opts = Options::default();
-				cf_opts.set_max_write_buffer_number(4);
-				cf_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB
-				cf_opts.set_level_compaction_dynamic_level_bytes(true);
+		let db = Self::open_db(&opts, Path::new(&db_path))?;
 
-				ColumnFamilyDescriptor::new(*name, cf_opts)
-			})
-			.collect();
+		// An empty database with every column family, closed once created
+		if !empty_path.exists() {
+			Self::open_db(&opts, &empty_path)?;
+		}
+
+		Ok(Self {
+			db: Arc::new(RwLock::new(Arc::new(db))),
+			db_path,
+			empty_path,
+			options: opts,
+			cpu_count,
+			indexes: IndexMode::default(),
+			codec: Codec::default(),
+			retry: RetryPolicy::default(),
+			context: RunContext::default(),
+		})
+	}
 
+	// Open the database at `path` with all the column families, creating what is missing
+	fn open_db(opts: &Options, path: &Path) -> Result<DB> {
 		// Try to open DB with all CFs, if it doesn't exist, create it
-		let db = match DB::open_cf_descriptors(&opts, &db_path, cf_descriptors) {
+		let db = match DB::open_cf_descriptors(opts, path, Self::cf_descriptors()) {
 			Ok(db) => db,
 			Err(_) => {
 				// Create DB with default column family
-				let db = DB::open(&opts, &db_path)?;
+				let db = DB::open(opts, path)?;
 
 				// Create all column families
-				for cf_name in cf_names {
-					let mut cf_opts = Options::default();
-					cf_opts.set_max_write_buffer_number(4);
-					cf_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB
-					cf_opts.set_level_compaction_dynamic_level_bytes(true);
-
-					db.create_cf(cf_name, &cf_opts)?;
+				for cf_name in COLUMN_FAMILIES {
+					db.create_cf(cf_name, &Self::cf_options(cf_name))?;
 				}
 				db
 			}
 		};
 
-		Ok(Self {
-			db: Arc::new(Mutex::new(db)),
-			db_path: db_path.to_string(),
-			cpu_count,
-		})
+		Ok(db)
 	}
 
-	// Helper functions to serialize and deserialize data
-	fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>> {
-		Ok(bincode::serialize(value)?)
+	fn cf_descriptors() -> Vec<ColumnFamilyDescriptor> {
+		COLUMN_FAMILIES
+			.iter()
+			.map(|name| ColumnFamilyDescriptor::new(*name, Self::cf_options(name)))
+			.collect()
 	}
 
-	fn deserialize<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T> {
-		Ok(bincode::deserialize(bytes)?)
+	// Replace the database with a copy of `snapshot`, a checkpoint or a closed database. Unlike
+	// deleting every key, this takes a time proportional to the number of files rather than of
+	// records, and leaves no tombstones behind for the next reads to skip over. The database is
+	// closed meanwhile, so it fails while an operation is still using it.
+	pub async fn reset_from_snapshot(&self, snapshot: &Path) -> Result<()> {
+		if !snapshot.exists() {
+			anyhow::bail!("No snapshot at {}", snapshot.display());
+		}
+
+		let db = self.db.clone();
+		let options = self.options.clone();
+		let db_path = PathBuf::from(&self.db_path);
+		let snapshot = snapshot.to_path_buf();
+		tokio::task::spawn_blocking(move || {
+			let mut db = db.write().unwrap();
+			if Arc::strong_count(&db) > 1 {
+				anyhow::bail!("The database is in use, it can't be reset from a snapshot");
+			}
+
+			// The database closes once its handle is dropped, the snapshot opened read-only stands
+			// in until the copy reopens
+			*db = Arc::new(DB::open_cf_descriptors_read_only(&options, &snapshot, Self::cf_descriptors(), false)?);
+			copy_snapshot(&snapshot, &db_path)?;
+			*db = Arc::new(Self::open_db(&options, &db_path)?);
+
+			Ok(())
+		}).await?
+	}
+
+	// Options of a column family; index column families get a prefix extractor and prefix bloom
+	// filters, so a lookup by value seeks straight to its entries instead of scanning the index
+	fn cf_options(name: &str) -> Options {
+		let mut cf_opts = Options::default();
+		cf_opts.set_max_write_buffer_number(4);
+		cf_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB
+		cf_opts.set_level_compaction_dynamic_level_bytes(true);
+
+		let prefix_len = match name {
+			USERS_EMAIL_INDEX_CF => EMAIL_INDEX_PREFIX_LEN,
+			PRODUCTS_NAME_INDEX_CF => NAME_INDEX_PREFIX_LEN,
+			ORDERS_USER_ID_INDEX_CF | ORDERS_PRODUCT_ID_INDEX_CF | ORDER_ITEMS_CF => UUID_INDEX_PREFIX_LEN,
+			ORDERS_TENANT_ID_INDEX_CF => TENANT_INDEX_PREFIX_LEN,
+			_ => {
+				return cf_opts;
+			}
+		};
+
+		let mut table_opts = BlockBasedOptions::default();
+		table_opts.set_bloom_filter(10.0, false);
+		// Index entries and order items are only ever read by prefix
+		table_opts.set_whole_key_filtering(false);
+
+		cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(prefix_len));
+		cf_opts.set_memtable_prefix_bloom_ratio(0.1);
+		cf_opts.set_block_based_table_factory(&table_opts);
+		cf_opts
+	}
+
+	// Helper to run blocking database operations on the blocking thread pool
+	async fn run_blocking<F, T>(&self, f: F) -> Result<T>
+		where F: FnOnce(&DB) -> Result<T> + Send + 'static, T: Send + 'static
+	{
+		let db = self.db.read().unwrap().clone();
+		let result = tokio::task::spawn_blocking(move || f(&db)).await??;
+
+		Ok(result)
+	}
+
+	// Split `count` operations into one contiguous range per worker and run them on `cpu_count`
+	// blocking threads at once; `work` receives the offset and length of its range
+	async fn run_parallel<F>(&self, count: usize, work: F) -> Result<()>
+		where F: Fn(&DB, usize, usize) -> Result<()> + Send + Sync + 'static
+	{
+		let work = Arc::new(work);
+		let db = self.db.read().unwrap().clone();
+		let workers = self.cpu_count.max(1);
+		let chunk = count.div_ceil(workers);
+
+		let tasks = (0..workers)
+			.map(|i| i * chunk)
+			.filter(|offset| *offset < count)
+			.map(|offset| {
+				let db = db.clone();
+				let work = work.clone();
+				let len = chunk.min(count - offset);
+				tokio::task::spawn_blocking(move || work(&db, offset, len))
+			});
+
+		for result in futures::future::try_join_all(tasks).await? {
+			result?;
+		}
+
+		Ok(())
+	}
+
+	// Keys of the first `count` entries of a column family
+	fn first_keys(db: &DB, cf_name: &str, count: usize) -> Result<Vec<Box<[u8]>>> {
+		let cf = db.cf_handle(cf_name).unwrap();
+		let mut keys = Vec::with_capacity(count);
+
+		for result in db.iterator_cf(&cf, IteratorMode::Start).take(count) {
+			let (key, _) = result?;
+			keys.push(key);
+		}
+
+		Ok(keys)
+	}
+
+
+	// Keys of the first `count` users not deleted, in the order of their ids
+	fn live_user_keys(db: &DB, codec: Codec, count: usize) -> Result<Vec<Box<[u8]>>> {
+		let users_cf = db.cf_handle(USERS_CF).unwrap();
+		let mut keys = Vec::with_capacity(count);
+
+		for result in db.iterator_cf(&users_cf, IteratorMode::Start) {
+			if keys.len() == count {
+				break;
+			}
+			let (key, value) = result?;
+			if codec.deserialize::<User>(&value)?.deleted_at.is_none() {
+				keys.push(key);
+			}
+		}
+
+		Ok(keys)
+	}
+
+	// Users whose email ends with `suffix`, found by going through the email index: its keys hold
+	// the emails, so only the matching users are read. The index is sorted by the start of the
+	// emails, so no seek skips to the ones of a domain
+	fn search_email_index(
+		db: &DB,
+		codec: Codec,
+		users_email_index_cf: &Arc<BoundColumnFamily>,
+		users_cf: &Arc<BoundColumnFamily>,
+		suffix: &str,
+		limit: usize
+	) -> Result<Vec<User>> {
+		let mut users = Vec::new();
+
+		for result in db.iterator_cf(users_email_index_cf, IteratorMode::Start) {
+			if users.len() >= limit {
+				break;
+			}
+			let (key, _) = result?;
+
+			// Split the index key (format: "email:key") at the first colon; emails have no colon
+			// while binary keys may contain one
+			let separator = key.iter().position(|byte| *byte == b':').unwrap_or_default();
+			if !key[..separator].ends_with(suffix.as_bytes()) {
+				continue;
+			}
+
+			if let Some(bytes) = db.get_cf(users_cf, &key[separator + 1..])? {
+				users.push(codec.deserialize(&bytes)?);
+			}
+		}
+
+		Ok(users)
+	}
+
+	// Users whose email ends with `suffix`, found by scanning every user
+	fn scan_emails(
+		db: &DB,
+		codec: Codec,
+		users_cf: &Arc<BoundColumnFamily>,
+		suffix: &str,
+		limit: usize
+	) -> Result<Vec<User>> {
+		let mut users = Vec::new();
+
+		for result in db.iterator_cf(users_cf, IteratorMode::Start) {
+			let (_, value) = result?;
+			let user: User = codec.deserialize(&value)?;
+			if user.email.ends_with(suffix) {
+				users.push(user);
+				if users.len() >= limit {
+					break;
+				}
+			}
+		}
+
+		Ok(users)
+	}
+
+	// A page of the orders of a tenant, found with a prefix seek on the tenant index when the
+	// indexes are on and by scanning the orders otherwise
+	fn tenant_orders(
+		db: &DB,
+		codec: Codec,
+		orders_cf: &Arc<BoundColumnFamily>,
+		orders_tenant_id_index_cf: Option<&Arc<BoundColumnFamily>>,
+		tenant_id: u32,
+		limit: usize
+	) -> Result<Vec<Order>> {
+		let mut orders = Vec::new();
+
+		let Some(index_cf) = orders_tenant_id_index_cf else {
+			for result in db.iterator_cf(orders_cf, IteratorMode::Start) {
+				let (_, value) = result?;
+				let order: Order = codec.deserialize(&value)?;
+				if order.tenant_id == tenant_id {
+					orders.push(order);
+					if orders.len() >= limit {
+						break;
+					}
+				}
+			}
+			return Ok(orders);
+		};
+
+		// Index keys are the tenant id, a colon and the order key
+		let prefix = tenant_id.to_be_bytes();
+		for result in db.prefix_iterator_cf(index_cf, prefix) {
+			let (key, _) = result?;
+			if !key.starts_with(&prefix) || orders.len() >= limit {
+				break;
+			}
+
+			if let Some(bytes) = db.get_cf(orders_cf, &key[TENANT_INDEX_PREFIX_LEN + 1..])? {
+				orders.push(codec.deserialize(&bytes)?);
+			}
+		}
+
+		Ok(orders)
+	}
+
+	// Number of keys in a column family
+	fn count_keys(db: &DB, cf_name: &str) -> Result<usize> {
+		let cf = db.cf_handle(cf_name).unwrap();
+		let mut count = 0;
+
+		for result in db.iterator_cf(&cf, IteratorMode::Start) {
+			result?;
+			count += 1;
+		}
+
+		Ok(count)
+	}
+
+	// Number of the first `limit` records of a column family for which `is_stale` holds
+	fn count_stale<T: Record>(
+		db: &DB,
+		codec: Codec,
+		cf_name: &str,
+		limit: usize,
+		is_stale: impl Fn(&T) -> bool
+	) -> Result<usize> {
+		let cf = db.cf_handle(cf_name).unwrap();
+		let mut stale = 0;
+
+		for result in db.iterator_cf(&cf, IteratorMode::Start).take(limit) {
+			let (_, value) = result?;
+			if is_stale(&codec.deserialize(&value)?) {
+				stale += 1;
+			}
+		}
+
+		Ok(stale)
+	}
+
+	// Check every entry of an index column family points to an existing record holding the
+	// indexed value, and every record has its entry
+	fn check_index<T: Record>(
+		db: &DB,
+		codec: Codec,
+		index_cf_name: &str,
+		records_cf_name: &str,
+		indexed_value: impl Fn(&T) -> Vec<u8>
+	) -> Result<()> {
+		let index_cf = db.cf_handle(index_cf_name).unwrap();
+		let records_cf = db.cf_handle(records_cf_name).unwrap();
+		// Index keys end with the record key, whose length only depends on the key encoding
+		let key_len = codec.record_key(&Uuid::nil()).len();
+		let mut entries = 0;
+
+		for result in db.iterator_cf(&index_cf, IteratorMode::Start) {
+			let (key, _) = result?;
+			anyhow::ensure!(key.len() > key_len, "{} holds a malformed entry", index_cf_name);
+			let value = &key[..key.len() - key_len - 1];
+			let record_key = &key[key.len() - key_len..];
+
+			let record: T = match db.get_cf(&records_cf, record_key)? {
+				Some(bytes) => codec.deserialize(&bytes)?,
+				None => anyhow::bail!("{} has an entry for a missing record", index_cf_name),
+			};
+			anyhow::ensure!(indexed_value(&record) == value, "{} has a stale entry", index_cf_name);
+			entries += 1;
+		}
+
+		let records = Self::count_keys(db, records_cf_name)?;
+		anyhow::ensure!(
+			entries == records,
+			"{} has {} entries for {} records",
+			index_cf_name,
+			entries,
+			records
+		);
+		Ok(())
+	}
+
+	// Write a batch, retrying it after a transient error; a write consumes its batch, so every
+	// attempt writes a copy of it
+	fn write_batch(db: &DB, retry: RetryPolicy, context: &RunContext, batch: WriteBatch) -> Result<()> {
+		retry.run(context, is_transient, || db.write(WriteBatch::from_data(batch.data())))?;
+		Ok(())
+	}
+
+	// Delete every imported record, in a single batch
+	fn clear_imported_records(db: &DB) -> Result<()> {
+		let imported_records_cf = db.cf_handle(IMPORTED_RECORDS_CF).unwrap();
+		let mut batch = WriteBatch::default();
+		for result in db.iterator_cf(&imported_records_cf, IteratorMode::Start) {
+			let (key, _) = result?;
+			batch.delete_cf(&imported_records_cf, key);
+		}
+		db.write(batch)?;
+		Ok(())
+	}
+
+	// Handle of an index column family, `None` when the indexes are off
+	fn index_cf<'a>(db: &'a DB, indexed: bool, cf_name: &str) -> Option<Arc<BoundColumnFamily<'a>>> {
+		indexed.then(|| db.cf_handle(cf_name).unwrap())
+	}
+
+	// Queue an updated user in `batch` along with its email index entry: the entry of the old
+	// email is deleted and the one of the new email written, so the index never goes stale
+	fn batch_user_update(
+		batch: &mut WriteBatch,
+		codec: Codec,
+		users_cf: &Arc<BoundColumnFamily>,
+		users_email_index_cf: Option<&Arc<BoundColumnFamily>>,
+		old: &User,
+		new: &User
+	) -> Result<()> {
+		if let Some(index_cf) = users_email_index_cf {
+			batch.delete_cf(index_cf, codec.index_key(&old.email, &old.id));
+			batch.put_cf(index_cf, codec.index_key(&new.email, &new.id), &[]);
+		}
+		batch.put_cf(users_cf, codec.record_key(&new.id), codec.serialize(new)?);
+		Ok(())
+	}
+
+	// Queue a new order in `batch` along with its user, product and tenant index entries
+	fn batch_order(
+		batch: &mut WriteBatch,
+		codec: Codec,
+		orders_cf: &Arc<BoundColumnFamily>,
+		orders_user_id_index_cf: Option<&Arc<BoundColumnFamily>>,
+		orders_product_id_index_cf: Option<&Arc<BoundColumnFamily>>,
+		orders_tenant_id_index_cf: Option<&Arc<BoundColumnFamily>>,
+		order: &Order
+	) -> Result<()> {
+		batch.put_cf(orders_cf, codec.record_key(&order.id), codec.serialize(order)?);
+
+		// User ID index
+		if let Some(index_cf) = orders_user_id_index_cf {
+			batch.put_cf(index_cf, codec.index_key(codec.record_key(&order.user_id), &order.id), &[]);
+		}
+
+		// Product ID index
+		if let Some(index_cf) = orders_product_id_index_cf {
+			batch.put_cf(index_cf, codec.index_key(codec.record_key(&order.product_id), &order.id), &[]);
+		}
+
+		// Tenant ID index
+		if let Some(index_cf) = orders_tenant_id_index_cf {
+			batch.put_cf(index_cf, codec.index_key(order.tenant_id.to_be_bytes(), &order.id), &[]);
+		}
+		Ok(())
+	}
+
+	// Queue an updated product in `batch` along with its name index entry
+	fn batch_product_update(
+		batch: &mut WriteBatch,
+		codec: Codec,
+		products_cf: &Arc<BoundColumnFamily>,
+		products_name_index_cf: Option<&Arc<BoundColumnFamily>>,
+		old: &Product,
+		new: &Product
+	) -> Result<()> {
+		if let Some(index_cf) = products_name_index_cf {
+			batch.delete_cf(index_cf, codec.index_key(&old.name, &old.id));
+			batch.put_cf(index_cf, codec.index_key(&new.name, &new.id), &[]);
+		}
+		batch.put_cf(products_cf, codec.record_key(&new.id), codec.serialize(new)?);
+		Ok(())
 	}
 }
 
 #[async_trait]
 impl DatabaseBenchmark for RocksDBBenchmark {
+	type Fixture = Fixture;
+
 	async fn init(&self) -> Result<()> {
 		// No schema setup needed for RocksDB as it's a key-value store
 		// Column families are already created in the constructor
@@ -118,703 +855,1577 @@ impl DatabaseBenchmark for RocksDBBenchmark {
 	}
 
 	async fn generate_test_data(&self, count: usize) -> Result<()> {
-		let db = self.db.lock().await;
-
-		// Generate users
-		let users: Vec<User> = (0..count).map(|_| generate_random_user()).collect();
-
-		// Generate products
-		let products: Vec<Product> = (0..count)
-			.map(|_| generate_random_product())
-			.collect();
-
-		// Generate orders (one per user/product pair for simplicity)
-		let mut orders = Vec::with_capacity(count);
-		for i in 0..count {
-			let user_id = users[i % users.len()].id;
-			let product_id = products[i % products.len()].id;
-			orders.push(generate_random_order(user_id, product_id));
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+		let retry = self.retry;
+		// Generate and write the data chunk by chunk, so only one chunk is held in memory
+		let chunks = generate_test_data_chunks(
+			count,
+			self.context.payload(),
+			self.context.tenants(),
+			self.context.key_strategy()
+		);
+		for chunk in chunks {
+			let context = self.context.clone();
+			self.run_blocking(move |db| {
+				// Get column family handles
+				let users_cf = db.cf_handle(USERS_CF).unwrap();
+				let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+				let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
+				let order_items_cf = db.cf_handle(ORDER_ITEMS_CF).unwrap();
+				let wide_records_cf = db.cf_handle(WIDE_RECORDS_CF).unwrap();
+				let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
+				let products_name_index_cf = Self::index_cf(db, indexed, PRODUCTS_NAME_INDEX_CF);
+				let orders_user_id_index_cf = Self::index_cf(db, indexed, ORDERS_USER_ID_INDEX_CF);
+				let orders_product_id_index_cf = Self::index_cf(
+					db,
+					indexed,
+					ORDERS_PRODUCT_ID_INDEX_CF
+				);
+				let orders_tenant_id_index_cf = Self::index_cf(db, indexed, ORDERS_TENANT_ID_INDEX_CF);
+
+				// Create one write batch per chunk for better performance
+				let mut batch = WriteBatch::default();
+
+				// Insert users and create email index
+				for user in &chunk.users {
+					let key = codec.record_key(&user.id);
+					let value = codec.serialize(user)?;
+					batch.put_cf(&users_cf, &key, &value);
+
+					// Email index
+					if let Some(index_cf) = &users_email_index_cf {
+						batch.put_cf(index_cf, codec.index_key(&user.email, &user.id), &[]);
+					}
+				}
+
+				// Insert products and create name index
+				for product in &chunk.products {
+					let key = codec.record_key(&product.id);
+					let value = codec.serialize(product)?;
+					batch.put_cf(&products_cf, &key, &value);
+
+					// Name index
+					if let Some(index_cf) = &products_name_index_cf {
+						batch.put_cf(
+							index_cf,
+							codec.index_key(&product.name, &product.id),
+							&[]
+						);
+					}
+				}
+
+				// Insert orders and create indexes
+				for order in &chunk.orders {
+					Self::batch_order(
+						&mut batch,
+						codec,
+						&orders_cf,
+						orders_user_id_index_cf.as_ref(),
+						orders_product_id_index_cf.as_ref(),
+						orders_tenant_id_index_cf.as_ref(),
+						order
+					)?;
+				}
+
+				// Insert order items
+				for item in &chunk.order_items {
+					batch.put_cf(&order_items_cf, codec.item_key(item), codec.serialize(item)?);
+				}
+
+				// Insert wide records
+				for record in &chunk.wide_records {
+					batch.put_cf(&wide_records_cf, codec.record_key(&record.id), codec.serialize(record)?);
+				}
+
+				// Write the whole chunk at once
+				Self::write_batch(db, retry, &context, batch)?;
+
+				Ok(())
+			}).await?;
 		}
 
-		// Get column family handles
-		let users_cf = db.cf_handle(USERS_CF).unwrap();
-		let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-		let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
-		let users_email_index_cf = db.cf_handle(USERS_EMAIL_INDEX_CF).unwrap();
-		let products_name_index_cf = db.cf_handle(PRODUCTS_NAME_INDEX_CF).unwrap();
-		let orders_user_id_index_cf = db.cf_handle(ORDERS_USER_ID_INDEX_CF).unwrap();
-		let orders_product_id_index_cf = db
-			.cf_handle(ORDERS_PRODUCT_ID_INDEX_CF)
-			.unwrap();
-
-		// Create a write batch for better performance
-		let mut batch = WriteBatch::default();
+		Ok(())
+	}
+
+	async fn cleanup(&self) -> Result<()> {
+		self.reset_from_snapshot(&self.empty_path).await
+	}
+
+	async fn snapshot(&self, dir: &Path) -> Result<()> {
+		std::fs::create_dir_all(dir)?;
+		let target = dir.join(SNAPSHOT_DIR);
+
+		self.run_blocking(move |db| {
+			// A checkpoint is created in a directory that doesn't exist yet
+			if target.exists() {
+				std::fs::remove_dir_all(&target)?;
+			}
+			// The files of the checkpoint are hard links to those of the database where possible
+			Checkpoint::new(db)?.create_checkpoint(&target)?;
+			Ok(())
+		}).await
+	}
+
+	async fn restore(&self, dir: &Path) -> Result<()> {
+		self.reset_from_snapshot(&dir.join(SNAPSHOT_DIR)).await
+	}
+
+	// Unlike a checkpoint, a backup copies the files, flushing the memtables first so it holds
+	// no WAL to replay
+	async fn backup(&self, dir: &Path) -> Result<()> {
+		let backups = dir.join(BACKUP_DIR);
+		self.run_blocking(move |db| {
+			let mut engine = BackupEngine::open(&BackupEngineOptions::new(&backups)?, &Env::new()?)?;
+			engine.create_new_backup_flush(db, true)?;
+			Ok(())
+		}).await
+	}
+
+	// The latest backup is restored into a directory of its own, which then replaces the database
+	// the way a snapshot does
+	async fn restore_backup(&self, dir: &Path) -> Result<()> {
+		let backups = dir.join(BACKUP_DIR);
+		if !backups.exists() {
+			anyhow::bail!("No backup at {}", backups.display());
+		}
+
+		let restored = dir.join(RESTORED_DIR);
+		let target = restored.clone();
+		tokio::task::spawn_blocking(move || -> Result<()> {
+			if target.exists() {
+				fs::remove_dir_all(&target)?;
+			}
+			let mut engine = BackupEngine::open(&BackupEngineOptions::new(&backups)?, &Env::new()?)?;
+			engine.restore_from_latest_backup(&target, &target, &RestoreOptions::default())?;
+			Ok(())
+		}).await??;
+
+		let reset = self.reset_from_snapshot(&restored).await;
+		fs::remove_dir_all(&restored)?;
+		reset
+	}
+
+	async fn reset(&mut self) -> Result<()> {
+		// The copy of the empty database is a new database, without the files of the previous one
+		self.cleanup().await
+	}
+
+	async fn ping(&self) -> Result<()> {
+		self.run_blocking(|db| {
+			// Reading a sentinel key goes through the full read path without needing data
+			db.get(PING_KEY)?;
+
+			Ok(())
+		}).await
+	}
+
+	async fn probe(&self) -> Result<Capabilities> {
+		self.run_blocking(|db| {
+			if db.cf_handle(PROBE_TABLE).is_some() {
+				db.drop_cf(PROBE_TABLE)?;
+			}
+			db.create_cf(PROBE_TABLE, &Options::default())?;
+			let written = (|| -> Result<Option<Vec<u8>>> {
+				let cf = db.cf_handle(PROBE_TABLE).unwrap();
+				db.put_cf(&cf, PING_KEY, PROBE_BLOB)?;
+				Ok(db.get_cf(&cf, PING_KEY)?)
+			})();
+			// A column family left behind would keep the database from opening with its known ones
+			db.drop_cf(PROBE_TABLE)?;
+
+			// Values are bytes, and there is no query language for the rest
+			Ok(Capabilities {
+				blobs: written?.as_deref() == Some(PROBE_BLOB),
+				..Capabilities::default()
+			})
+		}).await
+	}
+
+	// A key-value store: no joins, and no transactions without a `TransactionDB`
+	fn capabilities(&self) -> BackendCapabilities {
+		BackendCapabilities { joins: false, transactions: false }
+	}
+
+	// Bytes written to the WAL and to the SST files of the flushes and compactions
+	async fn bytes_written(&self) -> Result<Option<u64>> {
+		let stats = self.options.get_statistics().unwrap_or_default();
+		let bytes = ["rocksdb.wal.bytes", "rocksdb.flush.write.bytes", "rocksdb.compact.write.bytes"]
+			.iter()
+			.map(|ticker| ticker_count(&stats, ticker))
+			.sum();
+		Ok(Some(bytes))
+	}
+
+	// The SST files of a compaction's inputs are only deleted once it completes, and overwritten or
+	// deleted keys take space until they are compacted away, so the size shows the space
+	// amplification of the LSM tree
+	async fn database_size(&self) -> Result<Option<u64>> {
+		Ok(Some(dir_size(Path::new(&self.db_path))))
+	}
 
-		// Insert users and create email index
-		for user in &users {
-			let key = user.id.to_string();
-			let value = Self::serialize(user)?;
-			batch.put_cf(&users_cf, key.as_bytes(), &value);
-
-			// Email index
-			batch.put_cf(
-				&users_email_index_cf,
-				format!("{}:{}", user.email, user.id).as_bytes(),
-				&[]
-			);
+	// The tickers count since the database was opened, and give the hit rate of the block cache;
+	// the properties are read at the end of the test
+	async fn engine_stats(&self) -> Result<Option<serde_json::Value>> {
+		let statistics = self.options.get_statistics().unwrap_or_default();
+		let mut stats = serde_json::Map::new();
+		for ticker in ENGINE_STATS_TICKERS {
+			stats.insert(ticker.to_string(), ticker_count(&statistics, ticker).into());
+		}
+		let hits = ticker_count(&statistics, "rocksdb.block.cache.hit");
+		let lookups = hits + ticker_count(&statistics, "rocksdb.block.cache.miss");
+		if lookups > 0 {
+			stats.insert("block_cache_hit_rate".to_string(), ((hits as f64) / (lookups as f64)).into());
 		}
 
-		// Insert products and create name index
-		for product in &products {
-			let key = product.id.to_string();
-			let value = Self::serialize(product)?;
-			batch.put_cf(&products_cf, key.as_bytes(), &value);
-
-			// Name index
-			batch.put_cf(
-				&products_name_index_cf,
-				format!("{}:{}", product.name, product.id).as_bytes(),
-				&[]
-			);
+		let properties = self.run_blocking(|db| {
+			let mut properties = Vec::new();
+			for property in ENGINE_STATS_DB_PROPERTIES {
+				properties.push((property, db.property_int_value(property)?.unwrap_or(0)));
+			}
+			for property in ENGINE_STATS_CF_PROPERTIES {
+				let mut total = 0;
+				for name in COLUMN_FAMILIES {
+					let cf = db.cf_handle(name).unwrap();
+					total += db.property_int_value_cf(&cf, property)?.unwrap_or(0);
+				}
+				properties.push((property, total));
+			}
+			Ok(properties)
+		}).await?;
+		for (property, value) in properties {
+			stats.insert(property.to_string(), value.into());
 		}
+		Ok(Some(serde_json::Value::Object(stats)))
+	}
+
+	// The records of the import have a column family of their own, with no schema to create, so
+	// creating its table empties it
+	async fn create_import_table(&self, _columns: &[ImportColumn]) -> Result<()> {
+		self.run_blocking(Self::clear_imported_records).await
+	}
+
+	async fn insert_imported_records(&self, records: Arc<Vec<ImportedRecord>>) -> Result<()> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let codec = self.codec;
+
+		self.run_parallel(records.len(), move |db, offset, len| {
+			let imported_records_cf = db.cf_handle(IMPORTED_RECORDS_CF).unwrap();
 
-		// Insert orders and create indexes
-		for order in &orders {
-			let key = order.id.to_string();
-			let value = Self::serialize(order)?;
-			batch.put_cf(&orders_cf, key.as_bytes(), &value);
-
-			// User ID index
-			batch.put_cf(
-				&orders_user_id_index_cf,
-				format!("{}:{}", order.user_id, order.id).as_bytes(),
-				&[]
-			);
-
-			// Product ID index
-			batch.put_cf(
-				&orders_product_id_index_cf,
-				format!("{}:{}", order.product_id, order.id).as_bytes(),
-				&[]
-			);
+			for record in &records[offset..offset + len] {
+				context.check_cancelled()?;
+				let mut batch = WriteBatch::default();
+				batch.put_cf(&imported_records_cf, codec.record_key(&record.id), codec.serialize(record)?);
+
+				Self::write_batch(db, retry, &context, batch)?;
+				context.record_operation();
+			}
+
+			Ok(())
+		}).await
+	}
+
+	async fn read_imported_records(&self, ids: Arc<Vec<Uuid>>) -> Result<()> {
+		let context = self.context.clone();
+		let codec = self.codec;
+
+		self.run_parallel(ids.len(), move |db, offset, len| {
+			let imported_records_cf = db.cf_handle(IMPORTED_RECORDS_CF).unwrap();
+
+			for id in &ids[offset..offset + len] {
+				context.check_cancelled()?;
+				if let Some(bytes) = db.get_cf(&imported_records_cf, codec.record_key(id))? {
+					let _record: ImportedRecord = codec.deserialize(&bytes)?;
+				}
+				context.record_operation();
+			}
+
+			Ok(())
+		}).await
+	}
+
+	// A read-modify-write of the whole record, which is stored in a single value
+	async fn update_imported_records(
+		&self,
+		column: usize,
+		updates: Arc<Vec<(Uuid, Option<WideValue>)>>
+	) -> Result<()> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let codec = self.codec;
+
+		self.run_blocking(move |db| {
+			let imported_records_cf = db.cf_handle(IMPORTED_RECORDS_CF).unwrap();
+
+			for (id, value) in updates.iter() {
+				context.check_cancelled()?;
+				let key = codec.record_key(id);
+				if let Some(bytes) = db.get_cf(&imported_records_cf, &key)? {
+					let mut record: ImportedRecord = codec.deserialize(&bytes)?;
+					record.values[column] = value.clone();
+
+					let mut batch = WriteBatch::default();
+					batch.put_cf(&imported_records_cf, key, codec.serialize(&record)?);
+					Self::write_batch(db, retry, &context, batch)?;
+				}
+				context.record_operation();
+			}
+
+			Ok(())
+		}).await
+	}
+
+	async fn drop_import_table(&self) -> Result<()> {
+		self.run_blocking(Self::clear_imported_records).await
+	}
+
+	fn written_record_bytes(&self, test: BenchmarkTest) -> Option<u64> {
+		let payload = self.context.payload();
+		match test {
+			| BenchmarkTest::InsertSingleManyTimes
+			| BenchmarkTest::InsertManyAtOnce
+			| BenchmarkTest::ConcurrentInserts
+			| BenchmarkTest::UpdateSingleFieldOneEntry
+			| BenchmarkTest::UpdateSingleFieldManyEntries => Some(payload.user_record_bytes()),
+			| BenchmarkTest::UpdateMultipleFieldsOneEntry
+			| BenchmarkTest::UpdateMultipleFieldsManyEntries => Some(payload.product_record_bytes()),
+			// Orders have two items on average
+			BenchmarkTest::InsertOrdersWithItems => Some(ORDER_RECORD_BYTES + 2 * ORDER_ITEM_RECORD_BYTES),
+			BenchmarkTest::InsertWideRecords => Some(WIDE_RECORD_BYTES),
+			_ => None,
 		}
+	}
 
-		// Write all data at once
-		db.write(batch)?;
+	// Flushed memtables leave nothing in the write-ahead log to checkpoint
+	async fn flush(&self) -> Result<()> {
+		self.run_blocking(|db| {
+			// Persist the memtables and the write-ahead log so nothing is left to replay
+			db.flush()?;
+			for cf_name in COLUMN_FAMILIES {
+				let cf = db.cf_handle(cf_name).unwrap();
+				db.flush_cf(&cf)?;
+			}
+			db.flush_wal(true)?;
 
+			Ok(())
+		}).await
+	}
+
+	fn database_name(&self) -> String {
+		"RocksDB".to_string()
+	}
+
+	async fn set_cpu_count(&mut self, count: usize) -> Result<()> {
+		self.cpu_count = count;
 		Ok(())
 	}
 
-	async fn cleanup(&self) -> Result<()> {
-		let db = self.db.lock().await;
+	fn get_cpu_count(&self) -> usize {
+		self.cpu_count
+	}
+
+	fn set_index_mode(&mut self, indexes: IndexMode) {
+		self.indexes = indexes;
+	}
+
+	fn set_key_encoding(&mut self, encoding: KeyEncoding) {
+		self.codec.keys = encoding;
+	}
+
+	fn set_timestamp_encoding(&mut self, encoding: TimestampEncoding) {
+		self.codec.timestamps = encoding;
+	}
+
+	fn run_context(&self) -> &RunContext {
+		&self.context
+	}
+
+	async fn count_records(&self) -> Result<RecordCounts> {
+		self.run_blocking(|db| {
+			Ok(RecordCounts {
+				users: Self::count_keys(db, USERS_CF)?,
+				products: Self::count_keys(db, PRODUCTS_CF)?,
+				orders: Self::count_keys(db, ORDERS_CF)?,
+				order_items: Self::count_keys(db, ORDER_ITEMS_CF)?,
+				wide_records: Self::count_keys(db, WIDE_RECORDS_CF)?,
+			})
+		}).await
+	}
+
+	async fn verify_test(&self, test: BenchmarkTest, count: usize) -> Result<()> {
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+		// Values written by the last iteration of the single entry update tests
+		let last = count.saturating_sub(1);
+		// Under a distribution, the entry updated last by update_single_field_one_entry is random
+		let fixed_access = self.context.distribution().is_none();
+		let adversarial = self.context.payload().text == TextMode::Adversarial;
+
+		self.run_blocking(move |db| {
+			// Records the test updated that don't hold the values it wrote
+			let stale = match test {
+				BenchmarkTest::UpdateSingleFieldOneEntry if fixed_access => {
+					Self::count_stale(db, codec, USERS_CF, count.min(1), |user: &User| {
+						user.active != last.is_multiple_of(2)
+					})?
+				}
+				BenchmarkTest::UpdateSingleFieldManyEntries => {
+					Self::count_stale(db, codec, USERS_CF, count, |user: &User| !user.active)?
+				}
+				BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+					Self::count_stale(db, codec, PRODUCTS_CF, count.min(1), |product: &Product| {
+						product.description != format!("Updated description {}", last) ||
+							product.stock != 100 + ((last % 50) as i32)
+					})?
+				}
+				BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+					Self::count_stale(db, codec, PRODUCTS_CF, count, |product: &Product| {
+						!product.description.starts_with("Bulk updated description ")
+					})?
+				}
+				_ => 0,
+			};
+			anyhow::ensure!(stale == 0, "{} updated records don't hold the values written by the test", stale);
+
+			// Names not holding the adversarial text they were generated with
+			if adversarial {
+				let garbled =
+					Self::count_stale(db, codec, USERS_CF, usize::MAX, |user: &User| {
+						!holds_text(&user.id, &user.name)
+					})? +
+					Self::count_stale(db, codec, PRODUCTS_CF, usize::MAX, |product: &Product| {
+						!holds_text(&product.id, &product.name)
+					})?;
+				anyhow::ensure!(garbled == 0, "{} names don't read back as they were written", garbled);
+			}
+
+			// Orders referencing a user or product that no longer exists
+			let users_cf = db.cf_handle(USERS_CF).unwrap();
+			let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+			let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
+			let mut dangling = 0;
+			for result in db.iterator_cf(&orders_cf, IteratorMode::Start) {
+				let (_, value) = result?;
+				let order: Order = codec.deserialize(&value)?;
+				if
+					db.get_cf(&users_cf, codec.record_key(&order.user_id))?.is_none() ||
+					db.get_cf(&products_cf, codec.record_key(&order.product_id))?.is_none()
+				{
+					dangling += 1;
+				}
+			}
 
-		// Clear all column families
-		let cf_names = vec![
-			USERS_CF,
-			PRODUCTS_CF,
-			ORDERS_CF,
-			USERS_EMAIL_INDEX_CF,
-			PRODUCTS_NAME_INDEX_CF,
-			ORDERS_USER_ID_INDEX_CF,
-			ORDERS_PRODUCT_ID_INDEX_CF
-		];
+			// Order items referencing an order or product that no longer exists
+			let order_items_cf = db.cf_handle(ORDER_ITEMS_CF).unwrap();
+			for result in db.iterator_cf(&order_items_cf, IteratorMode::Start) {
+				let (_, value) = result?;
+				let item: OrderItem = codec.deserialize(&value)?;
+				if
+					db.get_cf(&orders_cf, codec.record_key(&item.order_id))?.is_none() ||
+					db.get_cf(&products_cf, codec.record_key(&item.product_id))?.is_none()
+				{
+					dangling += 1;
+				}
+			}
+			anyhow::ensure!(dangling == 0, "{} orders or order items reference a missing record", dangling);
+
+			// The index column families are only maintained while the indexes are on
+			if indexed {
+				Self::check_index(db, codec, USERS_EMAIL_INDEX_CF, USERS_CF, |user: &User| {
+					user.email.clone().into_bytes()
+				})?;
+				Self::check_index(db, codec, PRODUCTS_NAME_INDEX_CF, PRODUCTS_CF, |product: &Product| {
+					product.name.clone().into_bytes()
+				})?;
+				Self::check_index(db, codec, ORDERS_USER_ID_INDEX_CF, ORDERS_CF, |order: &Order| {
+					codec.record_key(&order.user_id)
+				})?;
+				Self::check_index(db, codec, ORDERS_PRODUCT_ID_INDEX_CF, ORDERS_CF, |order: &Order| {
+					codec.record_key(&order.product_id)
+				})?;
+				Self::check_index(db, codec, ORDERS_TENANT_ID_INDEX_CF, ORDERS_CF, |order: &Order| {
+					order.tenant_id.to_be_bytes().to_vec()
+				})?;
+			}
 
-		for cf_name in cf_names {
-			let cf = db.cf_handle(cf_name).unwrap();
+			Ok(())
+		}).await
+	}
 
-			// Iterate over all keys and delete them
-			let iter = db.iterator_cf(&cf, IteratorMode::Start);
-			let mut batch = WriteBatch::default();
+	async fn prepare(&self, test: BenchmarkTest, count: usize) -> Result<Fixture> {
+		let mut fixture = Fixture::default();
+		let payload = self.context.payload();
+		let tenants = self.context.tenants();
+		let key_strategy = self.context.key_strategy();
+
+		match test {
+			BenchmarkTest::InsertSingleManyTimes |
+			BenchmarkTest::InsertManyAtOnce |
+			BenchmarkTest::ConcurrentInserts => {
+				fixture.users = Arc::new((0..count).map(|_| generate_random_user(payload, tenants, key_strategy)).collect());
+			}
+			BenchmarkTest::ReadByIdManyTimes => {
+				let mut keys = self.run_blocking(move |db| Self::first_keys(db, USERS_CF, count)).await?;
+				anyhow::ensure!(!keys.is_empty(), "No users found to read");
+				if let Some(distribution) = self.context.distribution() {
+					keys = distribution::sample(distribution, &keys, count);
+				}
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::ReadManyByIds => {
+				let keys = self.run_blocking(move |db| Self::first_keys(db, USERS_CF, count)).await?;
+				anyhow::ensure!(!keys.is_empty(), "No users found to read");
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::ConsistencyProbe => {
+				let keys = self.run_blocking(|db| Self::first_keys(db, PRODUCTS_CF, 1)).await?;
+				anyhow::ensure!(!keys.is_empty(), "No product found to count with");
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::UpdateSingleFieldOneEntry => {
+				// Under a distribution, each update goes to an entry drawn from the first `count` users
+				let distribution = self.context.distribution();
+				let limit = if distribution.is_some() { count } else { 1 };
+				let mut keys = self.run_blocking(move |db| Self::first_keys(db, USERS_CF, limit)).await?;
+				if keys.is_empty() {
+					return Err(anyhow::anyhow!("No users found for update"));
+				}
+				if let Some(distribution) = distribution {
+					keys = distribution::sample(distribution, &keys, count);
+				}
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+				let keys = self.run_blocking(|db| Self::first_keys(db, PRODUCTS_CF, 1)).await?;
+				if keys.is_empty() {
+					return Err(anyhow::anyhow!("No products found for update"));
+				}
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::UpdateSingleFieldManyEntries => {
+				let keys = self.run_blocking(move |db| Self::first_keys(db, USERS_CF, count)).await?;
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+				let keys = self.run_blocking(move |db| Self::first_keys(db, PRODUCTS_CF, count)).await?;
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::InsertOrdersWithItems => {
+				let codec = self.codec;
+				let (user_ids, product_ids) = self.run_blocking(move |db| {
+					let parse = |keys: Vec<Box<[u8]>>| {
+						keys.iter().map(|key| codec.parse_key(key)).collect::<Result<Vec<_>>>()
+					};
+					Ok((
+						parse(Self::first_keys(db, USERS_CF, GENERATION_CHUNK_SIZE)?)?,
+						parse(Self::first_keys(db, PRODUCTS_CF, GENERATION_CHUNK_SIZE)?)?,
+					))
+				}).await?;
+				anyhow::ensure!(
+					!user_ids.is_empty() && !product_ids.is_empty(),
+					"No users or products found to order"
+				);
+				fixture.orders = Arc::new(generate_orders_with_items(
+					count,
+					payload,
+					&user_ids,
+					&product_ids,
+					tenants,
+					key_strategy
+				));
+			}
+			BenchmarkTest::InsertWideRecords => {
+				fixture.wide_records = Arc::new((0..count).map(|_| generate_wide_record(key_strategy)).collect());
+			}
+			BenchmarkTest::ReadWideRecordsById => {
+				let keys = self.run_blocking(move |db| Self::first_keys(db, WIDE_RECORDS_CF, count)).await?;
+				anyhow::ensure!(!keys.is_empty(), "No wide records found to read");
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::SoftDeleteUsers => {
+				let codec = self.codec;
+				let keys = self.run_blocking(move |db| Self::live_user_keys(db, codec, count)).await?;
+				anyhow::ensure!(!keys.is_empty(), "No live users found to soft-delete");
+				fixture.keys = Arc::new(keys);
+			}
+			BenchmarkTest::ReadLiveUsers => {
+				// Soft-delete users until half of them are
+				let codec = self.codec;
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let (mut users, mut deleted) = (0, 0);
+					for result in db.iterator_cf(&users_cf, IteratorMode::Start) {
+						let (_, value) = result?;
+						users += 1;
+						if codec.deserialize::<User>(&value)?.deleted_at.is_some() {
+							deleted += 1;
+						}
+					}
 
-			for result in iter {
-				let (key, _) = result?;
-				batch.delete_cf(&cf, &key);
+					let mut batch = WriteBatch::default();
+					for key in Self::live_user_keys(db, codec, users_to_soft_delete(users, deleted))? {
+						if let Some(bytes) = db.get_cf(&users_cf, &key)? {
+							let mut user: User = codec.deserialize(&bytes)?;
+							user.deleted_at = Some(Utc::now());
+							batch.put_cf(&users_cf, &key, codec.serialize(&user)?);
+						}
+					}
+					db.write(batch)?;
+					Ok(())
+				}).await?;
 			}
+			// The search, column subset and tenant tests walk their records in the loop they time
+			| BenchmarkTest::ReadByColumnSearch
+			| BenchmarkTest::ReadWideColumnSubset
+			| BenchmarkTest::ReadTenantOrders
+			| BenchmarkTest::AggregateOrdersByTenant
+			| BenchmarkTest::ReadUsersNotDeleted
+			| BenchmarkTest::ReadDiscountedProducts
+			| BenchmarkTest::ReadProjection => {}
+			// Skipped, as RocksDB has no joins and no transactions
+			| BenchmarkTest::ReadWithOneJoin
+			| BenchmarkTest::ReadWithTwoJoins
+			| BenchmarkTest::ReadOrdersWithItems
+			| BenchmarkTest::LongTransactionContention => {}
+		}
+
+		Ok(fixture)
+	}
 
-			db.write(batch)?;
+	fn traced_fixture(&self, step: TraceOp, chunk: &TraceChunk) -> Result<(BenchmarkTest, Fixture)> {
+		let mut fixture = Fixture::default();
+		if step == TraceOp::Insert {
+			fixture.users = Arc::new(chunk.users.clone());
+		} else {
+			fixture.keys = Arc::new(chunk.keys.iter().map(|id| self.codec.record_key(id).into_boxed_slice()).collect());
 		}
+		Ok((step.test(), fixture))
+	}
+
+	async fn insert_single_many_times(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = fixture.users;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::InsertSingleManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |db, offset, len| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
+
+					for user in &users[offset..offset + len] {
+						context.check_cancelled()?;
+						let key = codec.record_key(&user.id);
+						let value = codec.serialize(user)?;
+
+						// Insert user
+						retry.run(&context, is_transient, || {
+							db.put_cf(&users_cf, &key, &value)
+						})?;
+
+						// Email index
+						if let Some(index_cf) = &users_email_index_cf {
+							retry.run(&context, is_transient, || {
+								db.put_cf(
+									index_cf,
+									codec.index_key(&user.email, &user.id),
+									&[]
+								)
+							})?;
+						}
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn insert_many_at_once(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = fixture.users;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::InsertManyAtOnce.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
+
+					// Each batch of users is written as its own WriteBatch
+					for chunk in users.chunks(batch_len) {
+						let start = Instant::now();
+						let mut batch = WriteBatch::default();
+
+						for user in chunk {
+							context.check_cancelled()?;
+							let key = codec.record_key(&user.id);
+							let value = codec.serialize(user)?;
+
+							// Insert user
+							batch.put_cf(&users_cf, &key, &value);
+
+							// Email index
+							if let Some(index_cf) = &users_email_index_cf {
+								batch.put_cf(
+									index_cf,
+									codec.index_key(&user.email, &user.id),
+									&[]
+								);
+							}
+						}
+
+						Self::write_batch(db, retry, &context, batch)?;
+						context.record_batch(chunk.len(), start.elapsed());
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_by_id_many_times(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
+		let ids = fixture.keys;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadByIdManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |db, offset, len| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
 
-		Ok(())
-	}
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let id = &ids[i % ids.len()];
 
-	fn database_name(&self) -> String {
-		"RocksDB".to_string()
-	}
+						let value = db.get_cf(&users_cf, id)?;
 
-	fn set_cpu_count(&mut self, count: usize) {
-		self.cpu_count = count;
-	}
+						if let Some(bytes) = value {
+							let _user: User = codec.deserialize(&bytes)?;
+						}
+						context.record_operation();
+					}
 
-	fn get_cpu_count(&self) -> usize {
-		self.cpu_count
+					Ok(())
+				}).await
+			}
+		).await
 	}
 
-	async fn insert_single_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn read_many_by_ids(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
+		let ids = fixture.keys;
 
 		measure_execution(
 			&self.database_name(),
-			"Insert Single Many Times",
+			BenchmarkTest::ReadManyByIds.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-				let users_email_index_cf = db.cf_handle(USERS_EMAIL_INDEX_CF).unwrap();
+				// Each worker fetches its own slice of the IDs
+				self.run_parallel(ids.len(), move |db, offset, len| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
 
-				for _ in 0..count {
-					let user = generate_random_user();
-					let key = user.id.to_string();
-					let value = Self::serialize(&user)?;
+					let mut users = Vec::with_capacity(len);
 
-					// Insert user
-					db.put_cf(&users_cf, key.as_bytes(), &value)?;
+					for id in &ids[offset..offset + len] {
+						context.check_cancelled()?;
+						let value = db.get_cf(&users_cf, id)?;
 
-					// Email index
-					db.put_cf(
-						&users_email_index_cf,
-						format!("{}:{}", user.email, user.id).as_bytes(),
-						&[]
-					)?;
-				}
+						if let Some(bytes) = value {
+							let user: User = codec.deserialize(&bytes)?;
+							users.push(user);
+						}
+						context.record_operation();
+					}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn insert_many_at_once(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn read_by_column_search(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
+		let indexed = self.indexes.is_on();
+		let suffix = search_suffix(self.context.selectivity());
 
 		measure_execution(
 			&self.database_name(),
-			"Insert Many At Once",
+			BenchmarkTest::ReadByColumnSearch.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let users: Vec<User> = (0..count)
-					.map(|_| generate_random_user())
-					.collect();
-
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-				let users_email_index_cf = db.cf_handle(USERS_EMAIL_INDEX_CF).unwrap();
+				self.run_blocking(move |db| {
+					let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+
+					// Read the matching users up to the count, in a single pass over the emails
+					context.check_cancelled()?;
+					let users = match &users_email_index_cf {
+						Some(index_cf) => Self::search_email_index(db, codec, index_cf, &users_cf, &suffix, count)?,
+						None => Self::scan_emails(db, codec, &users_cf, &suffix, count)?,
+					};
+					context.record_operations(users.len() as u64);
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-				let mut batch = WriteBatch::default();
+	async fn update_single_field_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
 
-				for user in &users {
-					let key = user.id.to_string();
-					let value = Self::serialize(user)?;
+		let user_ids = fixture.keys;
 
-					// Insert user
-					batch.put_cf(&users_cf, key.as_bytes(), &value);
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateSingleFieldOneEntry.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
 
-					// Email index
-					batch.put_cf(
-						&users_email_index_cf,
-						format!("{}:{}", user.email, user.id).as_bytes(),
-						&[]
-					);
-				}
+					for i in 0..count {
+						context.check_cancelled()?;
+						// Read the user
+						let value = db.get_cf(&users_cf, &user_ids[i % user_ids.len()])?;
 
-				db.write(batch)?;
+						if let Some(bytes) = value {
+							let old: User = codec.deserialize(&bytes)?;
+							let mut user = old.clone();
+
+							// Update the active field
+							user.active = i % 2 == 0;
+
+							// Write back with its index entry in one atomic batch
+							let mut batch = WriteBatch::default();
+							Self::batch_user_update(
+								&mut batch,
+								codec,
+								&users_cf,
+								users_email_index_cf.as_ref(),
+								&old,
+								&user
+							)?;
+							Self::write_batch(db, retry, &context, batch)?;
+						}
+						context.record_operation();
+					}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn read_by_id_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn update_single_field_many_entries(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
 
-		// First get a list of IDs to fetch
-		let db = db_arc.lock().await;
-		let mut ids = Vec::with_capacity(count);
+		let user_ids = fixture.keys;
 
-		{
-			let users_cf = db.cf_handle(USERS_CF).unwrap();
-			let iter = db.iterator_cf(&users_cf, IteratorMode::Start);
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateSingleFieldManyEntries.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let users_email_index_cf = Self::index_cf(db, indexed, USERS_EMAIL_INDEX_CF);
+					// Each batch of updates is written as its own WriteBatch
+					for chunk in user_ids.chunks(batch_len) {
+						let mut batch = WriteBatch::default();
+
+						for user_id in chunk {
+							context.check_cancelled()?;
+							// Read the user
+							let value = db.get_cf(&users_cf, &user_id)?;
+
+							if let Some(bytes) = value {
+								let old: User = codec.deserialize(&bytes)?;
+								let mut user = old.clone();
+
+								// Update the active field
+								user.active = true;
+
+								// Add to batch
+								Self::batch_user_update(
+									&mut batch,
+									codec,
+									&users_cf,
+									users_email_index_cf.as_ref(),
+									&old,
+									&user
+								)?;
+							}
+						}
 
-			for (i, result) in iter.enumerate() {
-				if i >= count {
-					break;
-				}
+						Self::write_batch(db, retry, &context, batch)?;
+						context.record_operations(chunk.len() as u64);
+					}
 
-				let (key, _) = result?;
-				ids.push(String::from_utf8(key.to_vec())?);
+					Ok(())
+				}).await
 			}
-		}
+		).await
+	}
 
-		drop(db); // Release the lock
+	async fn update_multiple_fields_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+
+		let product_id = fixture.keys[0].clone();
 
 		measure_execution(
 			&self.database_name(),
-			"Read By ID Many Times",
+			BenchmarkTest::UpdateMultipleFieldsOneEntry.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-
-				for i in 0..count {
-					let id = &ids[i % ids.len()];
+				self.run_blocking(move |db| {
+					let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+					let products_name_index_cf = Self::index_cf(db, indexed, PRODUCTS_NAME_INDEX_CF);
 
-					let value = db.get_cf(&users_cf, id.as_bytes())?;
+					for i in 0..count {
+						context.check_cancelled()?;
+						// Read the product
+						let value = db.get_cf(&products_cf, &product_id)?;
 
-					if let Some(bytes) = value {
-						let _user: User = Self::deserialize(&bytes)?;
+						if let Some(bytes) = value {
+							let old: Product = codec.deserialize(&bytes)?;
+							let mut product = old.clone();
+
+							// Update multiple fields
+							product.price = 10.0 + ((i as f64) % 100.0);
+							product.stock = 100 + ((i as i32) % 50);
+							product.description = format!("Updated description {}", i);
+
+							// Write back with its index entry in one atomic batch
+							let mut batch = WriteBatch::default();
+							Self::batch_product_update(
+								&mut batch,
+								codec,
+								&products_cf,
+								products_name_index_cf.as_ref(),
+								&old,
+								&product
+							)?;
+							Self::write_batch(db, retry, &context, batch)?;
+						}
+						context.record_operation();
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn read_many_by_ids(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn update_multiple_fields_many_entries(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
 
-		// First get a list of IDs to fetch
-		let db = db_arc.lock().await;
-		let mut ids = Vec::with_capacity(count);
+		let product_ids = fixture.keys;
 
-		{
-			let users_cf = db.cf_handle(USERS_CF).unwrap();
-			let iter = db.iterator_cf(&users_cf, IteratorMode::Start);
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateMultipleFieldsManyEntries.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_blocking(move |db| {
+					let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+					let products_name_index_cf = Self::index_cf(db, indexed, PRODUCTS_NAME_INDEX_CF);
+					let update_time = chrono::Utc::now();
+
+					// Each batch of updates is written as its own WriteBatch
+					for chunk in product_ids.chunks(batch_len) {
+						let mut batch = WriteBatch::default();
+
+						for product_id in chunk {
+							context.check_cancelled()?;
+							// Read the product
+							let value = db.get_cf(&products_cf, &product_id)?;
+
+							if let Some(bytes) = value {
+								let old: Product = codec.deserialize(&bytes)?;
+								let mut product = old.clone();
+
+								// Update multiple fields
+								product.price *= 1.1;
+								product.stock += 10;
+								product.description = format!(
+									"Bulk updated description {}",
+									Uuid::new_v4()
+								);
+								product.created_at = update_time;
+
+								// Add to batch
+								Self::batch_product_update(
+									&mut batch,
+									codec,
+									&products_cf,
+									products_name_index_cf.as_ref(),
+									&old,
+									&product
+								)?;
+							}
+						}
 
-			for (i, result) in iter.enumerate() {
-				if i >= count {
-					break;
-				}
+						Self::write_batch(db, retry, &context, batch)?;
+						context.record_operations(chunk.len() as u64);
+					}
 
-				let (key, _) = result?;
-				ids.push(String::from_utf8(key.to_vec())?);
+					Ok(())
+				}).await
 			}
-		}
+		).await
+	}
 
-		drop(db); // Release the lock
+	async fn insert_orders_with_items(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let orders = fixture.orders;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Read Many By IDs",
+			BenchmarkTest::InsertOrdersWithItems.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-
-				let mut users = Vec::with_capacity(ids.len());
-
-				for id in &ids {
-					let value = db.get_cf(&users_cf, id.as_bytes())?;
+				self.run_parallel(count, move |db, offset, len| {
+					let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
+					let order_items_cf = db.cf_handle(ORDER_ITEMS_CF).unwrap();
+					let orders_user_id_index_cf = Self::index_cf(db, indexed, ORDERS_USER_ID_INDEX_CF);
+					let orders_product_id_index_cf = Self::index_cf(
+						db,
+						indexed,
+						ORDERS_PRODUCT_ID_INDEX_CF
+					);
+					let orders_tenant_id_index_cf = Self::index_cf(db, indexed, ORDERS_TENANT_ID_INDEX_CF);
+
+					// Each order is written with its items and index entries as one atomic WriteBatch
+					for order in &orders[offset..offset + len] {
+						context.check_cancelled()?;
+						let mut batch = WriteBatch::default();
+
+						Self::batch_order(
+							&mut batch,
+							codec,
+							&orders_cf,
+							orders_user_id_index_cf.as_ref(),
+							orders_product_id_index_cf.as_ref(),
+							orders_tenant_id_index_cf.as_ref(),
+							&order.order
+						)?;
+						for item in &order.items {
+							batch.put_cf(&order_items_cf, codec.item_key(item), codec.serialize(item)?);
+						}
 
-					if let Some(bytes) = value {
-						let user: User = Self::deserialize(&bytes)?;
-						users.push(user);
+						Self::write_batch(db, retry, &context, batch)?;
+						context.record_operation();
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn read_by_column_search(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn insert_wide_records(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let records = fixture.wide_records;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Read By Column Search",
+			BenchmarkTest::InsertWideRecords.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_email_index_cf = db.cf_handle(USERS_EMAIL_INDEX_CF).unwrap();
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
+				self.run_parallel(count, move |db, offset, len| {
+					let wide_records_cf = db.cf_handle(WIDE_RECORDS_CF).unwrap();
 
-				// Scan through email index
-				let iter = db.iterator_cf(&users_email_index_cf, IteratorMode::Start);
-				let mut users = Vec::with_capacity(count);
+					for record in &records[offset..offset + len] {
+						context.check_cancelled()?;
+						let mut batch = WriteBatch::default();
+						batch.put_cf(&wide_records_cf, codec.record_key(&record.id), codec.serialize(record)?);
 
-				for (i, result) in iter.enumerate() {
-					if i >= count {
-						break;
+						Self::write_batch(db, retry, &context, batch)?;
+						context.record_operation();
 					}
 
-					let (key, _) = result?;
-					let key_str = String::from_utf8(key.to_vec())?;
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					// Extract user ID from the index key (format: "email:id")
-					if key_str.contains("example.com") {
-						let user_id = key_str.split(':').nth(1).unwrap_or_default();
+	async fn read_wide_records_by_id(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
+		let ids = fixture.keys;
 
-						let value = db.get_cf(&users_cf, user_id.as_bytes())?;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWideRecordsById.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |db, offset, len| {
+					let wide_records_cf = db.cf_handle(WIDE_RECORDS_CF).unwrap();
 
-						if let Some(bytes) = value {
-							let user: User = Self::deserialize(&bytes)?;
-							users.push(user);
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let id = &ids[i % ids.len()];
 
-							if users.len() >= count {
-								break;
-							}
+						if let Some(bytes) = db.get_cf(&wide_records_cf, id)? {
+							let _record: WideRecord = codec.deserialize(&bytes)?;
 						}
+						context.record_operation();
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn read_with_one_join(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn read_wide_column_subset(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Read With One Join",
+			BenchmarkTest::ReadWideColumnSubset.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-
-				// Get orders
-				let iter = db.iterator_cf(&orders_cf, IteratorMode::Start);
-				let mut results = Vec::with_capacity(count);
-
-				for (i, result) in iter.enumerate() {
-					if i >= count {
-						break;
+				self.run_blocking(move |db| {
+					let wide_records_cf = db.cf_handle(WIDE_RECORDS_CF).unwrap();
+					let mut results = Vec::with_capacity(count);
+
+					// Rows are stored whole, so every column is read and decoded to keep a few
+					for result in db.iterator_cf(&wide_records_cf, IteratorMode::Start).take(count) {
+						context.check_cancelled()?;
+						let (_, value) = result?;
+						let record: WideRecord = codec.deserialize(&value)?;
+
+						let values: Vec<_> = WIDE_SUBSET_COLUMNS.iter()
+							.map(|&column| record.values[column].clone())
+							.collect();
+						results.push(values);
+						context.record_operation();
 					}
 
-					let (_, value) = result?;
-					let order: Order = Self::deserialize(&value)?;
-
-					// Get the associated user (this is the "join")
-					let user_key = order.user_id.to_string();
-					let user_value = db.get_cf(&users_cf, user_key.as_bytes())?;
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-					if let Some(user_bytes) = user_value {
-						let user: User = Self::deserialize(&user_bytes)?;
+	async fn read_tenant_orders(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
+		let indexed = self.indexes.is_on();
+		let tenants = self.context.tenants();
 
-						// Combine order and user (similar to a join result)
-						results.push((order, user));
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadTenantOrders.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel(count, move |db, offset, len| {
+					let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
+					let orders_tenant_id_index_cf = Self::index_cf(db, indexed, ORDERS_TENANT_ID_INDEX_CF);
+
+					// Each read is a page of the orders of the next tenant
+					for i in offset..offset + len {
+						context.check_cancelled()?;
+						let _orders = Self::tenant_orders(
+							db,
+							codec,
+							&orders_cf,
+							orders_tenant_id_index_cf.as_ref(),
+							(i % tenants) as u32,
+							TENANT_PAGE_LEN
+						)?;
+						context.record_operation();
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn read_with_two_joins(&self, count: usize) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
+	async fn aggregate_orders_by_tenant(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Read With Two Joins",
+			BenchmarkTest::AggregateOrdersByTenant.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-				let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-
-				// Get orders
-				let iter = db.iterator_cf(&orders_cf, IteratorMode::Start);
-				let mut results = Vec::with_capacity(count);
-
-				for (i, result) in iter.enumerate() {
-					if i >= count {
-						break;
-					}
-
-					let (_, value) = result?;
-					let order: Order = Self::deserialize(&value)?;
-
-					// Get the associated user (first "join")
-					let user_key = order.user_id.to_string();
-					let user_value = db.get_cf(&users_cf, user_key.as_bytes())?;
-
-					// Get the associated product (second "join")
-					let product_key = order.product_id.to_string();
-					let product_value = db.get_cf(&products_cf, product_key.as_bytes())?;
-
-					if
-						let (Some(user_bytes), Some(product_bytes)) = (
-							user_value,
-							product_value,
-						)
-					{
-						let user: User = Self::deserialize(&user_bytes)?;
-						let product: Product = Self::deserialize(&product_bytes)?;
-
-						// Combine order, user, and product (similar to a join result)
-						results.push(OrderWithDetails {
-							id: order.id,
-							quantity: order.quantity,
-							total_price: order.total_price,
-							created_at: order.created_at,
-							user,
-							product,
-						});
+				self.run_parallel(count, move |db, _offset, len| {
+					let orders_cf = db.cf_handle(ORDERS_CF).unwrap();
+
+					// Without a query engine, each aggregation scans every order and sums its
+					// orders, customers and revenue per tenant
+					for _ in 0..len {
+						let mut tenants: BTreeMap<u32, (u64, HashSet<Uuid>, f64)> = BTreeMap::new();
+						for result in db.iterator_cf(&orders_cf, IteratorMode::Start) {
+							context.check_cancelled()?;
+							let (_, value) = result?;
+							let order: Order = codec.deserialize(&value)?;
+
+							let (orders, customers, revenue) = tenants.entry(order.tenant_id).or_default();
+							*orders += 1;
+							customers.insert(order.user_id);
+							*revenue += order.total_price;
+						}
+						context.record_operation();
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn update_single_field_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
-
-		// Get a random user ID to update
-		let db = db_arc.lock().await;
-		let user_id;
-
-		{
-			let users_cf = db.cf_handle(USERS_CF).unwrap();
-			let iter = db.iterator_cf(&users_cf, IteratorMode::Start);
-
-			user_id = match iter.take(1).next() {
-				Some(Ok((key, _))) => String::from_utf8(key.to_vec())?,
-				_ => {
-					return Err(anyhow::anyhow!("No users found for update"));
-				}
-			};
-		}
-
-		drop(db); // Release the lock
+	async fn read_users_not_deleted(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Update Single Field One Entry",
+			BenchmarkTest::ReadUsersNotDeleted.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-
-				for i in 0..count {
-					// Read the user
-					let value = db.get_cf(&users_cf, user_id.as_bytes())?;
-
-					if let Some(bytes) = value {
-						let mut user: User = Self::deserialize(&bytes)?;
-
-						// Update the active field
-						user.active = i % 2 == 0;
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let mut users = Vec::with_capacity(count);
+
+					// Without an index on the optional field, every user is decoded until enough
+					// of them pass the filter
+					for result in db.iterator_cf(&users_cf, IteratorMode::Start) {
+						if users.len() == count {
+							break;
+						}
+						context.check_cancelled()?;
+						let (_, value) = result?;
+						let user: User = codec.deserialize(&value)?;
 
-						// Write back
-						db.put_cf(
-							&users_cf,
-							user_id.as_bytes(),
-							Self::serialize(&user)?
-						)?;
+						if user.deleted_at.is_none() {
+							users.push(user);
+							context.record_operation();
+						}
 					}
-				}
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn update_single_field_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
-
-		// Get user IDs to update
-		let db = db_arc.lock().await;
-		let mut user_ids = Vec::with_capacity(count);
+	async fn read_discounted_products(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
 
-		{
-			let users_cf = db.cf_handle(USERS_CF).unwrap();
-			let iter = db.iterator_cf(&users_cf, IteratorMode::Start);
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadDiscountedProducts.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_blocking(move |db| {
+					let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+					let mut products = Vec::with_capacity(count);
+
+					// Every product is decoded until enough of them are on discount
+					for result in db.iterator_cf(&products_cf, IteratorMode::Start) {
+						if products.len() == count {
+							break;
+						}
+						context.check_cancelled()?;
+						let (_, value) = result?;
+						let product: Product = codec.deserialize(&value)?;
 
-			for (i, result) in iter.enumerate() {
-				if i >= count {
-					break;
-				}
+						if product.discount.is_some() {
+							products.push(product);
+							context.record_operation();
+						}
+					}
 
-				let (key, _) = result?;
-				user_ids.push(String::from_utf8(key.to_vec())?);
+					Ok(())
+				}).await
 			}
-		}
+		).await
+	}
 
-		drop(db); // Release the lock
+	async fn soft_delete_users(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let codec = self.codec;
+
+		let user_ids = fixture.keys;
 
 		measure_execution(
 			&self.database_name(),
-			"Update Single Field Many Entries",
+			BenchmarkTest::SoftDeleteUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let users_cf = db.cf_handle(USERS_CF).unwrap();
-				let mut batch = WriteBatch::default();
-
-				for user_id in &user_ids {
-					// Read the user
-					let value = db.get_cf(&users_cf, user_id.as_bytes())?;
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
 
-					if let Some(bytes) = value {
-						let mut user: User = Self::deserialize(&bytes)?;
+					for i in 0..count {
+						context.check_cancelled()?;
+						let key = &user_ids[i % user_ids.len()];
 
-						// Update the active field
-						user.active = true;
+						// The email stays the same, so its index entry does too
+						if let Some(bytes) = db.get_cf(&users_cf, key)? {
+							let mut user: User = codec.deserialize(&bytes)?;
+							user.deleted_at = Some(Utc::now());
 
-						// Add to batch
-						batch.put_cf(
-							&users_cf,
-							user_id.as_bytes(),
-							Self::serialize(&user)?
-						);
+							let mut batch = WriteBatch::default();
+							batch.put_cf(&users_cf, key, codec.serialize(&user)?);
+							Self::write_batch(db, retry, &context, batch)?;
+						}
+						context.record_operation();
 					}
-				}
 
-				// Write all updates at once
-				db.write(batch)?;
-
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn update_multiple_fields_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
-
-		// Get a random product ID to update
-		let db = db_arc.lock().await;
-		let product_id;
-
-		{
-			let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-			let iter = db.iterator_cf(&products_cf, IteratorMode::Start);
-
-			product_id = match iter.take(1).next() {
-				Some(Ok((key, _))) => String::from_utf8(key.to_vec())?,
-				_ => {
-					return Err(anyhow::anyhow!("No products found for update"));
-				}
-			};
-		}
-
-		drop(db); // Release the lock
+	async fn read_live_users(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = self.codec;
 
 		measure_execution(
 			&self.database_name(),
-			"Update Multiple Fields One Entry",
+			BenchmarkTest::ReadLiveUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let db = db_arc.lock().await;
-				let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-
-				for i in 0..count {
-					// Read the product
-					let value = db.get_cf(&products_cf, product_id.as_bytes())?;
-
-					if let Some(bytes) = value {
-						let mut product: Product = Self::deserialize(&bytes)?;
-
-						// Update multiple fields
-						product.price = 10.0 + ((i as f64) % 100.0);
-						product.stock = 100 + ((i as i32) % 50);
-						product.description = format!("Updated description {}", i);
-
-						// Write back
-						db.put_cf(
-							&products_cf,
-							product_id.as_bytes(),
-							Self::serialize(&product)?
-						)?;
+				self.run_blocking(move |db| {
+					let users_cf = db.cf_handle(USERS_CF).unwrap();
+					let mut users = Vec::new();
+
+					// Without an index on the creation time, every user is decoded and the live
+					// ones sorted to find the newest
+					for result in db.iterator_cf(&users_cf, IteratorMode::Start) {
+						context.check_cancelled()?;
+						let (_, value) = result?;
+						let user: User = codec.deserialize(&value)?;
+
+						if user.deleted_at.is_none() {
+							users.push(user);
+						}
 					}
-				}
+					users.sort_unstable_by_key(|user| Reverse(user.created_at));
+					users.truncate(count);
+					context.record_operations(users.len() as u64);
 
-				Ok(())
+					Ok(())
+				}).await
 			}
 		).await
 	}
 
-	async fn update_multiple_fields_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let db_arc = self.db.clone();
-
-		// Get product IDs to update
-		let db = db_arc.lock().await;
-		let mut product_ids = Vec::with_capacity(count);
+	async fn concurrent_inserts(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = fixture.users;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let indexed = self.indexes.is_on();
+		let codec = self.codec;
+		let db = self.db.read().unwrap().clone();
 
-		{
-			let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-			let iter = db.iterator_cf(&products_cf, IteratorMode::Start);
+		// RocksDB takes concurrent writes: each writer puts its users from a blocking thread of its own
+		measure_concurrent_inserts(
+			&self.database_name(),
+			count,
+			writers,
+			self.cpu_count,
+			|_, range| {
+				let db = db.clone();
+				let users = users.clone();
+				let context = context.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						let users_cf = db.cf_handle(USERS_CF).unwrap();
+						let users_email_index_cf = Self::index_cf(&db, indexed, USERS_EMAIL_INDEX_CF);
+
+						for user in &users[range] {
+							context.check_cancelled()?;
+							let key = codec.record_key(&user.id);
+							let value = codec.serialize(user)?;
+							retry.run(&context, is_transient, || db.put_cf(&users_cf, &key, &value))?;
+							if let Some(index_cf) = &users_email_index_cf {
+								retry.run(&context, is_transient, || {
+									db.put_cf(index_cf, codec.index_key(&user.email, &user.id), &[])
+								})?;
+							}
+							context.record_operation();
+						}
 
-			for (i, result) in iter.enumerate() {
-				if i >= count {
-					break;
+						Ok(())
+					}).await?
 				}
-
-				let (key, _) = result?;
-				product_ids.push(String::from_utf8(key.to_vec())?);
 			}
-		}
+		).await
+	}
 
-		drop(db); // Release the lock
+	async fn consistency_probe(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let product_key = fixture.keys[0].clone();
+		let context = self.context.clone();
+		let codec = self.codec;
+		let db = self.db.read().unwrap().clone();
 
-		measure_execution(
+		// A plain RocksDB database has no transactions: an increment reads the product and writes
+		// it back, overwriting any increment written in between
+		measure_consistency(
 			&self.database_name(),
-			"Update Multiple Fields Many Entries",
 			count,
+			writers,
 			self.cpu_count,
-			|| async {
-				let db = db_arc.lock().await;
-				let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
-				let mut batch = WriteBatch::default();
-
-				let update_time = chrono::Utc::now();
+			|| {
+				let product_key = product_key.clone();
+				self.run_blocking(move |db| {
+					let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+					let bytes = db
+						.get_cf(&products_cf, &product_key)?
+						.ok_or_else(|| anyhow::anyhow!("The counted product is gone"))?;
+					let product: Product = codec.deserialize(&bytes)?;
+					Ok(product.stock as i64)
+				})
+			},
+			|_, range, counters| {
+				let db = db.clone();
+				let product_key = product_key.clone();
+				let context = context.clone();
+				async move {
+					tokio::task::spawn_blocking(move || {
+						let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+
+						for _ in range {
+							context.check_cancelled()?;
+							let outcome = match db.get_cf(&products_cf, &product_key) {
+								Ok(Some(bytes)) => {
+									let mut product: Product = codec.deserialize(&bytes)?;
+									product.stock += 1;
+									db.put_cf(&products_cf, &product_key, codec.serialize(&product)?)
+								}
+								Ok(None) => anyhow::bail!("The counted product is gone"),
+								Err(e) => Err(e),
+							};
+							match outcome {
+								Ok(()) => counters.record(true),
+								Err(e) if is_transient(&e) => counters.record(false),
+								Err(e) => return Err(e.into()),
+							}
+							context.record_operation();
+						}
 
-				for product_id in &product_ids {
-					// Read the product
-					let value = db.get_cf(&products_cf, product_id.as_bytes())?;
+						Ok(())
+					}).await?
+				}
+			}
+		).await
+	}
 
-					if let Some(bytes) = value {
-						let mut product: Product = Self::deserialize(&bytes)?;
+	async fn read_projection(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let codec = self.codec;
 
-						// Update multiple fields
-						product.price *= 1.1;
-						product.stock += 10;
-						product.description = format!(
-							"Bulk updated description {}",
-							Uuid::new_v4()
-						);
-						product.created_at = update_time;
+		measure_projection(&self.database_name(), count, self.cpu_count, |projection| {
+			let context = self.context.clone();
+			self.run_blocking(move |db| {
+				let products_cf = db.cf_handle(PRODUCTS_CF).unwrap();
+				let mut read = 0;
 
-						// Add to batch
-						batch.put_cf(
-							&products_cf,
-							product_id.as_bytes(),
-							Self::serialize(&product)?
-						);
-					}
+				// A product is stored as a single value, so it is decoded whole even when only its
+				// id and price are kept
+				for result in db.iterator_cf(&products_cf, IteratorMode::Start).take(count) {
+					context.check_cancelled()?;
+					let (_, value) = result?;
+					let _product: Product = codec.deserialize(&value)?;
+					read += 1;
+					projection.record(&context, 1);
 				}
 
-				// Write all updates at once
-				db.write(batch)?;
-
-				Ok(())
-			}
-		).await
+				Ok(read)
+			})
+		}).await
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::RocksDBBenchmark;
+
+	common::conformance_tests!(dir => RocksDBBenchmark::new(1, &dir));
+}
@@ -1,18 +1,25 @@
 use anyhow::Result;
+use tracing::info;
 mod sqlite_benchmark;
 
 use crate::sqlite_benchmark::SqliteBenchmark;
-use common::server::run_server;
+use common::{ config::ServerConfig, server::run_server };
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	println!("Starting SQLite benchmark");
-	// Create a new SQLite benchmark with 4 CPU cores
-	let benchmark = SqliteBenchmark::new(1).await?;
-	println!("Benchmark created");
+	let config = ServerConfig::load();
+	config.init_logging();
+	info!("Starting SQLite benchmark");
 
-	// Run the server on port 3001
-	run_server(benchmark, 3001).await?;
+	// Create a new SQLite benchmark with 4 CPU cores, on an existing database when one is given
+	let benchmark = match &config.connect {
+		Some(dsn) => SqliteBenchmark::connect(1, dsn).await?,
+		None => SqliteBenchmark::new(1, &config.data_dir).await?,
+	};
+	info!("Benchmark created");
+
+	// Run the server, on port 3001 by default
+	run_server(benchmark, &config, 3001).await?;
 
 	Ok(())
 }
@@ -1,37 +1,350 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use common::{
+	analytics::AnalyticsQuery,
+	codec::{ RawValue, Row, RowCodec },
 	benchmark::{
 		measure_execution,
 		DatabaseBenchmark,
-		generate_random_order,
-		generate_random_product,
+		files_size,
+		generate_order_chunks,
+		generate_orders_with_items,
 		generate_random_user,
+		generate_test_data_chunks,
+		GENERATION_CHUNK_SIZE,
+		PROBE_BLOB,
+		PROBE_TABLE,
 	},
-	models::{ BenchmarkResult, Product, User },
+	connect,
+	consistency::measure_consistency,
+	contention::{ is_contended_write, measure_contention, CONTENDED_USERS, CONTENTION_TIMEOUT_MS },
+	context::RunContext,
+	distribution,
+	fixtures::FIXTURE_TABLES,
+	import::{ self, ImportColumn, ImportColumnType, DROP_IMPORT_TABLE_QUERY, READ_IMPORTED_RECORD_QUERY },
+	nullable::{
+		users_to_soft_delete,
+		LIVE_USER_IDS_QUERY,
+		READ_DISCOUNTED_PRODUCTS_QUERY,
+		READ_LIVE_USERS_QUERY,
+		READ_USERS_NOT_DELETED_QUERY,
+		SOFT_DELETE_USERS_QUERY,
+		SOFT_DELETE_USER_QUERY,
+	},
+	plan::{ BenchmarkTest, IndexMode, KeyEncoding, TextMode, TimestampEncoding },
+	projection::{ measure_projection, READ_PROJECTION_QUERY },
+	models::{
+		BenchmarkResult,
+		Capabilities,
+		OrderWithDetails,
+		OrderWithItems,
+		Record,
+		RecordCounts,
+		User,
+		WideRecord,
+		WideValue,
+		ORDER_ITEM_RECORD_BYTES,
+		ORDER_RECORD_BYTES,
+		WIDE_RECORD_BYTES,
+	},
+	retry::RetryPolicy,
+	search::search_pattern,
+	statement_cache::PreparedStatements,
+	tenants::{ AGGREGATE_ORDERS_BY_TENANT_QUERY, READ_TENANT_ORDERS_QUERY, TENANT_PAGE_LEN },
+	text::holds_text,
+	trace::{ TraceChunk, TraceOp },
+	writers::measure_concurrent_inserts,
+	wide::{
+		self,
+		generate_wide_record,
+		WideColumnType,
+		INSERT_WIDE_RECORD_QUERY,
+		READ_WIDE_COLUMN_SUBSET_QUERY,
+		READ_WIDE_RECORD_QUERY,
+		WIDE_COLUMN_COUNT,
+	},
+};
+use rusqlite::{
+	params,
+	params_from_iter,
+	types::Value,
+	DatabaseName,
+	ErrorCode,
+	OpenFlags,
+	OptionalExtension,
+	TransactionBehavior,
 };
-use rusqlite::{ params, OptionalExtension };
 use tokio_rusqlite::Connection as AsyncConnection;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
 use uuid::Uuid;
+use chrono::{ DateTime, Utc };
+use tracing::debug;
+
+// File of the snapshot of the dataset in its fixture directory
+const SNAPSHOT_FILE: &str = "sqlite.db";
+
+// File of the backup in its directory
+const BACKUP_FILE: &str = "sqlite-backup.db";
+
+// Pragmas read into the engine statistics after each test
+const ENGINE_STATS_PRAGMAS: [&str; 5] = ["page_count", "page_size", "freelist_count", "cache_size", "mmap_size"];
+
+// Queries of the tests, also planned by `explain`
+const INSERT_USER_QUERY: &str =
+	"INSERT INTO users (id, tenant_id, name, email, created_at, active, deleted_at) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const READ_BY_ID_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE id = ?";
+const READ_BY_COLUMN_QUERY: &str =
+	"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE email LIKE ? LIMIT ? OFFSET ?";
+const ONE_JOIN_QUERY: &str =
+	"
+	SELECT o.id, o.quantity, o.total_price, o.created_at,
+		   u.id, u.tenant_id, u.name, u.email, u.created_at, u.active, u.deleted_at
+	FROM orders o
+	JOIN users u ON o.user_id = u.id
+	LIMIT ? OFFSET ?
+";
+const TWO_JOINS_QUERY: &str =
+	"
+	SELECT o.id, o.quantity, o.total_price, o.created_at,
+		   u.id, u.tenant_id, u.name, u.email, u.created_at, u.active, u.deleted_at,
+		   p.id, p.name, p.description, p.price, p.discount, p.stock, p.created_at
+	FROM orders o
+	JOIN users u ON o.user_id = u.id
+	JOIN products p ON o.product_id = p.id
+	LIMIT ? OFFSET ?
+";
+const INSERT_ORDER_QUERY: &str =
+	"INSERT INTO orders (id, tenant_id, user_id, product_id, quantity, total_price, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const INSERT_ORDER_ITEM_QUERY: &str =
+	"INSERT INTO order_items (id, order_id, product_id, quantity, unit_price) VALUES (?, ?, ?, ?, ?)";
+const READ_ORDER_WITH_ITEMS_QUERY: &str =
+	"
+	SELECT o.id, o.user_id, o.total_price, o.created_at,
+		   i.id, i.product_id, i.quantity, i.unit_price, p.name
+	FROM orders o
+	JOIN order_items i ON i.order_id = o.id
+	JOIN products p ON i.product_id = p.id
+	WHERE o.id = ?
+";
+const UPDATE_USER_QUERY: &str = "UPDATE users SET active = ? WHERE id = ?";
+const UPDATE_USERS_QUERY: &str =
+	"UPDATE users SET active = ? WHERE id IN (SELECT id FROM users LIMIT ? OFFSET ?)";
+const UPDATE_PRODUCT_QUERY: &str =
+	"UPDATE products SET price = ?, stock = ?, description = ? WHERE id = ?";
+const UPDATE_PRODUCTS_QUERY: &str =
+	"UPDATE products SET price = price * 1.1, stock = stock + 10, description = ?, created_at = ? WHERE id = ?";
+const READ_PRODUCT_STOCK_QUERY: &str = "SELECT stock FROM products WHERE id = ?";
+const UPDATE_PRODUCT_STOCK_QUERY: &str = "UPDATE products SET stock = ? WHERE id = ?";
+
+// Query of read_many_by_ids for `len` ids, one placeholder each
+fn read_many_by_ids_query(len: usize) -> String {
+	let placeholders = vec!["?"; len].join(",");
+	format!(
+		"SELECT id, tenant_id, name, email, created_at, active, deleted_at FROM users WHERE id IN ({})",
+		placeholders
+	)
+}
+
+// Stop a test loop once the run has been cancelled
+fn check_cancelled(context: &RunContext) -> tokio_rusqlite::Result<()> {
+	context.check_cancelled().map_err(|e| tokio_rusqlite::Error::Other(Box::new(e)))
+}
+
+// Values of a row fetched by a read test, left for the codec to decode
+fn raw_row(row: &rusqlite::Row) -> rusqlite::Result<Row> {
+	(0..row.as_ref().column_count())
+		.map(|column| {
+			row.get::<_, Value>(column).map(|value| match value {
+				Value::Null => RawValue::Null,
+				Value::Integer(value) => RawValue::Integer(value),
+				Value::Real(value) => RawValue::Real(value),
+				Value::Text(text) => RawValue::Text(text),
+				Value::Blob(bytes) => RawValue::Blob(bytes),
+			})
+		})
+		.collect::<rusqlite::Result<_>>()
+		.map(Row)
+}
+
+// Decode the rows of a read test with its codec, inside the call on the connection
+fn decode_rows<T>(
+	codec: &RowCodec,
+	rows: impl IntoIterator<Item = Row>,
+	decode: impl Fn(Row) -> Result<T>
+) -> tokio_rusqlite::Result<()> {
+	codec.decode(rows, decode).map_err(|e| tokio_rusqlite::Error::Other(e.into()))
+}
+
+// Affinity of a column of the declared type, by the rules of SQLite, naming how its values are
+// stored whatever the type was spelled as
+fn affinity(declared: &str) -> &'static str {
+	let declared = declared.to_ascii_uppercase();
+	if declared.contains("INT") {
+		"INTEGER"
+	} else if ["CHAR", "CLOB", "TEXT"].iter().any(|name| declared.contains(name)) {
+		"TEXT"
+	} else if declared.is_empty() || declared.contains("BLOB") {
+		"BLOB"
+	} else if ["REAL", "FLOA", "DOUB"].iter().any(|name| declared.contains(name)) {
+		"REAL"
+	} else {
+		"NUMERIC"
+	}
+}
+
+// Errors caused by another connection holding a lock, which go away once it releases it
+fn is_transient(e: &rusqlite::Error) -> bool {
+	matches!(e.sqlite_error_code(), Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked))
+}
+
+// Value an id is stored as: TEXT holding its string form or BLOB holding its 16 bytes
+fn key_value(encoding: KeyEncoding, id: &Uuid) -> Value {
+	match encoding {
+		KeyEncoding::String => Value::Text(id.to_string()),
+		KeyEncoding::Binary => Value::Blob(id.as_bytes().to_vec()),
+	}
+}
+
+// Id read back from a key column of either encoding
+fn parse_key(value: Value) -> Result<Uuid> {
+	match value {
+		Value::Text(text) => Ok(Uuid::parse_str(&text)?),
+		Value::Blob(bytes) => Ok(Uuid::from_slice(&bytes)?),
+		other => Err(anyhow::anyhow!("Unexpected id value: {:?}", other)),
+	}
+}
+
+// Value a timestamp is stored as: TEXT holding its RFC 3339 form or INTEGER holding the
+// microseconds since the epoch
+fn timestamp_value(encoding: TimestampEncoding, timestamp: &DateTime<Utc>) -> Value {
+	match encoding {
+		TimestampEncoding::String => Value::Text(timestamp.to_rfc3339()),
+		TimestampEncoding::Native => Value::Integer(timestamp.timestamp_micros()),
+	}
+}
+
+fn wide_value(value: &WideValue) -> Value {
+	match value {
+		WideValue::Integer(value) => Value::Integer(*value),
+		WideValue::Real(value) => Value::Real(*value),
+		WideValue::Text(value) => Value::Text(value.clone()),
+		WideValue::Boolean(value) => Value::Integer(*value as i64),
+	}
+}
+
+// Value of a column of an imported record, NULL for an empty cell
+fn imported_value(value: &Option<WideValue>) -> Value {
+	value.as_ref().map_or(Value::Null, wide_value)
+}
+
+// Parameters of INSERT_WIDE_RECORD_QUERY: the id, then every column
+fn wide_record_params(encoding: KeyEncoding, record: &WideRecord) -> Vec<Value> {
+	std::iter::once(key_value(encoding, &record.id)).chain(record.values.iter().map(wide_value)).collect()
+}
+
+// Connection handed to the test loops, preparing their hot queries through the statement cache
+// of the connection so they aren't prepared again on every call
+struct CachingConnection<'c> {
+	conn: &'c rusqlite::Connection,
+	statements: &'c PreparedStatements,
+}
+
+impl<'c> CachingConnection<'c> {
+	fn prepare_cached(&self, sql: &'static str) -> rusqlite::Result<rusqlite::CachedStatement<'c>> {
+		self.statements.record_lookup(sql);
+		self.conn.prepare_cached(sql)
+	}
+}
+
+impl std::ops::Deref for CachingConnection<'_> {
+	type Target = rusqlite::Connection;
+
+	fn deref(&self) -> &Self::Target {
+		self.conn
+	}
+}
+
+// Data a test needs, prepared before it is timed
+#[derive(Default)]
+pub struct Fixture {
+	// Users written by the insert tests
+	users: Vec<User>,
+	// Orders written by insert_orders_with_items
+	orders: Vec<OrderWithItems>,
+	// Records written by insert_wide_records
+	wide_records: Vec<WideRecord>,
+	// Ids of the records read or updated
+	ids: Arc<Vec<Value>>,
+	// Connection of each writer of concurrent_inserts and consistency_probe, or contender of
+	// long_transaction_contention
+	connections: Vec<AsyncConnection>,
+}
 
 pub struct SqliteBenchmark {
 	db_path: String,
+	// Whether `db_path` is an existing database attached with `--connect`, opened read-only
+	connected: bool,
+	// SQLite allows a single writer at a time, so every write goes through this connection
+	writer: AsyncConnection,
+	writer_statements: Arc<PreparedStatements>,
+	// Read-only pool; under WAL mode the readers run concurrently with each other and the writer
+	readers: Vec<(AsyncConnection, Arc<PreparedStatements>)>,
 	cpu_count: usize,
+	indexes: IndexMode,
+	key_encoding: KeyEncoding,
+	timestamp_encoding: TimestampEncoding,
+	retry: RetryPolicy,
+	context: RunContext,
 }
 
 impl SqliteBenchmark {
-	pub async fn new(cpu_count: usize) -> Result<Self> {
-		let db_path = "./data/sqlite-benchmark.db".to_string();
+	pub async fn new(cpu_count: usize, data_dir: &Path) -> Result<Self> {
+		let db_path = data_dir.join("sqlite-benchmark.db").to_string_lossy().to_string();
 
 		// Create data directory if it doesn't exist
-		let data_dir = Path::new("./data");
 		if !data_dir.exists() {
 			std::fs::create_dir_all(data_dir)?;
 		}
 
+		Self::open(cpu_count, db_path, false).await
+	}
+
+	// Attach to an existing database, a file path or a `file:` URI, which the runs only read
+	pub async fn connect(cpu_count: usize, dsn: &str) -> Result<Self> {
+		Self::open(cpu_count, dsn.to_string(), true).await
+	}
+
+	async fn open(cpu_count: usize, db_path: String, connected: bool) -> Result<Self> {
+		// One reader per available core; the read tests use as many of them as the CPU count allows
+		let reader_count = std::thread::available_parallelism().map_or(4, |count| count.get());
+		let context = RunContext::default();
+		let writer = Self::open_connection(&db_path, connected).await?;
+		let writer_statements = Arc::new(PreparedStatements::new(context.clone()));
+		let mut readers = Vec::with_capacity(reader_count);
+		for _ in 0..reader_count {
+			let statements = Arc::new(PreparedStatements::new(context.clone()));
+			readers.push((Self::open_connection(&db_path, connected).await?, statements));
+		}
+
 		// Create a new instance
-		let benchmark = Self { db_path, cpu_count };
+		let benchmark = Self {
+			db_path,
+			connected,
+			writer,
+			writer_statements,
+			readers,
+			cpu_count,
+			indexes: IndexMode::default(),
+			key_encoding: KeyEncoding::default(),
+			timestamp_encoding: TimestampEncoding::default(),
+			retry: RetryPolicy::default(),
+			context,
+		};
 
 		// Initialize database
 		benchmark.init().await?;
@@ -39,99 +352,312 @@ impl SqliteBenchmark {
 		Ok(benchmark)
 	}
 
-	// Helper to get an async connection
-	async fn get_async_connection(&self) -> Result<AsyncConnection> {
-		let conn = AsyncConnection::open(&self.db_path).await?;
+	// Helper to open an async connection, read-only to a connected database
+	async fn open_connection(db_path: &str, read_only: bool) -> Result<AsyncConnection> {
+		let conn = if read_only {
+			AsyncConnection::open_with_flags(
+				db_path,
+				OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX
+			).await?
+		} else {
+			AsyncConnection::open(db_path).await?
+		};
 
 		// Enable WAL mode and other optimizations
-		conn.call(|conn| {
-			println!("Setting PRAGMA journal_mode = WAL");
-			let _ = conn.prepare("PRAGMA journal_mode = WAL")?.query([])?;
+		conn.call(move |conn| {
+			// These return the new value, and only apply once their row is stepped to; the
+			// journal mode of a connected database is its own
+			if !read_only {
+				debug!("Setting PRAGMA journal_mode = WAL");
+				conn.prepare("PRAGMA journal_mode = WAL")?.query([])?.next()?;
+			}
 
-			println!("Setting PRAGMA synchronous = NORMAL");
+			debug!("Setting PRAGMA synchronous = NORMAL");
 			// These don't return results, so execute is fine
 			conn.execute("PRAGMA synchronous = NORMAL", [])?;
 
-			println!("Setting PRAGMA cache_size = 100000");
+			debug!("Setting PRAGMA cache_size = 100000");
 			conn.execute(&format!("PRAGMA cache_size = {}", 100000), [])?;
 
-			println!("Setting PRAGMA busy_timeout = 5000");
-			let _ = conn.prepare("PRAGMA busy_timeout = 5000")?.query([])?;
+			debug!("Setting PRAGMA busy_timeout = 5000");
+			conn.prepare("PRAGMA busy_timeout = 5000")?.query([])?.next()?;
 
-			println!("Setting PRAGMA mmap_size = 30000000000");
-			let _ = conn.prepare("PRAGMA mmap_size = 30000000000")?.query([])?;
+			debug!("Setting PRAGMA mmap_size = 30000000000");
+			conn.prepare("PRAGMA mmap_size = 30000000000")?.query([])?.next()?;
 
 			Ok(())
 		}).await?;
 
 		Ok(conn)
 	}
+
+	// Check that the connected database holds the tables of the suite, its users keyed and
+	// timestamped as the plan encodes them
+	async fn check_schema(&self, id_type: &'static str, timestamp_type: &'static str) -> Result<()> {
+		let (tables, id_column, created_at_column) = self.writer.call(|conn| {
+			let tables = conn
+				.prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?
+				.query_map([], |row| row.get(0))?
+				.collect::<Result<Vec<String>, _>>()?;
+			let column_type = |column: &str| {
+				conn
+					.query_row(
+						"SELECT type FROM pragma_table_info('users') WHERE name = ?",
+						[column],
+						|row| row.get::<_, String>(0)
+					)
+					.optional()
+			};
+			Ok((tables, column_type("id")?, column_type("created_at")?))
+		}).await?;
+
+		connect::check_tables(&tables)?;
+		for (column, declared, expected, setting) in [
+			("id", id_column, id_type, "key_encoding"),
+			("created_at", created_at_column, timestamp_type, "timestamp_encoding"),
+		] {
+			let Some(declared) = declared else {
+				anyhow::bail!("The users of the connected database have no {} column", column);
+			};
+			anyhow::ensure!(
+				affinity(&declared) == expected,
+				"The users of the connected database have a {} column of type {:?}, the {} of the plan expects {}",
+				column,
+				declared,
+				setting,
+				expected
+			);
+		}
+
+		Ok(())
+	}
+
+	// Split `count` operations into one contiguous range per reader and run them concurrently,
+	// each reader on its own connection; `read` receives the offset and length of its range
+	// Ids of the first `limit` rows of a table
+	async fn first_ids(&self, table: &'static str, limit: usize) -> Result<Vec<Value>> {
+		self.writer
+			.call(move |conn| {
+				let mut stmt = conn.prepare(&format!("SELECT id FROM {} LIMIT ?", table))?;
+				let ids = stmt
+					.query_map([limit], |row| row.get(0))?
+					.collect::<Result<Vec<Value>, _>>()?;
+				Ok(ids)
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	async fn run_parallel_reads<F>(&self, count: usize, read: F) -> Result<()>
+		where
+			F: Fn(&CachingConnection, usize, usize) -> tokio_rusqlite::Result<()> +
+				Send +
+				Sync +
+				'static
+	{
+		let read = Arc::new(read);
+		let workers = self.cpu_count.clamp(1, self.readers.len());
+		let chunk = count.div_ceil(workers);
+
+		let calls = self.readers[..workers]
+			.iter()
+			.enumerate()
+			.map(|(i, reader)| (reader, i * chunk))
+			.filter(|(_, offset)| *offset < count)
+			.map(|((conn, statements), offset)| {
+				let read = read.clone();
+				let statements = statements.clone();
+				let len = chunk.min(count - offset);
+				conn.call(move |conn| {
+					read(&CachingConnection { conn, statements: &statements }, offset, len)
+				})
+			});
+
+		futures::future::try_join_all(calls).await?;
+		Ok(())
+	}
 }
 
 #[async_trait]
 impl DatabaseBenchmark for SqliteBenchmark {
+	type Fixture = Fixture;
+
 	async fn init(&self) -> Result<()> {
-		let conn = self.get_async_connection().await?;
+		let conn = self.writer.clone();
+		let indexes = self.indexes;
+		let id_type = match self.key_encoding {
+			KeyEncoding::String => "TEXT",
+			KeyEncoding::Binary => "BLOB",
+		};
+		let timestamp_type = match self.timestamp_encoding {
+			TimestampEncoding::String => "TEXT",
+			TimestampEncoding::Native => "INTEGER",
+		};
+
+		// A connected database keeps its schema and indexes, which only have to suit the plan
+		if self.connected {
+			return self.check_schema(id_type, timestamp_type).await;
+		}
+
+		conn.call(move |conn| {
+			// Tables created with other key or timestamp encodings, or before the tenants and the
+			// optional fields were added, are dropped; their data is regenerated anyway
+			let column_type = |column: &str| {
+				conn
+					.query_row(
+						"SELECT type FROM pragma_table_info('users') WHERE name = ?",
+						[column],
+						|row| row.get::<_, String>(0)
+					)
+					.optional()
+			};
+			let outdated =
+				column_type("id")?.is_some_and(|current| current != id_type) ||
+				column_type("created_at")?.is_some_and(|current| current != timestamp_type) ||
+				(column_type("id")?.is_some() &&
+					(column_type("tenant_id")?.is_none() || column_type("deleted_at")?.is_none()));
+			if outdated {
+				conn.execute_batch(
+					"DROP TABLE IF EXISTS wide_records;
+					DROP TABLE IF EXISTS order_items;
+					DROP TABLE IF EXISTS orders;
+					DROP TABLE IF EXISTS products;
+					DROP TABLE IF EXISTS users;"
+				)?;
+			}
 
-		conn.call(|conn| {
 			// Create users table
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS users (
-                    id TEXT PRIMARY KEY,
+				&format!("CREATE TABLE IF NOT EXISTS users (
+                    id {id_type} PRIMARY KEY,
+                    tenant_id INTEGER NOT NULL,
                     name TEXT NOT NULL,
                     email TEXT NOT NULL,
-                    created_at TEXT NOT NULL,
-                    active INTEGER NOT NULL
-                )",
+                    created_at {timestamp_type} NOT NULL,
+                    active INTEGER NOT NULL,
+                    deleted_at {timestamp_type}
+                )"),
 				[]
 			)?;
 
 			// Create products table
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS products (
-                    id TEXT PRIMARY KEY,
+				&format!("CREATE TABLE IF NOT EXISTS products (
+                    id {id_type} PRIMARY KEY,
                     name TEXT NOT NULL,
                     description TEXT NOT NULL,
                     price REAL NOT NULL,
+                    discount REAL,
                     stock INTEGER NOT NULL,
-                    created_at TEXT NOT NULL
-                )",
+                    created_at {timestamp_type} NOT NULL
+                )"),
 				[]
 			)?;
 
 			// Create orders table with foreign keys
 			conn.execute(
-				"CREATE TABLE IF NOT EXISTS orders (
-                    id TEXT PRIMARY KEY,
-                    user_id TEXT NOT NULL,
-                    product_id TEXT NOT NULL,
+				&format!("CREATE TABLE IF NOT EXISTS orders (
+                    id {id_type} PRIMARY KEY,
+                    tenant_id INTEGER NOT NULL,
+                    user_id {id_type} NOT NULL,
+                    product_id {id_type} NOT NULL,
                     quantity INTEGER NOT NULL,
                     total_price REAL NOT NULL,
-                    created_at TEXT NOT NULL,
+                    created_at {timestamp_type} NOT NULL,
                     FOREIGN KEY (user_id) REFERENCES users (id),
                     FOREIGN KEY (product_id) REFERENCES products (id)
-                )",
+                )"),
 				[]
 			)?;
 
-			// Create indexes
-			conn.execute(
-				"CREATE INDEX IF NOT EXISTS idx_users_email ON users (email)",
-				[]
-			)?;
+			// Create order items table, one row per product of an order
 			conn.execute(
-				"CREATE INDEX IF NOT EXISTS idx_products_name ON products (name)",
-				[]
-			)?;
-			conn.execute(
-				"CREATE INDEX IF NOT EXISTS idx_orders_user_id ON orders (user_id)",
+				&format!("CREATE TABLE IF NOT EXISTS order_items (
+                    id {id_type} PRIMARY KEY,
+                    order_id {id_type} NOT NULL,
+                    product_id {id_type} NOT NULL,
+                    quantity INTEGER NOT NULL,
+                    unit_price REAL NOT NULL,
+                    FOREIGN KEY (order_id) REFERENCES orders (id),
+                    FOREIGN KEY (product_id) REFERENCES products (id)
+                )"),
 				[]
 			)?;
+
+			// Create wide records table, of many columns of mixed types
 			conn.execute(
-				"CREATE INDEX IF NOT EXISTS idx_orders_product_id ON orders (product_id)",
+				&wide::create_table_query(id_type, |column_type| match column_type {
+					WideColumnType::Integer | WideColumnType::Boolean => "INTEGER",
+					WideColumnType::Real => "REAL",
+					WideColumnType::Text => "TEXT",
+				}),
 				[]
 			)?;
 
+			// Create or drop the secondary indexes; the primary keys stay indexed
+			if indexes.is_on() {
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_users_email ON users (email)",
+					[]
+				)?;
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_products_name ON products (name)",
+					[]
+				)?;
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_orders_user_id ON orders (user_id)",
+					[]
+				)?;
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_orders_product_id ON orders (product_id)",
+					[]
+				)?;
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_order_items_order_id ON order_items (order_id)",
+					[]
+				)?;
+				conn.execute(
+					"CREATE INDEX IF NOT EXISTS idx_orders_tenant_id ON orders (tenant_id)",
+					[]
+				)?;
+				if indexes == IndexMode::Full {
+					// The same indexes as the partial ones below, over every row
+					conn.execute_batch(
+						"DROP INDEX IF EXISTS idx_users_deleted_at;
+						DROP INDEX IF EXISTS idx_products_discount;
+						DROP INDEX IF EXISTS idx_users_live_created_at;
+						CREATE INDEX IF NOT EXISTS idx_users_deleted_at_full ON users (deleted_at);
+						CREATE INDEX IF NOT EXISTS idx_products_discount_full ON products (discount);
+						CREATE INDEX IF NOT EXISTS idx_users_created_at_full ON users (created_at);"
+					)?;
+				} else {
+					// Partial indexes, of only the rows where the optional field is set, and of the
+					// live users by creation time
+					conn.execute_batch(
+						"DROP INDEX IF EXISTS idx_users_deleted_at_full;
+						DROP INDEX IF EXISTS idx_products_discount_full;
+						DROP INDEX IF EXISTS idx_users_created_at_full;
+						CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users (deleted_at) WHERE deleted_at IS NOT NULL;
+						CREATE INDEX IF NOT EXISTS idx_products_discount ON products (discount) WHERE discount IS NOT NULL;
+						CREATE INDEX IF NOT EXISTS idx_users_live_created_at ON users (created_at) WHERE deleted_at IS NULL;"
+					)?;
+				}
+			} else {
+				conn.execute_batch(
+					"DROP INDEX IF EXISTS idx_users_email;
+					DROP INDEX IF EXISTS idx_products_name;
+					DROP INDEX IF EXISTS idx_orders_user_id;
+					DROP INDEX IF EXISTS idx_orders_product_id;
+					DROP INDEX IF EXISTS idx_order_items_order_id;
+					DROP INDEX IF EXISTS idx_orders_tenant_id;
+					DROP INDEX IF EXISTS idx_users_deleted_at;
+					DROP INDEX IF EXISTS idx_products_discount;
+					DROP INDEX IF EXISTS idx_users_live_created_at;
+					DROP INDEX IF EXISTS idx_users_deleted_at_full;
+					DROP INDEX IF EXISTS idx_products_discount_full;
+					DROP INDEX IF EXISTS idx_users_created_at_full;"
+				)?;
+			}
+
 			Ok(())
 		}).await?;
 
@@ -139,223 +665,1346 @@ impl DatabaseBenchmark for SqliteBenchmark {
 	}
 
 	async fn generate_test_data(&self, count: usize) -> Result<()> {
-		let conn = self.get_async_connection().await?;
-
-		// Generate users
-		let users: Vec<User> = (0..count).map(|_| generate_random_user()).collect();
-
-		// Generate products
-		let products: Vec<Product> = (0..count)
-			.map(|_| generate_random_product())
-			.collect();
-
-		// Generate orders (one per user/product pair for simplicity)
-		let mut orders = Vec::with_capacity(count);
-		for i in 0..count {
-			let user_id = users[i % users.len()].id;
-			let product_id = products[i % products.len()].id;
-			orders.push(generate_random_order(user_id, product_id));
+		let conn = self.writer.clone();
+
+		// Generate and insert the data chunk by chunk, so only one chunk is held in memory
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		let retry = self.retry;
+		let chunks = generate_test_data_chunks(
+			count,
+			self.context.payload(),
+			self.context.tenants(),
+			self.context.key_strategy()
+		);
+		for chunk in chunks {
+			let context = self.context.clone();
+			conn.call(move |conn| {
+				// Use a transaction per chunk for better performance
+				let tx = conn.transaction()?;
+
+				// Insert users
+				for user in &chunk.users {
+					retry.run(&context, is_transient, || {
+						tx.execute(
+							INSERT_USER_QUERY,
+							params![
+								key_value(encoding, &user.id),
+								user.tenant_id,
+								user.name,
+								user.email,
+								timestamp_value(timestamps, &user.created_at),
+								user.active as i32,
+								user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+							]
+						)
+					})?;
+				}
+
+				// Insert products
+				for product in &chunk.products {
+					retry.run(&context, is_transient, || {
+						tx.execute(
+							"INSERT INTO products (id, name, description, price, discount, stock, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+							params![
+								key_value(encoding, &product.id),
+								product.name,
+								product.description,
+								product.price,
+								product.discount,
+								product.stock,
+								timestamp_value(timestamps, &product.created_at)
+							]
+						)
+					})?;
+				}
+
+				// Insert orders
+				for order in &chunk.orders {
+					retry.run(&context, is_transient, || {
+						tx.execute(
+							INSERT_ORDER_QUERY,
+							params![
+								key_value(encoding, &order.id),
+								order.tenant_id,
+								key_value(encoding, &order.user_id),
+								key_value(encoding, &order.product_id),
+								order.quantity,
+								order.total_price,
+								timestamp_value(timestamps, &order.created_at)
+							]
+						)
+					})?;
+				}
+
+				// Insert order items
+				for item in &chunk.order_items {
+					retry.run(&context, is_transient, || {
+						tx.execute(
+							INSERT_ORDER_ITEM_QUERY,
+							params![
+								key_value(encoding, &item.id),
+								key_value(encoding, &item.order_id),
+								key_value(encoding, &item.product_id),
+								item.quantity,
+								item.unit_price
+							]
+						)
+					})?;
+				}
+
+				// Insert wide records
+				for record in &chunk.wide_records {
+					retry.run(&context, is_transient, || {
+						tx.execute(INSERT_WIDE_RECORD_QUERY.as_str(), params_from_iter(wide_record_params(encoding, record)))
+					})?;
+				}
+
+				// Commit the transaction
+				tx.commit()?;
+
+				Ok(())
+			}).await?;
 		}
 
-		// Insert all data
-		conn.call(move |conn| {
+		Ok(())
+	}
+
+	async fn cleanup(&self) -> Result<()> {
+		let conn = self.writer.clone();
+
+		conn.call(|conn| {
 			// Use a transaction for better performance
 			let tx = conn.transaction()?;
 
-			// Insert users
-			for user in &users {
-				tx.execute(
-					"INSERT INTO users (id, name, email, created_at, active) VALUES (?, ?, ?, ?, ?)",
-					params![
-						user.id.to_string(),
-						user.name,
-						user.email,
-						user.created_at.to_rfc3339(),
-						user.active as i32
-					]
-				)?;
+			// Delete all data
+			tx.execute("DELETE FROM wide_records", [])?;
+			tx.execute("DELETE FROM order_items", [])?;
+			tx.execute("DELETE FROM orders", [])?;
+			tx.execute("DELETE FROM products", [])?;
+			tx.execute("DELETE FROM users", [])?;
+
+			// Commit the transaction
+			tx.commit()?;
+
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	async fn snapshot(&self, dir: &Path) -> Result<()> {
+		std::fs::create_dir_all(dir)?;
+		let path = dir.join(SNAPSHOT_FILE);
+		// VACUUM INTO refuses to overwrite a file
+		if path.exists() {
+			std::fs::remove_file(&path)?;
+		}
+
+		let path = path.to_string_lossy().into_owned();
+		self.writer.call(move |conn| {
+			conn.execute("VACUUM INTO ?1", [&path])?;
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	async fn restore(&self, dir: &Path) -> Result<()> {
+		let path = dir.join(SNAPSHOT_FILE);
+		if !path.exists() {
+			anyhow::bail!("No snapshot at {}", path.display());
+		}
+
+		let path = path.to_string_lossy().into_owned();
+		self.writer.call(move |conn| {
+			conn.execute("ATTACH DATABASE ?1 AS fixture", [&path])?;
+			let copied = (|| {
+				let tx = conn.transaction()?;
+				for table in FIXTURE_TABLES.iter().rev() {
+					tx.execute(&format!("DELETE FROM main.{}", table), [])?;
+				}
+				for table in FIXTURE_TABLES {
+					tx.execute(&format!("INSERT INTO main.{} SELECT * FROM fixture.{}", table, table), [])?;
+				}
+				tx.commit()
+			})();
+			// Detach even when the copy failed, the next restore attaches again
+			conn.execute("DETACH DATABASE fixture", [])?;
+			copied?;
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	// The online backup API copies the pages of the database to the file, starting over when
+	// another connection writes meanwhile
+	async fn backup(&self, dir: &Path) -> Result<()> {
+		std::fs::create_dir_all(dir)?;
+		let path = dir.join(BACKUP_FILE);
+
+		self.writer.call(move |conn| {
+			conn.backup(DatabaseName::Main, &path, None)?;
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	async fn restore_backup(&self, dir: &Path) -> Result<()> {
+		// Restoring from a missing file would open it as an empty database and copy that over
+		let path = dir.join(BACKUP_FILE);
+		if !path.exists() {
+			anyhow::bail!("No backup at {}", path.display());
+		}
+
+		self.writer.call(move |conn| {
+			conn.restore(DatabaseName::Main, &path, None::<fn(rusqlite::backup::Progress)>)?;
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	async fn reset(&mut self) -> Result<()> {
+		anyhow::ensure!(!self.connected, "A connected database isn't deleted");
+
+		// Every connection must be closed before the files are deleted
+		self.writer.clone().close().await?;
+		for (reader, _) in &self.readers {
+			reader.clone().close().await?;
+		}
+
+		for suffix in ["", "-wal", "-shm"] {
+			match std::fs::remove_file(format!("{}{}", self.db_path, suffix)) {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+					return Err(e.into());
+				}
+				_ => {}
 			}
+		}
 
-			// Insert products
-			for product in &products {
-				tx.execute(
-					"INSERT INTO products (id, name, description, price, stock, created_at) VALUES (?, ?, ?, ?, ?, ?)",
-					params![
-						product.id.to_string(),
-						product.name,
-						product.description,
-						product.price,
-						product.stock,
-						product.created_at.to_rfc3339()
-					]
-				)?;
+		// Reopen every connection on the new file, with empty statement caches
+		self.writer = Self::open_connection(&self.db_path, false).await?;
+		self.writer_statements = Arc::new(PreparedStatements::new(self.context.clone()));
+		for (reader, statements) in &mut self.readers {
+			*reader = Self::open_connection(&self.db_path, false).await?;
+			*statements = Arc::new(PreparedStatements::new(self.context.clone()));
+		}
+
+		self.init().await
+	}
+
+	async fn ping(&self) -> Result<()> {
+		let conn = self.writer.clone();
+
+		conn.call(|conn| {
+			conn.query_row("SELECT 1", [], |row| row.get::<_, i32>(0))?;
+			Ok(())
+		}).await?;
+
+		Ok(())
+	}
+
+	async fn probe(&self) -> Result<Capabilities> {
+		let conn = self.writer.clone();
+
+		let capabilities = conn.call(|conn| {
+			conn.execute_batch(
+				&format!(
+					"DROP TABLE IF EXISTS {table}; CREATE TABLE {table} (id INTEGER PRIMARY KEY, data BLOB NOT NULL);",
+					table = PROBE_TABLE
+				)
+			)?;
+			conn.execute(&format!("INSERT INTO {} (id, data) VALUES (1, ?1)", PROBE_TABLE), params![PROBE_BLOB])?;
+			let blob: Vec<u8> = conn.query_row(&format!("SELECT data FROM {}", PROBE_TABLE), [], |row| row.get(0))?;
+
+			let window_functions = conn
+				.query_row(
+					&format!("SELECT ROW_NUMBER() OVER (ORDER BY id) FROM {}", PROBE_TABLE),
+					[],
+					|row| row.get::<_, i64>(0)
+				)
+				.is_ok();
+			// FTS5 depends on how SQLite was compiled
+			let full_text_search = conn
+				.execute_batch(
+					&format!("CREATE VIRTUAL TABLE {table}_text USING fts5(body); DROP TABLE {table}_text;", table = PROBE_TABLE)
+				)
+				.is_ok();
+			conn.execute_batch(&format!("DROP TABLE {}", PROBE_TABLE))?;
+
+			Ok(Capabilities {
+				window_functions,
+				full_text_search,
+				blobs: blob == PROBE_BLOB,
+				analytics: false,
+			})
+		}).await?;
+
+		Ok(capabilities)
+	}
+
+	// Size of the database file and of its WAL, which the checkpoint before each test truncates
+	async fn bytes_written(&self) -> Result<Option<u64>> {
+		let wal_path = format!("{}-wal", self.db_path);
+		Ok(Some(files_size(&[&self.db_path, &wal_path])))
+	}
+
+	// The WAL grows until a checkpoint resets it, which only happens between tests
+	async fn database_size(&self) -> Result<Option<u64>> {
+		self.bytes_written().await
+	}
+
+	// The pages of the database, those left free by deletes, and the cache and memory map of each
+	// connection, with the size of the WAL the test left behind
+	async fn engine_stats(&self) -> Result<Option<serde_json::Value>> {
+		let mut stats = self.writer
+			.call(|conn| {
+				let mut stats = serde_json::Map::new();
+				for pragma in ENGINE_STATS_PRAGMAS {
+					let value: i64 = conn.query_row(&format!("PRAGMA {}", pragma), [], |row| row.get(0))?;
+					stats.insert(pragma.to_string(), value.into());
+				}
+				Ok(stats)
+			}).await
+			.map_err(anyhow::Error::from)?;
+
+		let wal_path = format!("{}-wal", self.db_path);
+		stats.insert("wal_bytes".to_string(), files_size(&[&wal_path]).into());
+		Ok(Some(serde_json::Value::Object(stats)))
+	}
+
+	async fn explain(&self, test: BenchmarkTest) -> Result<Option<String>> {
+		let query = match test {
+			BenchmarkTest::InsertSingleManyTimes |
+			BenchmarkTest::InsertManyAtOnce |
+			BenchmarkTest::ConcurrentInserts => INSERT_USER_QUERY.to_string(),
+			BenchmarkTest::ReadByIdManyTimes => READ_BY_ID_QUERY.to_string(),
+			BenchmarkTest::ReadManyByIds => read_many_by_ids_query(3),
+			BenchmarkTest::ReadByColumnSearch => READ_BY_COLUMN_QUERY.to_string(),
+			BenchmarkTest::ReadWithOneJoin => ONE_JOIN_QUERY.to_string(),
+			BenchmarkTest::ReadWithTwoJoins => TWO_JOINS_QUERY.to_string(),
+			BenchmarkTest::UpdateSingleFieldOneEntry | BenchmarkTest::LongTransactionContention => {
+				UPDATE_USER_QUERY.to_string()
 			}
+			BenchmarkTest::UpdateSingleFieldManyEntries => UPDATE_USERS_QUERY.to_string(),
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => UPDATE_PRODUCT_QUERY.to_string(),
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => UPDATE_PRODUCTS_QUERY.to_string(),
+			BenchmarkTest::InsertOrdersWithItems => INSERT_ORDER_QUERY.to_string(),
+			BenchmarkTest::ReadOrdersWithItems => READ_ORDER_WITH_ITEMS_QUERY.to_string(),
+			BenchmarkTest::InsertWideRecords => INSERT_WIDE_RECORD_QUERY.to_string(),
+			BenchmarkTest::ReadWideRecordsById => READ_WIDE_RECORD_QUERY.to_string(),
+			BenchmarkTest::ReadWideColumnSubset => READ_WIDE_COLUMN_SUBSET_QUERY.to_string(),
+			BenchmarkTest::ReadTenantOrders => READ_TENANT_ORDERS_QUERY.to_string(),
+			BenchmarkTest::AggregateOrdersByTenant => AGGREGATE_ORDERS_BY_TENANT_QUERY.to_string(),
+			BenchmarkTest::ReadUsersNotDeleted => READ_USERS_NOT_DELETED_QUERY.to_string(),
+			BenchmarkTest::ReadDiscountedProducts => READ_DISCOUNTED_PRODUCTS_QUERY.to_string(),
+			BenchmarkTest::SoftDeleteUsers => SOFT_DELETE_USER_QUERY.to_string(),
+			BenchmarkTest::ReadLiveUsers => READ_LIVE_USERS_QUERY.to_string(),
+			BenchmarkTest::ConsistencyProbe => UPDATE_PRODUCT_STOCK_QUERY.to_string(),
+			BenchmarkTest::ReadProjection => READ_PROJECTION_QUERY.to_string(),
+		};
+
+		// The plan doesn't depend on the values, so the parameters are left unbound
+		let steps = self.writer
+			.call(move |conn| {
+				let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", query))?;
+				let mut rows = stmt.raw_query();
+				let mut steps = Vec::new();
+				while let Some(row) = rows.next()? {
+					steps.push((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(3)?));
+				}
+				Ok(steps)
+			}).await
+			.map_err(anyhow::Error::from)?;
 
-			// Insert orders
-			for order in &orders {
-				tx.execute(
-					"INSERT INTO orders (id, user_id, product_id, quantity, total_price, created_at) VALUES (?, ?, ?, ?, ?, ?)",
-					params![
-						order.id.to_string(),
-						order.user_id.to_string(),
-						order.product_id.to_string(),
-						order.quantity,
-						order.total_price,
-						order.created_at.to_rfc3339()
-					]
-				)?;
+		// Indent each step under its parent, as the sqlite3 shell prints the plan
+		let mut depths = HashMap::new();
+		let mut lines = Vec::new();
+		for (id, parent, detail) in steps {
+			let depth = depths.get(&parent).map_or(0, |depth| depth + 1);
+			depths.insert(id, depth);
+			lines.push(format!("{}{}", "  ".repeat(depth), detail));
+		}
+
+		Ok(if lines.is_empty() { None } else { Some(lines.join("\n")) })
+	}
+
+	// The ids are TEXT or BLOB primary keys, indexed by an automatic index. The column search
+	// matches the domain at the end of the emails, which no index can seek, so it scans the table
+	// with or without the email index
+	fn expected_index(&self, test: BenchmarkTest) -> Option<&'static str> {
+		match test {
+			BenchmarkTest::ReadByIdManyTimes => Some("sqlite_autoindex_users_1"),
+			_ => None,
+		}
+	}
+
+	fn supports_analytics(&self) -> bool {
+		true
+	}
+
+	fn connected(&self) -> bool {
+		self.connected
+	}
+
+	async fn generate_orders(&self, count: usize) -> Result<()> {
+		// Orders go to the first users and products, enough of them to spread the revenue around
+		let parse = |ids: Vec<Value>| ids.into_iter().map(parse_key).collect::<Result<Vec<_>>>();
+		let user_ids = parse(self.first_ids("users", GENERATION_CHUNK_SIZE).await?)?;
+		let product_ids = parse(self.first_ids("products", GENERATION_CHUNK_SIZE).await?)?;
+		anyhow::ensure!(
+			!user_ids.is_empty() && !product_ids.is_empty(),
+			"No users or products found to order"
+		);
+
+		let conn = self.writer.clone();
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+		let retry = self.retry;
+		let chunks = generate_order_chunks(
+			count,
+			self.context.payload(),
+			user_ids,
+			product_ids,
+			self.context.tenants(),
+			self.context.key_strategy()
+		);
+		for orders in chunks {
+			let context = self.context.clone();
+			conn.call(move |conn| {
+				let tx = conn.transaction()?;
+				for order in &orders {
+					retry.run(&context, is_transient, || {
+						tx.execute(
+							INSERT_ORDER_QUERY,
+							params![
+								key_value(encoding, &order.id),
+								order.tenant_id,
+								key_value(encoding, &order.user_id),
+								key_value(encoding, &order.product_id),
+								order.quantity,
+								order.total_price,
+								timestamp_value(timestamps, &order.created_at)
+							]
+						)
+					})?;
+				}
+				tx.commit()?;
+				Ok(())
+			}).await?;
+		}
+
+		Ok(())
+	}
+
+	async fn run_analytics_query(&self, query: AnalyticsQuery) -> Result<usize> {
+		let (reader, _) = &self.readers[0];
+		reader
+			.call(move |conn| {
+				let mut stmt = conn.prepare_cached(query.sql())?;
+				let mut rows = stmt.query([])?;
+				let mut count = 0;
+				while rows.next()?.is_some() {
+					count += 1;
+				}
+				Ok(count)
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	async fn create_import_table(&self, columns: &[ImportColumn]) -> Result<()> {
+		let id_type = match self.key_encoding {
+			KeyEncoding::String => "TEXT",
+			KeyEncoding::Binary => "BLOB",
+		};
+		let query = import::create_table_query(id_type, columns, |column_type| match column_type {
+			ImportColumnType::Integer | ImportColumnType::Boolean => "INTEGER",
+			ImportColumnType::Real => "REAL",
+			ImportColumnType::Text => "TEXT",
+		});
+		self.writer
+			.call(move |conn| {
+				conn.execute(DROP_IMPORT_TABLE_QUERY, [])?;
+				conn.execute(&query, [])?;
+				Ok(())
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	async fn insert_imported_records(&self, records: Arc<Vec<Record>>) -> Result<()> {
+		let Some(first) = records.first() else {
+			return Ok(());
+		};
+		let query = import::insert_query(first.values.len());
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+
+		self.writer
+			.call(move |conn| {
+				// All the records are written in a single transaction, like insert_wide_records
+				let tx = conn.transaction()?;
+				let mut stmt = tx.prepare(&query)?;
+
+				for record in records.iter() {
+					check_cancelled(&context)?;
+					let params = std::iter::once(key_value(encoding, &record.id))
+						.chain(record.values.iter().map(imported_value));
+					retry.run(&context, is_transient, || stmt.execute(params_from_iter(params.clone())))?;
+					context.record_operation();
+				}
+				drop(stmt);
+
+				tx.commit()?;
+				Ok(())
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	async fn read_imported_records(&self, ids: Arc<Vec<Uuid>>) -> Result<()> {
+		let context = self.context.clone();
+		let encoding = self.key_encoding;
+
+		self.run_parallel_reads(ids.len(), move |conn, offset, len| {
+			let mut stmt = conn.prepare_cached(READ_IMPORTED_RECORD_QUERY)?;
+
+			for id in &ids[offset..offset + len] {
+				check_cancelled(&context)?;
+				// The id, then every column
+				let _: Option<Vec<Value>> = stmt
+					.query_row([key_value(encoding, id)], |row| {
+						(0..row.as_ref().column_count()).map(|column| row.get::<_, Value>(column)).collect()
+					})
+					.optional()?;
+				context.record_operation();
 			}
 
-			// Commit the transaction
-			tx.commit()?;
+			Ok(())
+		}).await
+	}
+
+	async fn update_imported_records(
+		&self,
+		column: usize,
+		updates: Arc<Vec<(Uuid, Option<WideValue>)>>
+	) -> Result<()> {
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let query = import::update_query(column);
+
+		self.writer
+			.call(move |conn| {
+				let mut stmt = conn.prepare(&query)?;
+
+				for (id, value) in updates.iter() {
+					check_cancelled(&context)?;
+					retry.run(&context, is_transient, || {
+						stmt.execute(params![imported_value(value), key_value(encoding, id)])
+					})?;
+					context.record_operation();
+				}
+
+				Ok(())
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	async fn drop_import_table(&self) -> Result<()> {
+		self.writer
+			.call(|conn| {
+				conn.execute(DROP_IMPORT_TABLE_QUERY, [])?;
+				Ok(())
+			}).await
+			.map_err(anyhow::Error::from)
+	}
+
+	fn written_record_bytes(&self, test: BenchmarkTest) -> Option<u64> {
+		let payload = self.context.payload();
+		match test {
+			| BenchmarkTest::InsertSingleManyTimes
+			| BenchmarkTest::InsertManyAtOnce
+			| BenchmarkTest::ConcurrentInserts
+			| BenchmarkTest::UpdateSingleFieldOneEntry
+			| BenchmarkTest::UpdateSingleFieldManyEntries => Some(payload.user_record_bytes()),
+			| BenchmarkTest::UpdateMultipleFieldsOneEntry
+			| BenchmarkTest::UpdateMultipleFieldsManyEntries => Some(payload.product_record_bytes()),
+			// Orders have two items on average
+			BenchmarkTest::InsertOrdersWithItems => Some(ORDER_RECORD_BYTES + 2 * ORDER_ITEM_RECORD_BYTES),
+			BenchmarkTest::InsertWideRecords => Some(WIDE_RECORD_BYTES),
+			_ => None,
+		}
+	}
 
+	async fn checkpoint(&self) -> Result<()> {
+		// The runs don't write to a connected database, whose WAL is left to its owner
+		if self.connected {
+			return Ok(());
+		}
+		let conn = self.writer.clone();
+
+		// Write the WAL into the database file and truncate it
+		conn.call(|conn| {
+			conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
 			Ok(())
 		}).await?;
 
-		Ok(())
+		Ok(())
+	}
+
+	fn database_name(&self) -> String {
+		"SQLite".to_string()
+	}
+
+	async fn set_cpu_count(&mut self, count: usize) -> Result<()> {
+		self.cpu_count = count;
+		Ok(())
+	}
+
+	fn get_cpu_count(&self) -> usize {
+		self.cpu_count
+	}
+
+	fn set_index_mode(&mut self, indexes: IndexMode) {
+		self.indexes = indexes;
+	}
+
+	fn set_key_encoding(&mut self, encoding: KeyEncoding) {
+		self.key_encoding = encoding;
+	}
+
+	fn set_timestamp_encoding(&mut self, encoding: TimestampEncoding) {
+		self.timestamp_encoding = encoding;
+	}
+
+	fn run_context(&self) -> &RunContext {
+		&self.context
+	}
+
+	async fn count_records(&self) -> Result<RecordCounts> {
+		let conn = self.writer.clone();
+
+		conn.call(|conn| {
+			let count = |table: &str| {
+				conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+			};
+
+			Ok(RecordCounts {
+				users: count("users")? as usize,
+				products: count("products")? as usize,
+				orders: count("orders")? as usize,
+				order_items: count("order_items")? as usize,
+				wide_records: count("wide_records")? as usize,
+			})
+		}).await.map_err(anyhow::Error::from)
+	}
+
+	async fn verify_test(&self, test: BenchmarkTest, count: usize) -> Result<()> {
+		let conn = self.writer.clone();
+		// Values written by the last iteration of the single entry update tests
+		let last = count.saturating_sub(1);
+		// Under a distribution, the entry updated last by update_single_field_one_entry is random
+		let fixed_access = self.context.distribution().is_none();
+
+		let (stale, dangling, integrity) = conn.call(move |conn| {
+			// Records the test updated that don't hold the values it wrote
+			let stale: i64 = match test {
+				BenchmarkTest::UpdateSingleFieldOneEntry if count > 0 && fixed_access => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM users WHERE id = (SELECT id FROM users LIMIT 1) AND active != ?",
+						params![last.is_multiple_of(2)],
+						|row| row.get(0)
+					)?
+				}
+				BenchmarkTest::UpdateSingleFieldManyEntries => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM users WHERE id IN (SELECT id FROM users LIMIT ?) AND NOT active",
+						params![count],
+						|row| row.get(0)
+					)?
+				}
+				BenchmarkTest::UpdateMultipleFieldsOneEntry if count > 0 => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM products WHERE id = (SELECT id FROM products LIMIT 1) AND (description != ? OR stock != ?)",
+						params![format!("Updated description {}", last), 100 + (last % 50)],
+						|row| row.get(0)
+					)?
+				}
+				BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+					conn.query_row(
+						"SELECT COUNT(*) FROM products WHERE id IN (SELECT id FROM products LIMIT ?) AND description NOT LIKE 'Bulk updated description %'",
+						params![count],
+						|row| row.get(0)
+					)?
+				}
+				_ => 0,
+			};
+
+			// Orders and order items referencing a record that no longer exists
+			let dangling: i64 = conn.query_row(
+				"SELECT
+					(SELECT COUNT(*) FROM orders o
+					WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = o.user_id)
+					OR NOT EXISTS (SELECT 1 FROM products p WHERE p.id = o.product_id))
+					+ (SELECT COUNT(*) FROM order_items i
+					WHERE NOT EXISTS (SELECT 1 FROM orders o WHERE o.id = i.order_id)
+					OR NOT EXISTS (SELECT 1 FROM products p WHERE p.id = i.product_id))",
+				[],
+				|row| row.get(0)
+			)?;
+
+			// Checks among others that every index holds exactly the rows of its table
+			let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+
+			Ok((stale, dangling, integrity))
+		}).await?;
+
+		anyhow::ensure!(stale == 0, "{} updated records don't hold the values written by the test", stale);
+		anyhow::ensure!(dangling == 0, "{} orders or order items reference a missing record", dangling);
+		anyhow::ensure!(integrity == "ok", "integrity check failed: {}", integrity);
+
+		// Names not holding the adversarial text they were generated with
+		if self.context.payload().text == TextMode::Adversarial {
+			let names = conn.call(|conn| {
+				let mut stmt = conn.prepare("SELECT id, name FROM users UNION ALL SELECT id, name FROM products")?;
+				let names = stmt
+					.query_map([], |row| Ok((row.get::<_, Value>(0)?, row.get::<_, String>(1)?)))?
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(names)
+			}).await?;
+
+			let mut garbled = 0;
+			for (id, name) in names {
+				if !holds_text(&parse_key(id)?, &name) {
+					garbled += 1;
+				}
+			}
+			anyhow::ensure!(garbled == 0, "{} names don't read back as they were written", garbled);
+		}
+		Ok(())
+	}
+
+	async fn prepare(&self, test: BenchmarkTest, count: usize) -> Result<Fixture> {
+		let mut fixture = Fixture::default();
+		let payload = self.context.payload();
+		let tenants = self.context.tenants();
+		let key_strategy = self.context.key_strategy();
+
+		match test {
+			BenchmarkTest::InsertSingleManyTimes | BenchmarkTest::InsertManyAtOnce => {
+				fixture.users = (0..count).map(|_| generate_random_user(payload, tenants, key_strategy)).collect();
+			}
+			BenchmarkTest::ConcurrentInserts => {
+				fixture.users = (0..count).map(|_| generate_random_user(payload, tenants, key_strategy)).collect();
+				// Each writer has a connection of its own; under WAL they take turns holding the
+				// write lock, waiting for it up to the busy timeout
+				for _ in 0..self.context.writers() {
+					fixture.connections.push(Self::open_connection(&self.db_path, false).await?);
+				}
+			}
+			BenchmarkTest::LongTransactionContention => {
+				let ids = self.first_ids("users", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found to contend for");
+				fixture.ids = Arc::new(ids);
+				// Each contender has a connection of its own, giving up on the write lock sooner
+				// than the busy timeout of the others
+				for _ in 0..self.context.writers() {
+					let conn = Self::open_connection(&self.db_path, false).await?;
+					conn.call(|conn| Ok(conn.busy_timeout(Duration::from_millis(CONTENTION_TIMEOUT_MS))?)).await?;
+					fixture.connections.push(conn);
+				}
+			}
+			BenchmarkTest::ConsistencyProbe => {
+				let ids = self.first_ids("products", 1).await?;
+				anyhow::ensure!(!ids.is_empty(), "No product found to count with");
+				fixture.ids = Arc::new(ids);
+				// Each writer has a connection of its own, whose transactions see the snapshot
+				// of the database at their first read
+				for _ in 0..self.context.writers() {
+					fixture.connections.push(Self::open_connection(&self.db_path, false).await?);
+				}
+			}
+			BenchmarkTest::ReadByIdManyTimes => {
+				let mut ids = self.first_ids("users", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found to read");
+				if let Some(distribution) = self.context.distribution() {
+					ids = distribution::sample(distribution, &ids, count);
+				}
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::ReadManyByIds => {
+				let ids = self.first_ids("users", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found to read");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::UpdateSingleFieldOneEntry => {
+				// Under a distribution, each update goes to an entry drawn from the first `count` users
+				let distribution = self.context.distribution();
+				let mut ids = self.first_ids("users", if distribution.is_some() { count } else { 1 }).await?;
+				anyhow::ensure!(!ids.is_empty(), "No users found for update");
+				if let Some(distribution) = distribution {
+					ids = distribution::sample(distribution, &ids, count);
+				}
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+				let ids = self.first_ids("products", 1).await?;
+				anyhow::ensure!(!ids.is_empty(), "No products found for update");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+				fixture.ids = Arc::new(self.first_ids("products", count).await?);
+			}
+			BenchmarkTest::InsertOrdersWithItems => {
+				let parse = |ids: Vec<Value>| ids.into_iter().map(parse_key).collect::<Result<Vec<_>>>();
+				let user_ids = parse(self.first_ids("users", GENERATION_CHUNK_SIZE).await?)?;
+				let product_ids = parse(self.first_ids("products", GENERATION_CHUNK_SIZE).await?)?;
+				anyhow::ensure!(
+					!user_ids.is_empty() && !product_ids.is_empty(),
+					"No users or products found to order"
+				);
+				fixture.orders = generate_orders_with_items(
+					count,
+					payload,
+					&user_ids,
+					&product_ids,
+					tenants,
+					key_strategy
+				);
+			}
+			BenchmarkTest::ReadOrdersWithItems => {
+				let ids = self.first_ids("orders", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No orders found to read");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::InsertWideRecords => {
+				fixture.wide_records = (0..count).map(|_| generate_wide_record(key_strategy)).collect();
+			}
+			BenchmarkTest::ReadWideRecordsById => {
+				let ids = self.first_ids("wide_records", count).await?;
+				anyhow::ensure!(!ids.is_empty(), "No wide records found to read");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::SoftDeleteUsers => {
+				let ids = self.writer
+					.call(move |conn| {
+						let mut stmt = conn.prepare(LIVE_USER_IDS_QUERY)?;
+						let ids = stmt
+							.query_map([count], |row| row.get(0))?
+							.collect::<Result<Vec<Value>, _>>()?;
+						Ok(ids)
+					}).await?;
+				anyhow::ensure!(!ids.is_empty(), "No live users found to soft-delete");
+				fixture.ids = Arc::new(ids);
+			}
+			BenchmarkTest::ReadLiveUsers => {
+				// Soft-delete users until half of them are
+				let deleted_at = timestamp_value(self.timestamp_encoding, &Utc::now());
+				self.writer
+					.call(move |conn| {
+						let (users, deleted): (usize, usize) = conn.query_row(
+							"SELECT COUNT(*), COUNT(deleted_at) FROM users",
+							[],
+							|row| Ok((row.get(0)?, row.get(1)?))
+						)?;
+						conn.execute(SOFT_DELETE_USERS_QUERY, params![deleted_at, users_to_soft_delete(users, deleted)])?;
+						Ok(())
+					}).await?;
+			}
+			// These tests select their rows in the statements they time
+			| BenchmarkTest::ReadByColumnSearch
+			| BenchmarkTest::ReadWithOneJoin
+			| BenchmarkTest::ReadWithTwoJoins
+			| BenchmarkTest::UpdateSingleFieldManyEntries
+			| BenchmarkTest::ReadWideColumnSubset
+			| BenchmarkTest::ReadTenantOrders
+			| BenchmarkTest::AggregateOrdersByTenant
+			| BenchmarkTest::ReadUsersNotDeleted
+			| BenchmarkTest::ReadDiscountedProducts
+			| BenchmarkTest::ReadProjection => {}
+		}
+
+		Ok(fixture)
+	}
+
+	fn traced_fixture(&self, step: TraceOp, chunk: &TraceChunk) -> Result<(BenchmarkTest, Fixture)> {
+		let mut fixture = Fixture::default();
+		if step == TraceOp::Insert {
+			fixture.users = chunk.users.clone();
+		} else {
+			fixture.ids = Arc::new(chunk.keys.iter().map(|id| key_value(self.key_encoding, id)).collect());
+		}
+		Ok((step.test(), fixture))
+	}
+
+	async fn insert_single_many_times(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let users = fixture.users;
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::InsertSingleManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				conn.call(move |conn| {
+					for user in &users {
+						check_cancelled(&context)?;
+						retry.run(&context, is_transient, || {
+							conn.execute(
+								INSERT_USER_QUERY,
+								params![
+									key_value(encoding, &user.id),
+									user.tenant_id,
+									user.name,
+									user.email,
+									timestamp_value(timestamps, &user.created_at),
+									user.active as i32,
+									user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+								]
+							)
+						})?;
+						context.record_operation();
+					}
+					Ok(())
+				}).await.map_err(anyhow::Error::from)
+			}
+		).await
+	}
+
+	async fn insert_many_at_once(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = fixture.users;
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::InsertManyAtOnce.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				conn.call(move |conn| {
+					// Each batch of users is inserted in its own transaction
+					for batch in users.chunks(batch_len) {
+						let start = Instant::now();
+						let tx = conn.transaction()?;
+
+						for user in batch {
+							check_cancelled(&context)?;
+							retry.run(&context, is_transient, || {
+								tx.execute(
+									INSERT_USER_QUERY,
+									params![
+										key_value(encoding, &user.id),
+										user.tenant_id,
+										user.name,
+										user.email,
+										timestamp_value(timestamps, &user.created_at),
+										user.active as i32,
+										user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+									]
+								)
+							})?;
+						}
+
+						tx.commit()?;
+						context.record_batch(batch.len(), start.elapsed());
+					}
+					Ok(())
+				}).await.map_err(anyhow::Error::from)
+			}
+		).await
+	}
+
+	async fn read_by_id_many_times(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+		let ids = fixture.ids;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadByIdManyTimes.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_BY_ID_QUERY)?;
+
+					for i in offset..offset + len {
+						check_cancelled(&context)?;
+						let id = &ids[i % ids.len()];
+
+						let user = stmt.query_row([id], raw_row).optional()?;
+						decode_rows(&codec, user, User::try_from)?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_many_by_ids(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+		let ids = fixture.ids;
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadManyByIds.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader fetches its own slice of the IDs in a single query
+				self.run_parallel_reads(ids.len(), move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let start = Instant::now();
+					let ids = &ids[offset..offset + len];
+					let mut stmt = conn.prepare(&read_many_by_ids_query(ids.len()))?;
+
+					let params: Vec<&dyn rusqlite::ToSql> = ids
+						.iter()
+						.map(|id| id as &dyn rusqlite::ToSql)
+						.collect();
+
+					let users = stmt
+						.query_map(params.as_slice(), raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = users.len();
+					decode_rows(&codec, users, User::try_from)?;
+					context.record_batch(count, start.elapsed());
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_by_column_search(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+		let pattern = search_pattern(self.context.selectivity());
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadByColumnSearch.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader scans its own page of the matching rows
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare(READ_BY_COLUMN_QUERY)?;
+
+					let users = stmt
+						.query_map(params![pattern, len, offset], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = users.len() as u64;
+					decode_rows(&codec, users, User::try_from)?;
+					context.record_operations(count);
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_with_one_join(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWithOneJoin.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare(ONE_JOIN_QUERY)?;
+
+					let results = stmt
+						.query_map([len, offset], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = results.len() as u64;
+					decode_rows(&codec, results, |Row(mut values)| {
+						// The order, then its user
+						let user = User::try_from(Row(values.split_off(4)))?;
+						let order = Row(values);
+						Ok((order.key(0)?, order.integer::<i32>(1)?, order.real(2)?, order.timestamp(3)?, user))
+					})?;
+					context.record_operations(count);
+
+					Ok(())
+				}).await
+			}
+		).await
 	}
 
-	async fn cleanup(&self) -> Result<()> {
-		let conn = self.get_async_connection().await?;
+	async fn read_with_two_joins(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let codec = RowCodec::new(&self.context);
 
-		conn.call(|conn| {
-			// Use a transaction for better performance
-			let tx = conn.transaction()?;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWithTwoJoins.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare(TWO_JOINS_QUERY)?;
 
-			// Delete all data
-			tx.execute("DELETE FROM orders", [])?;
-			tx.execute("DELETE FROM products", [])?;
-			tx.execute("DELETE FROM users", [])?;
+					let results = stmt
+						.query_map([len, offset], raw_row)?
+						.collect::<Result<Vec<_>, _>>()?;
+					let count = results.len() as u64;
+					decode_rows(&codec, results, OrderWithDetails::try_from)?;
+					context.record_operations(count);
 
-			// Commit the transaction
-			tx.commit()?;
+					Ok(())
+				}).await
+			}
+		).await
+	}
 
-			Ok(())
-		}).await?;
+	async fn update_single_field_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let ids = fixture.ids;
 
-		Ok(())
-	}
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateSingleFieldOneEntry.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				let statements = self.writer_statements.clone();
 
-	fn database_name(&self) -> String {
-		"SQLite".to_string()
-	}
+				conn.call(move |conn| {
+					let conn = CachingConnection { conn, statements: &statements };
 
-	fn set_cpu_count(&mut self, count: usize) {
-		self.cpu_count = count;
-	}
+					for i in 0..count {
+						check_cancelled(&context)?;
+						let user_id = &ids[i % ids.len()];
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(UPDATE_USER_QUERY)?.execute(params![i % 2 == 0, user_id])
+						})?;
+						context.record_operation();
+					}
 
-	fn get_cpu_count(&self) -> usize {
-		self.cpu_count
+					Ok(())
+				}).await.map_err(anyhow::Error::from)
+			}
+		).await
 	}
 
-	async fn insert_single_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn update_single_field_many_entries(
+		&self,
+		count: usize,
+		_fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
 
 		measure_execution(
 			&self.database_name(),
-			"Insert Single Many Times",
+			BenchmarkTest::UpdateSingleFieldManyEntries.name(),
 			count,
 			self.cpu_count,
 			|| async {
 				conn.call(move |conn| {
-					for _ in 0..count {
-						let user = generate_random_user();
-						conn.execute(
-							"INSERT INTO users (id, name, email, created_at, active) VALUES (?, ?, ?, ?, ?)",
-							params![
-								user.id.to_string(),
-								user.name,
-								user.email,
-								user.created_at.to_rfc3339(),
-								user.active as i32
-							]
-						)?;
+					// Each batch of users is updated by its own statement, in its own transaction
+					for offset in (0..count).step_by(batch_len) {
+						check_cancelled(&context)?;
+						let updated = retry.run(&context, is_transient, || {
+							conn.execute(
+								UPDATE_USERS_QUERY,
+								params![true, batch_len.min(count - offset), offset]
+							)
+						})?;
+						context.record_operations(updated as u64);
 					}
+
 					Ok(())
 				}).await.map_err(anyhow::Error::from)
 			}
 		).await
 	}
 
-	async fn insert_many_at_once(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn update_multiple_fields_one_entry(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let product_id = fixture.ids[0].clone();
 
 		measure_execution(
 			&self.database_name(),
-			"Insert Many At Once",
+			BenchmarkTest::UpdateMultipleFieldsOneEntry.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				// Generate users
-				let users: Vec<User> = (0..count)
-					.map(|_| generate_random_user())
-					.collect();
+				let product_id_clone = product_id.clone();
+				let statements = self.writer_statements.clone();
 
 				conn.call(move |conn| {
-					let tx = conn.transaction()?;
+					let conn = CachingConnection { conn, statements: &statements };
 
-					for user in &users {
-						tx.execute(
-							"INSERT INTO users (id, name, email, created_at, active) VALUES (?, ?, ?, ?, ?)",
-							params![
-								user.id.to_string(),
-								user.name,
-								user.email,
-								user.created_at.to_rfc3339(),
-								user.active as i32
-							]
-						)?;
+					for i in 0..count {
+						check_cancelled(&context)?;
+						let new_price = 10.0 + ((i as f64) % 100.0);
+						let new_stock = 100 + (i % 50);
+
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(UPDATE_PRODUCT_QUERY)?.execute(
+								params![
+									new_price,
+									new_stock,
+									format!("Updated description {}", i),
+									product_id_clone
+								]
+							)
+						})?;
+						context.record_operation();
 					}
 
-					tx.commit()?;
 					Ok(())
 				}).await.map_err(anyhow::Error::from)
 			}
 		).await
 	}
 
-	async fn read_by_id_many_times(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn update_multiple_fields_many_entries(
+		&self,
+		count: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let product_ids = fixture.ids;
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let batch_len = self.context.batch_len(count);
+		let timestamps = self.timestamp_encoding;
 
-		// First get a list of IDs to fetch
-		let ids = conn
-			.call(move |conn| {
-				let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-				let ids: Result<Vec<String>, _> = stmt
-					.query_map([count], |row| row.get(0))?
-					.collect();
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::UpdateMultipleFieldsManyEntries.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				let statements = self.writer_statements.clone();
 
-				Ok(ids?)
-			}).await
-			.map_err(anyhow::Error::from)?;
+				conn.call(move |conn| {
+					// Update each product with new values
+					let update_time = timestamp_value(timestamps, &Utc::now());
+
+					// Each batch of products is updated in its own transaction
+					for batch in product_ids.chunks(batch_len) {
+						let tx = conn.transaction()?;
+						let cached = CachingConnection { conn: &tx, statements: &statements };
+
+						for id in batch {
+							check_cancelled(&context)?;
+							retry.run(&context, is_transient, || {
+								cached.prepare_cached(UPDATE_PRODUCTS_QUERY)?.execute(
+									params![
+										format!("Bulk updated description {}", Uuid::new_v4()),
+										update_time,
+										id
+									]
+								)
+							})?;
+						}
+
+						tx.commit()?;
+						context.record_operations(batch.len() as u64);
+					}
+
+					Ok(())
+				}).await.map_err(anyhow::Error::from)
+			}
+		).await
+	}
+
+	async fn insert_orders_with_items(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let orders = fixture.orders;
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
 
 		measure_execution(
 			&self.database_name(),
-			"Read By ID Many Times",
+			BenchmarkTest::InsertOrdersWithItems.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let ids_clone = ids.clone();
+				let statements = self.writer_statements.clone();
 
 				conn.call(move |conn| {
-					for i in 0..count {
-						let id = &ids_clone[i % ids_clone.len()];
-
-						let _: Option<(String, String, String, String, bool)> = conn
-							.query_row(
-								"SELECT id, name, email, created_at, active FROM users WHERE id = ?",
-								[id],
-								|row| {
-									Ok((
-										row.get(0)?,
-										row.get(1)?,
-										row.get(2)?,
-										row.get(3)?,
-										row.get::<_, i32>(4)? == 1,
-									))
-								}
-							)
-							.optional()?;
+					// Each order is written with its items in its own transaction, retried as a whole
+					for order in &orders {
+						check_cancelled(&context)?;
+						retry.run(&context, is_transient, || {
+							let tx = conn.transaction()?;
+							let cached = CachingConnection { conn: &tx, statements: &statements };
+
+							cached.prepare_cached(INSERT_ORDER_QUERY)?.execute(
+								params![
+									key_value(encoding, &order.order.id),
+									order.order.tenant_id,
+									key_value(encoding, &order.order.user_id),
+									key_value(encoding, &order.order.product_id),
+									order.order.quantity,
+									order.order.total_price,
+									timestamp_value(timestamps, &order.order.created_at)
+								]
+							)?;
+							let mut stmt = cached.prepare_cached(INSERT_ORDER_ITEM_QUERY)?;
+							for item in &order.items {
+								stmt.execute(
+									params![
+										key_value(encoding, &item.id),
+										key_value(encoding, &item.order_id),
+										key_value(encoding, &item.product_id),
+										item.quantity,
+										item.unit_price
+									]
+								)?;
+							}
+							drop(stmt);
+
+							tx.commit()
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
@@ -364,231 +2013,307 @@ impl DatabaseBenchmark for SqliteBenchmark {
 		).await
 	}
 
-	async fn read_many_by_ids(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn read_orders_with_items(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let ids = fixture.ids;
 
-		// First get a list of IDs to fetch
-		let ids = conn
-			.call(move |conn| {
-				let mut stmt = conn.prepare("SELECT id FROM users LIMIT ?")?;
-				let ids: Result<Vec<String>, _> = stmt
-					.query_map([count], |row| row.get(0))?
-					.collect();
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadOrdersWithItems.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_ORDER_WITH_ITEMS_QUERY)?;
+
+					for i in offset..offset + len {
+						check_cancelled(&context)?;
+						let id = &ids[i % ids.len()];
+
+						let _items: Vec<_> = stmt
+							.query_map([id], |row| {
+								Ok((
+									// Order data
+									row.get::<_, Value>(0)?,
+									row.get::<_, Value>(1)?,
+									row.get::<_, f64>(2)?,
+									row.get::<_, Value>(3)?,
+									// Item data
+									row.get::<_, Value>(4)?,
+									row.get::<_, Value>(5)?,
+									row.get::<_, i32>(6)?,
+									row.get::<_, f64>(7)?,
+									row.get::<_, String>(8)?,
+								))
+							})?
+							.collect::<Result<Vec<_>, _>>()?;
+						context.record_operation();
+					}
 
-				Ok(ids?)
-			}).await
-			.map_err(anyhow::Error::from)?;
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn insert_wide_records(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let records = fixture.wide_records;
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
 
 		measure_execution(
 			&self.database_name(),
-			"Read Many By IDs",
+			BenchmarkTest::InsertWideRecords.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let ids_clone = ids.clone();
+				let statements = self.writer_statements.clone();
 
 				conn.call(move |conn| {
-					// Build a query with all IDs
-					// Note: This is not the most efficient way to do this in SQLite
-					// A better approach would use a temporary table or multiple parameters
-					// But this is a simple demonstration
-					let placeholders = std::iter
-						::repeat("?")
-						.take(ids_clone.len())
-						.collect::<Vec<_>>()
-						.join(",");
-
-					let query =
-						format!("SELECT id, name, email, created_at, active FROM users WHERE id IN ({})", placeholders);
-
-					let mut stmt = conn.prepare(&query)?;
-
-					let params: Vec<&dyn rusqlite::ToSql> = ids_clone
-						.iter()
-						.map(|id| id as &dyn rusqlite::ToSql)
-						.collect();
+					// All the records are written in a single transaction, like insert_many_at_once
+					let tx = conn.transaction()?;
+					let cached = CachingConnection { conn: &tx, statements: &statements };
+					let mut stmt = cached.prepare_cached(INSERT_WIDE_RECORD_QUERY.as_str())?;
+
+					for record in &records {
+						check_cancelled(&context)?;
+						retry.run(&context, is_transient, || {
+							stmt.execute(params_from_iter(wide_record_params(encoding, record)))
+						})?;
+						context.record_operation();
+					}
+					drop(stmt);
 
-					let _rows = stmt.query_map(params.as_slice(), |row| {
-						Ok((
-							row.get::<_, String>(0)?,
-							row.get::<_, String>(1)?,
-							row.get::<_, String>(2)?,
-							row.get::<_, String>(3)?,
-							row.get::<_, i32>(4)? == 1,
-						))
-					})?;
+					tx.commit()?;
+					Ok(())
+				}).await.map_err(anyhow::Error::from)
+			}
+		).await
+	}
+
+	async fn read_wide_records_by_id(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let ids = fixture.ids;
 
-					// We need to collect to actually process the rows
-					let _results: Vec<_> = _rows.collect::<Result<Vec<_>, _>>()?;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadWideRecordsById.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_WIDE_RECORD_QUERY)?;
+
+					for i in offset..offset + len {
+						check_cancelled(&context)?;
+						let id = &ids[i % ids.len()];
+
+						// The id, then every column
+						let _: Option<Vec<Value>> = stmt
+							.query_row([id], |row| {
+								(0..=WIDE_COLUMN_COUNT).map(|column| row.get::<_, Value>(column)).collect()
+							})
+							.optional()?;
+						context.record_operation();
+					}
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await
 			}
 		).await
 	}
 
-	async fn read_by_column_search(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn read_wide_column_subset(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
 
 		measure_execution(
 			&self.database_name(),
-			"Read By Column Search",
+			BenchmarkTest::ReadWideColumnSubset.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				conn.call(move |conn| {
-					let mut stmt = conn.prepare(
-						"SELECT id, name, email, created_at, active FROM users WHERE email LIKE ? LIMIT ?"
-					)?;
+				// Each reader scans its own page of the table
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare_cached(READ_WIDE_COLUMN_SUBSET_QUERY.as_str())?;
 
 					let _results: Vec<_> = stmt
-						.query_map(params!["%example.com%", count], |row| {
-							Ok((
-								row.get::<_, String>(0)?,
-								row.get::<_, String>(1)?,
-								row.get::<_, String>(2)?,
-								row.get::<_, String>(3)?,
-								row.get::<_, i32>(4)? == 1,
-							))
+						.query_map(params![len, offset], |row| {
+							Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
 						})?
 						.collect::<Result<Vec<_>, _>>()?;
+					context.record_operations(_results.len() as u64);
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await
 			}
 		).await
 	}
 
-	async fn read_with_one_join(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn read_tenant_orders(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+		let tenants = self.context.tenants();
 
 		measure_execution(
 			&self.database_name(),
-			"Read With One Join",
+			BenchmarkTest::ReadTenantOrders.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				conn.call(move |conn| {
-					let query =
-						"
-						SELECT o.id, o.quantity, o.total_price, o.created_at,
-							   u.id, u.name, u.email, u.created_at, u.active
-						FROM orders o
-						JOIN users u ON o.user_id = u.id
-						LIMIT ?
-					";
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					let mut stmt = conn.prepare_cached(READ_TENANT_ORDERS_QUERY)?;
+
+					// Each query reads a page of the orders of the next tenant
+					for i in offset..offset + len {
+						check_cancelled(&context)?;
+						let tenant_id = (i % tenants) as u32;
+
+						let _orders: Vec<_> = stmt
+							.query_map(params![tenant_id, TENANT_PAGE_LEN], |row| {
+								Ok((
+									row.get::<_, Value>(0)?,
+									row.get::<_, Value>(1)?,
+									row.get::<_, Value>(2)?,
+									row.get::<_, i32>(3)?,
+									row.get::<_, f64>(4)?,
+									row.get::<_, Value>(5)?,
+								))
+							})?
+							.collect::<Result<Vec<_>, _>>()?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn aggregate_orders_by_tenant(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
+
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::AggregateOrdersByTenant.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				self.run_parallel_reads(count, move |conn, _offset, len| {
+					let mut stmt = conn.prepare_cached(AGGREGATE_ORDERS_BY_TENANT_QUERY)?;
+
+					for _ in 0..len {
+						check_cancelled(&context)?;
+						let _tenants: Vec<_> = stmt
+							.query_map([], |row| {
+								Ok((
+									row.get::<_, u32>(0)?,
+									row.get::<_, i64>(1)?,
+									row.get::<_, i64>(2)?,
+									row.get::<_, f64>(3)?,
+								))
+							})?
+							.collect::<Result<Vec<_>, _>>()?;
+						context.record_operation();
+					}
+
+					Ok(())
+				}).await
+			}
+		).await
+	}
+
+	async fn read_users_not_deleted(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
 
-					let mut stmt = conn.prepare(query)?;
+		measure_execution(
+			&self.database_name(),
+			BenchmarkTest::ReadUsersNotDeleted.name(),
+			count,
+			self.cpu_count,
+			|| async {
+				// Each reader reads its own page of the users not deleted
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare_cached(READ_USERS_NOT_DELETED_QUERY)?;
 
 					let _results: Vec<_> = stmt
-						.query_map([count], |row| {
+						.query_map(params![len, offset], |row| {
 							Ok((
-								// Order data
-								row.get::<_, String>(0)?,
-								row.get::<_, i32>(1)?,
-								row.get::<_, f64>(2)?,
-								row.get::<_, String>(3)?,
-								// User data
-								row.get::<_, String>(4)?,
-								row.get::<_, String>(5)?,
-								row.get::<_, String>(6)?,
-								row.get::<_, String>(7)?,
-								row.get::<_, i32>(8)? == 1,
+								row.get::<_, Value>(0)?,
+								row.get::<_, String>(1)?,
+								row.get::<_, String>(2)?,
+								row.get::<_, Value>(3)?,
 							))
 						})?
 						.collect::<Result<Vec<_>, _>>()?;
+					context.record_operations(_results.len() as u64);
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await
 			}
 		).await
 	}
 
-	async fn read_with_two_joins(&self, count: usize) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn read_discounted_products(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
 
 		measure_execution(
 			&self.database_name(),
-			"Read With Two Joins",
+			BenchmarkTest::ReadDiscountedProducts.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				conn.call(move |conn| {
-					let query =
-						"
-						SELECT o.id, o.quantity, o.total_price, o.created_at,
-							   u.id, u.name, u.email, u.created_at, u.active,
-							   p.id, p.name, p.description, p.price, p.stock, p.created_at
-						FROM orders o
-						JOIN users u ON o.user_id = u.id
-						JOIN products p ON o.product_id = p.id
-						LIMIT ?
-					";
-
-					let mut stmt = conn.prepare(query)?;
+				// Each reader reads its own page of the products on discount
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare_cached(READ_DISCOUNTED_PRODUCTS_QUERY)?;
 
 					let _results: Vec<_> = stmt
-						.query_map([count], |row| {
+						.query_map(params![len, offset], |row| {
 							Ok((
-								// Order data
-								row.get::<_, String>(0)?,
-								row.get::<_, i32>(1)?,
+								row.get::<_, Value>(0)?,
+								row.get::<_, String>(1)?,
 								row.get::<_, f64>(2)?,
-								row.get::<_, String>(3)?,
-								// User data
-								row.get::<_, String>(4)?,
-								row.get::<_, String>(5)?,
-								row.get::<_, String>(6)?,
-								row.get::<_, String>(7)?,
-								row.get::<_, i32>(8)? == 1,
-								// Product data
-								row.get::<_, String>(9)?,
-								row.get::<_, String>(10)?,
-								row.get::<_, String>(11)?,
-								row.get::<_, f64>(12)?,
-								row.get::<_, i32>(13)?,
-								row.get::<_, String>(14)?,
+								row.get::<_, f64>(3)?,
 							))
 						})?
 						.collect::<Result<Vec<_>, _>>()?;
+					context.record_operations(_results.len() as u64);
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await
 			}
 		).await
 	}
 
-	async fn update_single_field_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
-
-		// Get a random user ID to update
-		let user_id = conn
-			.call(
-				|conn| -> Result<String, tokio_rusqlite::Error> {
-					conn.query_row("SELECT id FROM users LIMIT 1", [], |row| {
-						row.get::<_, String>(0)
-					}).map_err(tokio_rusqlite::Error::from)
-				}
-			).await
-			.map_err(anyhow::Error::from)?;
+	async fn soft_delete_users(&self, count: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let conn = self.writer.clone();
+		let context = self.context.clone();
+		let retry = self.retry;
+		let timestamps = self.timestamp_encoding;
+		let ids = fixture.ids;
 
 		measure_execution(
 			&self.database_name(),
-			"Update Single Field One Entry",
+			BenchmarkTest::SoftDeleteUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				let user_id_clone = user_id.clone();
+				let statements = self.writer_statements.clone();
 
 				conn.call(move |conn| {
+					let conn = CachingConnection { conn, statements: &statements };
+
 					for i in 0..count {
-						conn.execute(
-							"UPDATE users SET active = ? WHERE id = ?",
-							params![i % 2 == 0, user_id_clone]
-						)?;
+						check_cancelled(&context)?;
+						let user_id = &ids[i % ids.len()];
+						let deleted_at = timestamp_value(timestamps, &Utc::now());
+						retry.run(&context, is_transient, || {
+							conn.prepare_cached(SOFT_DELETE_USER_QUERY)?.execute(params![deleted_at, user_id])
+						})?;
+						context.record_operation();
 					}
 
 					Ok(())
@@ -597,122 +2322,231 @@ impl DatabaseBenchmark for SqliteBenchmark {
 		).await
 	}
 
-	async fn update_single_field_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn read_live_users(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		let context = self.context.clone();
 
 		measure_execution(
 			&self.database_name(),
-			"Update Single Field Many Entries",
+			BenchmarkTest::ReadLiveUsers.name(),
 			count,
 			self.cpu_count,
 			|| async {
-				conn.call(move |conn| {
-					conn.execute(
-						"UPDATE users SET active = ? WHERE id IN (SELECT id FROM users LIMIT ?)",
-						params![true, count]
-					)?;
+				// Each reader reads its own page of the live users
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare_cached(READ_LIVE_USERS_QUERY)?;
+
+					let _results: Vec<_> = stmt
+						.query_map(params![len, offset], |row| {
+							Ok((
+								row.get::<_, Value>(0)?,
+								row.get::<_, String>(1)?,
+								row.get::<_, String>(2)?,
+								row.get::<_, Value>(3)?,
+							))
+						})?
+						.collect::<Result<Vec<_>, _>>()?;
+					context.record_operations(_results.len() as u64);
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await
 			}
 		).await
 	}
 
-	async fn update_multiple_fields_one_entry(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
-
-		// Get a random product ID to update
-		let product_id = conn
-			.call(
-				|conn| -> Result<String, tokio_rusqlite::Error> {
-					conn.query_row("SELECT id FROM products LIMIT 1", [], |row| {
-						row.get::<_, String>(0)
-					}).map_err(tokio_rusqlite::Error::from)
-				}
-			).await
-			.map_err(anyhow::Error::from)?;
+	async fn concurrent_inserts(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let users = Arc::new(fixture.users);
+		let connections = fixture.connections;
+		let context = self.context.clone();
+		let retry = self.retry;
+		let encoding = self.key_encoding;
+		let timestamps = self.timestamp_encoding;
 
-		measure_execution(
+		measure_concurrent_inserts(
 			&self.database_name(),
-			"Update Multiple Fields One Entry",
 			count,
+			writers,
 			self.cpu_count,
-			|| async {
-				let product_id_clone = product_id.clone();
+			|writer, range| {
+				let conn = connections[writer].clone();
+				let users = users.clone();
+				let context = context.clone();
+				async move {
+					conn.call(move |conn| {
+						for user in &users[range] {
+							check_cancelled(&context)?;
+							retry.run(&context, is_transient, || {
+								conn.execute(
+									INSERT_USER_QUERY,
+									params![
+										key_value(encoding, &user.id),
+										user.tenant_id,
+										user.name,
+										user.email,
+										timestamp_value(timestamps, &user.created_at),
+										user.active as i32,
+										user.deleted_at.as_ref().map(|deleted_at| timestamp_value(timestamps, deleted_at))
+									]
+								)
+							})?;
+							context.record_operation();
+						}
+						Ok(())
+					}).await.map_err(anyhow::Error::from)
+				}
+			}
+		).await
+	}
 
-				conn.call(move |conn| {
-					for i in 0..count {
-						let new_price = 10.0 + ((i as f64) % 100.0);
-						let new_stock = 100 + (i % 50);
+	async fn long_transaction_contention(
+		&self,
+		count: usize,
+		contenders: usize,
+		fixture: Fixture
+	) -> Result<BenchmarkResult> {
+		let ids = fixture.ids;
+		let connections = fixture.connections;
+		let context = self.context.clone();
+		let holder = self.writer.clone();
+		let held_ids = ids.clone();
 
-						conn.execute(
-							"UPDATE products SET price = ?, stock = ?, description = ? WHERE id = ?",
-							params![
-								new_price,
-								new_stock,
-								format!("Updated description {}", i),
-								product_id_clone
-							]
-						)?;
+		measure_contention(
+			&self.database_name(),
+			count,
+			contenders,
+			self.cpu_count,
+			|hold| async move {
+				holder.call(move |conn| {
+					// The transaction takes the write lock as it begins, and rolls back if dropped
+					let transaction = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+					for (i, user_id) in held_ids.iter().take(CONTENDED_USERS).enumerate() {
+						transaction.execute(UPDATE_USER_QUERY, params![i % 2 == 0, user_id])?;
 					}
-
+					hold.hold();
+					transaction.commit()?;
 					Ok(())
 				}).await.map_err(anyhow::Error::from)
+			},
+			|contender, range, counters| {
+				let conn = connections[contender].clone();
+				let ids = ids.clone();
+				let context = context.clone();
+				async move {
+					conn.call(move |conn| {
+						for i in range {
+							check_cancelled(&context)?;
+							let user_id = &ids[i % ids.len()];
+							let write = is_contended_write(i);
+							let start = Instant::now();
+							let outcome = if write {
+								conn.execute(UPDATE_USER_QUERY, params![i % 2 == 0, user_id]).map(|_| ())
+							} else {
+								conn.query_row(READ_BY_ID_QUERY, [user_id], |_| Ok(())).optional().map(|_| ())
+							};
+							match outcome {
+								Ok(()) => counters.record(write, start.elapsed(), false),
+								Err(e) if is_transient(&e) => counters.record(write, start.elapsed(), true),
+								Err(e) => return Err(e.into()),
+							}
+							context.record_operation();
+						}
+						Ok(())
+					}).await.map_err(anyhow::Error::from)
+				}
 			}
 		).await
 	}
 
-	async fn update_multiple_fields_many_entries(
-		&self,
-		count: usize
-	) -> Result<BenchmarkResult> {
-		let conn = self.get_async_connection().await?;
+	async fn consistency_probe(&self, count: usize, writers: usize, fixture: Fixture) -> Result<BenchmarkResult> {
+		let product_id = fixture.ids[0].clone();
+		let connections = fixture.connections;
+		let context = self.context.clone();
+		let reader = self.writer.clone();
 
-		measure_execution(
+		measure_consistency(
 			&self.database_name(),
-			"Update Multiple Fields Many Entries",
 			count,
+			writers,
 			self.cpu_count,
-			|| async {
-				conn.call(move |conn| {
-					// Using a transaction for better performance
-					let tx = conn.transaction()?;
+			|| {
+				let product_id = product_id.clone();
+				let reader = reader.clone();
+				async move {
+					reader
+						.call(move |conn| Ok(conn.query_row(READ_PRODUCT_STOCK_QUERY, [product_id], |row| row.get(0))?))
+						.await
+						.map_err(anyhow::Error::from)
+				}
+			},
+			|writer, range, counters| {
+				let conn = connections[writer].clone();
+				let product_id = product_id.clone();
+				let context = context.clone();
+				async move {
+					conn.call(move |conn| {
+						for _ in range {
+							check_cancelled(&context)?;
+							// A deferred transaction reads from its snapshot; under WAL, writing
+							// after another connection committed fails with a busy snapshot
+							let outcome = (|| {
+								let transaction = conn.transaction()?;
+								let stock: i64 = transaction.query_row(
+									READ_PRODUCT_STOCK_QUERY,
+									[&product_id],
+									|row| row.get(0)
+								)?;
+								transaction.execute(UPDATE_PRODUCT_STOCK_QUERY, params![stock + 1, product_id])?;
+								transaction.commit()
+							})();
+							match outcome {
+								Ok(()) => counters.record(true),
+								Err(e) if is_transient(&e) => counters.record(false),
+								Err(e) => return Err(e.into()),
+							}
+							context.record_operation();
+						}
+						Ok(())
+					}).await.map_err(anyhow::Error::from)
+				}
+			}
+		).await
+	}
 
-					// Get product IDs to update
-					let product_ids = {
-						let mut stmt = tx.prepare("SELECT id FROM products LIMIT ?")?;
-						let ids: Vec<String> = stmt
-							.query_map([count], |row| row.get(0))?
+	async fn read_projection(&self, count: usize, _fixture: Fixture) -> Result<BenchmarkResult> {
+		measure_projection(&self.database_name(), count, self.cpu_count, |projection| {
+			let context = self.context.clone();
+			let rows = Arc::new(AtomicU64::new(0));
+			let read_rows = rows.clone();
+			async move {
+				// Each reader reads its own page of the products, getting every column it selected;
+				// a row is read from its page whole whichever columns are selected
+				self.run_parallel_reads(count, move |conn, offset, len| {
+					check_cancelled(&context)?;
+					let mut stmt = conn.prepare_cached(projection.query())?;
+					let columns = stmt.column_count();
+
+					let mut page = stmt.query(params![len, offset])?;
+					let mut read = 0;
+					while let Some(row) = page.next()? {
+						let _values = (0..columns)
+							.map(|column| row.get::<_, Value>(column))
 							.collect::<Result<Vec<_>, _>>()?;
-						ids
-					};
-
-					// Update each product with new values
-					let update_time = chrono::Utc::now().to_rfc3339();
-
-					for id in product_ids {
-						tx.execute(
-							"UPDATE products SET price = price * 1.1, stock = stock + 10, description = ?, created_at = ? WHERE id = ?",
-							params![
-								format!("Bulk updated description {}", Uuid::new_v4()),
-								update_time,
-								id
-							]
-						)?;
+						read += 1;
 					}
-
-					// Commit the transaction
-					tx.commit()?;
+					projection.record(&context, read);
+					read_rows.fetch_add(read, Ordering::Relaxed);
 
 					Ok(())
-				}).await.map_err(anyhow::Error::from)
+				}).await?;
+				Ok(rows.load(Ordering::Relaxed))
 			}
-		).await
+		}).await
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::SqliteBenchmark;
+
+	common::conformance_tests!(dir => SqliteBenchmark::new(1, &dir));
+}
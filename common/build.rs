@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+	// Use the bundled protoc so building doesn't require a system installation
+	std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+	tonic_build::compile_protos("proto/benchmark.proto")?;
+
+	Ok(())
+}
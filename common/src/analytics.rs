@@ -0,0 +1,77 @@
+// Analytical queries inspired by TPC-H, run over many more orders than users or products like
+// its line items, for the databases with a SQL engine. The SQL is standard enough to run as is in
+// SQLite and DuckDB, with either key or timestamp encoding.
+
+// Orders added per generated user when the plan doesn't say
+pub const DEFAULT_ANALYTICS_SCALE: usize = 10;
+
+// Times each query is run; the result reports them as its operations
+pub const ANALYTICS_QUERY_RUNS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalyticsQuery {
+	// Pricing summary of the orders above a price, per quantity (TPC-H Q1)
+	FilteredAggregation,
+	// Customers and revenue of cheap products, per user status (TPC-H Q3 and Q5)
+	JoinGroupBy,
+	// Products bringing the most revenue (TPC-H Q10 and Q18)
+	TopRevenue,
+}
+
+impl AnalyticsQuery {
+	pub const ALL: [AnalyticsQuery; 3] = [
+		AnalyticsQuery::FilteredAggregation,
+		AnalyticsQuery::JoinGroupBy,
+		AnalyticsQuery::TopRevenue,
+	];
+
+	// Name of the results of the query
+	pub fn name(&self) -> &'static str {
+		match self {
+			AnalyticsQuery::FilteredAggregation => "analytics_filtered_aggregation",
+			AnalyticsQuery::JoinGroupBy => "analytics_join_group_by",
+			AnalyticsQuery::TopRevenue => "analytics_top_revenue",
+		}
+	}
+
+	pub fn sql(&self) -> &'static str {
+		match self {
+			AnalyticsQuery::FilteredAggregation =>
+				"
+				SELECT
+					quantity,
+					COUNT(*) AS orders,
+					SUM(total_price) AS revenue,
+					AVG(total_price) AS average_price,
+					MAX(total_price) AS max_price
+				FROM orders
+				WHERE total_price >= 50
+				GROUP BY quantity
+				ORDER BY quantity
+			",
+			AnalyticsQuery::JoinGroupBy =>
+				"
+				SELECT
+					u.active,
+					COUNT(DISTINCT o.user_id) AS customers,
+					COUNT(*) AS orders,
+					SUM(o.total_price) AS revenue
+				FROM orders o
+				JOIN users u ON o.user_id = u.id
+				JOIN products p ON o.product_id = p.id
+				WHERE p.price < 50
+				GROUP BY u.active
+				ORDER BY revenue DESC
+			",
+			AnalyticsQuery::TopRevenue =>
+				"
+				SELECT p.id, p.name, SUM(o.quantity) AS units, SUM(o.total_price) AS revenue
+				FROM orders o
+				JOIN products p ON o.product_id = p.id
+				GROUP BY p.id, p.name
+				ORDER BY revenue DESC
+				LIMIT 10
+			",
+		}
+	}
+}
@@ -0,0 +1,26 @@
+// Backup and restore of the whole database with the tool of each engine, run last in a plan with
+// `backup` set: the SQLite online backup API, DuckDB's EXPORT and IMPORT DATABASE and the
+// BackupEngine of RocksDB. Each is recorded as a result of a single operation, the backup with
+// the size of the files it wrote.
+use std::path::Path;
+
+// Names of the results of the backup and of its restore
+pub const BACKUP_RESULT: &str = "backup";
+pub const RESTORE_BACKUP_RESULT: &str = "restore_backup";
+
+pub const BACKUP_RESULTS: [&str; 2] = [BACKUP_RESULT, RESTORE_BACKUP_RESULT];
+
+// Total size of the files under `path`, 0 when it doesn't exist
+pub fn dir_size(path: &Path) -> u64 {
+	let Ok(entries) = std::fs::read_dir(path) else {
+		return 0;
+	};
+	entries
+		.filter_map(|entry| entry.ok())
+		.map(|entry| match entry.metadata() {
+			Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+			Ok(metadata) => metadata.len(),
+			Err(_) => 0,
+		})
+		.sum()
+}
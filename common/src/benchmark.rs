@@ -1,21 +1,233 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{ Duration, Instant };
 use async_trait::async_trait;
 use chrono::Utc;
-use rand::Rng;
+use fake::{
+	faker::{ company::en::{ BsNoun, Buzzword }, lorem::en::Sentences, name::en::{ FirstName, LastName } },
+	Fake,
+};
+use rand::{ distributions::Alphanumeric, Rng };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
 use uuid::Uuid;
-use anyhow::Result;
+use anyhow::{ Context, Result };
+use tracing::{ field, info, info_span, warn, Instrument, Span };
 
+use crate::analytics::{ AnalyticsQuery, ANALYTICS_QUERY_RUNS };
+use crate::backup::{ dir_size, BACKUP_RESULT, RESTORE_BACKUP_RESULT };
+use crate::case::{ BenchmarkCase, CaseState };
+use crate::context::RunContext;
+use crate::distribution::{ draw_value, pareto_fan_out };
+use crate::error::BenchmarkError;
+use crate::fixtures::{ data_now, DataRng };
+use crate::import::{ self, Import, ImportColumn, IMPORT_READ_RESULT, IMPORT_RECORDS_RESULT, IMPORT_RESULTS };
+use crate::plan::{
+	BenchmarkPlan,
+	BenchmarkTest,
+	CacheState,
+	IndexMode,
+	KeyEncoding,
+	KeyStrategy,
+	PayloadProfile,
+	TestVariant,
+	TimestampEncoding,
+	DEFAULT_PARETO_ALPHA,
+};
+use crate::keys::new_key;
+use crate::search::email_domain;
+use crate::tenants::tenant_of;
+use crate::text::with_text;
+use crate::trace::{ self, TraceChunk, TraceMode, TraceOp };
+use crate::wide::generate_wide_record;
+use crate::workloads;
 use crate::models::{
 	BenchmarkResult,
 	BenchmarkResults,
+	BackendCapabilities,
+	Capabilities,
+	Capability,
 	Order,
-	OrderWithDetails,
+	OrderItem,
+	OrderWithItems,
 	Product,
+	Record,
+	RecordCounts,
+	SizeSample,
+	TestStatus,
 	User,
+	WideRecord,
+	WideValue,
 };
 
+// Number of records of each table generated and inserted at a time when building the dataset
+pub const GENERATION_CHUNK_SIZE: usize = 10_000;
+
+// Shape of the Pareto distribution of the generated orders among the users, the one of the 80/20
+// rule: the joins meet users with hundreds of orders and many with none
+pub const ORDER_FAN_OUT_ALPHA: f64 = DEFAULT_PARETO_ALPHA;
+
+// Table, or column family, created and dropped by the probe of a database
+pub const PROBE_TABLE: &str = "race_probe";
+
+// Value the probe writes and reads back, with a zero byte and bytes above 127
+pub const PROBE_BLOB: &[u8] = &[0, 1, 127, 128, 255];
+
+// Time a timed out test is given to stop before it is abandoned
+const ABORT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// Kind of work a test does, to select the tests of a plan by their tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TestTag {
+	// Inserts, updates or deletes records
+	Write,
+	// Reads records back
+	Read,
+	// Reads across several tables
+	Join,
+	// Changes existing records
+	Update,
+	// Aggregates many records
+	Analytics,
+}
+
+impl TestTag {
+	pub const ALL: [TestTag; 5] = [
+		TestTag::Write,
+		TestTag::Read,
+		TestTag::Join,
+		TestTag::Update,
+		TestTag::Analytics,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			TestTag::Write => "write",
+			TestTag::Read => "read",
+			TestTag::Join => "join",
+			TestTag::Update => "update",
+			TestTag::Analytics => "analytics",
+		}
+	}
+}
+
+impl fmt::Display for TestTag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for TestTag {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		TestTag::ALL.into_iter()
+			.find(|tag| tag.name() == name)
+			.ok_or_else(|| {
+				format!("unknown test tag: {}, expected write, read, join, update or analytics", name)
+			})
+	}
+}
+
+// A test of the trait with its tags, and the capabilities a backend needs to run it
+pub struct TestDescriptor {
+	pub test: BenchmarkTest,
+	pub tags: &'static [TestTag],
+	pub requires: &'static [Capability],
+}
+
+impl TestDescriptor {
+	pub fn has_tag(&self, tag: TestTag) -> bool {
+		self.tags.contains(&tag)
+	}
+
+	// First capability the test needs that `capabilities` lacks
+	pub fn missing_capability(&self, capabilities: BackendCapabilities) -> Option<Capability> {
+		self.requires.iter().copied().find(|&capability| !capabilities.supports(capability))
+	}
+}
+
+// Every test, in the order they are run
+pub static TEST_REGISTRY: [TestDescriptor; 26] = [
+	TestDescriptor { test: BenchmarkTest::InsertSingleManyTimes, tags: &[TestTag::Write], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::InsertManyAtOnce, tags: &[TestTag::Write], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadByIdManyTimes, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadManyByIds, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadByColumnSearch, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor {
+		test: BenchmarkTest::ReadWithOneJoin,
+		tags: &[TestTag::Read, TestTag::Join],
+		requires: &[Capability::Joins],
+	},
+	TestDescriptor {
+		test: BenchmarkTest::ReadWithTwoJoins,
+		tags: &[TestTag::Read, TestTag::Join],
+		requires: &[Capability::Joins],
+	},
+	TestDescriptor {
+		test: BenchmarkTest::UpdateSingleFieldOneEntry,
+		tags: &[TestTag::Write, TestTag::Update],
+		requires: &[],
+	},
+	TestDescriptor {
+		test: BenchmarkTest::UpdateSingleFieldManyEntries,
+		tags: &[TestTag::Write, TestTag::Update],
+		requires: &[],
+	},
+	TestDescriptor {
+		test: BenchmarkTest::UpdateMultipleFieldsOneEntry,
+		tags: &[TestTag::Write, TestTag::Update],
+		requires: &[],
+	},
+	TestDescriptor {
+		test: BenchmarkTest::UpdateMultipleFieldsManyEntries,
+		tags: &[TestTag::Write, TestTag::Update],
+		requires: &[],
+	},
+	TestDescriptor { test: BenchmarkTest::InsertOrdersWithItems, tags: &[TestTag::Write], requires: &[] },
+	TestDescriptor {
+		test: BenchmarkTest::ReadOrdersWithItems,
+		tags: &[TestTag::Read, TestTag::Join],
+		requires: &[Capability::Joins],
+	},
+	TestDescriptor { test: BenchmarkTest::InsertWideRecords, tags: &[TestTag::Write], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadWideRecordsById, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadWideColumnSubset, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadTenantOrders, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor {
+		test: BenchmarkTest::AggregateOrdersByTenant,
+		tags: &[TestTag::Read, TestTag::Analytics],
+		requires: &[],
+	},
+	TestDescriptor { test: BenchmarkTest::ReadUsersNotDeleted, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadDiscountedProducts, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::SoftDeleteUsers, tags: &[TestTag::Write, TestTag::Update], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadLiveUsers, tags: &[TestTag::Read], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ConcurrentInserts, tags: &[TestTag::Write], requires: &[] },
+	TestDescriptor {
+		test: BenchmarkTest::LongTransactionContention,
+		tags: &[TestTag::Write, TestTag::Read, TestTag::Update],
+		requires: &[Capability::Transactions],
+	},
+	TestDescriptor { test: BenchmarkTest::ConsistencyProbe, tags: &[TestTag::Write, TestTag::Update], requires: &[] },
+	TestDescriptor { test: BenchmarkTest::ReadProjection, tags: &[TestTag::Read], requires: &[] },
+];
+
+// Descriptor of a test in the registry
+pub fn test_descriptor(test: BenchmarkTest) -> &'static TestDescriptor {
+	TEST_REGISTRY.iter()
+		.find(|descriptor| descriptor.test == test)
+		.expect("every test is in the registry")
+}
+
 #[async_trait]
 pub trait DatabaseBenchmark {
+	/// Data a test needs before it starts, such as the records it inserts or the ids it reads
+	type Fixture: Send + Sync;
+
 	/// Initialize the database with schema and needed setup
 	async fn init(&self) -> Result<()>;
 
@@ -25,84 +237,1125 @@ pub trait DatabaseBenchmark {
 	/// Clean up any data from previous benchmarks
 	async fn cleanup(&self) -> Result<()>;
 
+	/// Delete and recreate the database files, leaving an empty initialized database
+	async fn reset(&mut self) -> Result<()>;
+
+	/// Check the database is reachable with a trivial query
+	async fn ping(&self) -> Result<()>;
+
+	/// Create `PROBE_TABLE`, write a blob to it and read it back, then drop it, reporting the
+	/// optional features of the engine. Checked by `/capabilities` before a long run
+	async fn probe(&self) -> Result<Capabilities>;
+
+	/// Save a copy of the data to `dir`, restored by `restore` before each test of a plan with
+	/// fixtures: a copy of the database file, or a checkpoint
+	async fn snapshot(&self, dir: &Path) -> Result<()>;
+
+	/// Replace the data with the copy `snapshot` saved to `dir`
+	async fn restore(&self, dir: &Path) -> Result<()>;
+
+	/// Back up the database to `dir` with the backup tool of the engine, for a plan with `backup`
+	async fn backup(&self, dir: &Path) -> Result<()>;
+
+	/// Replace the data with the backup `backup` wrote to `dir`
+	async fn restore_backup(&self, dir: &Path) -> Result<()>;
+
+	/// Write the writes still buffered in memory to the database files
+	async fn flush(&self) -> Result<()> {
+		Ok(())
+	}
+
+	/// Merge the write-ahead log into the database files
+	async fn checkpoint(&self) -> Result<()> {
+		Ok(())
+	}
+
+	/// Counter of the bytes written to storage, read before and after each write test. Engines
+	/// without I/O statistics report the size of their files, which only grow by what the test
+	/// writes since they are checkpointed before it. None when the database can't tell
+	async fn bytes_written(&self) -> Result<Option<u64>> {
+		Ok(None)
+	}
+
+	/// Size of the database files on disk, including the write-ahead log, recorded in the
+	/// `size_timeline` of the results after each test. None when the database can't tell
+	async fn database_size(&self) -> Result<Option<u64>> {
+		Ok(None)
+	}
+
+	/// Statistics of the engine read after each test, recorded as the `engine_stats` of its result.
+	/// The counters among them add up since the database was opened. None when the engine keeps none
+	async fn engine_stats(&self) -> Result<Option<serde_json::Value>> {
+		Ok(None)
+	}
+
+	/// Plan the database picks for the query a test runs, in a run with `explain` set. None for
+	/// the tests without a query and the databases without a query planner
+	async fn explain(&self, _test: BenchmarkTest) -> Result<Option<String>> {
+		Ok(None)
+	}
+
+	/// Index the query of a test should use, as `explain` names it in the plan, checked before
+	/// the test in a run with `assert_indexes` set. None for the tests reading without an index
+	fn expected_index(&self, _test: BenchmarkTest) -> Option<&'static str> {
+		None
+	}
+
+	/// Check the plan of the query of `test` uses the index it should, so a missing index fails
+	/// the test instead of passing for a slow engine
+	async fn check_index_usage(&self, test: BenchmarkTest) -> Result<()> {
+		let Some(index) = self.expected_index(test) else {
+			return Ok(());
+		};
+		let plan = self
+			.explain(test).await?
+			.with_context(|| format!("{} has no query plan to check for the index {}", test, index))?;
+		anyhow::ensure!(
+			plan.contains(index),
+			"{} doesn't use the index {}, its query plan is:\n{}",
+			test,
+			index,
+			plan
+		);
+		Ok(())
+	}
+
+	/// Whether the database runs the analytical queries of `run_analytics_suite`
+	fn supports_analytics(&self) -> bool {
+		false
+	}
+
+	/// Features of the engine the tests may need, a test needing one the backend lacks being
+	/// recorded as skipped instead of run. Every feature by default
+	fn capabilities(&self) -> BackendCapabilities {
+		BackendCapabilities::ALL
+	}
+
+	/// Whether the benchmark is attached to an existing database, given with `--connect`, which
+	/// the runs must leave unchanged
+	fn connected(&self) -> bool {
+		false
+	}
+
+	/// Add `count` orders of existing users and products, scaling up the data of the
+	/// analytical queries
+	async fn generate_orders(&self, _count: usize) -> Result<()> {
+		anyhow::bail!("{} doesn't support analytical queries", self.database_name())
+	}
+
+	/// Run an analytical query to completion, returning the number of rows it produced
+	async fn run_analytics_query(&self, _query: AnalyticsQuery) -> Result<usize> {
+		anyhow::bail!("{} doesn't support analytical queries", self.database_name())
+	}
+
+	/// Run the analytical queries over `orders` more orders, generated first. Each query is
+	/// recorded as a result, run `ANALYTICS_QUERY_RUNS` times
+	async fn run_analytics_suite(
+		&self,
+		orders: usize
+	) -> Result<Vec<BenchmarkResult>, BenchmarkError> {
+		if orders > 0 {
+			self.generate_orders(orders)
+				.instrument(info_span!("seed", orders))
+				.await.map_err(BenchmarkError::data_generation)?;
+		}
+		self.flush().await.map_err(BenchmarkError::engine)?;
+		self.checkpoint().await.map_err(BenchmarkError::engine)?;
+
+		let mut results = Vec::new();
+		for query in AnalyticsQuery::ALL {
+			self.run_context().check_cancelled_run()?;
+			self.run_context().start_test(query.name());
+			let span = test_span(query.name(), ANALYTICS_QUERY_RUNS);
+			let start = Instant::now();
+			let result = measure_execution(
+				&self.database_name(),
+				query.name(),
+				ANALYTICS_QUERY_RUNS,
+				self.get_cpu_count(),
+				|| async {
+					for _ in 0..ANALYTICS_QUERY_RUNS {
+						self.run_context().check_cancelled()?;
+						self.run_analytics_query(query).await?;
+						self.run_context().record_operation();
+					}
+					Ok(())
+				}
+			)
+				.instrument(span.clone())
+				.await;
+			self.run_context().finish_test();
+
+			let result = match result {
+				Ok(mut result) => {
+					result.latency_p99_us = self.run_context().latency_p99_us();
+					result.outliers = self.run_context().outlier_stats();
+					result
+				}
+				Err(e) => {
+					self.run_context().check_cancelled_run()?;
+					warn!(parent: &span, "Query {} failed: {:?}", query.name(), e);
+					self.run_context().record_failed_operation();
+					self.failed_result(query.name(), start.elapsed(), &e)
+				}
+			};
+			record_outcome(self.run_context(), &span, &result);
+			results.push(result);
+		}
+		Ok(results)
+	}
+
+	/// Create the `IMPORT_TABLE` with a column per column of an import, named as
+	/// `wide::column_name` names them, replacing the table of a previous import
+	async fn create_import_table(&self, _columns: &[ImportColumn]) -> Result<()> {
+		anyhow::bail!("{} doesn't support imports", self.database_name())
+	}
+
+	/// Insert the imported records, recording an operation for each
+	async fn insert_imported_records(&self, _records: Arc<Vec<Record>>) -> Result<()> {
+		anyhow::bail!("{} doesn't support imports", self.database_name())
+	}
+
+	/// Read the imported records of `ids` whole, one at a time, recording an operation for each
+	async fn read_imported_records(&self, _ids: Arc<Vec<Uuid>>) -> Result<()> {
+		anyhow::bail!("{} doesn't support imports", self.database_name())
+	}
+
+	/// Set the column `column` of each imported record of `updates` to its value, recording an
+	/// operation for each
+	async fn update_imported_records(
+		&self,
+		_column: usize,
+		_updates: Arc<Vec<(Uuid, Option<WideValue>)>>
+	) -> Result<()> {
+		anyhow::bail!("{} doesn't support imports", self.database_name())
+	}
+
+	/// Drop the `IMPORT_TABLE`
+	async fn drop_import_table(&self) -> Result<()> {
+		Ok(())
+	}
+
+	/// Load the records of the CSV file of `import` into the `IMPORT_TABLE`, then read each of them
+	/// by id and update its first column, each recorded as a result of one operation per record.
+	/// The table is dropped afterwards, leaving the dataset as it was
+	async fn run_import_suite(
+		&self,
+		import: &Import,
+		keys: KeyStrategy
+	) -> Result<Vec<BenchmarkResult>, BenchmarkError> {
+		let records = import::load(import, keys).map_err(BenchmarkError::data_generation)?;
+		let ids: Arc<Vec<Uuid>> = Arc::new(records.iter().map(|record| record.id).collect());
+		// Each record takes the value of the next one, of the same type
+		let updates: Arc<Vec<(Uuid, Option<WideValue>)>> = Arc::new(
+			records
+				.iter()
+				.zip(records.iter().cycle().skip(1))
+				.map(|(record, next)| (record.id, next.values[0].clone()))
+				.collect()
+		);
+		let records = Arc::new(records);
+		self.create_import_table(&import.columns).await.map_err(BenchmarkError::data_generation)?;
+
+		let mut results = Vec::new();
+		let mut imported = false;
+		for name in IMPORT_RESULTS {
+			self.run_context().check_cancelled_run()?;
+			self.run_context().start_test(name);
+			let span = test_span(name, records.len());
+			let start = Instant::now();
+			let result = measure_execution(
+				&self.database_name(),
+				name,
+				records.len(),
+				self.get_cpu_count(),
+				|| async {
+					match name {
+						IMPORT_RECORDS_RESULT => self.insert_imported_records(records.clone()).await,
+						_ if !imported => anyhow::bail!("The import failed, there are no records to read or update"),
+						IMPORT_READ_RESULT => self.read_imported_records(ids.clone()).await,
+						_ => self.update_imported_records(0, updates.clone()).await,
+					}
+				}
+			)
+				.instrument(span.clone())
+				.await;
+			self.run_context().finish_test();
+
+			let result = match result {
+				Ok(mut result) => {
+					imported = true;
+					result.latency_p99_us = self.run_context().latency_p99_us();
+					result.outliers = self.run_context().outlier_stats();
+					result
+				}
+				Err(e) => {
+					self.run_context().check_cancelled_run()?;
+					warn!(parent: &span, "{} failed: {:?}", name, e);
+					self.run_context().record_failed_operation();
+					self.failed_result(name, start.elapsed(), &e)
+				}
+			};
+			record_outcome(self.run_context(), &span, &result);
+			results.push(result);
+		}
+
+		self.drop_import_table().await.map_err(BenchmarkError::engine)?;
+		Ok(results)
+	}
+
+	/// Back up the database to `dir` then restore the backup, each recorded as a result of a
+	/// single operation; the backup reports the size of the files it wrote
+	async fn run_backup_suite(&self, dir: &Path) -> Result<Vec<BenchmarkResult>, BenchmarkError> {
+		self.flush().await.map_err(BenchmarkError::engine)?;
+		self.checkpoint().await.map_err(BenchmarkError::engine)?;
+
+		let mut results = Vec::new();
+		let mut backed_up = false;
+		for name in [BACKUP_RESULT, RESTORE_BACKUP_RESULT] {
+			self.run_context().check_cancelled_run()?;
+			self.run_context().start_test(name);
+			let span = test_span(name, 1);
+			let start = Instant::now();
+			let result = measure_execution(&self.database_name(), name, 1, self.get_cpu_count(), || async {
+				if name == BACKUP_RESULT {
+					self.backup(dir).await?;
+				} else if backed_up {
+					self.restore_backup(dir).await?;
+				} else {
+					anyhow::bail!("The backup failed, there is nothing to restore");
+				}
+				self.run_context().record_operation();
+				Ok(())
+			})
+				.instrument(span.clone())
+				.await;
+			self.run_context().finish_test();
+
+			let result = match result {
+				Ok(mut result) => {
+					if name == BACKUP_RESULT {
+						backed_up = true;
+						result.backup_bytes = Some(dir_size(dir));
+					}
+					result
+				}
+				Err(e) => {
+					self.run_context().check_cancelled_run()?;
+					warn!(parent: &span, "{} failed: {:?}", name, e);
+					self.run_context().record_failed_operation();
+					self.failed_result(name, start.elapsed(), &e)
+				}
+			};
+			record_outcome(self.run_context(), &span, &result);
+			results.push(result);
+		}
+		Ok(results)
+	}
+
+	/// Size of the record each operation of a write test inserts or updates, such as
+	/// `USER_RECORD_BYTES`, to compute its write amplification
+	fn written_record_bytes(&self, _test: BenchmarkTest) -> Option<u64> {
+		None
+	}
+
+	/// Flush pending writes before the server exits
+	async fn close(&self) -> Result<()> {
+		self.flush().await?;
+		self.checkpoint().await
+	}
+
 	/// Database name
 	fn database_name(&self) -> String;
 
-	/// Configure number of CPU cores to use (if supported)
-	fn set_cpu_count(&mut self, count: usize);
+	/// Configure number of CPU cores to use (if supported), applied before returning
+	async fn set_cpu_count(&mut self, count: usize) -> Result<()>;
 
 	/// Get current CPU core count setting
 	fn get_cpu_count(&self) -> usize;
 
+	/// Choose whether the secondary indexes exist, applied by the next `init()`
+	fn set_index_mode(&mut self, indexes: IndexMode);
+
+	/// Choose how the ids are stored, applied by the next `init()`
+	fn set_key_encoding(&mut self, encoding: KeyEncoding);
+
+	/// Choose how the timestamps are stored, applied by the next `init()`
+	fn set_timestamp_encoding(&mut self, encoding: TimestampEncoding);
+
+	/// Context shared with the server to report live progress
+	fn run_context(&self) -> &RunContext;
+
+	/// Count the users, products and orders currently stored
+	async fn count_records(&self) -> Result<RecordCounts>;
+
+	/// Check the writes of a test that just passed are visible and the indexes match the records
+	async fn verify_test(&self, test: BenchmarkTest, count: usize) -> Result<()>;
+
+	/// Prepare the fixture of a test, outside of its timing
+	async fn prepare(&self, test: BenchmarkTest, count: usize) -> Result<Self::Fixture>;
+
+	/// Test running a step of a trace chunk (one of `TraceOp::steps`) on the users of the chunk,
+	/// with its fixture. Databases without one can't record or replay traces
+	fn traced_fixture(&self, _step: TraceOp, _chunk: &TraceChunk) -> Result<(BenchmarkTest, Self::Fixture)> {
+		anyhow::bail!("{} can't record or replay traces", self.database_name())
+	}
+
 	/// Test 1: Insert single entry many times
-	async fn insert_single_many_times(&self, count: usize) -> Result<BenchmarkResult>;
+	async fn insert_single_many_times(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
 
 	/// Test 2: Insert many entries at once
-	async fn insert_many_at_once(&self, count: usize) -> Result<BenchmarkResult>;
+	async fn insert_many_at_once(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
 
 	/// Test 3: Read single entry by ID many times
-	async fn read_by_id_many_times(&self, count: usize) -> Result<BenchmarkResult>;
+	async fn read_by_id_many_times(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
 
 	/// Test 4: Read many entries by ID at once
-	async fn read_many_by_ids(&self, count: usize) -> Result<BenchmarkResult>;
+	async fn read_many_by_ids(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
 
 	/// Test 5: Read entries by column value
-	async fn read_by_column_search(&self, count: usize) -> Result<BenchmarkResult>;
+	async fn read_by_column_search(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
 
-	/// Test 6: Read entries with one join
-	async fn read_with_one_join(&self, count: usize) -> Result<BenchmarkResult>;
+	/// Test 6: Read entries with one join. Skipped for the backends without joins
+	async fn read_with_one_join(
+		&self,
+		_count: usize,
+		_fixture: Self::Fixture
+	) -> Result<BenchmarkResult> {
+		anyhow::bail!("{} doesn't support joins", self.database_name())
+	}
 
-	/// Test 7: Read entries with two joins
-	async fn read_with_two_joins(&self, count: usize) -> Result<BenchmarkResult>;
+	/// Test 7: Read entries with two joins. Skipped for the backends without joins
+	async fn read_with_two_joins(
+		&self,
+		_count: usize,
+		_fixture: Self::Fixture
+	) -> Result<BenchmarkResult> {
+		anyhow::bail!("{} doesn't support joins", self.database_name())
+	}
 
 	/// Test 8: Update single field in one entry
 	async fn update_single_field_one_entry(
 		&self,
-		count: usize
+		count: usize,
+		fixture: Self::Fixture
 	) -> Result<BenchmarkResult>;
 
 	/// Test 9: Update single field in many entries
 	async fn update_single_field_many_entries(
 		&self,
-		count: usize
+		count: usize,
+		fixture: Self::Fixture
 	) -> Result<BenchmarkResult>;
 
 	/// Test 10: Update multiple fields in one entry
 	async fn update_multiple_fields_one_entry(
 		&self,
-		count: usize
+		count: usize,
+		fixture: Self::Fixture
 	) -> Result<BenchmarkResult>;
 
 	/// Test 11: Update multiple fields in many entries
 	async fn update_multiple_fields_many_entries(
 		&self,
-		count: usize
+		count: usize,
+		fixture: Self::Fixture
 	) -> Result<BenchmarkResult>;
 
-	/// Run all benchmarks with the given operation count
-	async fn run_all_benchmarks(&self) -> Result<BenchmarkResults> {
-		println!("Running all benchmarks");
-		let mut results = Vec::new();
+	/// Test 12: Insert orders of several products, each with its items in one transaction
+	async fn insert_orders_with_items(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 13: Read orders with their items and the products they reference. Skipped for the
+	/// backends without joins
+	async fn read_orders_with_items(
+		&self,
+		_count: usize,
+		_fixture: Self::Fixture
+	) -> Result<BenchmarkResult> {
+		anyhow::bail!("{} doesn't support joins", self.database_name())
+	}
+
+	/// Test 14: Insert records of the wide table
+	async fn insert_wide_records(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 15: Read every column of wide records by ID many times
+	async fn read_wide_records_by_id(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 16: Read 3 of the 100 columns of many wide records
+	async fn read_wide_column_subset(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 17: Read pages of the orders of one tenant at a time
+	async fn read_tenant_orders(&self, count: usize, fixture: Self::Fixture) -> Result<BenchmarkResult>;
+
+	/// Test 18: Aggregate the orders of every tenant
+	async fn aggregate_orders_by_tenant(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 19: Read the users not deleted, filtering on `deleted_at IS NULL`
+	async fn read_users_not_deleted(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 20: Read the products on discount, filtering on `discount IS NOT NULL`
+	async fn read_discounted_products(
+		&self,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 21: Soft-delete users one at a time, setting their `deleted_at`
+	async fn soft_delete_users(&self, count: usize, fixture: Self::Fixture) -> Result<BenchmarkResult>;
+
+	/// Test 22: Read pages of the live users, newest first, once half of the users are
+	/// soft-deleted
+	async fn read_live_users(&self, count: usize, fixture: Self::Fixture) -> Result<BenchmarkResult>;
+
+	/// Test 23: Insert users from `writers` writers at the same time, each inserting its share
+	/// one at a time; the result reports the throughput of every writer
+	async fn concurrent_inserts(
+		&self,
+		count: usize,
+		writers: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 24: Read and update users from `contenders` connections while another one holds a
+	/// write transaction open; the result reports the operations the transaction made fail.
+	/// Skipped for the backends without transactions
+	async fn long_transaction_contention(
+		&self,
+		_count: usize,
+		_contenders: usize,
+		_fixture: Self::Fixture
+	) -> Result<BenchmarkResult> {
+		anyhow::bail!("{} doesn't support transactions", self.database_name())
+	}
+
+	/// Test 25: Increment a counter from `writers` connections at the same time, each increment
+	/// reading it then writing it back in a transaction; the result reports the lost updates
+	async fn consistency_probe(
+		&self,
+		count: usize,
+		writers: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult>;
+
+	/// Test 26: Read pages of the products with only their id and price, then with every column;
+	/// the result reports the throughput of both
+	async fn read_projection(&self, count: usize, fixture: Self::Fixture) -> Result<BenchmarkResult>;
+
+	/// Run a prepared test; only its operations are timed
+	async fn execute(
+		&self,
+		test: BenchmarkTest,
+		count: usize,
+		fixture: Self::Fixture
+	) -> Result<BenchmarkResult> {
+		self.run_context().start_timing();
+		match test {
+			BenchmarkTest::InsertSingleManyTimes => self.insert_single_many_times(count, fixture).await,
+			BenchmarkTest::InsertManyAtOnce => self.insert_many_at_once(count, fixture).await,
+			BenchmarkTest::ReadByIdManyTimes => self.read_by_id_many_times(count, fixture).await,
+			BenchmarkTest::ReadManyByIds => self.read_many_by_ids(count, fixture).await,
+			BenchmarkTest::ReadByColumnSearch => self.read_by_column_search(count, fixture).await,
+			BenchmarkTest::ReadWithOneJoin => self.read_with_one_join(count, fixture).await,
+			BenchmarkTest::ReadWithTwoJoins => self.read_with_two_joins(count, fixture).await,
+			BenchmarkTest::UpdateSingleFieldOneEntry => {
+				self.update_single_field_one_entry(count, fixture).await
+			}
+			BenchmarkTest::UpdateSingleFieldManyEntries => {
+				self.update_single_field_many_entries(count, fixture).await
+			}
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => {
+				self.update_multiple_fields_one_entry(count, fixture).await
+			}
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => {
+				self.update_multiple_fields_many_entries(count, fixture).await
+			}
+			BenchmarkTest::InsertOrdersWithItems => self.insert_orders_with_items(count, fixture).await,
+			BenchmarkTest::ReadOrdersWithItems => self.read_orders_with_items(count, fixture).await,
+			BenchmarkTest::InsertWideRecords => self.insert_wide_records(count, fixture).await,
+			BenchmarkTest::ReadWideRecordsById => self.read_wide_records_by_id(count, fixture).await,
+			BenchmarkTest::ReadWideColumnSubset => self.read_wide_column_subset(count, fixture).await,
+			BenchmarkTest::ReadTenantOrders => self.read_tenant_orders(count, fixture).await,
+			BenchmarkTest::AggregateOrdersByTenant => self.aggregate_orders_by_tenant(count, fixture).await,
+			BenchmarkTest::ReadUsersNotDeleted => self.read_users_not_deleted(count, fixture).await,
+			BenchmarkTest::ReadDiscountedProducts => self.read_discounted_products(count, fixture).await,
+			BenchmarkTest::SoftDeleteUsers => self.soft_delete_users(count, fixture).await,
+			BenchmarkTest::ReadLiveUsers => self.read_live_users(count, fixture).await,
+			BenchmarkTest::ConcurrentInserts => {
+				self.concurrent_inserts(count, self.run_context().writers(), fixture).await
+			}
+			BenchmarkTest::LongTransactionContention => {
+				self.long_transaction_contention(count, self.run_context().writers(), fixture).await
+			}
+			BenchmarkTest::ConsistencyProbe => {
+				self.consistency_probe(count, self.run_context().writers(), fixture).await
+			}
+			BenchmarkTest::ReadProjection => self.read_projection(count, fixture).await,
+		}
+	}
+
+	/// Run a single benchmark test by name, preparing it first
+	async fn run_test(&self, test: BenchmarkTest, count: usize) -> Result<BenchmarkResult> {
+		let fixture = self.prepare(test, count).await?;
+		self.execute(test, count, fixture).await
+	}
+
+	/// Run a test once, untimed, so that the run following it finds the caches warm. The
+	/// operations it records are cleared
+	async fn warm_up(&self, test: BenchmarkTest, count: usize, timeout: Option<Duration>) -> Result<()> {
+		match timeout {
+			Some(timeout) => self.run_test_with_timeout(test, count, timeout).await?,
+			None => self.run_test(test, count).await?,
+		};
+		self.run_context().start_test(test.name());
+		Ok(())
+	}
+
+	/// Run a single benchmark test, stopping it once the timeout has elapsed.
+	/// The timeout only covers the test itself, not its preparation
+	async fn run_test_with_timeout(
+		&self,
+		test: BenchmarkTest,
+		count: usize,
+		timeout: Duration
+	) -> Result<BenchmarkResult> {
+		let fixture = self.prepare(test, count).await?;
+		let start = Instant::now();
+		let test_run = self.execute(test, count, fixture);
+		tokio::pin!(test_run);
+
+		tokio::select! {
+			result = &mut test_run => {
+				return result;
+			}
+			_ = tokio::time::sleep(timeout) => {}
+		}
+
+		// Ask the test to stop at its next operation, and abandon it if it doesn't
+		warn!("Test {} timed out after {:?}", test, timeout);
+		self.run_context().abort_test();
+		let _ = tokio::time::timeout(ABORT_GRACE_PERIOD, &mut test_run).await;
+		self.run_context().check_cancelled_run()?;
+
+		let operations = self
+			.run_context()
+			.snapshot()
+			.map(|(_, operations)| operations as usize)
+			.unwrap_or(0);
+		let duration = start.elapsed();
+
+		Ok(BenchmarkResult {
+			operations,
+			operations_per_second: operations_per_second(operations, duration),
+			..self.unmeasured_result(test.name(), duration, TestStatus::TimedOut)
+		})
+	}
+
+	/// Result of a test or workload that failed with `error` after `duration`
+	fn failed_result(
+		&self,
+		test_name: &str,
+		duration: Duration,
+		error: &anyhow::Error
+	) -> BenchmarkResult {
+		self.unmeasured_result(test_name, duration, TestStatus::Failed { message: format!("{:#}", error) })
+	}
 
-		// Run all 11 benchmark tests
-		results.push(self.insert_single_many_times(20_00).await?);
-		results.push(self.insert_many_at_once(10_00).await?);
-		results.push(self.read_by_id_many_times(10_00).await?);
-		results.push(self.read_many_by_ids(20_00).await?);
-		results.push(self.read_by_column_search(20_00).await?);
-		results.push(self.read_with_one_join(20_00).await?);
-		results.push(self.read_with_two_joins(20_00).await?);
-		results.push(self.update_single_field_one_entry(5_00).await?);
-		results.push(self.update_single_field_many_entries(10_00).await?);
-		results.push(self.update_multiple_fields_one_entry(2_00).await?);
-		results.push(self.update_multiple_fields_many_entries(50_00).await?);
+	/// Result of a test not run, as the backend lacks `capability`
+	fn skipped_result(&self, test: BenchmarkTest, capability: Capability) -> BenchmarkResult {
+		let reason = format!("{} doesn't support {}", self.database_name(), capability.name());
+		self.unmeasured_result(test.name(), Duration::ZERO, TestStatus::Skipped { reason })
+	}
+
+	/// Result without a measurement, of a test or workload that ended with `status` after `duration`
+	fn unmeasured_result(
+		&self,
+		test_name: &str,
+		duration: Duration,
+		status: TestStatus
+	) -> BenchmarkResult {
+		BenchmarkResult::new(&self.database_name(), test_name, self.get_cpu_count(), duration, status)
+	}
+
+	/// Check the data after a test, given the record counts from before it
+	async fn verify_after_test(
+		&self,
+		test: BenchmarkTest,
+		count: usize,
+		before: RecordCounts
+	) -> Result<()> {
+		// Insert tests add `count` records, or orders with at least one item each, the other tests
+		// leave every table as it was
+		let after = self.count_records().await?;
+		let consistent = match test {
+			BenchmarkTest::InsertSingleManyTimes |
+			BenchmarkTest::InsertManyAtOnce |
+			BenchmarkTest::ConcurrentInserts => {
+				after.total() == before.total() + count
+			}
+			BenchmarkTest::InsertOrdersWithItems => {
+				after.orders == before.orders + count &&
+					after.order_items >= before.order_items + count &&
+					(after.users, after.products) == (before.users, before.products)
+			}
+			BenchmarkTest::InsertWideRecords => {
+				after.wide_records == before.wide_records + count && after.total() == before.total() + count
+			}
+			_ => after == before,
+		};
+		anyhow::ensure!(consistent, "found {:?} records after the test, {:?} before", after, before);
+
+		self.verify_test(test, count).await
+	}
+
+	/// Restore the snapshot of the dataset of a plan with fixtures, doing nothing otherwise
+	async fn restore_fixture(&self) -> Result<(), BenchmarkError> {
+		let Some(dir) = self.run_context().fixture() else {
+			return Ok(());
+		};
+		self.run_context().check_cancelled_run()?;
+		self.restore(&dir)
+			.instrument(info_span!("restore"))
+			.await
+			.map_err(|e| BenchmarkError::engine(e.context("Restoring the fixture dataset failed")))
+	}
+
+	/// Setup phase of a case: bring the database to the state the test starts from and take what
+	/// the later phases compare against
+	async fn setup_case(&self, case: &BenchmarkCase) -> Result<CaseState, BenchmarkError> {
+		let test = case.test();
+		// Start from the saved dataset rather than what the previous test left behind
+		self.restore_fixture().await?;
+
+		// Settle the writes of the seeding or of the previous test, so this test doesn't pay for them
+		self.flush().await.map_err(BenchmarkError::engine)?;
+		self.checkpoint().await.map_err(BenchmarkError::engine)?;
+
+		let bytes_before = if test.is_write() {
+			self.bytes_written().await.map_err(BenchmarkError::engine)?
+		} else {
+			None
+		};
+
+		let records_before = if case.verify {
+			Some(self.count_records().await.map_err(BenchmarkError::engine)?)
+		} else {
+			None
+		};
+
+		let TestVariant { batch_size, selectivity, cache_state } = case.variant;
+		self.run_context().set_batch_size(batch_size);
+		self.run_context().set_selectivity(selectivity);
+		self.run_context().set_distribution(case.distribution);
+		self.run_context().start_test(test.name());
+		let span = test_span(test.name(), case.count);
+		if let Some(batch_size) = batch_size {
+			span.record("batch_size", batch_size);
+		}
+		if let Some(selectivity) = selectivity {
+			span.record("selectivity", selectivity);
+		}
+		if let Some(cache_state) = cache_state {
+			span.record("cache_state", field::display(cache_state));
+		}
+
+		Ok(CaseState { span, bytes_before, bytes_after: None, records_before })
+	}
+
+	/// Measure phase of a case: check the index of the test, warm the caches and run the timed
+	/// test within `timeout`. A failing test is returned as a failed result, so an error means the
+	/// run itself could not go on
+	async fn measure_case(
+		&self,
+		case: &BenchmarkCase,
+		state: &mut CaseState,
+		timeout: Option<Duration>
+	) -> Result<BenchmarkResult, BenchmarkError> {
+		let test = case.test();
+		let start = Instant::now();
+		// A test missing its index would time the configuration rather than the engine, so it
+		// fails without running
+		let index_check = if case.assert_indexes {
+			self.check_index_usage(test).await.context("Index check failed")
+		} else {
+			Ok(())
+		};
+		// The warm run first reads what the timed one will, untimed
+		let warm_up = match index_check {
+			Ok(()) if case.variant.cache_state == Some(CacheState::Warm) => {
+				self.warm_up(test, case.count, timeout)
+					.instrument(state.span.clone())
+					.await
+					.context("Warm-up pass failed")
+			}
+			index_check => index_check,
+		};
+		let result = match warm_up {
+			Ok(()) => {
+				match timeout {
+					Some(timeout) => self.run_test_with_timeout(test, case.count, timeout),
+					None => self.run_test(test, case.count),
+				}
+					.instrument(state.span.clone())
+					.await
+			}
+			Err(e) => Err(e),
+		};
+		self.run_context().finish_test();
+
+		// Read before the next checkpoint moves the write-ahead log into the database files
+		if state.bytes_before.is_some() && result.is_ok() {
+			state.bytes_after = self.bytes_written().await.map_err(BenchmarkError::engine)?;
+		}
+
+		// A failing test is recorded and the run goes on, unless the run itself was cancelled
+		match result {
+			Ok(result) => Ok(result),
+			Err(e) => {
+				self.run_context().check_cancelled_run()?;
+				warn!(parent: &state.span, "Test {} failed: {:?}", test, e);
+				self.run_context().record_failed_operation();
+				Ok(self.failed_result(test.name(), start.elapsed(), &e))
+			}
+		}
+	}
+
+	/// Verify phase of a case: a passed test whose data turns out wrong is recorded as failed
+	async fn verify_case(&self, case: &BenchmarkCase, state: &CaseState, result: &mut BenchmarkResult) {
+		let (Some(before), TestStatus::Passed) = (state.records_before, &result.status) else {
+			return;
+		};
+		match self.verify_after_test(case.test(), case.count, before).await {
+			Ok(()) => result.verified = true,
+			Err(e) => {
+				warn!(parent: &state.span, "Test {} failed verification: {:?}", case.test(), e);
+				result.status = TestStatus::Failed {
+					message: format!("Verification failed: {:#}", e),
+				};
+			}
+		}
+	}
+
+	/// Teardown phase of a case: add to the result what it reports besides the measurement
+	async fn teardown_case(&self, case: &BenchmarkCase, state: &CaseState, result: &mut BenchmarkResult) {
+		let test = case.test();
+		let span = &state.span;
+		result.statement_cache = self.run_context().statement_cache_stats();
+		result.key_encoding = case.key_encoding;
+		result.key_strategy = case.key_strategy;
+		result.retries = self.run_context().retries();
+		result.decode_ms = self.run_context().decoding_ms();
+		result.batch_size = case.variant.batch_size;
+		result.selectivity = case.variant.selectivity;
+		result.cache_state = case.variant.cache_state;
+		// Failed tests have no measurement to report
+		if !matches!(result.status, TestStatus::Failed { .. }) {
+			result.latency_p99_us = self.run_context().latency_p99_us();
+			result.outliers = self.run_context().outlier_stats();
+			result.batches = self.run_context().batch_stats();
+		}
+		// The statistics are a diagnostic too, like the plan below
+		match self.engine_stats().await {
+			Ok(stats) => result.engine_stats = stats,
+			Err(e) => warn!(parent: span, "Could not read the engine statistics after {}: {:#}", test, e),
+		}
+		// The plan is a diagnostic: failing to get it leaves the result as it is
+		if case.explain {
+			match self.explain(test).await {
+				Ok(query_plan) => result.query_plan = query_plan,
+				Err(e) => warn!(parent: span, "Could not explain {}: {:#}", test, e),
+			}
+		}
+		if let (Some(before), Some(after)) = (state.bytes_before, state.bytes_after) {
+			let bytes = after.saturating_sub(before);
+			let record_bytes = self.written_record_bytes(test).map(|size| size * (result.operations as u64));
+			result.bytes_written = Some(bytes);
+			result.write_amplification = record_bytes
+				.filter(|&size| size > 0)
+				.map(|size| (bytes as f64) / (size as f64));
+		}
+	}
+
+	/// Run the benchmarks selected by the plan; failing tests are recorded in the results,
+	/// so an error means the run itself could not go on
+	async fn run_all_benchmarks(
+		&self,
+		plan: &BenchmarkPlan
+	) -> Result<BenchmarkResults, BenchmarkError> {
+		info!("Running all benchmarks");
+		// A resumed run skips the steps it completed before being interrupted, keeping their results
+		let (completed_steps, mut results) = self.run_context().completed_steps();
+		let mut step = 0;
+		// Sizes of the steps run since the run started or resumed
+		let mut size_timeline = Vec::new();
+		let run_deadline = plan.run_timeout().map(|timeout| Instant::now() + timeout);
+
+		let cases = plan.cases();
+		for case in &cases {
+			let test = case.test();
+			step += 1;
+			if step <= completed_steps {
+				continue;
+			}
+			self.run_context().check_cancelled_run()?;
+
+			// A test the backend can't run is recorded without preparing anything for it
+			if let Some(capability) = case.descriptor.missing_capability(self.capabilities()) {
+				info!("Skipping {}, {} doesn't support {}", test, self.database_name(), capability.name());
+				results.push(self.skipped_result(test, capability));
+				self.run_context().complete_step(&results);
+				continue;
+			}
+
+			// Each test may run until its own timeout or the end of the run, whichever comes first
+			let remaining = run_deadline.map(|deadline| {
+				deadline.saturating_duration_since(Instant::now())
+			});
+			let timeout = match (plan.test_timeout(), remaining) {
+				(Some(test_timeout), Some(remaining)) => Some(test_timeout.min(remaining)),
+				(test_timeout, remaining) => test_timeout.or(remaining),
+			};
+			if timeout == Some(Duration::ZERO) {
+				warn!("Run timed out, skipping the remaining tests");
+				break;
+			}
+
+			let mut state = self.setup_case(case).await?;
+			let mut result = self.measure_case(case, &mut state, timeout).await?;
+			self.verify_case(case, &state, &mut result).await;
+			self.teardown_case(case, &state, &mut result).await;
+			self.record_size(&mut size_timeline, test.name()).await;
+			record_outcome(self.run_context(), &state.span, &result);
+			results.push(result);
+			self.run_context().complete_step(&results);
+		}
+
+		// The workloads are numbered after every test, even those a timeout skipped
+		step = cases.len();
+
+		// A recording starts with an empty trace, the workloads then append their operations to
+		// it, unless they already started before the run was interrupted
+		let trace = self.run_context().trace().filter(|trace| trace.mode == TraceMode::Record);
+		if let Some(trace) = trace.filter(|_| step >= completed_steps) {
+			trace::create(&trace.path).map_err(BenchmarkError::engine)?;
+		}
+
+		for &workload in &plan.workloads {
+			step += 1;
+			if step <= completed_steps {
+				continue;
+			}
+			self.run_context().check_cancelled_run()?;
+			if run_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+				warn!("Run timed out, skipping the remaining workloads");
+				break;
+			}
+			// Like a test, a workload may run until its own timeout or the end of the run
+			let test_deadline = plan.test_timeout().map(|timeout| Instant::now() + timeout);
+			let deadline = match (test_deadline, run_deadline) {
+				(Some(test_deadline), Some(run_deadline)) => Some(test_deadline.min(run_deadline)),
+				(test_deadline, run_deadline) => test_deadline.or(run_deadline),
+			};
+
+			self.restore_fixture().await?;
+			self.flush().await.map_err(BenchmarkError::engine)?;
+			self.checkpoint().await.map_err(BenchmarkError::engine)?;
+
+			self.run_context().set_batch_size(None);
+			self.run_context().set_selectivity(None);
+			self.run_context().start_test(workload.name());
+			let span = test_span(workload.name(), plan.workload_operations);
+			let start = Instant::now();
+			let result = workloads::run_workload(
+				self,
+				workload,
+				plan.workload_operations,
+				plan.distribution,
+				deadline
+			)
+				.instrument(span.clone())
+				.await;
+			self.run_context().finish_test();
+
+			let mut result = match result {
+				Ok(mut result) => {
+					result.latency_p99_us = self.run_context().latency_p99_us();
+					result.outliers = self.run_context().outlier_stats();
+					result
+				}
+				Err(e) => {
+					self.run_context().check_cancelled_run()?;
+					warn!(parent: &span, "Workload {} failed: {:?}", workload, e);
+					self.run_context().record_failed_operation();
+					self.failed_result(workload.name(), start.elapsed(), &e)
+				}
+			};
+			result.key_encoding = plan.key_encoding;
+			result.key_strategy = plan.key_strategy;
+			result.retries = self.run_context().retries();
+			self.record_size(&mut size_timeline, workload.name()).await;
+			record_outcome(self.run_context(), &span, &result);
+			results.push(result);
+			self.run_context().complete_step(&results);
+		}
+
+		if plan.analytics && step >= completed_steps {
+			if !self.supports_analytics() {
+				warn!("{} has no analytical queries, skipping them", self.database_name());
+			} else if run_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+				warn!("Run timed out, skipping the analytical queries");
+			} else {
+				// A prebuilt dataset is queried with the orders it already holds
+				let orders = if plan.prebuilt { 0 } else { plan.dataset_size.saturating_mul(plan.analytics_scale) };
+				for mut result in self.run_analytics_suite(orders).await? {
+					result.key_encoding = plan.key_encoding;
+					result.key_strategy = plan.key_strategy;
+					results.push(result);
+				}
+			}
+			// Skipped queries complete their step too, so a resumed run numbers the next ones the same
+			self.run_context().complete_step(&results);
+		}
+		step += usize::from(plan.analytics);
+
+		if plan.import.is_some() && step >= completed_steps {
+			match self.run_context().import() {
+				None => warn!("No CSV file to import into {}, skipping the import", self.database_name()),
+				Some(_) if run_deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+					warn!("Run timed out, skipping the import");
+				}
+				Some(import) => {
+					for mut result in self.run_import_suite(&import, plan.key_strategy).await? {
+						result.key_encoding = plan.key_encoding;
+						result.key_strategy = plan.key_strategy;
+						results.push(result);
+					}
+				}
+			}
+			self.run_context().complete_step(&results);
+		}
+		step += usize::from(plan.import.is_some());
+
+		if plan.backup && step >= completed_steps {
+			match self.run_context().backup() {
+				None => warn!("No directory to back up {} to, skipping the backup", self.database_name()),
+				Some(_) if run_deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+					warn!("Run timed out, skipping the backup");
+				}
+				Some(dir) => {
+					for mut result in self.run_backup_suite(&dir).await? {
+						result.key_encoding = plan.key_encoding;
+						result.key_strategy = plan.key_strategy;
+						results.push(result);
+					}
+				}
+			}
+			self.run_context().complete_step(&results);
+		}
 
 		Ok(BenchmarkResults {
+			run_id: Uuid::new_v4(),
 			database: self.database_name(),
 			results,
 			timestamp: Utc::now(),
+			size_timeline,
+			calibration: None,
+			scores: None,
 		})
 	}
+
+	/// Add the size of the database after `test_name` to the timeline. The size is a
+	/// diagnostic: failing to read it leaves the timeline as it is
+	async fn record_size(&self, size_timeline: &mut Vec<SizeSample>, test_name: &str) {
+		match self.database_size().await {
+			Ok(Some(bytes)) => size_timeline.push(SizeSample {
+				test_name: test_name.to_string(),
+				bytes,
+				timestamp: Utc::now(),
+			}),
+			Ok(None) => {}
+			Err(e) => warn!("Could not read the size of {} after {}: {:#}", self.database_name(), test_name, e),
+		}
+	}
+}
+
+// Span of a test, workload or analytical query, logging its outcome once it closes
+fn test_span(name: &str, count: usize) -> Span {
+	info_span!(
+		"test",
+		test = name,
+		count,
+		batch_size = field::Empty,
+		selectivity = field::Empty,
+		cache_state = field::Empty,
+		status = field::Empty,
+		operations = field::Empty,
+		duration_ms = field::Empty,
+		operations_per_second = field::Empty
+	)
+}
+
+// Log the outcome of a test to its span, and its operations to the op-log of the run
+fn record_outcome(context: &RunContext, span: &Span, result: &BenchmarkResult) {
+	context.log_operations(&result.test_name);
+	span.record("status", result.status.as_str());
+	span.record("operations", result.operations);
+	span.record("duration_ms", result.duration_ms);
+	span.record("operations_per_second", result.operations_per_second);
+}
+
+// Operations per second of `operations` run in `duration`, to the nanosecond, so a test of a few
+// fast operations isn't rounded to a whole millisecond. 0 for a duration too short to measure
+pub fn operations_per_second(operations: usize, duration: Duration) -> f64 {
+	let seconds = duration.as_secs_f64();
+	if seconds > 0.0 {
+		(operations as f64) / seconds
+	} else {
+		0.0
+	}
 }
 
 // Helper function to measure execution time and create benchmark result
@@ -120,61 +1373,223 @@ pub async fn measure_execution<F, Fut>(
 	f().await?;
 	let duration = start.elapsed();
 
-	let duration_ms = duration.as_millis() as u64;
-	let operations_per_second = if duration_ms > 0 {
-		(operations as f64) / ((duration_ms as f64) / 1000.0)
-	} else {
-		operations as f64 // Avoid division by zero
-	};
-
 	Ok(BenchmarkResult {
-		database: database_name.to_string(),
-		test_name: test_name.to_string(),
 		operations,
-		duration_ms,
-		operations_per_second,
-		cpu_count,
-		timestamp: Utc::now(),
+		operations_per_second: operations_per_second(operations, duration),
+		..BenchmarkResult::new(database_name, test_name, cpu_count, duration, TestStatus::Passed)
 	})
 }
 
+// Total size of the files at `paths`, counting the missing ones as empty
+pub fn files_size<P: AsRef<Path>>(paths: &[P]) -> u64 {
+	paths
+		.iter()
+		.map(|path| std::fs::metadata(path).map_or(0, |metadata| metadata.len()))
+		.sum()
+}
+
 // Helper functions to generate random data for benchmarks
-pub fn generate_random_user() -> User {
-	let mut rng = rand::thread_rng();
+
+// `value` followed by as many `x` as it lacks to be `len` characters long
+fn pad(mut value: String, len: usize) -> String {
+	let missing = len.saturating_sub(value.len());
+	value.extend(std::iter::repeat_n('x', missing));
+	value
+}
+
+// `len` random alphanumeric characters
+fn filler(len: usize) -> String {
+	DataRng.sample_iter(Alphanumeric).take(len).map(char::from).collect()
+}
+
+// Generate a user of one of `tenants` tenants, its id picked following `keys`
+pub fn generate_random_user(payload: PayloadProfile, tenants: usize, keys: KeyStrategy) -> User {
+	let mut rng = DataRng;
+	let id = new_key(keys);
+	let first_name: String = FirstName().fake_with_rng(&mut rng);
+	let last_name: String = LastName().fake_with_rng(&mut rng);
+	let name = with_text(payload.text, &id, format!("{} {}", first_name, last_name));
+	let name = pad(name, payload.name_len);
+	// The padding goes between the local part and the domain, which the searches rely on
+	let domain = format!("@{}", email_domain(payload.email_domains, &mut rng));
+	let local = format!("{}.{}{}", first_name, last_name, rng.gen_range(1..100))
+		.to_lowercase()
+		.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.', "");
+	let local = pad(local, payload.email_len.saturating_sub(domain.len()));
 
 	User {
-		id: Uuid::new_v4(),
-		name: format!("User {}", rng.gen_range(1000..9999)),
-		email: format!("user{}@example.com", rng.gen_range(1000..9999)),
-		created_at: Utc::now(),
+		id,
+		tenant_id: tenant_of(&id, tenants),
+		name: name + &filler(payload.filler_len),
+		email: local + &domain,
+		created_at: data_now(),
 		active: rng.gen_bool(0.9),
+		deleted_at: (!rng.gen_bool(payload.null_rates.deleted_at)).then(data_now),
 	}
 }
 
-pub fn generate_random_product() -> Product {
-	let mut rng = rand::thread_rng();
+pub fn generate_random_product(payload: PayloadProfile, keys: KeyStrategy) -> Product {
+	let mut rng = DataRng;
+	let id = new_key(keys);
+	let buzzword: String = Buzzword().fake_with_rng(&mut rng);
+	let noun: String = BsNoun().fake_with_rng(&mut rng);
+	let name = with_text(payload.text, &id, format!("{} {}", buzzword, noun));
+	// One to five sentences, so the descriptions vary in length like real ones
+	let description = Sentences(1..6).fake_with_rng::<Vec<String>, _>(&mut rng).join(" ");
 
 	Product {
-		id: Uuid::new_v4(),
-		name: format!("Product {}", rng.gen_range(1000..9999)),
-		description: format!("Description for product {}", rng.gen_range(1000..9999)),
-		price: (rng.gen_range(100..10000) as f64) / 100.0,
-		stock: rng.gen_range(0..1000),
-		created_at: Utc::now(),
+		id,
+		name: pad(name, payload.name_len),
+		description: pad(description, payload.description_len) + &filler(payload.filler_len),
+		price: (draw_value(payload.values.price, 100..10000, &mut rng) as f64) / 100.0,
+		discount: (!rng.gen_bool(payload.null_rates.discount)).then(|| (rng.gen_range(5..50) as f64) / 100.0),
+		stock: draw_value(payload.values.stock, 0..1000, &mut rng) as i32,
+		created_at: data_now(),
 	}
 }
 
-pub fn generate_random_order(user_id: Uuid, product_id: Uuid) -> Order {
-	let mut rng = rand::thread_rng();
-	let quantity = rng.gen_range(1..10);
-	let price = (rng.gen_range(1000..10000) as f64) / 100.0;
+// Generate an order of `user_id`, of the same tenant as the user
+pub fn generate_random_order(
+	payload: PayloadProfile,
+	user_id: Uuid,
+	product_id: Uuid,
+	tenants: usize,
+	keys: KeyStrategy
+) -> Order {
+	let mut rng = DataRng;
+	let quantity = draw_value(payload.values.quantity, 1..10, &mut rng) as i32;
+	let price = (draw_value(payload.values.price, 1000..10000, &mut rng) as f64) / 100.0;
 
 	Order {
-		id: Uuid::new_v4(),
+		id: new_key(keys),
+		tenant_id: tenant_of(&user_id, tenants),
 		user_id,
 		product_id,
 		quantity,
 		total_price: price * (quantity as f64),
-		created_at: Utc::now(),
+		created_at: data_now(),
 	}
 }
+
+// Generate an order of 1 to 3 items: the first one of `product_id`, the others of products drawn
+// from `product_ids`
+pub fn generate_random_order_with_items(
+	payload: PayloadProfile,
+	user_id: Uuid,
+	product_id: Uuid,
+	product_ids: &[Uuid],
+	tenants: usize,
+	keys: KeyStrategy
+) -> OrderWithItems {
+	let mut rng = DataRng;
+	let mut order = generate_random_order(payload, user_id, product_id, tenants, keys);
+
+	let mut items = vec![OrderItem {
+		id: new_key(keys),
+		order_id: order.id,
+		product_id,
+		quantity: order.quantity,
+		unit_price: order.total_price / (order.quantity as f64),
+	}];
+	for _ in 0..rng.gen_range(0..3) {
+		items.push(OrderItem {
+			id: new_key(keys),
+			order_id: order.id,
+			product_id: product_ids[rng.gen_range(0..product_ids.len())],
+			quantity: draw_value(payload.values.quantity, 1..10, &mut rng) as i32,
+			unit_price: (draw_value(payload.values.price, 1000..10000, &mut rng) as f64) / 100.0,
+		});
+	}
+	order.total_price = items
+		.iter()
+		.map(|item| item.unit_price * (item.quantity as f64))
+		.sum();
+
+	OrderWithItems { order, items }
+}
+
+// Generate `count` orders with their items, of users and products drawn from the given ids
+pub fn generate_orders_with_items(
+	count: usize,
+	payload: PayloadProfile,
+	user_ids: &[Uuid],
+	product_ids: &[Uuid],
+	tenants: usize,
+	keys: KeyStrategy
+) -> Vec<OrderWithItems> {
+	let mut rng = DataRng;
+	(0..count)
+		.map(|_| {
+			let user_id = user_ids[rng.gen_range(0..user_ids.len())];
+			let product_id = product_ids[rng.gen_range(0..product_ids.len())];
+			generate_random_order_with_items(payload, user_id, product_id, product_ids, tenants, keys)
+		})
+		.collect()
+}
+
+// Generate `count` orders lazily, `GENERATION_CHUNK_SIZE` at a time, each of a user and a product
+// drawn at random from the given ids
+pub fn generate_order_chunks(
+	count: usize,
+	payload: PayloadProfile,
+	user_ids: Vec<Uuid>,
+	product_ids: Vec<Uuid>,
+	tenants: usize,
+	keys: KeyStrategy
+) -> impl Iterator<Item = Vec<Order>> {
+	(0..count).step_by(GENERATION_CHUNK_SIZE).map(move |start| {
+		let mut rng = DataRng;
+		(0..GENERATION_CHUNK_SIZE.min(count - start))
+			.map(|_| {
+				let user_id = user_ids[rng.gen_range(0..user_ids.len())];
+				let product_id = product_ids[rng.gen_range(0..product_ids.len())];
+				generate_random_order(payload, user_id, product_id, tenants, keys)
+			})
+			.collect()
+	})
+}
+
+// A slice of the generated dataset: users, products, one order of each product, with its items,
+// placed by the users of the slice with a Pareto fan-out, and as many wide records
+pub struct TestDataChunk {
+	pub users: Vec<User>,
+	pub products: Vec<Product>,
+	pub orders: Vec<Order>,
+	pub order_items: Vec<OrderItem>,
+	pub wide_records: Vec<WideRecord>,
+}
+
+// Generate `count` users, products and orders lazily, `GENERATION_CHUNK_SIZE` of each at a time,
+// so a backend inserting the chunks as they come never holds the whole dataset in memory
+pub fn generate_test_data_chunks(
+	count: usize,
+	payload: PayloadProfile,
+	tenants: usize,
+	keys: KeyStrategy
+) -> impl Iterator<Item = TestDataChunk> {
+	(0..count).step_by(GENERATION_CHUNK_SIZE).map(move |start| {
+		let len = GENERATION_CHUNK_SIZE.min(count - start);
+		let users: Vec<User> = (0..len).map(|_| generate_random_user(payload, tenants, keys)).collect();
+		let products: Vec<Product> = (0..len).map(|_| generate_random_product(payload, keys)).collect();
+		let product_ids: Vec<Uuid> = products.iter().map(|product| product.id).collect();
+		let mut orders = Vec::with_capacity(len);
+		let mut order_items = Vec::with_capacity(2 * len);
+		let buyers = pareto_fan_out(len, len, ORDER_FAN_OUT_ALPHA, &mut DataRng);
+		for (buyer, product) in buyers.into_iter().zip(&products) {
+			let order = generate_random_order_with_items(
+				payload,
+				users[buyer].id,
+				product.id,
+				&product_ids,
+				tenants,
+				keys
+			);
+			orders.push(order.order);
+			order_items.extend(order.items);
+		}
+
+		let wide_records = (0..len).map(|_| generate_wide_record(keys)).collect();
+
+		TestDataChunk { users, products, orders, order_items, wide_records }
+	})
+}
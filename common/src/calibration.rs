@@ -0,0 +1,80 @@
+// Calibration of the harness: what the client side of an operation costs with no database behind
+// it. Every test pays for generating the records it writes, the SQL databases for formatting
+// their timestamps and RocksDB for encoding its records, every call to a benchmark goes through a
+// boxed future and the blocking engines hand their work to a blocking thread, and each operation is
+// recorded. Each step is timed on its own, against a backend doing nothing, so the results can be
+// read knowing how much of them is the harness rather than the database.
+use std::future::Future;
+use std::hint::black_box;
+use std::pin::Pin;
+use std::time::Instant;
+use anyhow::Result;
+
+use crate::benchmark::generate_random_user;
+use crate::context::RunContext;
+use crate::models::CalibrationStats;
+use crate::plan::{ KeyStrategy, PayloadProfile };
+
+// Times each step is repeated
+pub const CALIBRATION_OPERATIONS: usize = 10_000;
+
+// Call of a backend doing nothing, boxed like the methods of `DatabaseBenchmark`
+fn noop_call() -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+	Box::pin(async { Ok(()) })
+}
+
+// Time the steps of the harness, each in nanoseconds per operation, generating the users the
+// tests of the plan would
+pub async fn calibrate(payload: PayloadProfile, tenants: usize, keys: KeyStrategy) -> Result<CalibrationStats> {
+	let operations = CALIBRATION_OPERATIONS as f64;
+	let per_operation = |start: Instant| (start.elapsed().as_nanos() as f64) / operations;
+
+	let start = Instant::now();
+	let users: Vec<_> = (0..CALIBRATION_OPERATIONS)
+		.map(|_| generate_random_user(payload, tenants, keys))
+		.collect();
+	let generate_user_ns = per_operation(start);
+
+	let start = Instant::now();
+	for user in &users {
+		black_box(user.created_at.to_rfc3339());
+	}
+	let rfc3339_ns = per_operation(start);
+
+	let start = Instant::now();
+	for user in &users {
+		black_box(bincode::serialize(user)?);
+	}
+	let bincode_ns = per_operation(start);
+
+	let start = Instant::now();
+	for _ in 0..CALIBRATION_OPERATIONS {
+		noop_call().await?;
+	}
+	let async_call_ns = per_operation(start);
+
+	let start = Instant::now();
+	for _ in 0..CALIBRATION_OPERATIONS {
+		tokio::task::spawn_blocking(|| ()).await?;
+	}
+	let spawn_blocking_ns = per_operation(start);
+
+	// A context of its own, leaving the counters of the run alone
+	let context = RunContext::default();
+	context.start_test("calibration");
+	let start = Instant::now();
+	for _ in 0..CALIBRATION_OPERATIONS {
+		context.record_operation();
+	}
+	let record_operation_ns = per_operation(start);
+
+	Ok(CalibrationStats {
+		operations: CALIBRATION_OPERATIONS,
+		generate_user_ns,
+		rfc3339_ns,
+		bincode_ns,
+		async_call_ns,
+		spawn_blocking_ns,
+		record_operation_ns,
+	})
+}
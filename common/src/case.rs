@@ -0,0 +1,54 @@
+// The pipeline every test of a run goes through. The plan turns the tests of the registry it
+// selects into cases, a test with one of its variants, once per repetition, and the run takes each
+// case through the same phases of `DatabaseBenchmark`, whatever the backend:
+//
+// - `setup_case` brings the database to the state the test starts from (the fixture dataset,
+//   settled writes) and takes what the later phases compare against
+// - `measure_case` checks the index, warms the caches and runs the timed test within its timeout;
+//   a test failing here is recorded as failed and the run goes on
+// - `verify_case` checks the data the test left, in a run with `verify` set
+// - `teardown_case` gathers what the result reports besides the measurement, such as the
+//   latencies, the engine statistics and the bytes written
+//
+// Each phase is a method of the trait with a default implementation, so a backend can hook into
+// one of them without repeating the others.
+use tracing::Span;
+
+use crate::benchmark::TestDescriptor;
+use crate::models::RecordCounts;
+use crate::plan::{ AccessDistribution, BenchmarkTest, KeyEncoding, KeyStrategy, TestVariant };
+
+// A test of the plan with one of its variants, and what the plan asks of it
+#[derive(Clone, Copy)]
+pub struct BenchmarkCase {
+	pub descriptor: &'static TestDescriptor,
+	pub variant: TestVariant,
+	// Records the test operates on
+	pub count: usize,
+	// Distribution of the ids the test accesses, fixed access patterns when `None`
+	pub distribution: Option<AccessDistribution>,
+	// Whether the data is checked after the test, its index before it, and its query explained
+	pub verify: bool,
+	pub assert_indexes: bool,
+	pub explain: bool,
+	// How the ids are stored and picked, reported with the result
+	pub key_encoding: KeyEncoding,
+	pub key_strategy: KeyStrategy,
+}
+
+impl BenchmarkCase {
+	pub fn test(&self) -> BenchmarkTest {
+		self.descriptor.test
+	}
+}
+
+// What the phases of a case pass on to the next ones
+pub struct CaseState {
+	// Span of the test, its outcome logged once the case is torn down
+	pub span: Span,
+	// Bytes the database wrote before and after a write test, when it can tell
+	pub bytes_before: Option<u64>,
+	pub bytes_after: Option<u64>,
+	// Records of each table before the test, in a run with `verify` set
+	pub records_before: Option<RecordCounts>,
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+use anyhow::Result;
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::BenchmarkResult;
+use crate::plan::BenchmarkPlan;
+
+// Progress of a run saved as it goes, so a run interrupted by a crash, a failure or a
+// cancellation can resume after the last step it completed instead of starting over. A step is a
+// test at a batch size and repetition, a workload, or the analytical queries as a whole. The
+// checkpoint of a run is removed once the run completes.
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RunCheckpoint {
+	pub run_id: Uuid,
+	pub database: String,
+	pub plan: BenchmarkPlan,
+	// Whether the dataset was generated; a resumed run goes on with the data left in the database
+	pub seeded: bool,
+	// Steps of the plan completed, in order
+	pub steps: usize,
+	// Results of the completed steps
+	pub results: Vec<BenchmarkResult>,
+	pub started_at: DateTime<Utc>,
+	pub updated_at: DateTime<Utc>,
+}
+
+impl RunCheckpoint {
+	// Checkpoint of a run that didn't do anything yet
+	pub fn new(run_id: Uuid, database: String, plan: BenchmarkPlan) -> Self {
+		let now = Utc::now();
+		RunCheckpoint {
+			run_id,
+			database,
+			plan,
+			seeded: false,
+			steps: 0,
+			results: Vec::new(),
+			started_at: now,
+			updated_at: now,
+		}
+	}
+}
+
+// On-disk store keeping the checkpoint of every unfinished run as a JSON file named after its run id
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+	dir: PathBuf,
+}
+
+impl CheckpointStore {
+	pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+		let dir = dir.as_ref().to_path_buf();
+		fs::create_dir_all(&dir)?;
+
+		Ok(Self { dir })
+	}
+
+	// Persist a checkpoint, replacing the previous one of its run
+	pub fn save(&self, checkpoint: &RunCheckpoint) -> Result<()> {
+		let json = serde_json::to_string_pretty(checkpoint)?;
+
+		// Write to a temporary file first so a crash never leaves a truncated checkpoint behind
+		let tmp_path = self.dir.join(format!("{}.json.tmp", checkpoint.run_id));
+		fs::write(&tmp_path, json)?;
+		fs::rename(&tmp_path, self.checkpoint_path(checkpoint.run_id))?;
+
+		Ok(())
+	}
+
+	pub fn get(&self, run_id: Uuid) -> Result<Option<RunCheckpoint>> {
+		let path = self.checkpoint_path(run_id);
+		if !path.exists() {
+			return Ok(None);
+		}
+
+		let json = fs::read_to_string(path)?;
+		Ok(Some(serde_json::from_str(&json)?))
+	}
+
+	// Checkpoints of every unfinished run, the most recently updated first
+	pub fn list(&self) -> Result<Vec<RunCheckpoint>> {
+		let mut checkpoints = Vec::new();
+
+		for entry in fs::read_dir(&self.dir)? {
+			let path = entry?.path();
+			if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+				continue;
+			}
+
+			let json = fs::read_to_string(&path)?;
+			match serde_json::from_str::<RunCheckpoint>(&json) {
+				Ok(checkpoint) => checkpoints.push(checkpoint),
+				Err(e) => tracing::warn!("Skipping unreadable checkpoint {:?}: {}", path, e),
+			}
+		}
+
+		checkpoints.sort_by_key(|checkpoint| std::cmp::Reverse(checkpoint.updated_at));
+		Ok(checkpoints)
+	}
+
+	// Forget the checkpoint of a run once it completed
+	pub fn remove(&self, run_id: Uuid) -> Result<()> {
+		let path = self.checkpoint_path(run_id);
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+		Ok(())
+	}
+
+	fn checkpoint_path(&self, run_id: Uuid) -> PathBuf {
+		self.dir.join(format!("{}.json", run_id))
+	}
+}
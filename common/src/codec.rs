@@ -0,0 +1,180 @@
+// Decoding of the rows the read tests fetch. The SQL databases return ids and timestamps in the
+// form they are stored in, a string or bytes for an id and an RFC 3339 string or a number of
+// microseconds for a timestamp, which a client has to parse to get the values back. How much of a
+// read test goes into that parsing depends on the client rather than the database, so the read
+// tests fetch their rows into engine-neutral `Row`s and leave the decoding to a `RowCodec`: the
+// `raw` one only fetches the rows, the `full` one also decodes each of them into its model, timing
+// the decoding on its own. A value that doesn't decode fails the test rather than the server.
+use std::fmt;
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::Instant;
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::context::RunContext;
+
+// Whether the read tests decode the rows they fetch into the models
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RowDecoding {
+	// Only fetch the values of the rows
+	#[default]
+	Raw,
+	// Also parse their ids and timestamps and build the models
+	Full,
+}
+
+impl fmt::Display for RowDecoding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			RowDecoding::Raw => "raw",
+			RowDecoding::Full => "full",
+		})
+	}
+}
+
+impl FromStr for RowDecoding {
+	type Err = String;
+
+	fn from_str(decoding: &str) -> Result<Self, Self::Err> {
+		match decoding {
+			"raw" => Ok(RowDecoding::Raw),
+			"full" => Ok(RowDecoding::Full),
+			_ => Err(format!("unknown row decoding: {}, expected raw or full", decoding)),
+		}
+	}
+}
+
+// A value as a database returns it, before it is decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawValue {
+	Null,
+	Integer(i64),
+	Real(f64),
+	Text(String),
+	Blob(Vec<u8>),
+	Boolean(bool),
+	// Microseconds since the epoch, of a native timestamp column
+	Timestamp(i64),
+}
+
+// A row fetched by a read test, its values in the order the query selects them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Row(pub Vec<RawValue>);
+
+impl Row {
+	fn value(&self, column: usize) -> Result<&RawValue> {
+		self.0.get(column).with_context(|| format!("The row has no column {}", column))
+	}
+
+	// Id of `column`, stored as its string form or its 16 bytes
+	pub fn key(&self, column: usize) -> Result<Uuid> {
+		decode_key(self.value(column)?).with_context(|| format!("Invalid id in column {}", column))
+	}
+
+	// Timestamp of `column`, stored as its RFC 3339 form or as microseconds
+	pub fn timestamp(&self, column: usize) -> Result<DateTime<Utc>> {
+		decode_timestamp(self.value(column)?).with_context(|| format!("Invalid timestamp in column {}", column))
+	}
+
+	pub fn optional_timestamp(&self, column: usize) -> Result<Option<DateTime<Utc>>> {
+		match self.value(column)? {
+			RawValue::Null => Ok(None),
+			_ => self.timestamp(column).map(Some),
+		}
+	}
+
+	pub fn text(&self, column: usize) -> Result<String> {
+		match self.value(column)? {
+			RawValue::Text(text) => Ok(text.clone()),
+			other => anyhow::bail!("Expected text in column {}, got {:?}", column, other),
+		}
+	}
+
+	pub fn integer<T: TryFrom<i64>>(&self, column: usize) -> Result<T> {
+		match self.value(column)? {
+			&RawValue::Integer(value) => {
+				T::try_from(value).map_err(|_| anyhow::anyhow!("Integer {} out of range in column {}", value, column))
+			}
+			other => anyhow::bail!("Expected an integer in column {}, got {:?}", column, other),
+		}
+	}
+
+	pub fn real(&self, column: usize) -> Result<f64> {
+		match self.value(column)? {
+			&RawValue::Real(value) => Ok(value),
+			&RawValue::Integer(value) => Ok(value as f64),
+			other => anyhow::bail!("Expected a number in column {}, got {:?}", column, other),
+		}
+	}
+
+	pub fn optional_real(&self, column: usize) -> Result<Option<f64>> {
+		match self.value(column)? {
+			RawValue::Null => Ok(None),
+			_ => self.real(column).map(Some),
+		}
+	}
+
+	// Boolean of `column`, stored as a boolean or as an integer, 0 for false
+	pub fn boolean(&self, column: usize) -> Result<bool> {
+		match self.value(column)? {
+			&RawValue::Boolean(value) => Ok(value),
+			&RawValue::Integer(value) => Ok(value != 0),
+			other => anyhow::bail!("Expected a boolean in column {}, got {:?}", column, other),
+		}
+	}
+}
+
+// Id stored as its string form or its 16 bytes
+pub fn decode_key(value: &RawValue) -> Result<Uuid> {
+	match value {
+		RawValue::Text(text) => Ok(Uuid::parse_str(text)?),
+		RawValue::Blob(bytes) => Ok(Uuid::from_slice(bytes)?),
+		other => anyhow::bail!("Unexpected id value: {:?}", other),
+	}
+}
+
+// Timestamp stored as its RFC 3339 form, or as microseconds since the epoch in a native timestamp
+// or an integer column
+pub fn decode_timestamp(value: &RawValue) -> Result<DateTime<Utc>> {
+	match value {
+		RawValue::Text(text) => Ok(DateTime::parse_from_rfc3339(text)?.with_timezone(&Utc)),
+		&RawValue::Timestamp(micros) | &RawValue::Integer(micros) => {
+			DateTime::from_timestamp_micros(micros).with_context(|| format!("Timestamp out of range: {}", micros))
+		}
+		other => anyhow::bail!("Unexpected timestamp value: {:?}", other),
+	}
+}
+
+// Decoder of the rows of the read tests, following the `row_decoding` of the run
+#[derive(Clone)]
+pub struct RowCodec {
+	decoding: RowDecoding,
+	context: RunContext,
+}
+
+impl RowCodec {
+	pub fn new(context: &RunContext) -> Self {
+		RowCodec { decoding: context.row_decoding(), context: context.clone() }
+	}
+
+	// Decode each of `rows` with `decode`, such as the `TryFrom<Row>` of a model, when the rows are
+	// fully decoded, adding the time it took to the decoding time of the current test; the raw codec
+	// drops them as they are
+	pub fn decode<T>(&self, rows: impl IntoIterator<Item = Row>, decode: impl Fn(Row) -> Result<T>) -> Result<()> {
+		if self.decoding == RowDecoding::Raw {
+			return Ok(());
+		}
+
+		let start = Instant::now();
+		for row in rows {
+			black_box(decode(row)?);
+		}
+		self.context.record_decoding(start.elapsed());
+		Ok(())
+	}
+}
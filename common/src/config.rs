@@ -0,0 +1,94 @@
+use std::net::{ IpAddr, Ipv4Addr, SocketAddr };
+use std::path::PathBuf;
+use anyhow::{ Context, Result };
+use chrono::Utc;
+use clap::Parser;
+
+use crate::{ logging::LogFormat, plan::BenchmarkPlan, plan_file::RaceFile, schedule::Schedule };
+
+// Settings of a benchmark server, read from command-line flags or environment variables
+#[derive(Debug, Clone, Parser)]
+pub struct ServerConfig {
+	/// Address the API server listens on
+	#[arg(long, env = "BENCHMARK_BIND", default_value_t = IpAddr::V4(Ipv4Addr::UNSPECIFIED))]
+	pub bind: IpAddr,
+
+	/// Port the API server listens on (each database has its own default)
+	#[arg(long, env = "BENCHMARK_PORT")]
+	pub port: Option<u16>,
+
+	/// Port of the gRPC interface, served alongside the HTTP API when set
+	#[arg(long, env = "BENCHMARK_GRPC_PORT")]
+	pub grpc_port: Option<u16>,
+
+	/// Directory holding the database files and the results history
+	#[arg(long, env = "BENCHMARK_DATA_DIR", default_value = "./data")]
+	pub data_dir: PathBuf,
+
+	/// Existing database to attach to instead of the one in the data directory, a file path or a
+	/// DSN; the runs only read it. Supported by the SQL backends
+	#[arg(long, env = "BENCHMARK_CONNECT")]
+	pub connect: Option<String>,
+
+	/// Format of the logs, `text` or `json` (one object per line)
+	#[arg(long, env = "BENCHMARK_LOG_FORMAT", default_value_t = LogFormat::Text)]
+	pub log_format: LogFormat,
+
+	/// Cron expression in UTC (e.g. `0 3 * * *`) running the scheduled plan periodically, its
+	/// results added to the history like those of any run
+	#[arg(long, env = "BENCHMARK_SCHEDULE")]
+	pub schedule: Option<Schedule>,
+
+	/// Plan of the scheduled runs in TOML, YAML or JSON, the default plan when not given
+	#[arg(long, env = "BENCHMARK_SCHEDULE_PLAN", requires = "schedule")]
+	pub schedule_plan: Option<PathBuf>,
+
+	/// Registered backend to serve, in a binary registering several of them
+	#[arg(long, env = "BENCHMARK_BACKEND")]
+	pub backend: Option<String>,
+}
+
+impl ServerConfig {
+	// Parse the command-line flags, falling back to the environment variables and defaults
+	pub fn load() -> Self {
+		Self::parse()
+	}
+
+	// Install the logging of the server, in the configured format
+	pub fn init_logging(&self) {
+		crate::logging::init(self.log_format);
+	}
+
+	// Address to listen on, using the default port of the database when none is configured
+	pub fn addr(&self, default_port: u16) -> SocketAddr {
+		SocketAddr::new(self.bind, self.port.unwrap_or(default_port))
+	}
+
+	// Schedule of the periodic runs with their plan, if enabled
+	pub fn scheduled_plan(&self) -> Result<Option<(Schedule, BenchmarkPlan)>> {
+		let Some(schedule) = &self.schedule else {
+			return Ok(None);
+		};
+		if schedule.next_after(Utc::now()).is_none() {
+			anyhow::bail!("The schedule {} never runs", schedule);
+		}
+
+		let plan = match &self.schedule_plan {
+			Some(path) => {
+				RaceFile::load(path)?
+					.into_plan()
+					.map_err(anyhow::Error::msg)
+					.with_context(|| format!("Invalid scheduled plan {}", path.display()))?
+			}
+			None => BenchmarkPlan::default(),
+		};
+		plan.validate().map_err(anyhow::Error::msg).context("Invalid scheduled plan")?;
+
+		Ok(Some((schedule.clone(), plan)))
+	}
+
+	// Address of the gRPC interface, if enabled
+	pub fn grpc_addr(&self) -> Option<SocketAddr> {
+		self.grpc_port.map(|port| SocketAddr::new(self.bind, port))
+	}
+}
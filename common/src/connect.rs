@@ -0,0 +1,37 @@
+// Runs over an existing database: a server started with `--connect` attaches its backend to a
+// database it didn't build, given by a file path or a DSN, instead of its own in the data
+// directory. The data there belongs to the user, so the backend opens it read-only when the
+// engine allows it, and every plan runs as over a prebuilt dataset: only the tests that leave the
+// data unchanged, without seeding, cleanup, import, backup or capability probe.
+use anyhow::{ ensure, Result };
+
+use crate::plan::BenchmarkPlan;
+
+// Tables the read tests query, which a connected database must hold with the columns of the suite
+pub const CONNECTED_TABLES: [&str; 4] = ["users", "products", "orders", "order_items"];
+
+// Check that a connected database holding `tables` has every table the read tests query
+pub fn check_tables(tables: &[String]) -> Result<()> {
+	let missing: Vec<_> = CONNECTED_TABLES.iter()
+		.filter(|&&table| !tables.iter().any(|name| name.eq_ignore_ascii_case(table)))
+		.copied()
+		.collect();
+	ensure!(
+		missing.is_empty(),
+		"The connected database has no {} table, which the read tests query",
+		missing.join(", ")
+	);
+	Ok(())
+}
+
+// Restrict `plan` to what a connected database allows: the tests leaving its data unchanged
+pub fn restrict_plan(plan: &mut BenchmarkPlan) -> Result<(), String> {
+	if plan.backup {
+		return Err("the backup restores the database, it can't run on a connected one".to_string());
+	}
+	if plan.import.is_some() {
+		return Err("an import writes its records, it can't run on a connected one".to_string());
+	}
+	plan.prebuilt = true;
+	plan.validate()
+}
@@ -0,0 +1,86 @@
+// Lost updates under concurrent read-modify-write, for consistency_probe: `writers` connections
+// increment the stock of the first product, each its share of the test's increments, every
+// increment reading the stock then writing it back plus one in a transaction of its own. An engine
+// isolating the transactions makes an increment that would overwrite another one fail instead,
+// with a busy snapshot in SQLite or a conflict in DuckDB, while one that doesn't, such as a plain
+// RocksDB database, lets it overwrite the other. The counter is read before and after the test,
+// and the committed increments it doesn't hold are reported as lost updates.
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::sync::Arc;
+use anyhow::Result;
+
+use crate::benchmark::measure_execution;
+use crate::models::{ BenchmarkResult, ConsistencyStats };
+use crate::plan::BenchmarkTest;
+use crate::writers::writer_ranges;
+
+// Increments of the writers, counted as they commit or fail
+#[derive(Debug, Default)]
+pub struct ConsistencyCounters {
+	committed: AtomicU64,
+	aborted: AtomicU64,
+}
+
+impl ConsistencyCounters {
+	// Count an increment, which failed on the lock or a conflict unless `committed`
+	pub fn record(&self, committed: bool) {
+		let counter = if committed { &self.committed } else { &self.aborted };
+		counter.fetch_add(1, Ordering::Relaxed);
+	}
+
+	// Stats of the increments, given the counter before and after the test
+	pub fn stats(&self, before: i64, after: i64) -> ConsistencyStats {
+		let committed = self.committed.load(Ordering::Relaxed);
+		let applied = after.saturating_sub(before).max(0) as u64;
+		let lost_updates = committed.saturating_sub(applied);
+		ConsistencyStats {
+			committed,
+			aborted: self.aborted.load(Ordering::Relaxed),
+			applied,
+			lost_updates,
+			lost_update_rate: (lost_updates as f64) / (committed.max(1) as f64),
+		}
+	}
+}
+
+// Run consistency_probe: `read_counter` reads the counter outside of the timing, before and after
+// the writers, then `increment` runs the increments of a writer's range on its own connection,
+// counting them. The writers are timed together
+pub async fn measure_consistency<R, RFut, F, FFut>(
+	database_name: &str,
+	count: usize,
+	writers: usize,
+	cpu_count: usize,
+	read_counter: R,
+	increment: F
+)
+	-> Result<BenchmarkResult>
+	where
+		R: Fn() -> RFut,
+		RFut: Future<Output = Result<i64>>,
+		F: Fn(usize, Range<usize>, Arc<ConsistencyCounters>) -> FFut,
+		FFut: Future<Output = Result<()>>
+{
+	let before = read_counter().await?;
+	let counters = Arc::new(ConsistencyCounters::default());
+	let mut result = measure_execution(
+		database_name,
+		BenchmarkTest::ConsistencyProbe.name(),
+		count,
+		cpu_count,
+		|| async {
+			let tasks = writer_ranges(count, writers)
+				.into_iter()
+				.enumerate()
+				.map(|(writer, range)| increment(writer, range, counters.clone()));
+			futures::future::try_join_all(tasks).await?;
+			Ok(())
+		}
+	).await?;
+	let after = read_counter().await?;
+
+	result.consistency = Some(counters.stats(before, after));
+	Ok(result)
+}
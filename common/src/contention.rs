@@ -0,0 +1,138 @@
+// Contention with a long write transaction, for long_transaction_contention: one connection opens
+// a write transaction, updates the first `CONTENDED_USERS` users and holds it open while `writers`
+// other connections read and update the users of the test, every other operation being a write.
+// SQLite allows a single writer, so under WAL the reads go on while every write waits for the
+// lock until `CONTENTION_TIMEOUT_MS` and fails. DuckDB keeps versions of the rows instead: the
+// reads and the writes to other users go through and only the writes to the users the transaction
+// changed fail, at once, on a conflict. The transaction commits once the contenders are done, and
+// the result reports how many of their operations failed and the time they spent on them.
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::sync::{ mpsc, Arc };
+use std::time::Duration;
+use anyhow::{ bail, Result };
+use tokio::sync::oneshot;
+
+use crate::benchmark::measure_execution;
+use crate::models::{ BenchmarkResult, ContentionStats };
+use crate::plan::BenchmarkTest;
+use crate::writers::writer_ranges;
+
+// Users the long transaction updates, at the start of those the contenders access
+pub const CONTENDED_USERS: usize = 10;
+
+// How long a contending operation waits for a lock before giving up
+pub const CONTENTION_TIMEOUT_MS: u64 = 100;
+
+// Whether the contending operation at position `i` of the test is a write rather than a read
+pub fn is_contended_write(i: usize) -> bool {
+	i % 2 == 1
+}
+
+// Handed to the holder of the long transaction, which opens it then calls `hold`
+pub struct TransactionHold {
+	opened: oneshot::Sender<()>,
+	release: mpsc::Receiver<()>,
+}
+
+impl TransactionHold {
+	// Let the contenders start and block until they are done, when the transaction can commit
+	pub fn hold(self) {
+		let _ = self.opened.send(());
+		let _ = self.release.recv();
+	}
+}
+
+// Operations of the contenders, counted as they run
+#[derive(Debug, Default)]
+pub struct ContentionCounters {
+	reads: AtomicU64,
+	writes: AtomicU64,
+	failed_reads: AtomicU64,
+	failed_writes: AtomicU64,
+	blocked_us: AtomicU64,
+}
+
+impl ContentionCounters {
+	// Count an operation that ran for `elapsed`, failing on the lock or a conflict when `blocked`
+	pub fn record(&self, write: bool, elapsed: Duration, blocked: bool) {
+		let (operations, failures) = if write {
+			(&self.writes, &self.failed_writes)
+		} else {
+			(&self.reads, &self.failed_reads)
+		};
+		operations.fetch_add(1, Ordering::Relaxed);
+		if blocked {
+			failures.fetch_add(1, Ordering::Relaxed);
+			self.blocked_us.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+		}
+	}
+
+	pub fn stats(&self) -> ContentionStats {
+		let reads = self.reads.load(Ordering::Relaxed);
+		let writes = self.writes.load(Ordering::Relaxed);
+		let failed_reads = self.failed_reads.load(Ordering::Relaxed);
+		let failed_writes = self.failed_writes.load(Ordering::Relaxed);
+		ContentionStats {
+			reads,
+			writes,
+			failed_reads,
+			failed_writes,
+			error_rate: ((failed_reads + failed_writes) as f64) / ((reads + writes).max(1) as f64),
+			blocked_ms: (self.blocked_us.load(Ordering::Relaxed) as f64) / 1000.0,
+		}
+	}
+}
+
+// Run long_transaction_contention: `hold` opens the long transaction and holds it through the
+// `TransactionHold` it is given, then `contend` runs the operations of a contender's range on its
+// own connection, counting them. Only the contenders are timed
+pub async fn measure_contention<H, HFut, C, CFut>(
+	database_name: &str,
+	count: usize,
+	contenders: usize,
+	cpu_count: usize,
+	hold: H,
+	contend: C
+)
+	-> Result<BenchmarkResult>
+	where
+		H: FnOnce(TransactionHold) -> HFut,
+		HFut: Future<Output = Result<()>> + Send + 'static,
+		C: Fn(usize, Range<usize>, Arc<ContentionCounters>) -> CFut,
+		CFut: Future<Output = Result<()>>
+{
+	let (opened_tx, opened) = oneshot::channel();
+	let (release, release_rx) = mpsc::channel();
+	let holder = tokio::spawn(hold(TransactionHold { opened: opened_tx, release: release_rx }));
+	if opened.await.is_err() {
+		holder.await??;
+		bail!("The long transaction was never held open");
+	}
+
+	let counters = Arc::new(ContentionCounters::default());
+	let measured = measure_execution(
+		database_name,
+		BenchmarkTest::LongTransactionContention.name(),
+		count,
+		cpu_count,
+		|| async {
+			let tasks = writer_ranges(count, contenders)
+				.into_iter()
+				.enumerate()
+				.map(|(contender, range)| contend(contender, range, counters.clone()));
+			futures::future::try_join_all(tasks).await?;
+			Ok(())
+		}
+	).await;
+
+	// Commit the transaction even when a contender failed, so the database is left unlocked
+	let _ = release.send(());
+	let held = holder.await?;
+	let mut result = measured?;
+	held?;
+
+	result.contention = Some(counters.stats());
+	Ok(result)
+}
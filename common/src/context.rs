@@ -0,0 +1,452 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{ AtomicBool, AtomicU64, AtomicUsize, Ordering };
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+use crate::checkpoint::{ CheckpointStore, RunCheckpoint };
+use crate::codec::RowDecoding;
+use crate::import::Import;
+use crate::models::{ BatchStats, BenchmarkResult, OutlierStats, StatementCacheStats };
+use crate::oplog;
+use crate::outliers::{ self, OutlierThresholds };
+use crate::plan::{ AccessDistribution, KeyStrategy, PayloadProfile };
+use crate::tenants::DEFAULT_TENANTS;
+use crate::writers::DEFAULT_WRITERS;
+use crate::trace::Trace;
+
+// State shared between a running benchmark and the server, cheap to clone
+// so it can be moved into blocking tasks and connection closures
+#[derive(Clone, Default)]
+pub struct RunContext {
+	inner: Arc<RunContextInner>,
+}
+
+#[derive(Default)]
+struct RunContextInner {
+	// Operations completed by the current test
+	operations: AtomicU64,
+	// Time per operation of the current test, taken between the operations it records
+	latencies: Mutex<Latencies>,
+	// Size and nanoseconds of each batch of the current test, for the tests reading or writing
+	// their records in batches
+	batches: Mutex<Vec<(u64, u64)>>,
+	// Thresholds of the outliers and stalls of the tests
+	outlier_thresholds: Mutex<OutlierThresholds>,
+	// Name of the test currently running
+	current_test: Mutex<Option<String>>,
+	// Set when the current run should stop as soon as possible
+	cancelled: AtomicBool,
+	// Set when the current test should stop, e.g. after its timeout
+	test_aborted: AtomicBool,
+	// Lookups of the prepared statement caches by the current test
+	statement_cache_hits: AtomicU64,
+	statement_cache_misses: AtomicU64,
+	// Operations of the current test retried after a transient error
+	retries: AtomicU64,
+	// Whether the read tests decode the rows they fetch, and the nanoseconds the current test
+	// spent decoding them
+	row_decoding: Mutex<RowDecoding>,
+	decoding_nanos: AtomicU64,
+	// Writes the current test groups per transaction or write batch, 0 for all at once
+	batch_size: AtomicUsize,
+	// Share of the users read_by_column_search matches, the default one when `None`
+	selectivity: Mutex<Option<f64>>,
+	// Distribution of the ids the tests access, fixed access patterns when `None`
+	distribution: Mutex<Option<AccessDistribution>>,
+	// Sizes of the users and products generated by the seeding and the insert tests
+	payload: Mutex<PayloadProfile>,
+	// Tenants the generated users and orders are spread across, the default when 0
+	tenants: AtomicUsize,
+	// Writers of concurrent_inserts and consistency_probe, and contenders of
+	// long_transaction_contention, the default when 0
+	writers: AtomicUsize,
+	// How the ids of the generated records are picked
+	key_strategy: Mutex<KeyStrategy>,
+	// Trace the workloads record or replay, if any
+	trace: Mutex<Option<Trace>>,
+	// Directory of the snapshot of the dataset restored before each test, if any
+	fixture: Mutex<Option<PathBuf>>,
+	// CSV file of the user's data the run imports, if any
+	import: Mutex<Option<Import>>,
+	// Directory the backup of the run is written to, if any
+	backup: Mutex<Option<PathBuf>>,
+	// Op-log the operations of the run are appended to, if any
+	oplog: Mutex<Option<PathBuf>>,
+	// Progress of the run, saved to the store after each step when set
+	checkpoint: Mutex<Option<(CheckpointStore, RunCheckpoint)>>,
+}
+
+// Time between the operations recorded by a test. An operation takes the time since the previous
+// one, and operations recorded together share the time since the previous record evenly.
+#[derive(Default)]
+struct Latencies {
+	// When the previous operations were recorded, or when the timing of the test started
+	last: Option<Instant>,
+	// Nanoseconds per operation of every record, with the number of operations recorded
+	samples: Vec<(u64, u64)>,
+	// Nanoseconds the operation the test failed at took, if it failed
+	failed: Option<u64>,
+}
+
+// Error returned by a test interrupted by a cancellation
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("Benchmark run cancelled")
+	}
+}
+
+impl std::error::Error for Cancelled {}
+
+impl RunContext {
+	// Mark the start of a test and reset the operation counter
+	pub fn start_test(&self, test_name: &str) {
+		self.inner.operations.store(0, Ordering::Relaxed);
+		self.inner.test_aborted.store(false, Ordering::Relaxed);
+		self.inner.statement_cache_hits.store(0, Ordering::Relaxed);
+		self.inner.statement_cache_misses.store(0, Ordering::Relaxed);
+		self.inner.retries.store(0, Ordering::Relaxed);
+		self.inner.decoding_nanos.store(0, Ordering::Relaxed);
+		*self.inner.latencies.lock().unwrap() = Latencies { last: Some(Instant::now()), ..Default::default() };
+		self.inner.batches.lock().unwrap().clear();
+		*self.inner.current_test.lock().unwrap() = Some(test_name.to_string());
+	}
+
+	// Start timing the operations of the current test, once its fixture is prepared
+	pub fn start_timing(&self) {
+		self.inner.latencies.lock().unwrap().last = Some(Instant::now());
+	}
+
+	// Mark the end of the current test
+	pub fn finish_test(&self) {
+		*self.inner.current_test.lock().unwrap() = None;
+		self.inner.test_aborted.store(false, Ordering::Relaxed);
+	}
+
+	// Record completed operations of the current test
+	pub fn record_operations(&self, count: u64) {
+		self.inner.operations.fetch_add(count, Ordering::Relaxed);
+		if count == 0 {
+			return;
+		}
+
+		let now = Instant::now();
+		let mut latencies = self.inner.latencies.lock().unwrap();
+		if let Some(last) = latencies.last.replace(now) {
+			let nanos = now.duration_since(last).as_nanos() as u64;
+			latencies.samples.push((nanos / count, count));
+		}
+	}
+
+	// Record a batch of `size` operations of the current test, completed in `duration` by a single
+	// statement, transaction or write batch
+	pub fn record_batch(&self, size: usize, duration: Duration) {
+		self.inner.batches.lock().unwrap().push((size as u64, duration.as_nanos() as u64));
+		self.record_operations(size as u64);
+	}
+
+	// Batches of the current test, if it recorded any
+	pub fn batch_stats(&self) -> Option<BatchStats> {
+		let batches = self.inner.batches.lock().unwrap();
+		let latencies: Vec<(u64, u64)> = batches.iter().map(|&(_, nanos)| (nanos, 1)).collect();
+		let micros = |quantile| {
+			outliers::percentile(latencies.clone(), quantile).map(|nanos| (nanos as f64) / 1000.0)
+		};
+		let total: u64 = batches.iter().map(|&(size, _)| size).sum();
+
+		Some(BatchStats {
+			count: batches.len() as u64,
+			mean_size: (total as f64) / (batches.len() as f64),
+			max_size: batches.iter().map(|&(size, _)| size).max()?,
+			latency_p50_us: micros(0.5)?,
+			latency_p99_us: micros(0.99)?,
+			latency_max_us: micros(1.0)?,
+		})
+	}
+
+	// Record a single completed operation of the current test
+	pub fn record_operation(&self) {
+		self.record_operations(1);
+	}
+
+	// Record that the current test failed at its next operation, which took the time since the
+	// previous one
+	pub fn record_failed_operation(&self) {
+		let mut latencies = self.inner.latencies.lock().unwrap();
+		if let Some(last) = latencies.last {
+			latencies.failed = Some(last.elapsed().as_nanos() as u64);
+		}
+	}
+
+	// Append the operations of the test that just finished, named `test_name`, to the op-log of
+	// the run, if it has one. Failing to write them only leaves them out of the op-log.
+	pub fn log_operations(&self, test_name: &str) {
+		let Some(path) = self.inner.oplog.lock().unwrap().clone() else {
+			return;
+		};
+
+		let operations: Vec<(u64, bool)> = {
+			let latencies = self.inner.latencies.lock().unwrap();
+			latencies.samples
+				.iter()
+				.flat_map(|&(nanos, count)| std::iter::repeat_n((nanos, true), count as usize))
+				.chain(latencies.failed.map(|nanos| (nanos, false)))
+				.collect()
+		};
+		if let Err(e) = oplog::append(&path, test_name, &operations) {
+			tracing::warn!("Failed to write the operations of {} to the op-log: {:?}", test_name, e);
+		}
+	}
+
+	// 99th percentile of the time per operation of the current test in microseconds, if it
+	// recorded any operation
+	pub fn latency_p99_us(&self) -> Option<f64> {
+		let samples = self.inner.latencies.lock().unwrap().samples.clone();
+		outliers::percentile(samples, 0.99).map(|nanos| (nanos as f64) / 1000.0)
+	}
+
+	// Set the thresholds of the outliers and stalls of the next tests
+	pub fn set_outlier_thresholds(&self, thresholds: OutlierThresholds) {
+		*self.inner.outlier_thresholds.lock().unwrap() = thresholds;
+	}
+
+	// Outliers and stalls of the current test, if it recorded any operation
+	pub fn outlier_stats(&self) -> Option<OutlierStats> {
+		let thresholds = *self.inner.outlier_thresholds.lock().unwrap();
+		outliers::analyze(&self.inner.latencies.lock().unwrap().samples, thresholds)
+	}
+
+	// Record a lookup of a prepared statement cache by the current test
+	pub fn record_statement_cache_lookup(&self, hit: bool) {
+		let counter = if hit {
+			&self.inner.statement_cache_hits
+		} else {
+			&self.inner.statement_cache_misses
+		};
+		counter.fetch_add(1, Ordering::Relaxed);
+	}
+
+	// Statement cache lookups of the current test, if it used a statement cache at all
+	pub fn statement_cache_stats(&self) -> Option<StatementCacheStats> {
+		let stats = StatementCacheStats {
+			hits: self.inner.statement_cache_hits.load(Ordering::Relaxed),
+			misses: self.inner.statement_cache_misses.load(Ordering::Relaxed),
+		};
+		(stats.hits + stats.misses > 0).then_some(stats)
+	}
+
+	// Record an operation of the current test retried after a transient error
+	pub fn record_retry(&self) {
+		self.inner.retries.fetch_add(1, Ordering::Relaxed);
+	}
+
+	// Retries of the current test
+	pub fn retries(&self) -> u64 {
+		self.inner.retries.load(Ordering::Relaxed)
+	}
+
+	// Set whether the next read tests decode the rows they fetch
+	pub fn set_row_decoding(&self, decoding: RowDecoding) {
+		*self.inner.row_decoding.lock().unwrap() = decoding;
+	}
+
+	pub fn row_decoding(&self) -> RowDecoding {
+		*self.inner.row_decoding.lock().unwrap()
+	}
+
+	// Record time the current test spent decoding the rows it fetched
+	pub fn record_decoding(&self, duration: Duration) {
+		self.inner.decoding_nanos.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+	}
+
+	// Milliseconds the current test spent decoding rows, if it decoded any
+	pub fn decoding_ms(&self) -> Option<f64> {
+		let nanos = self.inner.decoding_nanos.load(Ordering::Relaxed);
+		(nanos > 0).then(|| (nanos as f64) / 1e6)
+	}
+
+	// Set the batch size of the next tests, all their writes at once when `None`
+	pub fn set_batch_size(&self, batch_size: Option<usize>) {
+		self.inner.batch_size.store(batch_size.unwrap_or(0), Ordering::Relaxed);
+	}
+
+	// Number of writes to group per transaction or write batch for a test doing `total` of them
+	pub fn batch_len(&self, total: usize) -> usize {
+		match self.inner.batch_size.load(Ordering::Relaxed) {
+			0 => total.max(1),
+			batch_size => batch_size,
+		}
+	}
+
+	// Set the selectivity of the next searches
+	pub fn set_selectivity(&self, selectivity: Option<f64>) {
+		*self.inner.selectivity.lock().unwrap() = selectivity;
+	}
+
+	pub fn selectivity(&self) -> Option<f64> {
+		*self.inner.selectivity.lock().unwrap()
+	}
+
+	// Set the distribution of the ids accessed by the next tests
+	pub fn set_distribution(&self, distribution: Option<AccessDistribution>) {
+		*self.inner.distribution.lock().unwrap() = distribution;
+	}
+
+	pub fn distribution(&self) -> Option<AccessDistribution> {
+		*self.inner.distribution.lock().unwrap()
+	}
+
+	// Set the sizes of the records generated from now on
+	pub fn set_payload(&self, payload: PayloadProfile) {
+		*self.inner.payload.lock().unwrap() = payload;
+	}
+
+	pub fn payload(&self) -> PayloadProfile {
+		*self.inner.payload.lock().unwrap()
+	}
+
+	// Set the number of tenants of the records generated from now on
+	pub fn set_tenants(&self, tenants: usize) {
+		self.inner.tenants.store(tenants, Ordering::Relaxed);
+	}
+
+	pub fn tenants(&self) -> usize {
+		match self.inner.tenants.load(Ordering::Relaxed) {
+			0 => DEFAULT_TENANTS,
+			tenants => tenants,
+		}
+	}
+
+	// Set the number of writers of concurrent_inserts and consistency_probe, and contenders of
+	// long_transaction_contention
+	pub fn set_writers(&self, writers: usize) {
+		self.inner.writers.store(writers, Ordering::Relaxed);
+	}
+
+	pub fn writers(&self) -> usize {
+		match self.inner.writers.load(Ordering::Relaxed) {
+			0 => DEFAULT_WRITERS,
+			writers => writers,
+		}
+	}
+
+	// Set how the ids of the records generated from now on are picked
+	pub fn set_key_strategy(&self, strategy: KeyStrategy) {
+		*self.inner.key_strategy.lock().unwrap() = strategy;
+	}
+
+	pub fn key_strategy(&self) -> KeyStrategy {
+		*self.inner.key_strategy.lock().unwrap()
+	}
+
+	// Set the trace the next workloads record or replay
+	pub fn set_trace(&self, trace: Option<Trace>) {
+		*self.inner.trace.lock().unwrap() = trace;
+	}
+
+	pub fn trace(&self) -> Option<Trace> {
+		self.inner.trace.lock().unwrap().clone()
+	}
+
+	// Set the snapshot of the dataset restored before each test
+	pub fn set_fixture(&self, dir: Option<PathBuf>) {
+		*self.inner.fixture.lock().unwrap() = dir;
+	}
+
+	pub fn fixture(&self) -> Option<PathBuf> {
+		self.inner.fixture.lock().unwrap().clone()
+	}
+
+	// Set the CSV file the run imports
+	pub fn set_import(&self, import: Option<Import>) {
+		*self.inner.import.lock().unwrap() = import;
+	}
+
+	pub fn import(&self) -> Option<Import> {
+		self.inner.import.lock().unwrap().clone()
+	}
+
+	// Set the directory the backup of the run is written to
+	pub fn set_backup(&self, dir: Option<PathBuf>) {
+		*self.inner.backup.lock().unwrap() = dir;
+	}
+
+	pub fn backup(&self) -> Option<PathBuf> {
+		self.inner.backup.lock().unwrap().clone()
+	}
+
+	// Set the op-log the operations of the next tests are appended to
+	pub fn set_oplog(&self, path: Option<PathBuf>) {
+		*self.inner.oplog.lock().unwrap() = path;
+	}
+
+	// Save the progress of the run to `store` after each step, going on from `checkpoint`
+	pub fn set_checkpoint(&self, checkpoint: Option<(CheckpointStore, RunCheckpoint)>) {
+		*self.inner.checkpoint.lock().unwrap() = checkpoint;
+	}
+
+	// Steps of the run completed before it was interrupted, and their results
+	pub fn completed_steps(&self) -> (usize, Vec<BenchmarkResult>) {
+		match &*self.inner.checkpoint.lock().unwrap() {
+			Some((_, checkpoint)) => (checkpoint.steps, checkpoint.results.clone()),
+			None => (0, Vec::new()),
+		}
+	}
+
+	// Record that the next step of the run completed, with the results of the run so far. Failing
+	// to save the checkpoint only loses the ability to resume from this step.
+	pub fn complete_step(&self, results: &[BenchmarkResult]) {
+		if let Some((store, checkpoint)) = &mut *self.inner.checkpoint.lock().unwrap() {
+			checkpoint.steps += 1;
+			checkpoint.results = results.to_vec();
+			checkpoint.updated_at = chrono::Utc::now();
+			if let Err(e) = store.save(checkpoint) {
+				tracing::warn!("Failed to save the checkpoint of run {}: {:?}", checkpoint.run_id, e);
+			}
+		}
+	}
+
+	// Name of the running test and the number of operations it completed so far
+	pub fn snapshot(&self) -> Option<(String, u64)> {
+		let current_test = self.inner.current_test.lock().unwrap().clone();
+		current_test.map(|test_name| (test_name, self.inner.operations.load(Ordering::Relaxed)))
+	}
+
+	// Request the current run to stop
+	pub fn cancel(&self) {
+		self.inner.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	// Clear a previous cancellation before starting a new run
+	pub fn reset_cancellation(&self) {
+		self.inner.cancelled.store(false, Ordering::Relaxed);
+		self.inner.test_aborted.store(false, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.inner.cancelled.load(Ordering::Relaxed)
+	}
+
+	// Fail with `Cancelled` once the whole run has been cancelled, ignoring aborted tests
+	pub fn check_cancelled_run(&self) -> Result<(), Cancelled> {
+		if self.is_cancelled() { Err(Cancelled) } else { Ok(()) }
+	}
+
+	// Request the current test to stop, the run goes on with the next test
+	pub fn abort_test(&self) {
+		self.inner.test_aborted.store(true, Ordering::Relaxed);
+	}
+
+	// Fail with `Cancelled` once the run has been cancelled or the current test aborted,
+	// meant to be called in test loops
+	pub fn check_cancelled(&self) -> Result<(), Cancelled> {
+		if self.is_cancelled() || self.inner.test_aborted.load(Ordering::Relaxed) {
+			Err(Cancelled)
+		} else {
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,128 @@
+use std::f64::consts::TAU;
+use std::ops::Range;
+use rand::Rng;
+
+use crate::plan::{ AccessDistribution, ValueDistribution };
+
+// Picks indexes in `0..len` following an access distribution. Index 0 is the hottest under a
+// Zipfian distribution, then index 1 and so on, so the records to access are best ordered the
+// way the database returns them, e.g. by key.
+pub struct KeyChooser {
+	len: usize,
+	zipfian: Option<Zipfian>,
+}
+
+// Zipfian generator of "Quickly generating billion-record synthetic databases" (Gray et al.),
+// as used by YCSB: the constants are computed once, each draw is then O(1)
+struct Zipfian {
+	theta: f64,
+	zeta_n: f64,
+	alpha: f64,
+	eta: f64,
+}
+
+impl KeyChooser {
+	pub fn new(distribution: AccessDistribution, len: usize) -> Self {
+		let zipfian = match distribution {
+			AccessDistribution::Uniform => None,
+			AccessDistribution::Zipfian { theta } => Some(Zipfian::new(theta, len)),
+		};
+		KeyChooser { len, zipfian }
+	}
+
+	// Next index to access, 0 when there is nothing to choose from
+	pub fn next_index<R: Rng>(&self, rng: &mut R) -> usize {
+		if self.len <= 1 {
+			return 0;
+		}
+		match &self.zipfian {
+			None => rng.gen_range(0..self.len),
+			Some(zipfian) => zipfian.next(self.len, rng.gen::<f64>()),
+		}
+	}
+}
+
+impl Zipfian {
+	fn new(theta: f64, len: usize) -> Self {
+		let zeta_2 = zeta(2, theta);
+		let zeta_n = zeta(len.max(2), theta);
+		Zipfian {
+			theta,
+			zeta_n,
+			alpha: 1.0 / (1.0 - theta),
+			eta: (1.0 - (2.0 / (len.max(2) as f64)).powf(1.0 - theta)) / (1.0 - zeta_2 / zeta_n),
+		}
+	}
+
+	fn next(&self, len: usize, u: f64) -> usize {
+		let uz = u * self.zeta_n;
+		if uz < 1.0 {
+			return 0;
+		}
+		if uz < 1.0 + 0.5_f64.powf(self.theta) {
+			return 1;
+		}
+		let index = (len as f64) * (self.eta * u - self.eta + 1.0).powf(self.alpha);
+		(index as usize).min(len - 1)
+	}
+}
+
+// Generalized harmonic number of `n` for `theta`
+fn zeta(n: usize, theta: f64) -> f64 {
+	(1..=n).map(|i| 1.0 / (i as f64).powf(theta)).sum()
+}
+
+// `count` items drawn from `items` following `distribution`, in the order they are to be accessed
+pub fn sample<T: Clone>(distribution: AccessDistribution, items: &[T], count: usize) -> Vec<T> {
+	if items.is_empty() {
+		return Vec::new();
+	}
+	let chooser = KeyChooser::new(distribution, items.len());
+	let mut rng = rand::thread_rng();
+	(0..count).map(|_| items[chooser.next_index(&mut rng)].clone()).collect()
+}
+
+// A value in `range` following `distribution`. The normal distribution is centered on the middle
+// of the range and drawn with the Box-Muller transform, the values falling outside clamped to its
+// ends. The Pareto one is bounded to the range, its minimum being the start of the range, so no
+// value needs clamping.
+pub fn draw_value<R: Rng>(distribution: ValueDistribution, range: Range<i64>, rng: &mut R) -> i64 {
+	let span = (range.end - range.start) as f64;
+	let offset = match distribution {
+		ValueDistribution::Uniform => return rng.gen_range(range),
+		ValueDistribution::Normal { std_dev } => {
+			let u = 1.0 - rng.gen::<f64>();
+			let z = (-2.0 * u.ln()).sqrt() * (TAU * rng.gen::<f64>()).cos();
+			span / 2.0 + z * std_dev * span
+		}
+		ValueDistribution::Pareto { alpha } => {
+			// Inverse of the CDF of the Pareto distribution bounded to [1, span]
+			let tail = span.powf(-alpha);
+			(1.0 - rng.gen::<f64>() * (1.0 - tail)).powf(-1.0 / alpha) - 1.0
+		}
+	};
+	(range.start + (offset as i64)).clamp(range.start, range.end - 1)
+}
+
+// Parents of `children` records drawn among `parents` with a Pareto fan-out: each parent gets a
+// weight drawn from a Pareto distribution of shape `alpha`, less its minimum so that most weights
+// are close to 0, then each child picks its parent in proportion to the weights. A few parents
+// end up with hundreds of children and many with none, like the customers of a shop
+pub fn pareto_fan_out<R: Rng>(parents: usize, children: usize, alpha: f64, rng: &mut R) -> Vec<usize> {
+	if parents == 0 {
+		return Vec::new();
+	}
+	let mut total = 0.0;
+	let cumulative: Vec<f64> = (0..parents)
+		.map(|_| {
+			total += (1.0 - rng.gen::<f64>()).powf(-1.0 / alpha) - 1.0;
+			total
+		})
+		.collect();
+	(0..children)
+		.map(|_| {
+			let target = rng.gen::<f64>() * total;
+			cumulative.partition_point(|&weight| weight <= target).min(parents - 1)
+		})
+		.collect()
+}
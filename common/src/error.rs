@@ -0,0 +1,140 @@
+use std::fmt;
+use std::time::Duration;
+use axum::{ http::StatusCode, response::{ IntoResponse, Response }, Json };
+use serde::{ Deserialize, Serialize };
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::context::Cancelled;
+
+// Failure of a run or of a dataset operation, keeping its kind so clients can react to it
+#[derive(Debug, Error)]
+pub enum BenchmarkError {
+	#[error("Database initialization failed: {0}")]
+	InitFailed(String),
+	#[error("Test data generation failed: {0}")]
+	DataGeneration(String),
+	#[error("{0}")]
+	Engine(String),
+	#[error("Timed out after {0:?}")]
+	Timeout(Duration),
+	#[error("Benchmark run cancelled")]
+	Cancelled,
+}
+
+// Kind of a `BenchmarkError`, as serialized in error responses and failed run statuses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+	InitFailed,
+	DataGeneration,
+	Engine,
+	Timeout,
+	Cancelled,
+}
+
+// Body of the error responses of the API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+	pub error: ErrorKind,
+	pub message: String,
+}
+
+impl BenchmarkError {
+	// Wrap an error of the database engine, keeping its whole chain of causes
+	pub fn engine(error: anyhow::Error) -> Self {
+		BenchmarkError::Engine(format!("{:#}", error))
+	}
+
+	pub fn init_failed(error: anyhow::Error) -> Self {
+		BenchmarkError::InitFailed(format!("{:#}", error))
+	}
+
+	pub fn data_generation(error: anyhow::Error) -> Self {
+		BenchmarkError::DataGeneration(format!("{:#}", error))
+	}
+
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			BenchmarkError::InitFailed(_) => ErrorKind::InitFailed,
+			BenchmarkError::DataGeneration(_) => ErrorKind::DataGeneration,
+			BenchmarkError::Engine(_) => ErrorKind::Engine,
+			BenchmarkError::Timeout(_) => ErrorKind::Timeout,
+			BenchmarkError::Cancelled => ErrorKind::Cancelled,
+		}
+	}
+
+	pub fn status_code(&self) -> StatusCode {
+		match self {
+			BenchmarkError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+			BenchmarkError::Cancelled => StatusCode::CONFLICT,
+			_ => StatusCode::INTERNAL_SERVER_ERROR,
+		}
+	}
+}
+
+impl ErrorKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ErrorKind::InitFailed => "init_failed",
+			ErrorKind::DataGeneration => "data_generation",
+			ErrorKind::Engine => "engine",
+			ErrorKind::Timeout => "timeout",
+			ErrorKind::Cancelled => "cancelled",
+		}
+	}
+}
+
+impl From<Cancelled> for BenchmarkError {
+	fn from(_: Cancelled) -> Self {
+		BenchmarkError::Cancelled
+	}
+}
+
+impl From<&BenchmarkError> for ErrorResponse {
+	fn from(error: &BenchmarkError) -> Self {
+		ErrorResponse { error: error.kind(), message: error.to_string() }
+	}
+}
+
+// Lets clients turn an error response back into an error, keeping its kind
+impl fmt::Display for ErrorResponse {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for ErrorResponse {}
+
+impl IntoResponse for BenchmarkError {
+	fn into_response(self) -> Response {
+		(self.status_code(), Json(ErrorResponse::from(&self))).into_response()
+	}
+}
+
+// Error of an API handler: a bare status for rejected requests, or a failure with a JSON body
+pub(crate) enum ApiError {
+	Status(StatusCode),
+	Benchmark(BenchmarkError),
+}
+
+impl From<StatusCode> for ApiError {
+	fn from(status: StatusCode) -> Self {
+		ApiError::Status(status)
+	}
+}
+
+impl From<BenchmarkError> for ApiError {
+	fn from(error: BenchmarkError) -> Self {
+		ApiError::Benchmark(error)
+	}
+}
+
+impl IntoResponse for ApiError {
+	fn into_response(self) -> Response {
+		match self {
+			ApiError::Status(status) => status.into_response(),
+			ApiError::Benchmark(error) => error.into_response(),
+		}
+	}
+}
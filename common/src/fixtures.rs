@@ -0,0 +1,82 @@
+// Fixture datasets: a dataset generated once, from a seed when the plan gives one, then saved by
+// the database (a copy of the SQLite or DuckDB file, a RocksDB checkpoint) and restored before
+// each test, so every test starts from the same data instead of whatever the previous test left
+// behind.
+//
+// A seeded dataset has the same content on every run and every database: its values and UUIDv4
+// ids are drawn from a generator seeded by the plan, and its timestamps are `FIXTURE_EPOCH`. The
+// ids of the time-based key strategies still depend on the clock, and sequential ids go on from
+// the previous ones.
+use std::sync::Mutex;
+use chrono::{ DateTime, TimeZone, Utc };
+use rand::{ rngs::StdRng, RngCore, SeedableRng };
+
+// Time of every timestamp of a seeded dataset: 2024-01-01T00:00:00Z
+pub const FIXTURE_EPOCH: i64 = 1_704_067_200;
+
+// Tables a snapshot holds, parents before children: a restore empties them in reverse and fills
+// them in this order
+pub const FIXTURE_TABLES: [&str; 5] = ["users", "products", "orders", "order_items", "wide_records"];
+
+// Generator of the dataset being seeded, if any
+static SEEDED: Mutex<Option<StdRng>> = Mutex::new(None);
+
+// Draw the values of the data generated until the guard is dropped from `seed`, or at random
+// when there is none. Only the generation of the dataset is seeded, the data the tests generate
+// stays random.
+pub fn seed_dataset(seed: Option<u64>) -> SeededDataset {
+	*SEEDED.lock().unwrap() = seed.map(StdRng::seed_from_u64);
+	SeededDataset
+}
+
+// Ends the seeding of the dataset once dropped
+pub struct SeededDataset;
+
+impl Drop for SeededDataset {
+	fn drop(&mut self) {
+		if let Ok(mut seeded) = SEEDED.lock() {
+			*seeded = None;
+		}
+	}
+}
+
+// Random numbers of the generated data: from the seeded generator while a dataset is seeded, from
+// the thread's generator otherwise
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DataRng;
+
+impl DataRng {
+	fn with<T>(&mut self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+		match SEEDED.lock().unwrap().as_mut() {
+			Some(rng) => f(rng),
+			None => f(&mut rand::thread_rng()),
+		}
+	}
+}
+
+impl RngCore for DataRng {
+	fn next_u32(&mut self) -> u32 {
+		self.with(|rng| rng.next_u32())
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		self.with(|rng| rng.next_u64())
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		self.with(|rng| rng.fill_bytes(dest))
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+		self.with(|rng| rng.try_fill_bytes(dest))
+	}
+}
+
+// Time of the timestamps of the generated data: `FIXTURE_EPOCH` while a dataset is seeded
+pub fn data_now() -> DateTime<Utc> {
+	if SEEDED.lock().unwrap().is_some() {
+		Utc.timestamp_opt(FIXTURE_EPOCH, 0).unwrap()
+	} else {
+		Utc::now()
+	}
+}
@@ -0,0 +1,417 @@
+// `tonic::Status` is the error type of every RPC, however large it is
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use axum::http::StatusCode;
+use tokio_stream::{ wrappers::BroadcastStream, Stream, StreamExt };
+use tonic::{ transport::Server, Request, Response, Status };
+use tracing::{ info, error };
+use uuid::Uuid;
+
+use crate::{
+	benchmark::{ DatabaseBenchmark, TestTag },
+	codec::RowDecoding,
+	import::{ ImportColumn, ImportConfig },
+	models,
+	plan::{
+		BenchmarkPlan,
+		BenchmarkTest,
+		IndexMode,
+		KeyEncoding,
+		KeyStrategy,
+		NullRates,
+		PayloadProfile,
+		TextMode,
+		TimestampEncoding,
+		ValueDistribution,
+		ValueDistributions,
+	},
+	server::{ start_run, tokens_match, AppState },
+	trace::TraceConfig,
+	workloads::Workload,
+};
+
+pub mod proto {
+	tonic::include_proto!("databaserace");
+}
+
+use proto::benchmark_service_server::{ BenchmarkService, BenchmarkServiceServer };
+
+// gRPC control interface, sharing its state with the HTTP API
+pub struct BenchmarkGrpcService<T: DatabaseBenchmark + Send + Sync + 'static> {
+	state: Arc<AppState<T>>,
+}
+
+// Serve the gRPC interface until the server shuts down
+pub async fn run_grpc_server<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: Arc<AppState<T>>,
+	addr: SocketAddr
+) -> anyhow::Result<()> {
+	info!("gRPC server listening on {}", addr);
+	let mut shutdown = state.shutdown.subscribe();
+
+	Server::builder()
+		.add_service(BenchmarkServiceServer::new(BenchmarkGrpcService { state }))
+		.serve_with_shutdown(addr, async move {
+			shutdown.wait_for(|shutting_down| *shutting_down).await.ok();
+		}).await?;
+
+	Ok(())
+}
+
+#[tonic::async_trait]
+impl<T: DatabaseBenchmark + Send + Sync + 'static> BenchmarkService for BenchmarkGrpcService<T> {
+	async fn run_benchmarks(
+		&self,
+		request: Request<proto::RunRequest>
+	) -> Result<Response<proto::RunInfo>, Status> {
+		info!("gRPC RunBenchmarks");
+		self.check_api_token(&request)?;
+
+		let plan = BenchmarkPlan::try_from(request.into_inner()).map_err(Status::invalid_argument)?;
+		plan.validate().map_err(Status::invalid_argument)?;
+
+		let run = start_run(self.state.clone(), plan).await.map_err(|status| {
+			match status {
+				StatusCode::CONFLICT => Status::already_exists("Another run is executing"),
+				StatusCode::BAD_REQUEST => Status::invalid_argument("The plan writes to the connected database"),
+				StatusCode::SERVICE_UNAVAILABLE => Status::unavailable("The server is shutting down"),
+				_ => Status::internal("Failed to start the run"),
+			}
+		})?;
+
+		Ok(Response::new(run.into()))
+	}
+
+	async fn get_run_status(
+		&self,
+		request: Request<proto::GetRunStatusRequest>
+	) -> Result<Response<proto::RunInfo>, Status> {
+		let run_id = parse_run_id(&request.get_ref().run_id)?;
+		info!("gRPC GetRunStatus for {}", run_id);
+
+		let run = self.state.runs.lock().unwrap().get(&run_id).cloned();
+		run
+			.map(|run| Response::new(run.into()))
+			.ok_or_else(|| Status::not_found(format!("Unknown run {}", run_id)))
+	}
+
+	async fn get_results(
+		&self,
+		request: Request<proto::GetResultsRequest>
+	) -> Result<Response<proto::BenchmarkResults>, Status> {
+		info!("gRPC GetResults");
+		let results = match &request.get_ref().run_id {
+			Some(run_id) => {
+				let run_id = parse_run_id(run_id)?;
+				self.state.history.get(run_id).map_err(|e| {
+					error!("Failed to read run {} from history: {:?}", run_id, e);
+					Status::internal("Failed to read the run from history")
+				})?
+			}
+			None => self.state.results.lock().unwrap().clone(),
+		};
+
+		results
+			.map(|results| Response::new(results.into()))
+			.ok_or_else(|| Status::not_found("No results"))
+	}
+
+	type StreamProgressStream = Pin<
+		Box<dyn Stream<Item = Result<proto::ThroughputSample, Status>> + Send>
+	>;
+
+	async fn stream_progress(
+		&self,
+		_request: Request<proto::StreamProgressRequest>
+	) -> Result<Response<Self::StreamProgressStream>, Status> {
+		info!("gRPC StreamProgress");
+		let samples = BroadcastStream::new(self.state.throughput.subscribe())
+			// A slow client only misses samples, it keeps receiving the next ones
+			.filter_map(|sample| sample.ok())
+			.map(|sample| Ok(sample.into()));
+
+		Ok(Response::new(Box::pin(samples)))
+	}
+}
+
+impl<T: DatabaseBenchmark + Send + Sync + 'static> BenchmarkGrpcService<T> {
+	// Same bearer token check as the mutating HTTP endpoints, read from the request metadata
+	fn check_api_token<M>(&self, request: &Request<M>) -> Result<(), Status> {
+		let Some(expected) = &self.state.api_token else {
+			return Ok(());
+		};
+
+		let token = request
+			.metadata()
+			.get("authorization")
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.strip_prefix("Bearer "));
+
+		match token {
+			Some(token) if tokens_match(token, expected) => Ok(()),
+			_ => Err(Status::unauthenticated("Missing or invalid API token")),
+		}
+	}
+}
+
+fn parse_run_id(run_id: &str) -> Result<Uuid, Status> {
+	run_id.parse().map_err(|_| Status::invalid_argument(format!("Invalid run id {}", run_id)))
+}
+
+impl TryFrom<proto::RunRequest> for BenchmarkPlan {
+	type Error = String;
+
+	fn try_from(request: proto::RunRequest) -> Result<Self, Self::Error> {
+		let defaults = BenchmarkPlan::default();
+
+		let counts = request.counts
+			.into_iter()
+			.map(|(test, count)| Ok((test.parse::<BenchmarkTest>()?, count as usize)))
+			.collect::<Result<HashMap<_, _>, String>>()?;
+		let tests = request.tests
+			.iter()
+			.map(|test| test.parse::<BenchmarkTest>())
+			.collect::<Result<Vec<_>, String>>()?;
+		let tags = request.tags
+			.iter()
+			.map(|tag| tag.parse::<TestTag>())
+			.collect::<Result<Vec<_>, String>>()?;
+		let workloads = request.workloads
+			.iter()
+			.map(|workload| workload.parse::<Workload>())
+			.collect::<Result<Vec<_>, String>>()?;
+
+		Ok(BenchmarkPlan {
+			dataset_size: request.dataset_size.map_or(defaults.dataset_size, |size| size as usize),
+			counts,
+			tests,
+			tags,
+			cpu_count: request.cpu_count.map(|count| count as usize),
+			repetitions: request.repetitions.map_or(defaults.repetitions, |count| count as usize),
+			test_timeout_secs: request.test_timeout_secs,
+			run_timeout_secs: request.run_timeout_secs,
+			notify_url: request.notify_url,
+			indexes: request.indexes
+				.as_deref()
+				.map_or(Ok(IndexMode::default()), str::parse)?,
+			fresh_database: request.fresh_database.unwrap_or(false),
+			key_encoding: request.key_encoding
+				.as_deref()
+				.map_or(Ok(KeyEncoding::default()), str::parse)?,
+			key_strategy: request.key_strategy
+				.as_deref()
+				.map_or(Ok(KeyStrategy::default()), str::parse)?,
+			timestamp_encoding: request.timestamp_encoding
+				.as_deref()
+				.map_or(Ok(TimestampEncoding::default()), str::parse)?,
+			verify: request.verify.unwrap_or(false),
+			batch_size: request.batch_size.map(|size| size as usize),
+			batch_size_sweep: request.batch_size_sweep
+				.into_iter()
+				.map(|size| size as usize)
+				.collect(),
+			explain: request.explain.unwrap_or(false),
+			assert_indexes: request.assert_indexes.unwrap_or(false),
+			compare_cache: request.compare_cache.unwrap_or(false),
+			oplog: request.oplog.unwrap_or(false),
+			calibrate: request.calibrate.unwrap_or(false),
+			row_decoding: request.row_decoding
+				.as_deref()
+				.map_or(Ok(RowDecoding::default()), str::parse)?,
+			outlier_factor: request.outlier_factor.unwrap_or(defaults.outlier_factor),
+			stall_ms: request.stall_ms.unwrap_or(defaults.stall_ms),
+			distribution: request.distribution.as_deref().map(str::parse).transpose()?,
+			workloads,
+			workload_operations: request.workload_operations
+				.map_or(defaults.workload_operations, |count| count as usize),
+			trace: match (request.trace_mode, request.trace_name) {
+				(Some(mode), Some(name)) => Some(TraceConfig { mode: mode.parse()?, name }),
+				(None, None) => None,
+				_ => return Err("trace_mode and trace_name must be set together".to_string()),
+			},
+			analytics: request.analytics.unwrap_or(false),
+			import: request.import
+				.map(|import| {
+					let columns = import.columns
+						.into_iter()
+						.map(|column| {
+							Ok(ImportColumn {
+								name: column.name,
+								column_type: column.r#type.parse()?,
+								anonymize: column.anonymize,
+							})
+						})
+						.collect::<Result<Vec<_>, String>>()?;
+					Ok::<_, String>(ImportConfig { name: import.name, columns })
+				})
+				.transpose()?,
+			backup: request.backup.unwrap_or(false),
+			analytics_scale: request.analytics_scale
+				.map_or(defaults.analytics_scale, |scale| scale as usize),
+			payload: match request.payload {
+				Some(payload) => PayloadProfile {
+					name_len: payload.name_len as usize,
+					email_len: payload.email_len as usize,
+					description_len: payload.description_len as usize,
+					filler_len: payload.filler_len as usize,
+					text: payload.text.as_deref().map_or(Ok(TextMode::default()), str::parse)?,
+					null_rates: NullRates {
+						deleted_at: payload.deleted_at_null_rate.unwrap_or(defaults.payload.null_rates.deleted_at),
+						discount: payload.discount_null_rate.unwrap_or(defaults.payload.null_rates.discount),
+					},
+					email_domains: payload.email_domains
+						.map_or(defaults.payload.email_domains, |domains| domains as usize),
+					values: ValueDistributions {
+						price: payload.price_distribution
+							.as_deref()
+							.map_or(Ok(ValueDistribution::default()), str::parse)?,
+						stock: payload.stock_distribution
+							.as_deref()
+							.map_or(Ok(ValueDistribution::default()), str::parse)?,
+						quantity: payload.quantity_distribution
+							.as_deref()
+							.map_or(Ok(ValueDistribution::default()), str::parse)?,
+					},
+				},
+				None => defaults.payload,
+			},
+			tenants: request.tenants.map_or(defaults.tenants, |tenants| tenants as usize),
+			writers: request.writers.map_or(defaults.writers, |writers| writers as usize),
+			seed: request.seed,
+			fixtures: request.fixtures.unwrap_or(false),
+			prebuilt: request.prebuilt.unwrap_or(false),
+		})
+	}
+}
+
+impl From<models::RunInfo> for proto::RunInfo {
+	fn from(run: models::RunInfo) -> Self {
+		let (state, message, error) = match run.status {
+			models::RunStatus::Running => ("running", None, None),
+			models::RunStatus::Completed => ("completed", None, None),
+			models::RunStatus::Cancelled => ("cancelled", None, None),
+			models::RunStatus::Failed { message, error } => {
+				("failed", Some(message), error.map(|kind| kind.as_str().to_string()))
+			}
+		};
+
+		proto::RunInfo {
+			run_id: run.run_id.to_string(),
+			database: run.database,
+			state: state.to_string(),
+			message,
+			tests: run.tests,
+			started_at: run.started_at.to_rfc3339(),
+			finished_at: run.finished_at.map(|finished_at| finished_at.to_rfc3339()),
+			error,
+		}
+	}
+}
+
+impl From<models::BenchmarkResult> for proto::BenchmarkResult {
+	fn from(result: models::BenchmarkResult) -> Self {
+		proto::BenchmarkResult {
+			database: result.database,
+			test_name: result.test_name,
+			operations: result.operations as u64,
+			duration_ms: result.duration_ms,
+			duration_ns: result.duration_ns,
+			operations_per_second: result.operations_per_second,
+			cpu_count: result.cpu_count as u64,
+			timestamp: result.timestamp.to_rfc3339(),
+			status: result.status.as_str().to_string(),
+			error: result.status.message().map(str::to_string),
+			statement_cache_hits: result.statement_cache.map(|stats| stats.hits),
+			statement_cache_misses: result.statement_cache.map(|stats| stats.misses),
+			key_encoding: result.key_encoding.to_string(),
+			key_strategy: result.key_strategy.to_string(),
+			verified: result.verified,
+			retries: result.retries,
+			batch_size: result.batch_size.map(|size| size as u64),
+			bytes_written: result.bytes_written,
+			write_amplification: result.write_amplification,
+			query_plan: result.query_plan,
+			latency_p99_us: result.latency_p99_us,
+			slow_operations: result.outliers.map(|stats| stats.slow_operations),
+			stalls: result.outliers.map(|stats| stats.stalls),
+			longest_stall_ms: result.outliers.map(|stats| stats.longest_stall_ms),
+			batches: result.batches.map(|stats| stats.count),
+			mean_batch_size: result.batches.map(|stats| stats.mean_size),
+			max_batch_size: result.batches.map(|stats| stats.max_size),
+			batch_p50_us: result.batches.map(|stats| stats.latency_p50_us),
+			batch_p99_us: result.batches.map(|stats| stats.latency_p99_us),
+			batch_max_us: result.batches.map(|stats| stats.latency_max_us),
+			decode_ms: result.decode_ms,
+			writer_ops_per_second: result.writer_ops_per_second.unwrap_or_default(),
+			contention_reads: result.contention.map(|stats| stats.reads),
+			contention_writes: result.contention.map(|stats| stats.writes),
+			contention_failed_reads: result.contention.map(|stats| stats.failed_reads),
+			contention_failed_writes: result.contention.map(|stats| stats.failed_writes),
+			contention_error_rate: result.contention.map(|stats| stats.error_rate),
+			contention_blocked_ms: result.contention.map(|stats| stats.blocked_ms),
+			consistency_committed: result.consistency.map(|stats| stats.committed),
+			consistency_aborted: result.consistency.map(|stats| stats.aborted),
+			consistency_applied: result.consistency.map(|stats| stats.applied),
+			consistency_lost_updates: result.consistency.map(|stats| stats.lost_updates),
+			consistency_lost_update_rate: result.consistency.map(|stats| stats.lost_update_rate),
+			backup_bytes: result.backup_bytes,
+			all_columns_ops_per_second: result.all_columns_ops_per_second,
+			engine_stats: result.engine_stats.map(|stats| stats.to_string()),
+			selectivity: result.selectivity,
+			cache_state: result.cache_state.map(|state| state.to_string()),
+		}
+	}
+}
+
+impl From<models::BenchmarkResults> for proto::BenchmarkResults {
+	fn from(results: models::BenchmarkResults) -> Self {
+		proto::BenchmarkResults {
+			run_id: results.run_id.to_string(),
+			database: results.database,
+			results: results.results.into_iter().map(Into::into).collect(),
+			timestamp: results.timestamp.to_rfc3339(),
+			size_timeline: results.size_timeline.into_iter().map(Into::into).collect(),
+			calibration: results.calibration.map(Into::into),
+		}
+	}
+}
+
+impl From<models::CalibrationStats> for proto::CalibrationStats {
+	fn from(stats: models::CalibrationStats) -> Self {
+		proto::CalibrationStats {
+			operations: stats.operations as u64,
+			generate_user_ns: stats.generate_user_ns,
+			rfc3339_ns: stats.rfc3339_ns,
+			bincode_ns: stats.bincode_ns,
+			async_call_ns: stats.async_call_ns,
+			spawn_blocking_ns: stats.spawn_blocking_ns,
+			record_operation_ns: stats.record_operation_ns,
+		}
+	}
+}
+
+impl From<models::SizeSample> for proto::SizeSample {
+	fn from(sample: models::SizeSample) -> Self {
+		proto::SizeSample {
+			test_name: sample.test_name,
+			bytes: sample.bytes,
+			timestamp: sample.timestamp.to_rfc3339(),
+		}
+	}
+}
+
+impl From<models::ThroughputSample> for proto::ThroughputSample {
+	fn from(sample: models::ThroughputSample) -> Self {
+		proto::ThroughputSample {
+			database: sample.database,
+			test_name: sample.test_name,
+			operations: sample.operations,
+			operations_per_second: sample.operations_per_second,
+			timestamp: sample.timestamp.to_rfc3339(),
+		}
+	}
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+use anyhow::Result;
+use chrono::{ DateTime, Utc };
+use uuid::Uuid;
+
+use crate::models::{ BenchmarkResults, RunSummary };
+
+// On-disk store keeping every run as a JSON file named after its run id
+pub struct HistoryStore {
+	dir: PathBuf,
+}
+
+impl HistoryStore {
+	pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+		let dir = dir.as_ref().to_path_buf();
+
+		// Create the history directory if it doesn't exist
+		if !dir.exists() {
+			fs::create_dir_all(&dir)?;
+		}
+
+		Ok(Self { dir })
+	}
+
+	// Persist a run, overwriting any previous file for the same run id
+	pub fn save(&self, results: &BenchmarkResults) -> Result<()> {
+		let json = serde_json::to_string_pretty(results)?;
+
+		// Write to a temporary file first so a crash never leaves a truncated run behind
+		let tmp_path = self.dir.join(format!("{}.json.tmp", results.run_id));
+		fs::write(&tmp_path, json)?;
+		fs::rename(&tmp_path, self.run_path(results.run_id))?;
+
+		Ok(())
+	}
+
+	// Load a single run by id
+	pub fn get(&self, run_id: Uuid) -> Result<Option<BenchmarkResults>> {
+		let path = self.run_path(run_id);
+		if !path.exists() {
+			return Ok(None);
+		}
+
+		let json = fs::read_to_string(path)?;
+		Ok(Some(serde_json::from_str(&json)?))
+	}
+
+	// Load every stored run, oldest first
+	pub fn load_all(&self) -> Result<Vec<BenchmarkResults>> {
+		let mut runs = Vec::new();
+
+		for entry in fs::read_dir(&self.dir)? {
+			let path = entry?.path();
+			if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+				continue;
+			}
+
+			let json = fs::read_to_string(&path)?;
+			match serde_json::from_str::<BenchmarkResults>(&json) {
+				Ok(results) => runs.push(results),
+				Err(e) => tracing::warn!("Skipping unreadable history file {:?}: {}", path, e),
+			}
+		}
+
+		runs.sort_by_key(|run| run.timestamp);
+		Ok(runs)
+	}
+
+	// Summaries of the stored runs, oldest first, keeping only those that include the given test
+	// and were recorded at or after the given time
+	pub fn list(&self, test: Option<&str>, since: Option<DateTime<Utc>>) -> Result<Vec<RunSummary>> {
+		Ok(
+			self
+				.load_all()?
+				.iter()
+				.filter(|run| since.is_none_or(|since| run.timestamp >= since))
+				.filter(|run| {
+					test.is_none_or(|test| run.results.iter().any(|result| result.test_name == test))
+				})
+				.map(|run| run.summary())
+				.collect()
+		)
+	}
+
+	// The most recent run, if any
+	pub fn latest(&self) -> Result<Option<BenchmarkResults>> {
+		Ok(self.load_all()?.pop())
+	}
+
+	fn run_path(&self, run_id: Uuid) -> PathBuf {
+		self.dir.join(format!("{}.json", run_id))
+	}
+}
@@ -0,0 +1,243 @@
+// Imports of the user's own data: a CSV file uploaded with `PUT /imports/{name}`, whose columns
+// the plan maps onto the generic `Record` model by header name, typing each of them. Each backend
+// loads the records into a table or column family of its own, `imported_records`, then reads them
+// by id and updates a field of each, so engines race on the shape of real data rather than on the
+// generated users. The ids are generated, the CSV columns the plan doesn't list are left out and
+// empty cells are stored as NULL.
+//
+// The columns marked `anonymize` have their values replaced before anything is written, keeping
+// their shape: the same length and character classes for text, as many digits for integers, the
+// same magnitude for reals. A value is replaced the same way wherever it appears in its column, so
+// the column keeps its cardinality.
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+use anyhow::{ Context, Result };
+use rand::{ rngs::StdRng, Rng, SeedableRng };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+use crate::keys::new_key;
+use crate::models::{ Record, WideValue };
+use crate::plan::KeyStrategy;
+use crate::wide::column_name;
+
+// Table, or column family, holding the imported records
+pub const IMPORT_TABLE: &str = "imported_records";
+
+// Names of the results of the import: inserting the records, reading each by id and updating
+// the first column of each
+pub const IMPORT_RECORDS_RESULT: &str = "import_records";
+pub const IMPORT_READ_RESULT: &str = "import_read_by_id";
+pub const IMPORT_UPDATE_RESULT: &str = "import_update_field";
+
+pub const IMPORT_RESULTS: [&str; 3] = [IMPORT_RECORDS_RESULT, IMPORT_READ_RESULT, IMPORT_UPDATE_RESULT];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportColumnType {
+	Integer,
+	Real,
+	Text,
+	Boolean,
+}
+
+impl fmt::Display for ImportColumnType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			ImportColumnType::Integer => "integer",
+			ImportColumnType::Real => "real",
+			ImportColumnType::Text => "text",
+			ImportColumnType::Boolean => "boolean",
+		})
+	}
+}
+
+impl FromStr for ImportColumnType {
+	type Err = String;
+
+	fn from_str(column_type: &str) -> Result<Self, Self::Err> {
+		match column_type {
+			"integer" => Ok(ImportColumnType::Integer),
+			"real" => Ok(ImportColumnType::Real),
+			"text" => Ok(ImportColumnType::Text),
+			"boolean" => Ok(ImportColumnType::Boolean),
+			_ =>
+				Err(
+					format!("unknown column type: {}, expected integer, real, text or boolean", column_type)
+				),
+		}
+	}
+}
+
+// Column of the CSV file mapped onto a column of the records, named `c00`, `c01`… in the
+// database in the order of the plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportColumn {
+	// Header of the column in the CSV file
+	pub name: String,
+	#[serde(rename = "type")]
+	pub column_type: ImportColumnType,
+	// Replace the values of the column before they are written
+	#[serde(default)]
+	pub anonymize: bool,
+}
+
+// Import of a plan: the CSV file stored in the imports directory of the server under its name,
+// and its columns the records keep
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportConfig {
+	pub name: String,
+	pub columns: Vec<ImportColumn>,
+}
+
+impl ImportConfig {
+	// CSV file of the config in `dir`
+	pub fn at(&self, dir: &Path) -> Import {
+		Import { path: import_path(dir, &self.name), columns: self.columns.clone() }
+	}
+}
+
+// Import a run loads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+	pub path: PathBuf,
+	pub columns: Vec<ImportColumn>,
+}
+
+// File of the import `name` in `dir`
+pub fn import_path(dir: &Path, name: &str) -> PathBuf {
+	dir.join(format!("{}.csv", name))
+}
+
+// Headers of an uploaded CSV file, failing when it has none or a row can't be read
+pub fn check_csv(contents: &[u8]) -> Result<Vec<String>> {
+	let mut reader = csv::Reader::from_reader(contents);
+	let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+	anyhow::ensure!(headers.iter().any(|header| !header.is_empty()), "The CSV file has no header row");
+	for row in reader.records() {
+		row?;
+	}
+	Ok(headers)
+}
+
+// Records of the CSV file of `import`, with ids picked by `keys`
+pub fn load(import: &Import, keys: KeyStrategy) -> Result<Vec<Record>> {
+	let mut reader = csv::Reader::from_path(&import.path)
+		.with_context(|| format!("Could not open the import {}", import.path.display()))?;
+	let headers = reader.headers()?.clone();
+	let positions = import.columns
+		.iter()
+		.map(|column| {
+			headers
+				.iter()
+				.position(|header| header == column.name)
+				.with_context(|| format!("The CSV file has no {} column", column.name))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	let mut records = Vec::new();
+	for (line, row) in reader.records().enumerate() {
+		let row = row?;
+		let values = import.columns
+			.iter()
+			.zip(&positions)
+			.enumerate()
+			.map(|(index, (column, &position))| {
+				let cell = row.get(position).unwrap_or("").trim();
+				parse_value(index, column, cell).with_context(|| {
+					// The header is the first line
+					format!("Invalid {} value {:?} on line {} of the CSV file", column.column_type, cell, line + 2)
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+		records.push(Record { id: new_key(keys), values });
+	}
+	Ok(records)
+}
+
+// Value of the cell of the column at `index`, anonymized when the column is; None when it is empty
+fn parse_value(index: usize, column: &ImportColumn, cell: &str) -> Result<Option<WideValue>> {
+	if cell.is_empty() {
+		return Ok(None);
+	}
+	let value = match column.column_type {
+		ImportColumnType::Integer => WideValue::Integer(cell.parse()?),
+		ImportColumnType::Real => WideValue::Real(cell.parse()?),
+		ImportColumnType::Text => WideValue::Text(cell.to_string()),
+		ImportColumnType::Boolean =>
+			WideValue::Boolean(match cell.to_ascii_lowercase().as_str() {
+				"true" | "t" | "yes" | "1" => true,
+				"false" | "f" | "no" | "0" => false,
+				_ => anyhow::bail!("expected true or false"),
+			}),
+	};
+	Ok(Some(if column.anonymize { anonymize(index, cell, value) } else { value }))
+}
+
+// Value replacing `value`, drawn from a generator seeded by its column and cell so the same value
+// always gets the same replacement
+fn anonymize(index: usize, cell: &str, value: WideValue) -> WideValue {
+	let mut hasher = DefaultHasher::new();
+	(index, cell).hash(&mut hasher);
+	let mut rng = StdRng::seed_from_u64(hasher.finish());
+
+	match value {
+		WideValue::Integer(value) => {
+			let digits = value.unsigned_abs().to_string().len() as u32;
+			let low = if digits > 1 { 10u128.pow(digits - 1) } else { 0 };
+			let replaced = i64::try_from(rng.gen_range(low..10u128.pow(digits))).unwrap_or(i64::MAX);
+			WideValue::Integer(if value < 0 { -replaced } else { replaced })
+		}
+		WideValue::Real(value) if value.is_finite() => WideValue::Real(value * rng.gen_range(0.5..2.0)),
+		WideValue::Real(value) => WideValue::Real(value),
+		WideValue::Text(text) => {
+			let text = text
+				.chars()
+				.map(|c| match c {
+					'a'..='z' => rng.gen_range(b'a'..=b'z') as char,
+					'A'..='Z' => rng.gen_range(b'A'..=b'Z') as char,
+					'0'..='9' => rng.gen_range(b'0'..=b'9') as char,
+					c if c.is_alphanumeric() => rng.gen_range(b'a'..=b'z') as char,
+					c => c,
+				})
+				.collect();
+			WideValue::Text(text)
+		}
+		WideValue::Boolean(_) => WideValue::Boolean(rng.gen_bool(0.5)),
+	}
+}
+
+// CREATE TABLE statement of the imported records, `sql_type` naming the type of each column.
+// Empty cells are NULL, so no column is NOT NULL
+pub fn create_table_query(
+	id_type: &str,
+	columns: &[ImportColumn],
+	sql_type: impl Fn(ImportColumnType) -> &'static str
+) -> String {
+	let columns: Vec<String> = columns
+		.iter()
+		.enumerate()
+		.map(|(index, column)| format!("{} {}", column_name(index), sql_type(column.column_type)))
+		.collect();
+	format!("CREATE TABLE {} (id {} PRIMARY KEY, {})", IMPORT_TABLE, id_type, columns.join(", "))
+}
+
+pub fn insert_query(columns: usize) -> String {
+	format!(
+		"INSERT INTO {} (id, {}) VALUES (?{})",
+		IMPORT_TABLE,
+		(0..columns).map(column_name).collect::<Vec<_>>().join(", "),
+		", ?".repeat(columns)
+	)
+}
+
+pub fn update_query(column: usize) -> String {
+	format!("UPDATE {} SET {} = ? WHERE id = ?", IMPORT_TABLE, column_name(column))
+}
+
+pub const READ_IMPORTED_RECORD_QUERY: &str = "SELECT * FROM imported_records WHERE id = ?";
+
+pub const DROP_IMPORT_TABLE_QUERY: &str = "DROP TABLE IF EXISTS imported_records";
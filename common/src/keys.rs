@@ -0,0 +1,50 @@
+// Ids of the generated records, picked following the plan's key strategy. Random UUIDs land
+// anywhere in a B-tree or LSM key space, while time-ordered and sequential keys are appended at
+// its end. Every strategy yields a 128-bit value, stored like any other id.
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::sync::{ LazyLock, Mutex };
+use chrono::Utc;
+use rand::Rng;
+use uuid::{ Builder, Uuid };
+
+use crate::fixtures::DataRng;
+use crate::plan::KeyStrategy;
+
+// Next sequential key. It starts at the current time in microseconds rather than 1, so the keys
+// generated after a restart don't collide with the ones a previous server process stored.
+static NEXT_SEQUENTIAL: LazyLock<AtomicU64> = LazyLock::new(|| {
+	AtomicU64::new(Utc::now().timestamp_micros() as u64)
+});
+
+// Last ULID generated, to keep them increasing within a millisecond
+static LAST_ULID: Mutex<u128> = Mutex::new(0);
+
+// ULID random part: the 80 bits after the 48-bit millisecond timestamp
+const ULID_RANDOM_BITS: u32 = 80;
+
+pub fn new_key(strategy: KeyStrategy) -> Uuid {
+	match strategy {
+		// Drawn like the other generated values, so a seeded dataset has the same ids
+		KeyStrategy::UuidV4 => Builder::from_random_bytes(DataRng.gen()).into_uuid(),
+		KeyStrategy::UuidV7 => Uuid::now_v7(),
+		KeyStrategy::Ulid => new_ulid(),
+		KeyStrategy::Sequential => {
+			Uuid::from_u128(NEXT_SEQUENTIAL.fetch_add(1, Ordering::Relaxed) as u128)
+		}
+	}
+}
+
+// A monotonic ULID: the ULIDs of the same millisecond increment the last one instead of drawing
+// a new random part, as the spec recommends
+fn new_ulid() -> Uuid {
+	let millis = (Utc::now().timestamp_millis() as u128) & ((1 << 48) - 1);
+	let random = rand::random::<u128>() & ((1 << ULID_RANDOM_BITS) - 1);
+	let mut ulid = (millis << ULID_RANDOM_BITS) | random;
+
+	let mut last = LAST_ULID.lock().unwrap();
+	if ulid >> ULID_RANDOM_BITS <= *last >> ULID_RANDOM_BITS {
+		ulid = *last + 1;
+	}
+	*last = ulid;
+	Uuid::from_u128(ulid)
+}
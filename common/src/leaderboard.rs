@@ -0,0 +1,175 @@
+use std::fmt::Write;
+
+use crate::models::{ BenchmarkResult, BenchmarkResults, Scores, TestStatus };
+use crate::report::{ database_names, find_measured_result, find_result, geometric_mean, test_names };
+
+// GitHub-flavored Markdown leaderboard of one or more result sets: the standings of the databases,
+// the winner of every test with how many times slower the others were, and the runs the numbers
+// come from. A test is won by the highest throughput; failed results don't compete.
+pub fn render_markdown_leaderboard(runs: &[BenchmarkResults]) -> String {
+	let databases = database_names(runs);
+	let tests = test_names(runs);
+
+	let mut markdown = String::from("# Database Race Leaderboard\n\n");
+	if runs.is_empty() {
+		markdown.push_str("No benchmark results available yet.\n");
+		return markdown;
+	}
+
+	// Throughput of the winner of each test, and the factor of each database against it
+	let mut wins = vec![0usize; databases.len()];
+	let mut factors: Vec<Vec<f64>> = vec![Vec::new(); databases.len()];
+	let mut rows = Vec::new();
+	for test in &tests {
+		let winner = databases
+			.iter()
+			.enumerate()
+			.filter_map(|(i, database)| find_measured_result(runs, database, test).map(|result| (i, result)))
+			.filter(|(_, result)| result.operations_per_second > 0.0)
+			.max_by(|(_, a), (_, b)| a.operations_per_second.total_cmp(&b.operations_per_second));
+
+		let mut row = vec![
+			escape(test),
+			winner.map_or(String::new(), |(i, _)| format!("**{}**", escape(&databases[i]))),
+		];
+		for (i, database) in databases.iter().enumerate() {
+			let cell = match (find_result(runs, database, test), winner) {
+				(None, _) => String::new(),
+				(Some(result), _) if !result.status.is_measured() => {
+					result.status.as_str().to_string()
+				}
+				(Some(result), Some((winner, best))) if result.operations_per_second > 0.0 => {
+					let factor = best.operations_per_second / result.operations_per_second;
+					factors[i].push(factor);
+					if i == winner {
+						wins[i] += 1;
+						format!("**{}**", throughput(result))
+					} else {
+						format!("{} ({:.2}× slower)", throughput(result), factor)
+					}
+				}
+				(Some(result), _) => throughput(result),
+			};
+			row.push(cell);
+		}
+		rows.push(row);
+	}
+
+	// Standings: most wins first, then the smallest mean factor against the winners
+	let mut standings: Vec<(usize, Option<f64>)> = (0..databases.len())
+		.map(|i| (i, geometric_mean(&factors[i])))
+		.collect();
+	standings.sort_by(|(a, a_factor), (b, b_factor)| {
+		wins[*b]
+			.cmp(&wins[*a])
+			.then(a_factor.unwrap_or(f64::INFINITY).total_cmp(&b_factor.unwrap_or(f64::INFINITY)))
+	});
+
+	markdown.push_str("## Standings\n\n");
+	markdown.push_str(
+		"Mean factor is the geometric mean of how many times slower than the winner a database was, over the tests it completed.\n\n"
+	);
+	table_header(&mut markdown, &["Rank", "Database", "Wins", "Tests", "Mean factor"], 2);
+	for (rank, (i, factor)) in standings.iter().enumerate() {
+		let _ = writeln!(
+			markdown,
+			"| {} | {} | {} | {} | {} |",
+			rank + 1,
+			escape(&databases[*i]),
+			wins[*i],
+			factors[*i].len(),
+			factor.map_or(String::new(), |factor| format!("{:.2}×", factor))
+		);
+	}
+
+	// Scores of the latest run of each database, when the race scored them
+	let scores: Vec<(&String, &Scores)> = databases
+		.iter()
+		.filter_map(|database| {
+			runs.iter()
+				.rev()
+				.find(|run| &run.database == database)
+				.and_then(|run| run.scores.as_ref())
+				.map(|scores| (database, scores))
+		})
+		.collect();
+	// Runs read from several races may have been scored against different baselines, so each row names its own
+	if !scores.is_empty() {
+		markdown.push_str(
+			"\n## Scores\n\nGeometric mean of the throughput of each test against the baseline, which scores 100, over the tests both completed.\n\n"
+		);
+		table_header(
+			&mut markdown,
+			&["Database", "Baseline", "Overall", "Read", "Write", "Update", "Analytics", "Tests"],
+			2
+		);
+		for (database, scores) in &scores {
+			let _ = writeln!(
+				markdown,
+				"| {} | {} | {} | {} | {} | {} | {} | {} |",
+				escape(database),
+				escape(&scores.baseline),
+				score(scores.overall),
+				score(scores.read),
+				score(scores.write),
+				score(scores.update),
+				score(scores.analytics),
+				scores.tests
+			);
+		}
+	}
+
+	markdown.push_str("\n## Tests\n\nThroughput in operations per second, the winner of each test in bold.\n\n");
+	let mut header = vec!["Test".to_string(), "Winner".to_string()];
+	header.extend(databases.iter().map(|database| escape(database)));
+	let header: Vec<&str> = header.iter().map(String::as_str).collect();
+	table_header(&mut markdown, &header, 2);
+	for row in rows {
+		let _ = writeln!(markdown, "| {} |", row.join(" | "));
+	}
+
+	markdown.push_str("\n## Environment\n\n");
+	table_header(&mut markdown, &["Database", "Run", "Started", "CPUs", "Tests"], 3);
+	for run in runs {
+		let mut cpus: Vec<usize> = run.results.iter().map(|result| result.cpu_count).collect();
+		cpus.sort_unstable();
+		cpus.dedup();
+		let _ = writeln!(
+			markdown,
+			"| {} | `{}` | {} | {} | {} |",
+			escape(&run.database),
+			run.run_id,
+			run.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+			cpus.iter().map(|cpus| cpus.to_string()).collect::<Vec<_>>().join(", "),
+			run.results.len()
+		);
+	}
+
+	markdown
+}
+
+// Header row and alignment row of a table, the columns from `right` on aligned to the right
+fn table_header(markdown: &mut String, columns: &[&str], right: usize) {
+	let _ = writeln!(markdown, "| {} |", columns.join(" | "));
+	let alignments: Vec<&str> = (0..columns.len())
+		.map(|i| if i >= right { "---:" } else { "---" })
+		.collect();
+	let _ = writeln!(markdown, "| {} |", alignments.join(" | "));
+}
+
+fn score(score: Option<f64>) -> String {
+	score.map_or(String::new(), |score| format!("{:.1}", score))
+}
+
+fn throughput(result: &BenchmarkResult) -> String {
+	let mut throughput = format!("{:.0}", result.operations_per_second);
+	if result.status == TestStatus::TimedOut {
+		throughput.push_str(" (timed out)");
+	}
+	throughput
+}
+
+// Keep pipes and line breaks of names from breaking the table
+fn escape(text: &str) -> String {
+	text.replace('|', "\\|").replace('\n', " ")
+}
@@ -1,3 +1,44 @@
 pub mod models;
 pub mod benchmark;
+pub mod case;
 pub mod server;
+pub mod report;
+pub mod leaderboard;
+pub mod history;
+pub mod checkpoint;
+pub mod metastore;
+pub mod plan;
+pub mod plan_file;
+pub mod distribution;
+pub mod workloads;
+pub mod trace;
+pub mod oplog;
+pub mod outliers;
+pub mod calibration;
+pub mod codec;
+pub mod fixtures;
+pub mod analytics;
+pub mod backup;
+pub mod wide;
+pub mod tenants;
+pub mod writers;
+pub mod contention;
+pub mod consistency;
+pub mod projection;
+pub mod search;
+pub mod connect;
+pub mod import;
+pub mod keys;
+pub mod text;
+pub mod nullable;
+pub mod context;
+pub mod config;
+pub mod registry;
+pub mod schedule;
+pub mod logging;
+pub mod grpc;
+pub mod statement_cache;
+pub mod retry;
+pub mod error;
+pub mod testsuite;
+pub mod mock;
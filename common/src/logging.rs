@@ -0,0 +1,52 @@
+use std::fmt;
+use std::str::FromStr;
+use tracing_subscriber::{ fmt::format::FmtSpan, EnvFilter };
+
+// Logs of the benchmark servers, filtered by `RUST_LOG` (`info` by default). Each phase of a run
+// is a span: `run` with its id, `init`, `seed` with the records generated, and `test` with the
+// name of the test, workload or analytical query, its count, and once over its operations and duration. A span logs
+// its fields and its busy and idle times when it closes.
+
+// Level of the logs when `RUST_LOG` is unset
+const DEFAULT_FILTER: &str = "info";
+
+// How log lines are written: human-readable text, or one JSON object per line carrying the
+// fields of the event and of the spans it happened in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+	#[default]
+	Text,
+	Json,
+}
+
+impl fmt::Display for LogFormat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			LogFormat::Text => "text",
+			LogFormat::Json => "json",
+		})
+	}
+}
+
+impl FromStr for LogFormat {
+	type Err = String;
+
+	fn from_str(format: &str) -> Result<Self, Self::Err> {
+		match format {
+			"text" => Ok(LogFormat::Text),
+			"json" => Ok(LogFormat::Json),
+			_ => Err(format!("unknown log format: {}, expected text or json", format)),
+		}
+	}
+}
+
+// Install the global subscriber writing the logs in `format`
+pub fn init(format: LogFormat) {
+	let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+	let builder = tracing_subscriber::fmt().with_env_filter(filter).with_span_events(FmtSpan::CLOSE);
+
+	match format {
+		LogFormat::Text => builder.init(),
+		LogFormat::Json => builder.json().init(),
+	}
+}
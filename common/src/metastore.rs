@@ -0,0 +1,253 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{ Context, Result };
+use chrono::{ DateTime, Utc };
+use rusqlite::{ params, Connection, OptionalExtension };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::BenchmarkResults;
+
+// SQLite database keeping every stored run with one row per result, so the results of a test can
+// be followed across runs: its throughput over the last 30 runs of DuckDB, say. The runs of the
+// JSON history are imported when the store is opened, and each new run is added once stored.
+
+// File of the store, in the data directory of a server or the results directory of the race runner
+pub const METASTORE_FILE: &str = "results.sqlite";
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS runs (
+		run_id TEXT PRIMARY KEY,
+		database TEXT NOT NULL,
+		timestamp INTEGER NOT NULL,
+		results TEXT NOT NULL
+	);
+	CREATE TABLE IF NOT EXISTS results (
+		run_id TEXT NOT NULL REFERENCES runs (run_id) ON DELETE CASCADE,
+		position INTEGER NOT NULL,
+		database TEXT NOT NULL,
+		test_name TEXT NOT NULL,
+		status TEXT NOT NULL,
+		operations INTEGER NOT NULL,
+		duration_ms INTEGER NOT NULL,
+		operations_per_second REAL NOT NULL,
+		latency_p99_us REAL,
+		batch_size INTEGER,
+		cpu_count INTEGER NOT NULL,
+		timestamp INTEGER NOT NULL,
+		PRIMARY KEY (run_id, position)
+	);
+	CREATE INDEX IF NOT EXISTS idx_results_test ON results (test_name, database);
+";
+
+// Measured results of a test in the last runs that ran it, oldest first. Failed results have no
+// measurement and are left out.
+const TREND_QUERY: &str = "
+	SELECT results.run_id, results.database, runs.timestamp, results.batch_size, results.status, results.{metric}
+	FROM results
+	JOIN runs ON runs.run_id = results.run_id
+	WHERE results.test_name = ?1 AND results.status NOT IN ('failed', 'skipped') AND results.run_id IN (
+		SELECT runs.run_id
+		FROM runs
+		WHERE runs.timestamp >= ?3 AND EXISTS (
+			SELECT 1 FROM results
+			WHERE results.run_id = runs.run_id
+				AND results.test_name = ?1
+				AND (?2 IS NULL OR lower(results.database) = lower(?2))
+		)
+		ORDER BY runs.timestamp DESC
+		LIMIT ?4
+	)
+	ORDER BY runs.timestamp, results.position
+";
+
+// Value of a result followed by a trend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendMetric {
+	#[default]
+	OperationsPerSecond,
+	LatencyP99Us,
+	DurationMs,
+	Operations,
+}
+
+impl TrendMetric {
+	pub fn name(&self) -> &'static str {
+		match self {
+			TrendMetric::OperationsPerSecond => "operations_per_second",
+			TrendMetric::LatencyP99Us => "latency_p99_us",
+			TrendMetric::DurationMs => "duration_ms",
+			TrendMetric::Operations => "operations",
+		}
+	}
+}
+
+impl fmt::Display for TrendMetric {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for TrendMetric {
+	type Err = String;
+
+	fn from_str(metric: &str) -> Result<Self, Self::Err> {
+		[
+			TrendMetric::OperationsPerSecond,
+			TrendMetric::LatencyP99Us,
+			TrendMetric::DurationMs,
+			TrendMetric::Operations,
+		]
+			.into_iter()
+			.find(|candidate| candidate.name() == metric)
+			.ok_or_else(|| {
+				format!(
+					"unknown metric: {}, expected operations_per_second, latency_p99_us, duration_ms or operations",
+					metric
+				)
+			})
+	}
+}
+
+// Results of a test to follow across runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrendQuery {
+	pub test: String,
+	// Only the runs of this database, ignoring case, when set
+	pub database: Option<String>,
+	pub metric: TrendMetric,
+	// Number of the most recent runs to include
+	pub last: usize,
+	// Only the runs recorded at or after this time, when set
+	pub since: Option<DateTime<Utc>>,
+}
+
+// Result of a test in a run; the value is absent when the result didn't measure the metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TrendPoint {
+	pub run_id: Uuid,
+	pub database: String,
+	pub timestamp: DateTime<Utc>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub batch_size: Option<usize>,
+	pub status: String,
+	pub value: Option<f64>,
+}
+
+pub struct MetaStore {
+	conn: Connection,
+}
+
+impl MetaStore {
+	pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref();
+		if let Some(dir) = path.parent() {
+			std::fs::create_dir_all(dir)?;
+		}
+		let conn = Connection::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+		conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+		conn.execute_batch(SCHEMA)?;
+
+		Ok(Self { conn })
+	}
+
+	// Store a run, replacing any previous one with the same id
+	pub fn insert(&mut self, run: &BenchmarkResults) -> Result<()> {
+		let tx = self.conn.transaction()?;
+		tx.execute("DELETE FROM runs WHERE run_id = ?1", params![run.run_id.to_string()])?;
+		tx.execute(
+			"INSERT INTO runs (run_id, database, timestamp, results) VALUES (?1, ?2, ?3, ?4)",
+			params![
+				run.run_id.to_string(),
+				run.database,
+				run.timestamp.timestamp_micros(),
+				serde_json::to_string(run)?
+			]
+		)?;
+
+		{
+			let mut insert_result = tx.prepare(
+				"INSERT INTO results (
+					run_id, position, database, test_name, status, operations, duration_ms,
+					operations_per_second, latency_p99_us, batch_size, cpu_count, timestamp
+				) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+			)?;
+			for (position, result) in run.results.iter().enumerate() {
+				insert_result.execute(
+					params![
+						run.run_id.to_string(),
+						position,
+						result.database,
+						result.test_name,
+						result.status.as_str(),
+						result.operations,
+						result.duration_ms,
+						result.operations_per_second,
+						result.latency_p99_us,
+						result.batch_size,
+						result.cpu_count,
+						result.timestamp.timestamp_micros()
+					]
+				)?;
+			}
+		}
+
+		tx.commit()?;
+		Ok(())
+	}
+
+	// Store the runs that aren't stored yet, returning how many were added
+	pub fn import(&mut self, runs: &[BenchmarkResults]) -> Result<usize> {
+		let mut imported = 0;
+		for run in runs {
+			if !self.contains(run.run_id)? {
+				self.insert(run)?;
+				imported += 1;
+			}
+		}
+		Ok(imported)
+	}
+
+	pub fn contains(&self, run_id: Uuid) -> Result<bool> {
+		Ok(
+			self.conn
+				.query_row("SELECT 1 FROM runs WHERE run_id = ?1", params![run_id.to_string()], |_| Ok(()))
+				.optional()?
+				.is_some()
+		)
+	}
+
+	// Results of the test of `query` in the last runs that ran it, oldest first
+	pub fn trend(&self, query: &TrendQuery) -> Result<Vec<TrendPoint>> {
+		// The metric is one of the columns of `TrendMetric`, never text from the request
+		let sql = TREND_QUERY.replace("{metric}", query.metric.name());
+		let since = query.since.map_or(i64::MIN, |since| since.timestamp_micros());
+
+		let mut statement = self.conn.prepare(&sql)?;
+		let rows = statement.query_map(
+			params![query.test, query.database, since, query.last as i64],
+			|row| {
+				let run_id: String = row.get(0)?;
+				let timestamp: i64 = row.get(2)?;
+				Ok((run_id, row.get(1)?, timestamp, row.get(3)?, row.get(4)?, row.get(5)?))
+			}
+		)?;
+
+		let mut points = Vec::new();
+		for row in rows {
+			let (run_id, database, timestamp, batch_size, status, value) = row?;
+			points.push(TrendPoint {
+				run_id: run_id.parse()?,
+				database,
+				timestamp: DateTime::from_timestamp_micros(timestamp).context("Invalid run timestamp")?,
+				batch_size,
+				status,
+				value,
+			});
+		}
+		Ok(points)
+	}
+}
@@ -0,0 +1,502 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+use async_trait::async_trait;
+use anyhow::{ bail, Result };
+use uuid::Uuid;
+
+use crate::benchmark::DatabaseBenchmark;
+use crate::context::RunContext;
+use crate::import::ImportColumn;
+use crate::models::{
+	BackendCapabilities,
+	BenchmarkResult,
+	Capabilities,
+	ConsistencyStats,
+	ContentionStats,
+	Record,
+	RecordCounts,
+	TestStatus,
+	WideValue,
+};
+use crate::plan::{ BenchmarkTest, IndexMode, KeyEncoding, TimestampEncoding };
+use crate::trace::{ TraceChunk, TraceOp };
+use crate::writers::writer_ranges;
+
+// Time the mock reports for each operation, so results are the same on every machine
+const MOCK_OPERATION_MS: u64 = 1;
+
+// Size the mock reports for each record it holds
+const MOCK_RECORD_BYTES: u64 = 100;
+
+// Index the plan of the mock's reads by id goes through
+const MOCK_ID_INDEX: &str = "mock_users_id";
+
+// In-memory benchmark returning deterministic results without a database engine, so the server
+// can be tested on its own. Only the record counts are kept, to support cleanup and verification.
+pub struct MockBenchmark {
+	context: RunContext,
+	counts: Mutex<RecordCounts>,
+	// Records of the import table, by id
+	imported: Mutex<HashMap<Uuid, Record>>,
+	cpu_count: usize,
+	// Wall-clock time each test runs for, checking for cancellation along the way
+	test_duration: Duration,
+	// Test failing with an engine error
+	failing_test: Option<BenchmarkTest>,
+	// Whether init, cleanup and data generation fail
+	failing_setup: bool,
+	// Whether the reads by id scan the users instead of using their index
+	missing_index: bool,
+	// Whether it stands for an existing database attached with `--connect`
+	connected: bool,
+	// Features it declares, every one unless a test takes some away
+	capabilities: BackendCapabilities,
+}
+
+impl MockBenchmark {
+	pub fn new() -> Self {
+		MockBenchmark {
+			context: RunContext::default(),
+			counts: Mutex::new(RecordCounts::default()),
+			imported: Mutex::new(HashMap::new()),
+			cpu_count: 1,
+			test_duration: Duration::ZERO,
+			failing_test: None,
+			failing_setup: false,
+			missing_index: false,
+			connected: false,
+			capabilities: BackendCapabilities::ALL,
+		}
+	}
+
+	// Keep each test running for `duration`, long enough to cancel or time out a run
+	pub fn with_test_duration(mut self, duration: Duration) -> Self {
+		self.test_duration = duration;
+		self
+	}
+
+	// Make `test` fail every time it runs
+	pub fn with_failing_test(mut self, test: BenchmarkTest) -> Self {
+		self.failing_test = Some(test);
+		self
+	}
+
+	// Declare `capabilities`, so the tests needing the features it lacks are skipped
+	pub fn with_capabilities(mut self, capabilities: BackendCapabilities) -> Self {
+		self.capabilities = capabilities;
+		self
+	}
+
+	// Make init, cleanup and data generation fail
+	pub fn with_failing_setup(mut self) -> Self {
+		self.failing_setup = true;
+		self
+	}
+
+	// Make the plan of the reads by id scan the users
+	pub fn with_missing_index(mut self) -> Self {
+		self.missing_index = true;
+		self
+	}
+
+	// Stand for an existing database of `records` records in each table, which the runs must
+	// leave unchanged
+	pub fn with_connected_database(mut self, records: usize) -> Self {
+		self.connected = true;
+		*self.counts.get_mut().unwrap() = RecordCounts {
+			users: records,
+			products: records,
+			orders: records,
+			order_items: records,
+			wide_records: records,
+		};
+		self
+	}
+
+	fn check_setup(&self) -> Result<()> {
+		if self.failing_setup {
+			bail!("mock setup failure");
+		}
+		Ok(())
+	}
+
+	async fn execute(&self, test: BenchmarkTest, count: usize) -> Result<BenchmarkResult> {
+		// Sleep in small steps so a cancelled or aborted test stops quickly
+		let step = Duration::from_millis(10);
+		let mut elapsed = Duration::ZERO;
+		while elapsed < self.test_duration {
+			self.context.check_cancelled()?;
+			tokio::time::sleep(step).await;
+			elapsed += step;
+		}
+		self.context.check_cancelled()?;
+
+		if self.failing_test == Some(test) {
+			bail!("mock failure of {}", test);
+		}
+
+		match test {
+			BenchmarkTest::InsertSingleManyTimes |
+			BenchmarkTest::InsertManyAtOnce |
+			BenchmarkTest::ConcurrentInserts => {
+				self.counts.lock().unwrap().users += count;
+			}
+			// Orders of a single item
+			BenchmarkTest::InsertOrdersWithItems => {
+				let mut counts = self.counts.lock().unwrap();
+				counts.orders += count;
+				counts.order_items += count;
+			}
+			BenchmarkTest::InsertWideRecords => {
+				self.counts.lock().unwrap().wide_records += count;
+			}
+			_ => {}
+		}
+		// The batched tests read or write all their records in a single batch
+		match test {
+			BenchmarkTest::ReadManyByIds | BenchmarkTest::InsertManyAtOnce => self.context.record_batch(count, elapsed),
+			_ => self.context.record_operations(count as u64),
+		}
+
+		let duration = Duration::from_millis((count as u64) * MOCK_OPERATION_MS);
+		Ok(BenchmarkResult {
+			operations: count,
+			operations_per_second: 1000.0 / (MOCK_OPERATION_MS as f64),
+			..BenchmarkResult::new(&self.database_name(), test.name(), self.cpu_count, duration, TestStatus::Passed)
+		})
+	}
+}
+
+impl Default for MockBenchmark {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait]
+impl DatabaseBenchmark for MockBenchmark {
+	// The mock has no data to prepare
+	type Fixture = ();
+
+	async fn init(&self) -> Result<()> {
+		self.check_setup()
+	}
+
+	async fn generate_test_data(&self, count: usize) -> Result<()> {
+		self.check_setup()?;
+		let mut counts = self.counts.lock().unwrap();
+		counts.users += count;
+		counts.products += count;
+		counts.orders += count;
+		counts.order_items += count;
+		counts.wide_records += count;
+		Ok(())
+	}
+
+	async fn cleanup(&self) -> Result<()> {
+		self.check_setup()?;
+		*self.counts.lock().unwrap() = RecordCounts::default();
+		Ok(())
+	}
+
+	async fn reset(&mut self) -> Result<()> {
+		self.cleanup().await
+	}
+
+	// The snapshot of the mock is its record counts
+	async fn snapshot(&self, dir: &Path) -> Result<()> {
+		fs::create_dir_all(dir)?;
+		let counts = *self.counts.lock().unwrap();
+		fs::write(dir.join("mock.json"), serde_json::to_string(&counts)?)?;
+		Ok(())
+	}
+
+	async fn restore(&self, dir: &Path) -> Result<()> {
+		let counts = serde_json::from_str(&fs::read_to_string(dir.join("mock.json"))?)?;
+		*self.counts.lock().unwrap() = counts;
+		Ok(())
+	}
+
+	// The backup is a snapshot under another name
+	async fn backup(&self, dir: &Path) -> Result<()> {
+		self.snapshot(dir).await
+	}
+
+	async fn restore_backup(&self, dir: &Path) -> Result<()> {
+		self.restore(dir).await
+	}
+
+	async fn create_import_table(&self, _columns: &[ImportColumn]) -> Result<()> {
+		self.imported.lock().unwrap().clear();
+		Ok(())
+	}
+
+	async fn insert_imported_records(&self, records: Arc<Vec<Record>>) -> Result<()> {
+		let mut imported = self.imported.lock().unwrap();
+		for record in records.iter() {
+			imported.insert(record.id, record.clone());
+		}
+		self.context.record_operations(records.len() as u64);
+		Ok(())
+	}
+
+	async fn read_imported_records(&self, ids: Arc<Vec<Uuid>>) -> Result<()> {
+		let imported = self.imported.lock().unwrap();
+		if let Some(id) = ids.iter().find(|id| !imported.contains_key(id)) {
+			bail!("No imported record {}", id);
+		}
+		self.context.record_operations(ids.len() as u64);
+		Ok(())
+	}
+
+	async fn update_imported_records(
+		&self,
+		column: usize,
+		updates: Arc<Vec<(Uuid, Option<WideValue>)>>
+	) -> Result<()> {
+		let mut imported = self.imported.lock().unwrap();
+		for (id, value) in updates.iter() {
+			match imported.get_mut(id) {
+				Some(record) => record.values[column] = value.clone(),
+				None => bail!("No imported record {}", id),
+			}
+		}
+		self.context.record_operations(updates.len() as u64);
+		Ok(())
+	}
+
+	async fn drop_import_table(&self) -> Result<()> {
+		self.imported.lock().unwrap().clear();
+		Ok(())
+	}
+
+	async fn database_size(&self) -> Result<Option<u64>> {
+		Ok(Some((self.counts.lock().unwrap().total() as u64) * MOCK_RECORD_BYTES))
+	}
+
+	// The statistics of the mock are its record counts
+	async fn engine_stats(&self) -> Result<Option<serde_json::Value>> {
+		Ok(Some(serde_json::to_value(*self.counts.lock().unwrap())?))
+	}
+
+	// Only the reads by id have a plan
+	async fn explain(&self, test: BenchmarkTest) -> Result<Option<String>> {
+		let plan = if self.missing_index {
+			"SCAN users".to_string()
+		} else {
+			format!("SEARCH users USING INDEX {} (id=?)", MOCK_ID_INDEX)
+		};
+		Ok((test == BenchmarkTest::ReadByIdManyTimes).then_some(plan))
+	}
+
+	fn expected_index(&self, test: BenchmarkTest) -> Option<&'static str> {
+		(test == BenchmarkTest::ReadByIdManyTimes).then_some(MOCK_ID_INDEX)
+	}
+
+	async fn ping(&self) -> Result<()> {
+		Ok(())
+	}
+
+	async fn probe(&self) -> Result<Capabilities> {
+		Ok(Capabilities { blobs: true, ..Capabilities::default() })
+	}
+
+	fn database_name(&self) -> String {
+		"Mock".to_string()
+	}
+
+	fn connected(&self) -> bool {
+		self.connected
+	}
+
+	fn capabilities(&self) -> BackendCapabilities {
+		self.capabilities
+	}
+
+	async fn set_cpu_count(&mut self, count: usize) -> Result<()> {
+		self.cpu_count = count;
+		Ok(())
+	}
+
+	fn get_cpu_count(&self) -> usize {
+		self.cpu_count
+	}
+
+	fn set_index_mode(&mut self, _indexes: IndexMode) {}
+
+	fn set_key_encoding(&mut self, _encoding: KeyEncoding) {}
+
+	fn set_timestamp_encoding(&mut self, _encoding: TimestampEncoding) {}
+
+	fn run_context(&self) -> &RunContext {
+		&self.context
+	}
+
+	async fn count_records(&self) -> Result<RecordCounts> {
+		Ok(*self.counts.lock().unwrap())
+	}
+
+	async fn verify_test(&self, _test: BenchmarkTest, _count: usize) -> Result<()> {
+		Ok(())
+	}
+
+	async fn prepare(&self, _test: BenchmarkTest, _count: usize) -> Result<()> {
+		Ok(())
+	}
+
+	fn traced_fixture(&self, step: TraceOp, _chunk: &TraceChunk) -> Result<(BenchmarkTest, ())> {
+		Ok((step.test(), ()))
+	}
+
+	async fn insert_single_many_times(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::InsertSingleManyTimes, count).await
+	}
+
+	async fn insert_many_at_once(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::InsertManyAtOnce, count).await
+	}
+
+	async fn read_by_id_many_times(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadByIdManyTimes, count).await
+	}
+
+	async fn read_many_by_ids(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadManyByIds, count).await
+	}
+
+	async fn read_by_column_search(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadByColumnSearch, count).await
+	}
+
+	async fn read_with_one_join(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadWithOneJoin, count).await
+	}
+
+	async fn read_with_two_joins(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadWithTwoJoins, count).await
+	}
+
+	async fn update_single_field_one_entry(
+		&self,
+		count: usize,
+		_fixture: ()
+	) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::UpdateSingleFieldOneEntry, count).await
+	}
+
+	async fn update_single_field_many_entries(
+		&self,
+		count: usize,
+		_fixture: ()
+	) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::UpdateSingleFieldManyEntries, count).await
+	}
+
+	async fn update_multiple_fields_one_entry(
+		&self,
+		count: usize,
+		_fixture: ()
+	) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::UpdateMultipleFieldsOneEntry, count).await
+	}
+
+	async fn update_multiple_fields_many_entries(
+		&self,
+		count: usize,
+		_fixture: ()
+	) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::UpdateMultipleFieldsManyEntries, count).await
+	}
+
+	async fn insert_orders_with_items(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::InsertOrdersWithItems, count).await
+	}
+
+	async fn read_orders_with_items(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadOrdersWithItems, count).await
+	}
+
+	async fn insert_wide_records(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::InsertWideRecords, count).await
+	}
+
+	async fn read_wide_records_by_id(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadWideRecordsById, count).await
+	}
+
+	async fn read_wide_column_subset(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadWideColumnSubset, count).await
+	}
+
+	async fn read_tenant_orders(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadTenantOrders, count).await
+	}
+
+	async fn aggregate_orders_by_tenant(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::AggregateOrdersByTenant, count).await
+	}
+
+	async fn read_users_not_deleted(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadUsersNotDeleted, count).await
+	}
+
+	async fn read_discounted_products(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadDiscountedProducts, count).await
+	}
+
+	async fn soft_delete_users(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::SoftDeleteUsers, count).await
+	}
+
+	async fn read_live_users(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		self.execute(BenchmarkTest::ReadLiveUsers, count).await
+	}
+
+	// The writers share the throughput of the test evenly
+	async fn concurrent_inserts(&self, count: usize, writers: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		let mut result = self.execute(BenchmarkTest::ConcurrentInserts, count).await?;
+		let shares = writer_ranges(count, writers);
+		let rate = result.operations_per_second / (shares.len() as f64);
+		result.writer_ops_per_second = Some(vec![rate; shares.len()]);
+		Ok(result)
+	}
+
+	// Nothing holds up the contenders: half of their operations are reads, none fail
+	async fn long_transaction_contention(
+		&self,
+		count: usize,
+		_contenders: usize,
+		_fixture: ()
+	) -> Result<BenchmarkResult> {
+		let mut result = self.execute(BenchmarkTest::LongTransactionContention, count).await?;
+		let writes = (count / 2) as u64;
+		result.contention = Some(ContentionStats {
+			reads: (count as u64) - writes,
+			writes,
+			..ContentionStats::default()
+		});
+		Ok(result)
+	}
+
+	// Every increment commits and none is lost
+	async fn consistency_probe(&self, count: usize, _writers: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		let mut result = self.execute(BenchmarkTest::ConsistencyProbe, count).await?;
+		let committed = count as u64;
+		result.consistency = Some(ConsistencyStats {
+			committed,
+			applied: committed,
+			..ConsistencyStats::default()
+		});
+		Ok(result)
+	}
+
+	// Every column reads as fast as the projection
+	async fn read_projection(&self, count: usize, _fixture: ()) -> Result<BenchmarkResult> {
+		let mut result = self.execute(BenchmarkTest::ReadProjection, count).await?;
+		result.all_columns_ops_per_second = Some(result.operations_per_second);
+		Ok(result)
+	}
+}
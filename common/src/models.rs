@@ -1,29 +1,58 @@
+use std::time::Duration;
+use anyhow::Context;
 use chrono::{ DateTime, Utc };
 use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::codec::Row;
+use crate::error::ErrorKind;
+use crate::plan::{ CacheState, KeyEncoding, KeyStrategy };
+
+// Size of the data of a generated record, counting ids as 16 bytes, numbers and timestamps as
+// their binary size and strings at their average generated length. The write amplification of a
+// test compares the bytes a database writes with the size of the records the test writes.
+// Optional fields are counted as if they were set
+pub const USER_RECORD_BYTES: u64 = 16 + 4 + 14 + 26 + 8 + 1 + 8;
+pub const PRODUCT_RECORD_BYTES: u64 = 16 + 21 + 134 + 8 + 8 + 4 + 8;
+pub const ORDER_RECORD_BYTES: u64 = 16 + 4 + 16 + 16 + 4 + 8 + 8;
+pub const ORDER_ITEM_RECORD_BYTES: u64 = 16 + 16 + 16 + 4 + 8;
+// 25 integer, 25 real and 25 text columns of 16 characters, and 24 boolean columns
+pub const WIDE_RECORD_BYTES: u64 = 16 + 25 * 8 + 25 * 8 + 25 * 16 + 24;
+
+// Columns of a user and of a product, as the read tests select them
+pub const USER_COLUMNS: usize = 7;
+pub const PRODUCT_COLUMNS: usize = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct User {
 	pub id: Uuid,
+	pub tenant_id: u32,
 	pub name: String,
 	pub email: String,
 	pub created_at: DateTime<Utc>,
 	pub active: bool,
+	// Set when the user was soft-deleted
+	pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Product {
 	pub id: Uuid,
 	pub name: String,
 	pub description: String,
 	pub price: f64,
+	// Share of the price taken off, when the product is on discount
+	pub discount: Option<f64>,
 	pub stock: i32,
 	pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Order {
 	pub id: Uuid,
+	// Tenant of the user placing the order
+	pub tenant_id: u32,
 	pub user_id: Uuid,
 	pub product_id: Uuid,
 	pub quantity: i32,
@@ -31,7 +60,48 @@ pub struct Order {
 	pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Line of an order, one per product ordered. The `product_id` and `quantity` of the order itself
+// are those of its first item, and its `total_price` the sum of its items
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderItem {
+	pub id: Uuid,
+	pub order_id: Uuid,
+	pub product_id: Uuid,
+	pub quantity: i32,
+	pub unit_price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderWithItems {
+	pub order: Order,
+	pub items: Vec<OrderItem>,
+}
+
+// Value of a column of a wide record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum WideValue {
+	Integer(i64),
+	Real(f64),
+	Text(String),
+	Boolean(bool),
+}
+
+// Record of the wide table: an id and one value per column, of the type `wide::column_type` gives
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WideRecord {
+	pub id: Uuid,
+	pub values: Vec<WideValue>,
+}
+
+// Row of an imported CSV file: an id and one value per column of the import, in its order, None
+// for an empty cell
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Record {
+	pub id: Uuid,
+	pub values: Vec<Option<WideValue>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OrderWithDetails {
 	pub id: Uuid,
 	pub quantity: i32,
@@ -41,20 +111,606 @@ pub struct OrderWithDetails {
 	pub product: Product,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// User of a row of `id, tenant_id, name, email, created_at, active, deleted_at`
+impl TryFrom<Row> for User {
+	type Error = anyhow::Error;
+
+	fn try_from(row: Row) -> anyhow::Result<Self> {
+		Ok(User {
+			id: row.key(0)?,
+			tenant_id: row.integer(1)?,
+			name: row.text(2)?,
+			email: row.text(3)?,
+			created_at: row.timestamp(4)?,
+			active: row.boolean(5)?,
+			deleted_at: row.optional_timestamp(6)?,
+		})
+	}
+}
+
+// Product of a row of `id, name, description, price, discount, stock, created_at`
+impl TryFrom<Row> for Product {
+	type Error = anyhow::Error;
+
+	fn try_from(row: Row) -> anyhow::Result<Self> {
+		Ok(Product {
+			id: row.key(0)?,
+			name: row.text(1)?,
+			description: row.text(2)?,
+			price: row.real(3)?,
+			discount: row.optional_real(4)?,
+			stock: row.integer(5)?,
+			created_at: row.timestamp(6)?,
+		})
+	}
+}
+
+// Order of a row of `id, quantity, total_price, created_at`, followed by the columns of its user
+// and of its product
+impl TryFrom<Row> for OrderWithDetails {
+	type Error = anyhow::Error;
+
+	fn try_from(Row(mut values): Row) -> anyhow::Result<Self> {
+		let columns = 4 + USER_COLUMNS + PRODUCT_COLUMNS;
+		anyhow::ensure!(values.len() == columns, "Expected {} columns for an order, got {}", columns, values.len());
+
+		let product = Product::try_from(Row(values.split_off(4 + USER_COLUMNS))).context("Invalid product")?;
+		let user = User::try_from(Row(values.split_off(4))).context("Invalid user")?;
+		let order = Row(values);
+		Ok(OrderWithDetails {
+			id: order.key(0)?,
+			quantity: order.integer(1)?,
+			total_price: order.real(2)?,
+			created_at: order.timestamp(3)?,
+			user,
+			product,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BenchmarkResult {
 	pub database: String,
 	pub test_name: String,
 	pub operations: usize,
+	// Duration of the test in whole milliseconds, derived from `duration_ns`
 	pub duration_ms: u64,
+	// Duration of the test in nanoseconds, which `operations_per_second` is computed from
+	#[serde(default)]
+	pub duration_ns: u64,
 	pub operations_per_second: f64,
 	pub cpu_count: usize,
 	pub timestamp: DateTime<Utc>,
+	#[serde(default)]
+	pub status: TestStatus,
+	// Only reported by the databases caching their prepared statements
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub statement_cache: Option<StatementCacheStats>,
+	// How the ids were stored during the test
+	#[serde(default)]
+	pub key_encoding: KeyEncoding,
+	// How the ids of the generated records were picked
+	#[serde(default)]
+	pub key_strategy: KeyStrategy,
+	// Whether the data was checked after the test, in a run with `verify` set
+	#[serde(default)]
+	pub verified: bool,
+	// Operations retried after a transient error, such as a busy database
+	#[serde(default)]
+	pub retries: u64,
+	// Writes grouped per transaction or write batch, only for the batched tests
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub batch_size: Option<usize>,
+	// Bytes the database wrote to storage during a write test, when it can tell
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub bytes_written: Option<u64>,
+	// Bytes written per byte of the records written, when the bytes written are known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub write_amplification: Option<f64>,
+	// Plan the database picked for the query of the test, in a run with `explain` set
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub query_plan: Option<String>,
+	// 99th percentile of the time per operation in microseconds, from the time between the
+	// operations the test recorded
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub latency_p99_us: Option<f64>,
+	// Operations far slower than the 99th percentile and periods without any completed
+	// operation, from the same times as `latency_p99_us`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub outliers: Option<OutlierStats>,
+	// Statements of a test reading or writing its records in batches, timed each on its own, only
+	// for read_many_by_ids and insert_many_at_once
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub batches: Option<BatchStats>,
+	// Part of the duration spent decoding the rows the test fetched into the models, in
+	// milliseconds, in a run with `row_decoding` set to `full`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub decode_ms: Option<f64>,
+	// Operations per second of each writer of a concurrent test, in the order of their shares
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub writer_ops_per_second: Option<Vec<f64>>,
+	// Operations run against a long write transaction and how many of them it held up, only
+	// for long_transaction_contention
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub contention: Option<ContentionStats>,
+	// Increments of a counter committed by concurrent read-modify-writes and those the counter
+	// lost, only for consistency_probe
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub consistency: Option<ConsistencyStats>,
+	// Size of the files written by the backup, only for its result
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub backup_bytes: Option<u64>,
+	// Operations per second of read_projection reading every column instead of the projection
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub all_columns_ops_per_second: Option<f64>,
+	// Counters and gauges of the engine read after the test, such as its cache hits or write
+	// stalls, named as the engine names them
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	#[schema(value_type = Option<Object>)]
+	pub engine_stats: Option<serde_json::Value>,
+	// Share of the users the search matched, only for read_by_column_search
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub selectivity: Option<f64>,
+	// Whether the caches were cold or warmed up by a pass of the test, in a run with
+	// `compare_cache` set
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cache_state: Option<CacheState>,
+}
+
+// Number of records stored in each table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordCounts {
+	pub users: usize,
+	pub products: usize,
+	pub orders: usize,
+	pub order_items: usize,
+	pub wide_records: usize,
+}
+
+impl RecordCounts {
+	pub fn total(&self) -> usize {
+		self.users + self.products + self.orders + self.order_items + self.wide_records
+	}
+}
+
+// Lookups of the prepared statement caches during a test; a miss prepares the statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StatementCacheStats {
+	pub hits: u64,
+	pub misses: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Operations contending with a long write transaction; an operation fails when it gives up
+// waiting for the lock of the transaction or conflicts with its changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ContentionStats {
+	pub reads: u64,
+	pub writes: u64,
+	pub failed_reads: u64,
+	pub failed_writes: u64,
+	// Share of the operations that failed
+	pub error_rate: f64,
+	// Time the failed operations spent before failing, in milliseconds
+	pub blocked_ms: f64,
+}
+
+// Batches of a test, the records read or written by a single statement, transaction or write
+// batch: how many the test ran, how many records they held, and the time each of them took in
+// microseconds. One giant batch and many small ones read differently here even at the same
+// throughput.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BatchStats {
+	pub count: u64,
+	pub mean_size: f64,
+	pub max_size: u64,
+	pub latency_p50_us: f64,
+	pub latency_p99_us: f64,
+	pub latency_max_us: f64,
+}
+
+// Operations slower than `outlier_factor` times the 99th percentile of their test, and stalls,
+// periods without any completed operation longer than `stall_ms` and than `outlier_factor` times
+// the median time between completions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OutlierStats {
+	pub slow_operations: u64,
+	pub stalls: u64,
+	// Longest stall in milliseconds, 0 without any
+	pub longest_stall_ms: f64,
+}
+
+// Concurrent read-modify-writes of a counter; an increment is aborted when it fails on the lock or
+// a conflict, and lost when it committed but another one overwrote it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ConsistencyStats {
+	pub committed: u64,
+	pub aborted: u64,
+	// Increase of the counter over the test
+	pub applied: u64,
+	pub lost_updates: u64,
+	// Share of the committed increments that were lost
+	pub lost_update_rate: f64,
+}
+
+// Outcome of a single test
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum TestStatus {
+	#[default]
+	Passed,
+	// Stopped after its timeout; the result covers the operations done until then
+	TimedOut,
+	// The test returned an error; the rest of the run goes on
+	Failed {
+		message: String,
+	},
+	// Not run, as it needs a capability the backend declares it lacks
+	Skipped {
+		reason: String,
+	},
+}
+
+impl TestStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TestStatus::Passed => "passed",
+			TestStatus::TimedOut => "timed_out",
+			TestStatus::Failed { .. } => "failed",
+			TestStatus::Skipped { .. } => "skipped",
+		}
+	}
+
+	// Error of a failed test, or why a test was skipped
+	pub fn message(&self) -> Option<&str> {
+		match self {
+			TestStatus::Failed { message } => Some(message),
+			TestStatus::Skipped { reason } => Some(reason),
+			_ => None,
+		}
+	}
+
+	// Whether the result holds a measurement, which failed and skipped tests don't
+	pub fn is_measured(&self) -> bool {
+		!matches!(self, TestStatus::Failed { .. } | TestStatus::Skipped { .. })
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BenchmarkResults {
+	pub run_id: Uuid,
 	pub database: String,
 	pub results: Vec<BenchmarkResult>,
 	pub timestamp: DateTime<Utc>,
+	// Size of the database files after each test and workload, in the order they ran
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub size_timeline: Vec<SizeSample>,
+	// Cost of the harness with no database behind it, in a run with `calibrate` set
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub calibration: Option<CalibrationStats>,
+	// Throughput of the run against that of a baseline database, once a race gathered both
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub scores: Option<Scores>,
+}
+
+// Scores of a run against the run of a baseline database: the geometric mean of the ratio of the
+// throughput of each test to that of the baseline, times 100, over the tests both measured. A
+// score of 100 is as fast as the baseline, 200 twice as fast. Each category covers the tests of
+// its tag, the analytical queries for `analytics`; a category without any test has no score.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Scores {
+	// Database the tests were normalized against
+	pub baseline: String,
+	pub overall: Option<f64>,
+	pub read: Option<f64>,
+	pub write: Option<f64>,
+	pub update: Option<f64>,
+	pub analytics: Option<f64>,
+	// Tests both databases measured
+	pub tests: usize,
+}
+
+// Nanoseconds per operation the harness spends on each step of an operation with a backend doing
+// nothing, measured over `operations` operations
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CalibrationStats {
+	pub operations: usize,
+	// Generating a user, as the insert tests do before they are timed
+	pub generate_user_ns: f64,
+	// Formatting a timestamp as RFC 3339, as the SQL databases store them by default
+	pub rfc3339_ns: f64,
+	// Encoding a user with bincode, as RocksDB stores it
+	pub bincode_ns: f64,
+	// Awaiting a call to a backend, boxed like the methods of `DatabaseBenchmark`
+	pub async_call_ns: f64,
+	// Running a closure on a blocking thread, as the embedded engines run their calls
+	pub spawn_blocking_ns: f64,
+	// Recording a completed operation
+	pub record_operation_ns: f64,
+}
+
+// Size of the database files once a test or workload finished, before the next one settles its
+// writes, so it includes the write-ahead log and the files a compaction has yet to remove
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SizeSample {
+	pub test_name: String,
+	pub bytes: u64,
+	pub timestamp: DateTime<Utc>,
+}
+
+// Throughput of the running test over the last sampling interval, streamed on /ws
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ThroughputSample {
+	pub database: String,
+	pub test_name: String,
+	pub operations: u64,
+	pub operations_per_second: f64,
+	pub timestamp: DateTime<Utc>,
+}
+
+// Body and response of the /config/cpu endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CpuConfig {
+	pub cpu_count: usize,
+}
+
+// Response of the /health and /ready endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthStatus {
+	pub status: String,
+	pub database: String,
+	pub ping_ms: Option<f64>,
+	pub error: Option<String>,
+	pub active_run: Option<Uuid>,
+}
+
+// Optional features of a database engine, found by its probe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Capabilities {
+	// SQL window functions, like `ROW_NUMBER() OVER (...)`
+	pub window_functions: bool,
+	// A full-text index, like the FTS5 tables of SQLite or the fts extension of DuckDB
+	pub full_text_search: bool,
+	// Binary values stored and read back unchanged
+	pub blobs: bool,
+	// The analytical queries of a plan with `analytics` set
+	pub analytics: bool,
+}
+
+// Feature of a database some tests can't run without, beyond storing and reading records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+	Joins,
+	Transactions,
+}
+
+impl Capability {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Capability::Joins => "joins",
+			Capability::Transactions => "transactions",
+		}
+	}
+}
+
+// Features a backend declares for its engine, unlike the `Capabilities` its probe finds. A test
+// needing one the backend lacks is skipped rather than faked, as a key-value store would have to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BackendCapabilities {
+	// Joins run by the engine, rather than a lookup of each referenced record
+	pub joins: bool,
+	// Transactions holding their writes and locks until they commit
+	pub transactions: bool,
+}
+
+impl BackendCapabilities {
+	// Every feature, as the SQL databases have
+	pub const ALL: BackendCapabilities = BackendCapabilities {
+		joins: true,
+		transactions: true,
+	};
+
+	pub fn supports(&self, capability: Capability) -> bool {
+		match capability {
+			Capability::Joins => self.joins,
+			Capability::Transactions => self.transactions,
+		}
+	}
+}
+
+// Response of the /capabilities endpoint: whether the database answers and can create, write and
+// drop a probe table, which optional features it supports, and those its backend declares
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CapabilityReport {
+	pub database: String,
+	pub connected: bool,
+	pub probe: bool,
+	pub capabilities: Capabilities,
+	pub declared: BackendCapabilities,
+	pub error: Option<String>,
+}
+
+// Short description of a stored run, used to list the history
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RunSummary {
+	pub run_id: Uuid,
+	pub database: String,
+	pub tests: usize,
+	pub timestamp: DateTime<Utc>,
+}
+
+// Lifecycle of a benchmark run executed in the background
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum RunStatus {
+	Running,
+	Completed,
+	Cancelled,
+	Failed {
+		message: String,
+		// Kind of failure, so clients can tell an engine error from a failed initialization
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		error: Option<ErrorKind>,
+	},
+}
+
+// Status of a background run, returned by `POST /run` and `/runs/{id}/status`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RunInfo {
+	pub run_id: Uuid,
+	pub database: String,
+	pub status: RunStatus,
+	pub tests: Vec<String>,
+	pub started_at: DateTime<Utc>,
+	pub finished_at: Option<DateTime<Utc>>,
+	// Test in progress, only in the status of a running run
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub progress: Option<TestProgress>,
+}
+
+// Test a run is executing, with the operations it completed so far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TestProgress {
+	pub test_name: String,
+	pub operations: u64,
+}
+
+// Body of the webhook sent to a plan's `notify_url` when its run ends
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RunNotification {
+	pub run: RunInfo,
+	// Results of the run, only when it completed
+	pub results: Option<BenchmarkResults>,
+}
+
+impl BenchmarkResults {
+	pub const CSV_HEADER: &'static str =
+		"database,test_name,operations,duration_ms,operations_per_second,cpu_count,timestamp,status,error,statement_cache_hits,statement_cache_misses,key_encoding,key_strategy,verified,retries,batch_size,bytes_written,write_amplification,query_plan,latency_p99_us,selectivity,cache_state,slow_operations,stalls,longest_stall_ms,decode_ms,duration_ns,batches,mean_batch_size,max_batch_size,batch_p50_us,batch_p99_us,batch_max_us";
+
+	pub fn summary(&self) -> RunSummary {
+		RunSummary {
+			run_id: self.run_id,
+			database: self.database.clone(),
+			tests: self.results.len(),
+			timestamp: self.timestamp,
+		}
+	}
+
+	// Serialize the results as CSV, one row per test, with a header line
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from(Self::CSV_HEADER);
+		csv.push('\n');
+		for result in &self.results {
+			csv.push_str(&result.to_csv_row());
+			csv.push('\n');
+		}
+		csv
+	}
+
+	// Serialize the results as JSON Lines, one JSON object per test
+	pub fn to_jsonl(&self) -> serde_json::Result<String> {
+		let mut jsonl = String::new();
+		for result in &self.results {
+			jsonl.push_str(&serde_json::to_string(result)?);
+			jsonl.push('\n');
+		}
+		Ok(jsonl)
+	}
+}
+
+impl BenchmarkResult {
+	// Result of a test that ended with `status` after `duration`, without any operation counted
+	// yet nor any of the details only some tests report
+	pub fn new(database: &str, test_name: &str, cpu_count: usize, duration: Duration, status: TestStatus) -> Self {
+		BenchmarkResult {
+			database: database.to_string(),
+			test_name: test_name.to_string(),
+			operations: 0,
+			duration_ms: duration.as_millis() as u64,
+			duration_ns: duration.as_nanos() as u64,
+			operations_per_second: 0.0,
+			cpu_count,
+			timestamp: Utc::now(),
+			status,
+			statement_cache: None,
+			key_encoding: KeyEncoding::default(),
+			key_strategy: KeyStrategy::default(),
+			verified: false,
+			retries: 0,
+			batch_size: None,
+			bytes_written: None,
+			write_amplification: None,
+			query_plan: None,
+			latency_p99_us: None,
+			outliers: None,
+			batches: None,
+			decode_ms: None,
+			writer_ops_per_second: None,
+			contention: None,
+			consistency: None,
+			backup_bytes: None,
+			all_columns_ops_per_second: None,
+			engine_stats: None,
+			selectivity: None,
+			cache_state: None,
+		}
+	}
+
+	// Duration of the test, only to the millisecond for the results stored before it was kept in
+	// nanoseconds
+	pub fn duration(&self) -> Duration {
+		if self.duration_ns > 0 {
+			Duration::from_nanos(self.duration_ns)
+		} else {
+			Duration::from_millis(self.duration_ms)
+		}
+	}
+
+	// Serialize the result as a single CSV row (without trailing newline)
+	pub fn to_csv_row(&self) -> String {
+		[
+			csv_field(&self.database),
+			csv_field(&self.test_name),
+			self.operations.to_string(),
+			self.duration_ms.to_string(),
+			self.operations_per_second.to_string(),
+			self.cpu_count.to_string(),
+			self.timestamp.to_rfc3339(),
+			self.status.as_str().to_string(),
+			csv_field(self.status.message().unwrap_or("")),
+			self.statement_cache.map_or(String::new(), |stats| stats.hits.to_string()),
+			self.statement_cache.map_or(String::new(), |stats| stats.misses.to_string()),
+			self.key_encoding.to_string(),
+			self.key_strategy.to_string(),
+			self.verified.to_string(),
+			self.retries.to_string(),
+			self.batch_size.map_or(String::new(), |size| size.to_string()),
+			self.bytes_written.map_or(String::new(), |bytes| bytes.to_string()),
+			self.write_amplification.map_or(String::new(), |factor| factor.to_string()),
+			csv_field(self.query_plan.as_deref().unwrap_or("")),
+			self.latency_p99_us.map_or(String::new(), |micros| micros.to_string()),
+			self.selectivity.map_or(String::new(), |selectivity| selectivity.to_string()),
+			self.cache_state.map_or(String::new(), |state| state.to_string()),
+			self.outliers.map_or(String::new(), |stats| stats.slow_operations.to_string()),
+			self.outliers.map_or(String::new(), |stats| stats.stalls.to_string()),
+			self.outliers.map_or(String::new(), |stats| stats.longest_stall_ms.to_string()),
+			self.decode_ms.map_or(String::new(), |millis| millis.to_string()),
+			self.duration_ns.to_string(),
+			self.batches.map_or(String::new(), |stats| stats.count.to_string()),
+			self.batches.map_or(String::new(), |stats| stats.mean_size.to_string()),
+			self.batches.map_or(String::new(), |stats| stats.max_size.to_string()),
+			self.batches.map_or(String::new(), |stats| stats.latency_p50_us.to_string()),
+			self.batches.map_or(String::new(), |stats| stats.latency_p99_us.to_string()),
+			self.batches.map_or(String::new(), |stats| stats.latency_max_us.to_string()),
+		].join(",")
+	}
+}
+
+// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
 }
@@ -0,0 +1,40 @@
+// Optional fields of the users and products: the time a user was soft-deleted and the discount of
+// a product, left NULL at configurable rates. The filter tests select the rows where they are
+// NULL or set, which the engines plan and index differently. The soft-delete tests mark users
+// deleted, then list the live ones of a table where half of them are. The SQL is the same in
+// SQLite and DuckDB.
+
+// Share of the users never deleted, whose `deleted_at` is NULL, when the plan doesn't say
+pub const DEFAULT_DELETED_AT_NULL_RATE: f64 = 0.9;
+
+// Share of the products without a discount when the plan doesn't say
+pub const DEFAULT_DISCOUNT_NULL_RATE: f64 = 0.5;
+
+// A page of the users not deleted
+pub const READ_USERS_NOT_DELETED_QUERY: &str =
+	"SELECT id, name, email, created_at FROM users WHERE deleted_at IS NULL LIMIT ? OFFSET ?";
+
+// A page of the products on discount
+pub const READ_DISCOUNTED_PRODUCTS_QUERY: &str =
+	"SELECT id, name, price, discount FROM products WHERE discount IS NOT NULL LIMIT ? OFFSET ?";
+
+// Ids of users not deleted yet
+pub const LIVE_USER_IDS_QUERY: &str = "SELECT id FROM users WHERE deleted_at IS NULL LIMIT ?";
+
+// Soft-delete of a user
+pub const SOFT_DELETE_USER_QUERY: &str = "UPDATE users SET deleted_at = ? WHERE id = ?";
+
+// Soft-delete of users not deleted yet, picked in the order of their ids so that they spread
+// over the table
+pub const SOFT_DELETE_USERS_QUERY: &str =
+	"UPDATE users SET deleted_at = ? WHERE id IN (SELECT id FROM users WHERE deleted_at IS NULL ORDER BY id LIMIT ?)";
+
+// A page of the live users, newest first
+pub const READ_LIVE_USERS_QUERY: &str =
+	"SELECT id, name, email, created_at FROM users WHERE deleted_at IS NULL ORDER BY created_at DESC LIMIT ? OFFSET ?";
+
+// Users to soft-delete before read_live_users so that half of the `users` are, given the
+// `deleted` ones
+pub fn users_to_soft_delete(users: usize, deleted: usize) -> usize {
+	(users / 2).saturating_sub(deleted)
+}
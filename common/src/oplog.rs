@@ -0,0 +1,123 @@
+use std::fs::{ self, File, OpenOptions };
+use std::io::{ BufWriter, Write };
+use std::path::{ Path, PathBuf };
+use anyhow::{ Context, Result };
+use uuid::Uuid;
+
+// Op-logs of the runs: every operation timed by the tests, workloads, analytical queries, import
+// and backup of a run, with its latency, for the analyses the summary of the results can't give,
+// such as any percentile or the outliers of a test. A run writes its op-log to a file of its own
+// while it goes, in a compact binary format, every number little-endian:
+//
+// - the header, `OPLOG_MAGIC` followed by the version byte `OPLOG_VERSION`
+// - then a section per test, in the order they ran: the length of the name of the test (u16) and
+//   the name in UTF-8, the number of operations (u64), then for each operation its index in the
+//   test (u64), its latency in nanoseconds (u64) and whether it succeeded (u8, 1 or 0)
+//
+// Operations recorded together share their time evenly, as they do for `latency_p99_us`. A test
+// that fails ends with the operation it failed at, taking the time since the last one that
+// succeeded.
+
+pub const OPLOG_MAGIC: &[u8; 5] = b"OPLOG";
+pub const OPLOG_VERSION: u8 = 1;
+
+// Size of an operation in the file
+const ENTRY_BYTES: usize = 17;
+
+// An operation of a test, as read back from an op-log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpLogEntry {
+	pub test: String,
+	pub index: u64,
+	pub latency_ns: u64,
+	pub success: bool,
+}
+
+// File of the op-log of the run `run_id` in `dir`
+pub fn oplog_path(dir: &Path, run_id: Uuid) -> PathBuf {
+	dir.join(format!("{}.oplog", run_id))
+}
+
+// Start the op-log of a run, unless it already has one: a resumed run appends to the op-log of
+// the steps it completed before it was interrupted
+pub fn create(path: &Path) -> Result<()> {
+	if path.exists() {
+		return Ok(());
+	}
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let mut file = File::create(path).with_context(|| format!("Could not create op-log {}", path.display()))?;
+	file.write_all(OPLOG_MAGIC)?;
+	file.write_all(&[OPLOG_VERSION])?;
+	Ok(())
+}
+
+// Append the operations of a test, given as their latency in nanoseconds and whether they
+// succeeded, in order
+pub fn append(path: &Path, test: &str, operations: &[(u64, bool)]) -> Result<()> {
+	let file = OpenOptions::new()
+		.append(true)
+		.open(path)
+		.with_context(|| format!("Could not open op-log {}", path.display()))?;
+	let mut writer = BufWriter::new(file);
+
+	let name = test.as_bytes();
+	writer.write_all(&u16::try_from(name.len())?.to_le_bytes())?;
+	writer.write_all(name)?;
+	writer.write_all(&(operations.len() as u64).to_le_bytes())?;
+	for (index, &(latency_ns, success)) in operations.iter().enumerate() {
+		writer.write_all(&(index as u64).to_le_bytes())?;
+		writer.write_all(&latency_ns.to_le_bytes())?;
+		writer.write_all(&[u8::from(success)])?;
+	}
+
+	writer.flush()?;
+	Ok(())
+}
+
+// Operations of an op-log, in the order they were written
+pub fn decode(bytes: &[u8]) -> Result<Vec<OpLogEntry>> {
+	anyhow::ensure!(bytes.starts_with(OPLOG_MAGIC), "Not an op-log");
+	let mut reader = Reader { bytes, offset: OPLOG_MAGIC.len() };
+	let version = reader.take(1)?[0];
+	anyhow::ensure!(version == OPLOG_VERSION, "Unsupported op-log version {}", version);
+
+	let mut entries = Vec::new();
+	while reader.offset < bytes.len() {
+		let name_len = u16::from_le_bytes(reader.take(2)?.try_into()?) as usize;
+		let test = std::str::from_utf8(reader.take(name_len)?).context("Invalid test name")?.to_string();
+		let count = u64::from_le_bytes(reader.take(8)?.try_into()?);
+		for _ in 0..count {
+			let entry = reader.take(ENTRY_BYTES)?;
+			entries.push(OpLogEntry {
+				test: test.clone(),
+				index: u64::from_le_bytes(entry[..8].try_into()?),
+				latency_ns: u64::from_le_bytes(entry[8..16].try_into()?),
+				success: entry[16] != 0,
+			});
+		}
+	}
+	Ok(entries)
+}
+
+// Operations of the op-log at `path`
+pub fn read(path: &Path) -> Result<Vec<OpLogEntry>> {
+	let bytes = fs::read(path).with_context(|| format!("Could not read op-log {}", path.display()))?;
+	decode(&bytes).with_context(|| format!("Invalid op-log {}", path.display()))
+}
+
+struct Reader<'a> {
+	bytes: &'a [u8],
+	offset: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		let bytes = self.bytes
+			.get(self.offset..self.offset + len)
+			.with_context(|| format!("Truncated op-log at byte {}", self.offset))?;
+		self.offset += len;
+		Ok(bytes)
+	}
+}
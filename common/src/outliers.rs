@@ -0,0 +1,69 @@
+// Outliers and stalls of a test, from the time between the operations it recorded. An outlier is
+// an operation slower than `outlier_factor` times the 99th percentile of the test. A stall is a
+// period without any completed operation, such as a write held up by an LSM compaction, longer
+// than `stall_ms` and than `outlier_factor` times the median time between completions, so a test
+// whose operations are all slow, like the analytical queries, doesn't stall on each of them.
+use std::time::Duration;
+
+use crate::models::OutlierStats;
+
+// Times the 99th percentile an operation takes to be an outlier, when the plan doesn't say
+pub const DEFAULT_OUTLIER_FACTOR: f64 = 10.0;
+
+// Shortest period without a completed operation counted as a stall, when the plan doesn't say
+pub const DEFAULT_STALL_MS: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutlierThresholds {
+	pub factor: f64,
+	pub stall: Duration,
+}
+
+impl Default for OutlierThresholds {
+	fn default() -> Self {
+		OutlierThresholds { factor: DEFAULT_OUTLIER_FACTOR, stall: Duration::from_millis(DEFAULT_STALL_MS) }
+	}
+}
+
+// Outliers and stalls among `samples`, the nanoseconds per operation of each record of operations
+// in the order they were recorded, with the number of operations recorded; `None` without any
+pub fn analyze(samples: &[(u64, u64)], thresholds: OutlierThresholds) -> Option<OutlierStats> {
+	let p99 = percentile(samples.to_vec(), 0.99)?;
+	let slowest = (p99 as f64) * thresholds.factor;
+	let slow_operations = samples
+		.iter()
+		.filter(|&&(nanos, _)| (nanos as f64) > slowest)
+		.map(|&(_, count)| count)
+		.sum();
+
+	// Operations recorded together completed at once, after a period without any completion
+	let gaps: Vec<u64> = samples.iter().map(|&(nanos, count)| nanos * count).collect();
+	let median = percentile(gaps.iter().map(|&gap| (gap, 1)).collect(), 0.5)?;
+	let shortest_stall = (thresholds.stall.as_nanos() as f64).max((median as f64) * thresholds.factor);
+	let stalls: Vec<u64> = gaps
+		.into_iter()
+		.filter(|&gap| (gap as f64) >= shortest_stall)
+		.collect();
+
+	Some(OutlierStats {
+		slow_operations,
+		stalls: stalls.len() as u64,
+		longest_stall_ms: stalls.into_iter().max().map_or(0.0, |nanos| (nanos as f64) / 1e6),
+	})
+}
+
+// Value at `quantile` of samples of a value with the number of times they were taken
+pub(crate) fn percentile(mut samples: Vec<(u64, u64)>, quantile: f64) -> Option<u64> {
+	samples.sort_unstable();
+
+	let total: u64 = samples.iter().map(|&(_, count)| count).sum();
+	let rank = ((total as f64) * quantile).ceil() as u64;
+	let mut seen = 0;
+	samples
+		.into_iter()
+		.find(|&(_, count)| {
+			seen += count;
+			seen >= rank
+		})
+		.map(|(value, _)| value)
+}
@@ -0,0 +1,1038 @@
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+use crate::analytics::{ AnalyticsQuery, DEFAULT_ANALYTICS_SCALE };
+use crate::backup::BACKUP_RESULTS;
+use crate::benchmark::{ test_descriptor, TestTag, TEST_REGISTRY };
+use crate::case::BenchmarkCase;
+use crate::codec::RowDecoding;
+use crate::import::{ ImportConfig, IMPORT_RESULTS };
+use crate::models::{ PRODUCT_RECORD_BYTES, USER_RECORD_BYTES };
+use crate::outliers::{ OutlierThresholds, DEFAULT_OUTLIER_FACTOR, DEFAULT_STALL_MS };
+use crate::nullable::{ DEFAULT_DELETED_AT_NULL_RATE, DEFAULT_DISCOUNT_NULL_RATE };
+use crate::search::{ DEFAULT_EMAIL_DOMAINS, SEARCH_SELECTIVITIES };
+use crate::tenants::DEFAULT_TENANTS;
+use crate::text::ADVERSARIAL_TEXT_BYTES;
+use crate::trace::{ self, TraceConfig };
+use crate::workloads::{ Workload, DEFAULT_WORKLOAD_OPERATIONS };
+use crate::writers::DEFAULT_WRITERS;
+
+// Number of records generated when no size is given
+pub const DEFAULT_DATASET_SIZE: usize = 1_000;
+
+// The benchmark tests every database implements, in the order they are run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkTest {
+	InsertSingleManyTimes,
+	InsertManyAtOnce,
+	ReadByIdManyTimes,
+	ReadManyByIds,
+	ReadByColumnSearch,
+	ReadWithOneJoin,
+	ReadWithTwoJoins,
+	UpdateSingleFieldOneEntry,
+	UpdateSingleFieldManyEntries,
+	UpdateMultipleFieldsOneEntry,
+	UpdateMultipleFieldsManyEntries,
+	InsertOrdersWithItems,
+	ReadOrdersWithItems,
+	InsertWideRecords,
+	ReadWideRecordsById,
+	ReadWideColumnSubset,
+	ReadTenantOrders,
+	AggregateOrdersByTenant,
+	ReadUsersNotDeleted,
+	ReadDiscountedProducts,
+	SoftDeleteUsers,
+	ReadLiveUsers,
+	ConcurrentInserts,
+	LongTransactionContention,
+	ConsistencyProbe,
+	ReadProjection,
+}
+
+impl BenchmarkTest {
+	pub const ALL: [BenchmarkTest; 26] = [
+		BenchmarkTest::InsertSingleManyTimes,
+		BenchmarkTest::InsertManyAtOnce,
+		BenchmarkTest::ReadByIdManyTimes,
+		BenchmarkTest::ReadManyByIds,
+		BenchmarkTest::ReadByColumnSearch,
+		BenchmarkTest::ReadWithOneJoin,
+		BenchmarkTest::ReadWithTwoJoins,
+		BenchmarkTest::UpdateSingleFieldOneEntry,
+		BenchmarkTest::UpdateSingleFieldManyEntries,
+		BenchmarkTest::UpdateMultipleFieldsOneEntry,
+		BenchmarkTest::UpdateMultipleFieldsManyEntries,
+		BenchmarkTest::InsertOrdersWithItems,
+		BenchmarkTest::ReadOrdersWithItems,
+		BenchmarkTest::InsertWideRecords,
+		BenchmarkTest::ReadWideRecordsById,
+		BenchmarkTest::ReadWideColumnSubset,
+		BenchmarkTest::ReadTenantOrders,
+		BenchmarkTest::AggregateOrdersByTenant,
+		BenchmarkTest::ReadUsersNotDeleted,
+		BenchmarkTest::ReadDiscountedProducts,
+		BenchmarkTest::SoftDeleteUsers,
+		BenchmarkTest::ReadLiveUsers,
+		BenchmarkTest::ConcurrentInserts,
+		BenchmarkTest::LongTransactionContention,
+		BenchmarkTest::ConsistencyProbe,
+		BenchmarkTest::ReadProjection,
+	];
+
+	// Name used in URLs, plans and results
+	pub fn name(&self) -> &'static str {
+		match self {
+			BenchmarkTest::InsertSingleManyTimes => "insert_single_many_times",
+			BenchmarkTest::InsertManyAtOnce => "insert_many_at_once",
+			BenchmarkTest::ReadByIdManyTimes => "read_by_id_many_times",
+			BenchmarkTest::ReadManyByIds => "read_many_by_ids",
+			BenchmarkTest::ReadByColumnSearch => "read_by_column_search",
+			BenchmarkTest::ReadWithOneJoin => "read_with_one_join",
+			BenchmarkTest::ReadWithTwoJoins => "read_with_two_joins",
+			BenchmarkTest::UpdateSingleFieldOneEntry => "update_single_field_one_entry",
+			BenchmarkTest::UpdateSingleFieldManyEntries => "update_single_field_many_entries",
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => "update_multiple_fields_one_entry",
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => "update_multiple_fields_many_entries",
+			BenchmarkTest::InsertOrdersWithItems => "insert_orders_with_items",
+			BenchmarkTest::ReadOrdersWithItems => "read_orders_with_items",
+			BenchmarkTest::InsertWideRecords => "insert_wide_records",
+			BenchmarkTest::ReadWideRecordsById => "read_wide_records_by_id",
+			BenchmarkTest::ReadWideColumnSubset => "read_wide_column_subset",
+			BenchmarkTest::ReadTenantOrders => "read_tenant_orders",
+			BenchmarkTest::AggregateOrdersByTenant => "aggregate_orders_by_tenant",
+			BenchmarkTest::ReadUsersNotDeleted => "read_users_not_deleted",
+			BenchmarkTest::ReadDiscountedProducts => "read_discounted_products",
+			BenchmarkTest::SoftDeleteUsers => "soft_delete_users",
+			BenchmarkTest::ReadLiveUsers => "read_live_users",
+			BenchmarkTest::ConcurrentInserts => "concurrent_inserts",
+			BenchmarkTest::LongTransactionContention => "long_transaction_contention",
+			BenchmarkTest::ConsistencyProbe => "consistency_probe",
+			BenchmarkTest::ReadProjection => "read_projection",
+		}
+	}
+
+	// Number of operations used when the plan doesn't override it
+	pub fn default_count(&self) -> usize {
+		match self {
+			BenchmarkTest::InsertSingleManyTimes => 2_000,
+			BenchmarkTest::InsertManyAtOnce => 1_000,
+			BenchmarkTest::ReadByIdManyTimes => 1_000,
+			BenchmarkTest::ReadManyByIds => 2_000,
+			BenchmarkTest::ReadByColumnSearch => 2_000,
+			BenchmarkTest::ReadWithOneJoin => 2_000,
+			BenchmarkTest::ReadWithTwoJoins => 2_000,
+			BenchmarkTest::UpdateSingleFieldOneEntry => 500,
+			BenchmarkTest::UpdateSingleFieldManyEntries => 1_000,
+			BenchmarkTest::UpdateMultipleFieldsOneEntry => 200,
+			BenchmarkTest::UpdateMultipleFieldsManyEntries => 5_000,
+			BenchmarkTest::InsertOrdersWithItems => 1_000,
+			BenchmarkTest::ReadOrdersWithItems => 2_000,
+			BenchmarkTest::InsertWideRecords => 1_000,
+			BenchmarkTest::ReadWideRecordsById => 1_000,
+			BenchmarkTest::ReadWideColumnSubset => 2_000,
+			BenchmarkTest::ReadTenantOrders => 1_000,
+			BenchmarkTest::AggregateOrdersByTenant => 20,
+			BenchmarkTest::ReadUsersNotDeleted => 2_000,
+			BenchmarkTest::ReadDiscountedProducts => 2_000,
+			BenchmarkTest::SoftDeleteUsers => 500,
+			BenchmarkTest::ReadLiveUsers => 2_000,
+			BenchmarkTest::ConcurrentInserts => 2_000,
+			// Every write of a contender may wait for the lock until it gives up
+			BenchmarkTest::LongTransactionContention => 200,
+			BenchmarkTest::ConsistencyProbe => 1_000,
+			BenchmarkTest::ReadProjection => 1_000,
+		}
+	}
+
+	// Whether the test inserts or updates records
+	pub fn is_write(&self) -> bool {
+		test_descriptor(*self).has_tag(TestTag::Write)
+	}
+
+	// Whether the test groups its writes in transactions or write batches, whose size the
+	// plan's `batch_size` controls
+	pub fn is_batched(&self) -> bool {
+		matches!(
+			self,
+			BenchmarkTest::InsertManyAtOnce |
+				BenchmarkTest::UpdateSingleFieldManyEntries |
+				BenchmarkTest::UpdateMultipleFieldsManyEntries
+		)
+	}
+
+	// Shares of the users the test is run to match, once each; the other tests run once
+	pub fn selectivities(&self) -> Vec<Option<f64>> {
+		match self {
+			BenchmarkTest::ReadByColumnSearch => SEARCH_SELECTIVITIES.into_iter().map(Some).collect(),
+			_ => vec![None],
+		}
+	}
+}
+
+impl fmt::Display for BenchmarkTest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for BenchmarkTest {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		BenchmarkTest::ALL.into_iter()
+			.find(|test| test.name() == name)
+			.ok_or_else(|| format!("unknown benchmark test: {}", name))
+	}
+}
+
+// Whether the databases keep secondary indexes (on emails, product names and order references);
+// lookups by primary key stay indexed either way. `full` keeps them like `on`, but the SQLite
+// indexes that are partial, of only the rows a filter selects, cover every row instead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexMode {
+	#[default]
+	On,
+	Off,
+	Full,
+}
+
+impl IndexMode {
+	pub fn is_on(&self) -> bool {
+		*self != IndexMode::Off
+	}
+}
+
+impl FromStr for IndexMode {
+	type Err = String;
+
+	fn from_str(mode: &str) -> Result<Self, Self::Err> {
+		match mode {
+			"on" => Ok(IndexMode::On),
+			"off" => Ok(IndexMode::Off),
+			"full" => Ok(IndexMode::Full),
+			_ => Err(format!("unknown index mode: {}, expected on, off or full", mode)),
+		}
+	}
+}
+
+// Caches of a read test run in a plan with `compare_cache`: cold when it runs right after the
+// dataset is seeded or restored, warm when an untimed pass of the same test ran just before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheState {
+	Cold,
+	Warm,
+}
+
+impl fmt::Display for CacheState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CacheState::Cold => f.write_str("cold"),
+			CacheState::Warm => f.write_str("warm"),
+		}
+	}
+}
+
+// One run of a test among those of a plan: the batched tests run once per batch size,
+// read_by_column_search once per selectivity and, with `compare_cache`, the read tests once per
+// cache state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestVariant {
+	pub batch_size: Option<usize>,
+	pub selectivity: Option<f64>,
+	pub cache_state: Option<CacheState>,
+}
+
+// How the databases store the UUID keys and the columns referencing them: as their 36-character
+// string form or as their 16 bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyEncoding {
+	#[default]
+	String,
+	Binary,
+}
+
+impl KeyEncoding {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			KeyEncoding::String => "string",
+			KeyEncoding::Binary => "binary",
+		}
+	}
+}
+
+impl fmt::Display for KeyEncoding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for KeyEncoding {
+	type Err = String;
+
+	fn from_str(encoding: &str) -> Result<Self, Self::Err> {
+		match encoding {
+			"string" => Ok(KeyEncoding::String),
+			"binary" => Ok(KeyEncoding::Binary),
+			_ => Err(format!("unknown key encoding: {}, expected string or binary", encoding)),
+		}
+	}
+}
+
+// How the ids of the generated records are picked: random UUIDs, time-ordered UUIDs or ULIDs,
+// or sequential integers, the last three inserted in key order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStrategy {
+	#[default]
+	UuidV4,
+	UuidV7,
+	Ulid,
+	Sequential,
+}
+
+impl KeyStrategy {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			KeyStrategy::UuidV4 => "uuid_v4",
+			KeyStrategy::UuidV7 => "uuid_v7",
+			KeyStrategy::Ulid => "ulid",
+			KeyStrategy::Sequential => "sequential",
+		}
+	}
+}
+
+impl fmt::Display for KeyStrategy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for KeyStrategy {
+	type Err = String;
+
+	fn from_str(strategy: &str) -> Result<Self, Self::Err> {
+		match strategy {
+			"uuid_v4" => Ok(KeyStrategy::UuidV4),
+			"uuid_v7" => Ok(KeyStrategy::UuidV7),
+			"ulid" => Ok(KeyStrategy::Ulid),
+			"sequential" => Ok(KeyStrategy::Sequential),
+			_ => Err(format!("unknown key strategy: {}, expected uuid_v4, uuid_v7, ulid or sequential", strategy)),
+		}
+	}
+}
+
+// How the databases store the `created_at` timestamps: as RFC 3339 strings or in a native
+// type (TIMESTAMP in DuckDB, microseconds since the epoch in SQLite and RocksDB)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampEncoding {
+	#[default]
+	String,
+	Native,
+}
+
+impl FromStr for TimestampEncoding {
+	type Err = String;
+
+	fn from_str(encoding: &str) -> Result<Self, Self::Err> {
+		match encoding {
+			"string" => Ok(TimestampEncoding::String),
+			"native" => Ok(TimestampEncoding::Native),
+			_ => Err(format!("unknown timestamp encoding: {}, expected string or native", encoding)),
+		}
+	}
+}
+
+// Zipfian skew used when none is given, the one of the YCSB core workloads
+pub const DEFAULT_ZIPFIAN_THETA: f64 = 0.99;
+
+// How the tests reading or updating records by id pick them: all equally likely, or a few hot
+// records taking most of the accesses. Serialized as `"uniform"` or `{"zipfian": {"theta": 0.99}}`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessDistribution {
+	Uniform,
+	Zipfian {
+		theta: f64,
+	},
+}
+
+impl fmt::Display for AccessDistribution {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AccessDistribution::Uniform => f.write_str("uniform"),
+			AccessDistribution::Zipfian { theta } => write!(f, "zipfian:{}", theta),
+		}
+	}
+}
+
+// Parses `uniform`, `zipfian` or `zipfian:<theta>`
+impl FromStr for AccessDistribution {
+	type Err = String;
+
+	fn from_str(distribution: &str) -> Result<Self, Self::Err> {
+		match distribution.split_once(':') {
+			None if distribution == "uniform" => Ok(AccessDistribution::Uniform),
+			None if distribution == "zipfian" => {
+				Ok(AccessDistribution::Zipfian { theta: DEFAULT_ZIPFIAN_THETA })
+			}
+			Some(("zipfian", theta)) => {
+				let theta = theta.parse().map_err(|_| format!("invalid zipfian theta: {}", theta))?;
+				Ok(AccessDistribution::Zipfian { theta })
+			}
+			_ => {
+				Err(
+					format!(
+						"unknown distribution: {}, expected uniform, zipfian or zipfian:<theta>",
+						distribution
+					)
+				)
+			}
+		}
+	}
+}
+
+// Normal spread used when none is given, as a share of the range of the values
+pub const DEFAULT_NORMAL_STD_DEV: f64 = 0.15;
+
+// Pareto shape used when none is given, the one of the 80/20 rule
+pub const DEFAULT_PARETO_ALPHA: f64 = 1.16;
+
+// How a generated numeric field spreads over its range: evenly, around the middle of the range
+// with a standard deviation given as a share of it, or heavy-tailed, most values near the low end
+// and a few far above. Serialized as `"uniform"`, `{"normal": {"std_dev": 0.15}}` or
+// `{"pareto": {"alpha": 1.16}}`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueDistribution {
+	#[default]
+	Uniform,
+	Normal {
+		std_dev: f64,
+	},
+	Pareto {
+		alpha: f64,
+	},
+}
+
+impl fmt::Display for ValueDistribution {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ValueDistribution::Uniform => f.write_str("uniform"),
+			ValueDistribution::Normal { std_dev } => write!(f, "normal:{}", std_dev),
+			ValueDistribution::Pareto { alpha } => write!(f, "pareto:{}", alpha),
+		}
+	}
+}
+
+// Parses `uniform`, `normal`, `normal:<std_dev>`, `pareto` or `pareto:<alpha>`
+impl FromStr for ValueDistribution {
+	type Err = String;
+
+	fn from_str(distribution: &str) -> Result<Self, Self::Err> {
+		let parameter = |value: &str| {
+			value.parse::<f64>().map_err(|_| format!("invalid {} parameter: {}", distribution, value))
+		};
+		match distribution.split_once(':') {
+			None if distribution == "uniform" => Ok(ValueDistribution::Uniform),
+			None if distribution == "normal" => {
+				Ok(ValueDistribution::Normal { std_dev: DEFAULT_NORMAL_STD_DEV })
+			}
+			None if distribution == "pareto" => {
+				Ok(ValueDistribution::Pareto { alpha: DEFAULT_PARETO_ALPHA })
+			}
+			Some(("normal", std_dev)) => Ok(ValueDistribution::Normal { std_dev: parameter(std_dev)? }),
+			Some(("pareto", alpha)) => Ok(ValueDistribution::Pareto { alpha: parameter(alpha)? }),
+			_ => {
+				Err(
+					format!(
+						"unknown value distribution: {}, expected uniform, normal[:<std_dev>] or pareto[:<alpha>]",
+						distribution
+					)
+				)
+			}
+		}
+	}
+}
+
+// Distributions of the numeric fields of the generated records: the prices of the products and
+// order items, the stock of the products and the quantities ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ValueDistributions {
+	pub price: ValueDistribution,
+	pub stock: ValueDistribution,
+	pub quantity: ValueDistribution,
+}
+
+impl ValueDistributions {
+	fn iter(&self) -> impl Iterator<Item = ValueDistribution> {
+		[self.price, self.stock, self.quantity].into_iter()
+	}
+}
+
+// Sizes of the generated users and products, so the same tests run over small or large records.
+// Names, emails and descriptions are padded up to their length, keeping the prefixes and email
+// domain the searches rely on, and `filler_len` random characters, which compression can't
+// shrink, are appended to the user names and product descriptions. `text` adds non-ASCII and
+// adversarial text to the user and product names, and `null_rates` sets how often the optional
+// fields are left NULL. `email_domains` is the number of domains the emails are spread over and
+// `values` sets how the prices, stocks and quantities spread. The default leaves the generated
+// values as they are, around 80 bytes per user and 200 per product
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct PayloadProfile {
+	pub name_len: usize,
+	pub email_len: usize,
+	pub description_len: usize,
+	pub filler_len: usize,
+	pub text: TextMode,
+	pub null_rates: NullRates,
+	pub email_domains: usize,
+	pub values: ValueDistributions,
+}
+
+impl Default for PayloadProfile {
+	fn default() -> Self {
+		Self {
+			name_len: 0,
+			email_len: 0,
+			description_len: 0,
+			filler_len: 0,
+			text: TextMode::default(),
+			null_rates: NullRates::default(),
+			email_domains: DEFAULT_EMAIL_DOMAINS,
+			values: ValueDistributions::default(),
+		}
+	}
+}
+
+// Share of the generated users and products whose optional field is NULL, between 0 and 1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct NullRates {
+	pub deleted_at: f64,
+	pub discount: f64,
+}
+
+impl Default for NullRates {
+	fn default() -> Self {
+		Self {
+			deleted_at: DEFAULT_DELETED_AT_NULL_RATE,
+			discount: DEFAULT_DISCOUNT_NULL_RATE,
+		}
+	}
+}
+
+impl PayloadProfile {
+	// Size of a generated user, counted like `USER_RECORD_BYTES`
+	pub fn user_record_bytes(&self) -> u64 {
+		USER_RECORD_BYTES +
+			padding(14, self.name_len) +
+			padding(26, self.email_len) +
+			(self.filler_len as u64) +
+			self.text_bytes()
+	}
+
+	// Size of a generated product, counted like `PRODUCT_RECORD_BYTES`
+	pub fn product_record_bytes(&self) -> u64 {
+		PRODUCT_RECORD_BYTES +
+			padding(21, self.name_len) +
+			padding(134, self.description_len) +
+			(self.filler_len as u64) +
+			self.text_bytes()
+	}
+
+	// Average bytes the text mode adds to a name
+	fn text_bytes(&self) -> u64 {
+		match self.text {
+			TextMode::Ascii => 0,
+			TextMode::Adversarial => *ADVERSARIAL_TEXT_BYTES,
+		}
+	}
+}
+
+// Text of the generated names: ASCII only, or followed by multi-byte Unicode, emoji, quotes, SQL
+// metacharacters or a very long string, to check the databases store any text unchanged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TextMode {
+	#[default]
+	Ascii,
+	Adversarial,
+}
+
+impl FromStr for TextMode {
+	type Err = String;
+
+	fn from_str(mode: &str) -> Result<Self, Self::Err> {
+		match mode {
+			"ascii" => Ok(TextMode::Ascii),
+			"adversarial" => Ok(TextMode::Adversarial),
+			_ => Err(format!("unknown text mode: {}, expected ascii or adversarial", mode)),
+		}
+	}
+}
+
+// Characters added to a generated value of `len` characters on average to reach `target`
+fn padding(len: usize, target: usize) -> u64 {
+	target.saturating_sub(len) as u64
+}
+
+// Configuration of a benchmark run, sent as the JSON body of `POST /run`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BenchmarkPlan {
+	// Number of users, products and orders generated before the tests
+	#[serde(default = "default_dataset_size")]
+	pub dataset_size: usize,
+
+	// Per-test operation counts, overriding the defaults
+	#[serde(default)]
+	pub counts: HashMap<BenchmarkTest, usize>,
+
+	// Tests to run; all tests when empty
+	#[serde(default)]
+	pub tests: Vec<BenchmarkTest>,
+
+	// Only the tests with any of these tags; all tests when empty
+	#[serde(default)]
+	pub tags: Vec<TestTag>,
+
+	// Number of CPU cores the database may use; unchanged when not set
+	#[serde(default)]
+	pub cpu_count: Option<usize>,
+
+	// Number of times each test is repeated
+	#[serde(default = "default_repetitions")]
+	pub repetitions: usize,
+
+	// Maximum duration of a single test, in seconds; a test running longer is stopped
+	// and recorded as timed out
+	#[serde(default)]
+	pub test_timeout_secs: Option<u64>,
+
+	// Maximum duration of all the tests of the run, in seconds; the tests left once it is
+	// reached are skipped
+	#[serde(default)]
+	pub run_timeout_secs: Option<u64>,
+
+	// URL receiving a POST with the outcome of the run once it ends
+	#[serde(default)]
+	pub notify_url: Option<String>,
+
+	// Whether the secondary indexes exist during the run
+	#[serde(default)]
+	pub indexes: IndexMode,
+
+	// Delete and recreate the database files before the run instead of emptying the tables
+	#[serde(default)]
+	pub fresh_database: bool,
+
+	// How the ids are stored
+	#[serde(default)]
+	pub key_encoding: KeyEncoding,
+
+	// How the ids of the generated records are picked
+	#[serde(default)]
+	pub key_strategy: KeyStrategy,
+
+	// How the timestamps are stored
+	#[serde(default)]
+	pub timestamp_encoding: TimestampEncoding,
+
+	// Check the data is correct after each test
+	#[serde(default)]
+	pub verify: bool,
+
+	// Number of writes grouped per transaction or write batch by the batched tests; all the
+	// writes of a test at once when not set
+	#[serde(default)]
+	pub batch_size: Option<usize>,
+
+	// Batch sizes the batched tests are each run with, overriding `batch_size` when not empty
+	#[serde(default)]
+	pub batch_size_sweep: Vec<usize>,
+
+	// Attach the plan of the query each test runs to its result
+	#[serde(default)]
+	pub explain: bool,
+
+	// Check the queries of the tests reading through an index use it, failing those that don't
+	// before they run
+	#[serde(default)]
+	pub assert_indexes: bool,
+
+	// Run each read test twice, with cold caches then after a warm-up pass
+	#[serde(default)]
+	pub compare_cache: bool,
+
+	// Write every timed operation of the run, with its latency, to an op-log
+	#[serde(default)]
+	pub oplog: bool,
+
+	// Time the harness with a backend doing nothing before the tests, reported with the results
+	#[serde(default)]
+	pub calibrate: bool,
+
+	// Whether the read tests decode the rows they fetch into the models, or only fetch them
+	#[serde(default)]
+	pub row_decoding: RowDecoding,
+
+	// Times the 99th percentile an operation takes to be counted as an outlier, and shortest
+	// period without a completed operation counted as a stall
+	#[serde(default = "default_outlier_factor")]
+	pub outlier_factor: f64,
+
+	#[serde(default = "default_stall_ms")]
+	pub stall_ms: u64,
+
+	// Distribution of the ids read by `read_by_id_many_times` and updated by
+	// `update_single_field_one_entry`; when not set, they keep their fixed access pattern
+	#[serde(default)]
+	pub distribution: Option<AccessDistribution>,
+
+	// YCSB workloads run after the tests
+	#[serde(default)]
+	pub workloads: Vec<Workload>,
+
+	// Number of operations of each workload
+	#[serde(default = "default_workload_operations")]
+	pub workload_operations: usize,
+
+	// Trace the workloads write their operations to, or run the operations of
+	#[serde(default)]
+	pub trace: Option<TraceConfig>,
+
+	// Run the analytical queries last, over `analytics_scale` more orders per generated user
+	#[serde(default)]
+	pub analytics: bool,
+
+	#[serde(default = "default_analytics_scale")]
+	pub analytics_scale: usize,
+
+	// CSV file of the user's data loaded into a table of its own, then read and updated, after
+	// the analytical queries
+	#[serde(default)]
+	pub import: Option<ImportConfig>,
+
+	// Back up the database and restore the backup last, timing both
+	#[serde(default)]
+	pub backup: bool,
+
+	// Sizes of the generated users and products
+	#[serde(default)]
+	pub payload: PayloadProfile,
+
+	// Number of tenants the generated users and orders are spread across
+	#[serde(default = "default_tenants")]
+	pub tenants: usize,
+
+	// Writers inserting at the same time in concurrent_inserts, connections contending with the
+	// long transaction of long_transaction_contention and incrementing the counter of consistency_probe
+	#[serde(default = "default_writers")]
+	pub writers: usize,
+
+	// Seed of the generated dataset, which then has the same content on every run and database;
+	// random when not set
+	#[serde(default)]
+	pub seed: Option<u64>,
+
+	// Save the dataset once generated and restore it before each test and workload, so each one
+	// starts from the same data
+	#[serde(default)]
+	pub fixtures: bool,
+
+	// Run over the dataset the database already holds, built beforehand with `race seed`, instead
+	// of emptying it and generating one; only the tests leaving the data unchanged run
+	#[serde(default)]
+	pub prebuilt: bool,
+}
+
+impl Default for BenchmarkPlan {
+	fn default() -> Self {
+		Self {
+			dataset_size: default_dataset_size(),
+			counts: HashMap::new(),
+			tests: Vec::new(),
+			tags: Vec::new(),
+			cpu_count: None,
+			repetitions: default_repetitions(),
+			test_timeout_secs: None,
+			run_timeout_secs: None,
+			notify_url: None,
+			indexes: IndexMode::default(),
+			fresh_database: false,
+			key_encoding: KeyEncoding::default(),
+			key_strategy: KeyStrategy::default(),
+			timestamp_encoding: TimestampEncoding::default(),
+			verify: false,
+			batch_size: None,
+			batch_size_sweep: Vec::new(),
+			explain: false,
+			assert_indexes: false,
+			compare_cache: false,
+			oplog: false,
+			calibrate: false,
+			row_decoding: RowDecoding::default(),
+			outlier_factor: default_outlier_factor(),
+			stall_ms: default_stall_ms(),
+			distribution: None,
+			workloads: Vec::new(),
+			workload_operations: default_workload_operations(),
+			trace: None,
+			analytics: false,
+			analytics_scale: default_analytics_scale(),
+			import: None,
+			backup: false,
+			payload: PayloadProfile::default(),
+			tenants: default_tenants(),
+			writers: default_writers(),
+			seed: None,
+			fixtures: false,
+			prebuilt: false,
+		}
+	}
+}
+
+impl BenchmarkPlan {
+	// Check the plan describes a runnable benchmark
+	pub fn validate(&self) -> Result<(), String> {
+		if self.dataset_size == 0 {
+			return Err("dataset_size must be greater than 0".to_string());
+		}
+		if self.repetitions == 0 {
+			return Err("repetitions must be greater than 0".to_string());
+		}
+		if self.cpu_count == Some(0) {
+			return Err("cpu_count must be greater than 0".to_string());
+		}
+		if let Some((test, _)) = self.counts.iter().find(|(_, count)| **count == 0) {
+			return Err(format!("count for {} must be greater than 0", test));
+		}
+		if !self.tags.is_empty() && self.selected_tests().is_empty() {
+			return Err("no selected test has any of the tags".to_string());
+		}
+		if self.workload_operations == 0 || self.analytics_scale == 0 {
+			return Err("workload_operations and analytics_scale must be greater than 0".to_string());
+		}
+		if !self.outlier_factor.is_finite() || self.outlier_factor < 1.0 {
+			return Err("outlier_factor must be at least 1".to_string());
+		}
+		if self.stall_ms == 0 {
+			return Err("stall_ms must be greater than 0".to_string());
+		}
+		if self.tenants == 0 {
+			return Err("tenants must be greater than 0".to_string());
+		}
+		if self.writers == 0 {
+			return Err("writers must be greater than 0".to_string());
+		}
+		let NullRates { deleted_at, discount } = self.payload.null_rates;
+		if !(0.0..=1.0).contains(&deleted_at) || !(0.0..=1.0).contains(&discount) {
+			return Err("payload.null_rates must be between 0 and 1".to_string());
+		}
+		if self.payload.email_domains == 0 {
+			return Err("payload.email_domains must be greater than 0".to_string());
+		}
+		let invalid = self.payload.values.iter().any(|distribution| match distribution {
+			ValueDistribution::Uniform => false,
+			ValueDistribution::Normal { std_dev } => !std_dev.is_finite() || std_dev <= 0.0,
+			ValueDistribution::Pareto { alpha } => !alpha.is_finite() || alpha <= 0.0,
+		});
+		if invalid {
+			return Err("the std_dev and alpha of payload.values must be greater than 0".to_string());
+		}
+		if self.batch_size == Some(0) || self.batch_size_sweep.contains(&0) {
+			return Err("batch sizes must be greater than 0".to_string());
+		}
+		if self.test_timeout_secs == Some(0) || self.run_timeout_secs == Some(0) {
+			return Err("timeouts must be greater than 0".to_string());
+		}
+		if let Some(AccessDistribution::Zipfian { theta }) = self.distribution {
+			if !(theta > 0.0 && theta < 1.0) {
+				return Err("the zipfian theta must be between 0 and 1, exclusive".to_string());
+			}
+		}
+		if let Some(trace) = &self.trace {
+			if !trace::is_valid_name(&trace.name) {
+				return Err("trace.name must be 1 to 64 letters, digits, '-' or '_'".to_string());
+			}
+			if self.workloads.is_empty() {
+				return Err("a trace needs workloads to record or replay".to_string());
+			}
+		}
+		if let Some(import) = &self.import {
+			if !trace::is_valid_name(&import.name) {
+				return Err("import.name must be 1 to 64 letters, digits, '-' or '_'".to_string());
+			}
+			if import.columns.is_empty() {
+				return Err("an import needs columns to load".to_string());
+			}
+			let names: HashSet<&str> = import.columns.iter().map(|column| column.name.as_str()).collect();
+			if names.len() < import.columns.len() {
+				return Err("the columns of an import must have different names".to_string());
+			}
+		}
+		if self.prebuilt {
+			if self.fresh_database || self.fixtures || !self.workloads.is_empty() {
+				return Err("a prebuilt dataset can't be combined with fresh_database, fixtures or workloads".to_string());
+			}
+			if let Some(test) = self.tests.iter().find(|test| test.is_write()) {
+				return Err(format!("{} writes, it can't run on a prebuilt dataset", test));
+			}
+			if self.selected_tests().is_empty() && !self.analytics && self.import.is_none() && !self.backup {
+				return Err("no selected test leaves a prebuilt dataset unchanged".to_string());
+			}
+		}
+		if let Some(url) = &self.notify_url {
+			if !url.starts_with("http://") && !url.starts_with("https://") {
+				return Err("notify_url must be an http:// or https:// URL".to_string());
+			}
+		}
+		Ok(())
+	}
+
+	// Tests to run, in the standard order: those listed that have one of the tags; all of them
+	// when neither tests nor tags are given, unless the plan runs workloads, the analytical
+	// queries, an import or the backup. Over a prebuilt dataset, only those that don't write
+	pub fn selected_tests(&self) -> Vec<BenchmarkTest> {
+		let others = !self.workloads.is_empty() || self.analytics || self.import.is_some() || self.backup;
+		if self.tests.is_empty() && self.tags.is_empty() && others {
+			return Vec::new();
+		}
+		TEST_REGISTRY.iter()
+			.filter(|descriptor| self.tests.is_empty() || self.tests.contains(&descriptor.test))
+			.filter(|descriptor| self.tags.is_empty() || self.tags.iter().any(|&tag| descriptor.has_tag(tag)))
+			.filter(|descriptor| !self.prebuilt || !descriptor.has_tag(TestTag::Write))
+			.map(|descriptor| descriptor.test)
+			.collect()
+	}
+
+	// Names of the results the plan produces, in order: its tests, workloads, analytical queries,
+	// import and backup
+	pub fn result_names(&self) -> Vec<&'static str> {
+		let analytics = if self.analytics { AnalyticsQuery::ALL.as_slice() } else { &[] };
+		let import = if self.import.is_some() { IMPORT_RESULTS.as_slice() } else { &[] };
+		let backup = if self.backup { BACKUP_RESULTS.as_slice() } else { &[] };
+		self.selected_tests()
+			.iter()
+			.map(|test| test.name())
+			.chain(self.workloads.iter().map(|workload| workload.name()))
+			.chain(analytics.iter().map(|query| query.name()))
+			.chain(import.iter().copied())
+			.chain(backup.iter().copied())
+			.collect()
+	}
+
+	pub fn test_timeout(&self) -> Option<Duration> {
+		self.test_timeout_secs.map(Duration::from_secs)
+	}
+
+	pub fn run_timeout(&self) -> Option<Duration> {
+		self.run_timeout_secs.map(Duration::from_secs)
+	}
+
+	// Number of operations for a test
+	pub fn count_for(&self, test: BenchmarkTest) -> usize {
+		self.counts.get(&test).copied().unwrap_or_else(|| test.default_count())
+	}
+
+	// Cases of the plan, in the order they run: each selected test of the registry once per
+	// variant and repetition
+	pub fn cases(&self) -> Vec<BenchmarkCase> {
+		let mut cases = Vec::new();
+		for test in self.selected_tests() {
+			for variant in self.variants_for(test) {
+				let case = BenchmarkCase {
+					descriptor: test_descriptor(test),
+					variant,
+					count: self.count_for(test),
+					distribution: self.distribution,
+					verify: self.verify,
+					assert_indexes: self.assert_indexes,
+					explain: self.explain,
+					key_encoding: self.key_encoding,
+					key_strategy: self.key_strategy,
+				};
+				cases.extend(std::iter::repeat_n(case, self.repetitions));
+			}
+		}
+		cases
+	}
+
+	// Runs of `test`, each repeated `repetitions` times
+	pub fn variants_for(&self, test: BenchmarkTest) -> Vec<TestVariant> {
+		let cache_states = if self.compare_cache && !test.is_write() {
+			vec![Some(CacheState::Cold), Some(CacheState::Warm)]
+		} else {
+			vec![None]
+		};
+		let mut variants = Vec::new();
+		for batch_size in self.batch_sizes_for(test) {
+			for selectivity in test.selectivities() {
+				for &cache_state in &cache_states {
+					variants.push(TestVariant { batch_size, selectivity, cache_state });
+				}
+			}
+		}
+		variants
+	}
+
+	// Batch sizes a test is run with: one run per size of the sweep for the batched tests
+	pub fn batch_sizes_for(&self, test: BenchmarkTest) -> Vec<Option<usize>> {
+		if !test.is_batched() {
+			vec![None]
+		} else if self.batch_size_sweep.is_empty() {
+			vec![self.batch_size]
+		} else {
+			self.batch_size_sweep.iter().copied().map(Some).collect()
+		}
+	}
+
+	// Thresholds of the outliers and stalls of the results
+	pub fn outlier_thresholds(&self) -> OutlierThresholds {
+		OutlierThresholds { factor: self.outlier_factor, stall: Duration::from_millis(self.stall_ms) }
+	}
+}
+
+fn default_dataset_size() -> usize {
+	DEFAULT_DATASET_SIZE
+}
+
+fn default_workload_operations() -> usize {
+	DEFAULT_WORKLOAD_OPERATIONS
+}
+
+fn default_analytics_scale() -> usize {
+	DEFAULT_ANALYTICS_SCALE
+}
+
+fn default_outlier_factor() -> f64 {
+	DEFAULT_OUTLIER_FACTOR
+}
+
+fn default_stall_ms() -> u64 {
+	DEFAULT_STALL_MS
+}
+
+fn default_tenants() -> usize {
+	DEFAULT_TENANTS
+}
+
+fn default_writers() -> usize {
+	DEFAULT_WRITERS
+}
+
+fn default_repetitions() -> usize {
+	1
+}
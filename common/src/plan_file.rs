@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::{ Path, PathBuf };
+use anyhow::{ bail, Context, Result };
+use serde::{ de::DeserializeOwned, Deserialize };
+
+use crate::plan::BenchmarkPlan;
+
+// Plans written as files, like `race.toml`: the fields of a `BenchmarkPlan` along with what a race
+// needs besides the plan, in TOML, YAML or JSON. The race CLI loads them, and `POST /run` accepts
+// them as TOML or YAML bodies, keeping only the plan.
+
+// Language a plan is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+	Json,
+	Toml,
+	Yaml,
+}
+
+impl PlanFormat {
+	// Format of a file, from its extension
+	pub fn from_path(path: &Path) -> Result<Self> {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("json") => Ok(PlanFormat::Json),
+			Some("toml") => Ok(PlanFormat::Toml),
+			Some("yaml" | "yml") => Ok(PlanFormat::Yaml),
+			_ => bail!("{} is not a .toml, .yaml, .yml or .json file", path.display()),
+		}
+	}
+
+	// Format of a request body, from its content type
+	pub fn from_content_type(content_type: &str) -> Option<Self> {
+		let mime = content_type.split(';').next().unwrap_or("").trim();
+		match mime {
+			"application/json" => Some(PlanFormat::Json),
+			"application/toml" | "text/toml" => Some(PlanFormat::Toml),
+			"application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Some(PlanFormat::Yaml),
+			_ => None,
+		}
+	}
+
+	pub fn parse<T: DeserializeOwned>(&self, text: &str) -> Result<T> {
+		Ok(match self {
+			PlanFormat::Json => serde_json::from_str(text)?,
+			PlanFormat::Toml => toml::from_str(text)?,
+			PlanFormat::Yaml => serde_yaml::from_str(text)?,
+		})
+	}
+}
+
+// Description of a race: the databases it runs on, the plan, and where its results go
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RaceFile {
+	// Databases to race; the runner's own list when empty
+	#[serde(default)]
+	pub databases: Vec<String>,
+
+	// Dataset sizes to race in turn, each with the rest of the plan, instead of its `dataset_size`
+	#[serde(default)]
+	pub dataset_sizes: Vec<usize>,
+
+	#[serde(default)]
+	pub output: OutputConfig,
+
+	#[serde(flatten)]
+	pub plan: BenchmarkPlan,
+}
+
+impl RaceFile {
+	pub fn load(path: &Path) -> Result<Self> {
+		let text = fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+		PlanFormat::from_path(path)?
+			.parse(&text)
+			.with_context(|| format!("Invalid race file {}", path.display()))
+	}
+
+	// Plans of the race, one per dataset size
+	pub fn plans(&self) -> Vec<BenchmarkPlan> {
+		if self.dataset_sizes.is_empty() {
+			return vec![self.plan.clone()];
+		}
+		self.dataset_sizes
+			.iter()
+			.map(|&dataset_size| BenchmarkPlan { dataset_size, ..self.plan.clone() })
+			.collect()
+	}
+
+	// Plan of a single run, which can't sweep dataset sizes
+	pub fn into_plan(self) -> Result<BenchmarkPlan, String> {
+		match self.plans().as_slice() {
+			[plan] => Ok(plan.clone()),
+			_ => Err("a run has a single dataset size, set dataset_size rather than dataset_sizes".to_string()),
+		}
+	}
+}
+
+// Where the runner writes the results of a race, and in which formats
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutputConfig {
+	// Directory of the results; the runner's own when unset
+	#[serde(default)]
+	pub dir: Option<PathBuf>,
+
+	#[serde(default = "default_output_formats")]
+	pub formats: Vec<OutputFormat>,
+}
+
+impl Default for OutputConfig {
+	fn default() -> Self {
+		Self {
+			dir: None,
+			formats: default_output_formats(),
+		}
+	}
+}
+
+// File written with the results of a race: `race.json` holding the whole race, `results.jsonl`
+// and `results.csv` with one result per line, the comparison report `report.html`, or the
+// Markdown leaderboard `leaderboard.md`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+	Json,
+	Jsonl,
+	Csv,
+	Html,
+	Markdown,
+}
+
+fn default_output_formats() -> Vec<OutputFormat> {
+	vec![OutputFormat::Json, OutputFormat::Html]
+}
@@ -0,0 +1,80 @@
+// Column projection, for read_projection: pages of the products are read with only their id and
+// price, then again with every column. A columnar engine such as DuckDB only reads the two
+// columns, while a row store such as SQLite or RocksDB reads whole rows either way, so the gap
+// between the two throughputs shows what a projection saves. The SQL is the same in SQLite and
+// DuckDB.
+use std::future::Future;
+use std::time::Instant;
+use anyhow::{ ensure, Result };
+
+use crate::benchmark::measure_execution;
+use crate::context::RunContext;
+use crate::models::BenchmarkResult;
+use crate::plan::BenchmarkTest;
+
+// A page of the products, with only the columns the projection needs
+pub const READ_PROJECTION_QUERY: &str = "SELECT id, price FROM products LIMIT ? OFFSET ?";
+
+// The same page with every column
+pub const READ_ALL_COLUMNS_QUERY: &str = "SELECT * FROM products LIMIT ? OFFSET ?";
+
+// Columns read by a pass of read_projection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Projection {
+	IdAndPrice,
+	AllColumns,
+}
+
+impl Projection {
+	pub fn query(self) -> &'static str {
+		match self {
+			Projection::IdAndPrice => READ_PROJECTION_QUERY,
+			Projection::AllColumns => READ_ALL_COLUMNS_QUERY,
+		}
+	}
+
+	// Record the rows a pass read; only those of the timed projection are the test's operations
+	pub fn record(self, context: &RunContext, rows: u64) {
+		if self == Projection::IdAndPrice {
+			context.record_operations(rows);
+		}
+	}
+}
+
+// Run read_projection: `read` reads `count` products with the columns of a projection and returns
+// the rows it read. The projection is the timed test, every column is read after it, so the
+// comparison favors the full rows with the pages the projection left in the cache
+pub async fn measure_projection<F, Fut>(
+	database_name: &str,
+	count: usize,
+	cpu_count: usize,
+	read: F
+)
+	-> Result<BenchmarkResult>
+	where F: Fn(Projection) -> Fut, Fut: Future<Output = Result<u64>>
+{
+	let mut projected = 0;
+	let mut result = measure_execution(
+		database_name,
+		BenchmarkTest::ReadProjection.name(),
+		count,
+		cpu_count,
+		|| async {
+			projected = read(Projection::IdAndPrice).await?;
+			Ok(())
+		}
+	).await?;
+
+	let start = Instant::now();
+	let all_columns = read(Projection::AllColumns).await?;
+	let duration = start.elapsed();
+	ensure!(
+		all_columns == projected,
+		"{} products read with every column, {} with the projection",
+		all_columns,
+		projected
+	);
+
+	result.all_columns_ops_per_second = Some((all_columns as f64) / duration.as_secs_f64().max(f64::EPSILON));
+	Ok(result)
+}
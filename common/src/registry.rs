@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{ Arc, Mutex };
+use anyhow::{ bail, Result };
+use tracing::info;
+
+use crate::benchmark::DatabaseBenchmark;
+use crate::config::ServerConfig;
+use crate::server::run_server;
+
+// Backends a server binary can serve, by name, so a crate outside of the workspace can plug its
+// own `DatabaseBenchmark` into the servers without forking them. The crate registers a factory
+// building its benchmark from the server settings, then `serve` runs the server of the backend
+// named by `--backend`, or of the only one registered. A binary named `benchmarks-<name>`, or a
+// `benchmarks` binary registering several backends, is started by the race runner like the
+// built-in databases.
+
+// Port of a registered backend when none is configured, past those of the built-in databases
+pub const DEFAULT_BACKEND_PORT: u16 = 3100;
+
+type Launch = Arc<dyn Fn(ServerConfig) -> Pin<Box<dyn Future<Output = Result<()>>>> + Send + Sync>;
+
+static BACKENDS: Mutex<BTreeMap<String, Launch>> = Mutex::new(BTreeMap::new());
+
+// Make the benchmark built by `factory` available as `name`, which is lowercase letters, digits,
+// '-' or '_', as it names the binary and the data directory of the backend for the race runner
+pub fn register_backend<T, F, Fut>(name: &str, factory: F) -> Result<()>
+	where
+		T: DatabaseBenchmark + Send + Sync + 'static,
+		F: Fn(ServerConfig) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<T>> + 'static
+{
+	let valid = !name.is_empty() &&
+		name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+	if !valid {
+		bail!("Invalid backend name {:?}, expected lowercase letters, digits, '-' or '_'", name);
+	}
+
+	let mut backends = BACKENDS.lock().unwrap();
+	if backends.contains_key(name) {
+		bail!("A backend named {} is already registered", name);
+	}
+
+	let factory = Arc::new(factory);
+	let launch: Launch = Arc::new(move |config: ServerConfig| {
+		let factory = factory.clone();
+		Box::pin(async move {
+			let benchmark = factory(config.clone()).await?;
+			run_server(benchmark, &config, DEFAULT_BACKEND_PORT).await
+		})
+	});
+	backends.insert(name.to_string(), launch);
+
+	Ok(())
+}
+
+// Names of the registered backends, in alphabetical order
+pub fn registered_backends() -> Vec<String> {
+	BACKENDS.lock().unwrap().keys().cloned().collect()
+}
+
+// Build the benchmark of the backend named `name` and run its server until it shuts down
+pub async fn serve_backend(name: &str, config: &ServerConfig) -> Result<()> {
+	let launch = BACKENDS.lock().unwrap().get(name).cloned();
+	let Some(launch) = launch else {
+		bail!("Unknown backend {}, expected one of: {}", name, registered_backends().join(", "));
+	};
+
+	info!("Starting the {} benchmark", name);
+	launch(config.clone()).await
+}
+
+// Run the server of the backend named by `--backend`, or of the only registered backend
+pub async fn serve(config: &ServerConfig) -> Result<()> {
+	let name = match &config.backend {
+		Some(name) => name.clone(),
+		None => match registered_backends().as_slice() {
+			[name] => name.clone(),
+			[] => bail!("No backend is registered"),
+			names => bail!("Several backends are registered, pick one with --backend: {}", names.join(", ")),
+		},
+	};
+
+	serve_backend(&name, config).await
+}
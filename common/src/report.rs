@@ -0,0 +1,733 @@
+use std::fmt::Write;
+
+use crate::analytics::AnalyticsQuery;
+use crate::benchmark::{ test_descriptor, TestTag };
+use crate::models::{ BenchmarkResult, BenchmarkResults, Scores };
+use crate::plan::BenchmarkTest;
+
+// Chart geometry (in SVG user units)
+const CHART_WIDTH: f64 = 640.0;
+const BAR_HEIGHT: f64 = 22.0;
+const BAR_GAP: f64 = 8.0;
+const LABEL_WIDTH: f64 = 160.0;
+const VALUE_WIDTH: f64 = 110.0;
+const MARGIN: f64 = 12.0;
+
+// Latency distribution chart: height of the plot area, and room left of it for the fraction labels
+const CDF_PLOT_HEIGHT: f64 = 240.0;
+const CDF_AXIS_WIDTH: f64 = 48.0;
+
+// Room above a standalone chart for its title
+const TITLE_HEIGHT: f64 = 32.0;
+
+// Text of standalone charts, which don't get the style of the HTML report
+const CHART_STYLE: &str =
+	"text { font-family: -apple-system, Segoe UI, Helvetica, Arial, sans-serif; font-size: 12px; fill: #222; }";
+
+// One color per database, cycled when there are more databases than colors
+const PALETTE: [&str; 6] = ["#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#76b7b2", "#b07aa1"];
+
+// Render a standalone HTML report (no external assets) for one or more result sets
+pub fn render_html_report(runs: &[BenchmarkResults]) -> String {
+	let databases = database_names(runs);
+	let tests = test_names(runs);
+
+	let mut html = String::new();
+	html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+	html.push_str("<title>Database Race Report</title>\n");
+	html.push_str(
+		"<style>\
+		body { font-family: -apple-system, Segoe UI, Helvetica, Arial, sans-serif; margin: 2em; color: #222; }\
+		h1 { margin-bottom: 0.2em; }\
+		h2 { margin-top: 2em; border-bottom: 1px solid #ddd; padding-bottom: 0.2em; }\
+		h3 { margin-bottom: 0.3em; }\
+		.meta { color: #666; font-size: 0.9em; }\
+		.legend span { display: inline-block; margin-right: 1.5em; }\
+		.legend i { display: inline-block; width: 0.9em; height: 0.9em; margin-right: 0.4em; vertical-align: middle; }\
+		svg text { font-size: 12px; fill: #222; }\
+		table { border-collapse: collapse; margin-top: 1em; }\
+		th, td { border: 1px solid #ddd; padding: 4px 10px; text-align: right; }\
+		th:first-child, td:first-child { text-align: left; }\
+		</style>\n"
+	);
+	html.push_str("</head>\n<body>\n<h1>Database Race Report</h1>\n");
+
+	if runs.is_empty() {
+		html.push_str("<p>No benchmark results available yet. Use /run to run benchmarks.</p>\n");
+		html.push_str("</body>\n</html>\n");
+		return html;
+	}
+
+	// Metadata about each run included in the report
+	html.push_str("<p class=\"meta\">");
+	for (i, run) in runs.iter().enumerate() {
+		if i > 0 {
+			html.push_str("<br>");
+		}
+		let _ = write!(
+			html,
+			"{}: {} tests, run at {}",
+			escape(&run.database),
+			run.results.len(),
+			run.timestamp.to_rfc3339()
+		);
+	}
+	html.push_str("</p>\n");
+
+	// Legend mapping colors to databases
+	html.push_str("<p class=\"legend\">");
+	for (i, database) in databases.iter().enumerate() {
+		let _ = write!(
+			html,
+			"<span><i style=\"background:{}\"></i>{}</span>",
+			color(i),
+			escape(database)
+		);
+	}
+	html.push_str("</p>\n");
+
+	// Throughput: one bar chart per test
+	html.push_str("<h2>Throughput (operations per second)</h2>\n");
+	for test in &tests {
+		let bars: Vec<(usize, &str, f64)> = databases
+			.iter()
+			.enumerate()
+			.filter_map(|(i, database)| {
+				find_measured_result(runs, database, test).map(|result| {
+					(i, database.as_str(), result.operations_per_second)
+				})
+			})
+			.collect();
+
+		let _ = writeln!(html, "<h3>{}</h3>", escape(test));
+		html.push_str(&bar_chart(&bars, "ops/s"));
+	}
+
+	// Latency: mean time per operation for every test, one row per database
+	html.push_str("<h2>Latency distribution (mean time per operation)</h2>\n");
+	for (i, database) in databases.iter().enumerate() {
+		let points: Vec<(&str, f64)> = tests
+			.iter()
+			.filter_map(|test| {
+				find_measured_result(runs, database, test).map(|result| {
+					(test.as_str(), mean_latency_us(result))
+				})
+			})
+			.collect();
+
+		let _ = writeln!(html, "<h3>{}</h3>", escape(database));
+		html.push_str(&latency_plot(&points, color(i)));
+	}
+
+	// Harness overhead, for the runs that calibrated it, to tell it apart from the database
+	let calibrated: Vec<_> = runs
+		.iter()
+		.filter_map(|run| run.calibration.map(|stats| (run.database.as_str(), stats)))
+		.collect();
+	if !calibrated.is_empty() {
+		html.push_str("<h2>Harness overhead (time per operation with no database)</h2>\n<table>\n");
+		html.push_str(
+			"<tr><th>Database</th><th>Generate user</th><th>RFC 3339</th><th>bincode</th><th>Async call</th><th>Blocking thread</th><th>Record operation</th></tr>\n"
+		);
+		for (database, stats) in calibrated {
+			let _ = writeln!(
+				html,
+				"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+				escape(database),
+				format_micros(stats.generate_user_ns / 1000.0),
+				format_micros(stats.rfc3339_ns / 1000.0),
+				format_micros(stats.bincode_ns / 1000.0),
+				format_micros(stats.async_call_ns / 1000.0),
+				format_micros(stats.spawn_blocking_ns / 1000.0),
+				format_micros(stats.record_operation_ns / 1000.0)
+			);
+		}
+		html.push_str("</table>\n");
+	}
+
+	// Raw numbers
+	html.push_str("<h2>Raw results</h2>\n<table>\n");
+	html.push_str(
+		"<tr><th>Test</th><th>Database</th><th>Operations</th><th>Duration (ms)</th><th>Ops/s</th><th>CPUs</th><th>Status</th></tr>\n"
+	);
+	for test in &tests {
+		for database in &databases {
+			if let Some(result) = find_result(runs, database, test) {
+				let _ = writeln!(
+					html,
+					"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>",
+					escape(test),
+					escape(database),
+					result.operations,
+					result.duration_ms,
+					result.operations_per_second,
+					result.cpu_count,
+					match result.status.message() {
+						Some(message) => format!("{}: {}", result.status.as_str(), escape(message)),
+						None => result.status.as_str().to_string(),
+					}
+				);
+			}
+		}
+	}
+	html.push_str("</table>\n</body>\n</html>\n");
+
+	html
+}
+
+// Standalone SVG images of a race, to embed without the HTML report: a throughput bar chart per
+// test, and the distribution of the latency of the tests of each database. Each chart comes with
+// its file name.
+pub fn render_svg_charts(runs: &[BenchmarkResults]) -> Vec<(String, String)> {
+	let databases = database_names(runs);
+	let mut charts = Vec::new();
+
+	for test in test_names(runs) {
+		let bars: Vec<(usize, &str, f64)> = databases
+			.iter()
+			.enumerate()
+			.filter_map(|(i, database)| {
+				find_measured_result(runs, database, &test).map(|result| {
+					(i, database.as_str(), result.operations_per_second)
+				})
+			})
+			.collect();
+		if bars.is_empty() {
+			continue;
+		}
+
+		let title = format!("{} (operations per second)", test);
+		charts.push((
+			format!("throughput-{}.svg", file_name(&test)),
+			standalone_chart(&title, &bar_chart(&bars, "ops/s"), bar_chart_height(bars.len())),
+		));
+	}
+
+	let series: Vec<(usize, &str, Vec<f64>)> = databases
+		.iter()
+		.enumerate()
+		.map(|(i, database)| {
+			let latencies = test_names(runs)
+				.iter()
+				.filter_map(|test| find_measured_result(runs, database, test))
+				.map(mean_latency_us)
+				.filter(|latency| *latency > 0.0)
+				.collect();
+			(i, database.as_str(), latencies)
+		})
+		.collect();
+	if series.iter().any(|(_, _, latencies)| !latencies.is_empty()) {
+		charts.push((
+			"latency-cdf.svg".to_string(),
+			standalone_chart(
+				"Latency distribution (share of tests by mean time per operation)",
+				&cdf_plot(&series),
+				cdf_plot_height(series.len())
+			),
+		));
+	}
+
+	charts
+}
+
+// SVG document of a chart under its title
+fn standalone_chart(title: &str, chart: &str, height: f64) -> String {
+	let mut svg = String::new();
+	let _ = writeln!(
+		svg,
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+		w = CHART_WIDTH,
+		h = height + TITLE_HEIGHT
+	);
+	let _ = writeln!(svg, "<style>{}</style>", CHART_STYLE);
+	let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>");
+	let _ = writeln!(
+		svg,
+		"<text x=\"{}\" y=\"{}\" style=\"font-size: 15px; font-weight: bold\">{}</text>",
+		MARGIN,
+		TITLE_HEIGHT - 10.0,
+		escape(title)
+	);
+	// The chart keeps its own coordinates, nested below the title
+	let _ = write!(svg, "<svg y=\"{}\"{}", TITLE_HEIGHT, chart.trim_start_matches("<svg"));
+	svg.push_str("</svg>\n");
+	svg
+}
+
+// Name of a test usable in a file name
+fn file_name(test: &str) -> String {
+	test
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+		.collect()
+}
+
+fn bar_chart_height(bars: usize) -> f64 {
+	MARGIN * 2.0 + (bars as f64) * (BAR_HEIGHT + BAR_GAP)
+}
+
+// Horizontal bar chart with one bar per database
+fn bar_chart(bars: &[(usize, &str, f64)], unit: &str) -> String {
+	let max = bars
+		.iter()
+		.map(|(_, _, value)| *value)
+		.fold(0.0, f64::max);
+	let plot_width = CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+	let height = bar_chart_height(bars.len());
+
+	let mut svg = String::new();
+	let _ = writeln!(
+		svg,
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+		w = CHART_WIDTH,
+		h = height
+	);
+
+	for (row, (color_index, label, value)) in bars.iter().enumerate() {
+		let y = MARGIN + (row as f64) * (BAR_HEIGHT + BAR_GAP);
+		let width = if max > 0.0 { (value / max) * plot_width } else { 0.0 };
+		let text_y = y + BAR_HEIGHT / 2.0 + 4.0;
+
+		let _ = writeln!(
+			svg,
+			"<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+			LABEL_WIDTH - 8.0,
+			text_y,
+			escape(label)
+		);
+		let _ = writeln!(
+			svg,
+			"<rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"><title>{:.2} {}</title></rect>",
+			LABEL_WIDTH,
+			y,
+			width,
+			BAR_HEIGHT,
+			color(*color_index),
+			value,
+			unit
+		);
+		let _ = writeln!(
+			svg,
+			"<text x=\"{:.1}\" y=\"{}\">{:.0} {}</text>",
+			LABEL_WIDTH + width + 6.0,
+			text_y,
+			value,
+			unit
+		);
+	}
+
+	svg.push_str("</svg>\n");
+	svg
+}
+
+// Dot plot of mean latency per test on a log10 axis, since tests differ by orders of magnitude
+fn latency_plot(points: &[(&str, f64)], color: &str) -> String {
+	let positive: Vec<f64> = points
+		.iter()
+		.map(|(_, value)| *value)
+		.filter(|value| *value > 0.0)
+		.collect();
+	let min = positive.iter().cloned().fold(f64::INFINITY, f64::min);
+	let max = positive.iter().cloned().fold(0.0, f64::max);
+
+	// Round the axis out to whole decades
+	let (low, high) = if positive.is_empty() {
+		(0.0, 1.0)
+	} else {
+		let low = min.log10().floor();
+		let high = max.log10().ceil().max(low + 1.0);
+		(low, high)
+	};
+
+	let plot_width = CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+	let axis_y = MARGIN + (points.len() as f64) * (BAR_HEIGHT + BAR_GAP);
+	let height = axis_y + 30.0;
+	let scale = |value: f64| -> f64 {
+		if value <= 0.0 {
+			LABEL_WIDTH
+		} else {
+			LABEL_WIDTH + ((value.log10() - low) / (high - low)) * plot_width
+		}
+	};
+
+	let mut svg = String::new();
+	let _ = writeln!(
+		svg,
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+		w = CHART_WIDTH,
+		h = height
+	);
+
+	// Decade grid lines and labels
+	let mut decade = low;
+	while decade <= high {
+		let x = scale((10f64).powf(decade));
+		let _ = writeln!(
+			svg,
+			"<line x1=\"{x:.1}\" y1=\"{}\" x2=\"{x:.1}\" y2=\"{}\" stroke=\"#ddd\"/>",
+			MARGIN,
+			axis_y,
+			x = x
+		);
+		let _ = writeln!(
+			svg,
+			"<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+			x,
+			axis_y + 16.0,
+			format_micros((10f64).powf(decade))
+		);
+		decade += 1.0;
+	}
+
+	for (row, (label, value)) in points.iter().enumerate() {
+		let y = MARGIN + (row as f64) * (BAR_HEIGHT + BAR_GAP) + BAR_HEIGHT / 2.0;
+		let x = scale(*value);
+
+		let _ = writeln!(
+			svg,
+			"<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+			LABEL_WIDTH - 8.0,
+			y + 4.0,
+			escape(label)
+		);
+		let _ = writeln!(
+			svg,
+			"<line x1=\"{}\" y1=\"{y}\" x2=\"{x:.1}\" y2=\"{y}\" stroke=\"{c}\" stroke-opacity=\"0.35\"/>\
+			<circle cx=\"{x:.1}\" cy=\"{y}\" r=\"5\" fill=\"{c}\"><title>{v}</title></circle>",
+			LABEL_WIDTH,
+			y = y,
+			x = x,
+			c = color,
+			v = format_micros(*value)
+		);
+		let _ = writeln!(
+			svg,
+			"<text x=\"{:.1}\" y=\"{}\">{}</text>",
+			x + 10.0,
+			y + 4.0,
+			format_micros(*value)
+		);
+	}
+
+	svg.push_str("</svg>\n");
+	svg
+}
+
+fn cdf_plot_height(series: usize) -> f64 {
+	MARGIN + CDF_PLOT_HEIGHT + 30.0 + (series as f64) * 18.0 + MARGIN
+}
+
+// Empirical distribution of latencies, one step line per database: the share of its tests whose
+// mean time per operation is at most the latency of the x axis, on a log10 scale. The legend sits
+// below the axis.
+fn cdf_plot(series: &[(usize, &str, Vec<f64>)]) -> String {
+	let all = series.iter().flat_map(|(_, _, latencies)| latencies.iter().cloned());
+	let min = all.clone().fold(f64::INFINITY, f64::min);
+	let max = all.fold(0.0, f64::max);
+	let low = min.log10().floor();
+	let high = max.log10().ceil().max(low + 1.0);
+
+	let left = CDF_AXIS_WIDTH;
+	let right = CHART_WIDTH - MARGIN * 2.0;
+	let top = MARGIN;
+	let bottom = MARGIN + CDF_PLOT_HEIGHT;
+	let x = |value: f64| left + ((value.log10() - low) / (high - low)) * (right - left);
+	let y = |share: f64| bottom - share * CDF_PLOT_HEIGHT;
+
+	let mut svg = String::new();
+	let _ = writeln!(
+		svg,
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+		w = CHART_WIDTH,
+		h = cdf_plot_height(series.len())
+	);
+
+	// Grid: decades of latency, quarters of the tests
+	let mut decade = low;
+	while decade <= high {
+		let grid_x = x((10f64).powf(decade));
+		let _ = writeln!(
+			svg,
+			"<line x1=\"{x:.1}\" y1=\"{}\" x2=\"{x:.1}\" y2=\"{}\" stroke=\"#ddd\"/>",
+			top,
+			bottom,
+			x = grid_x
+		);
+		let _ = writeln!(
+			svg,
+			"<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+			grid_x,
+			bottom + 16.0,
+			format_micros((10f64).powf(decade))
+		);
+		decade += 1.0;
+	}
+	for quarter in 0..=4 {
+		let share = (quarter as f64) / 4.0;
+		let _ = writeln!(
+			svg,
+			"<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>",
+			left,
+			right,
+			y = y(share)
+		);
+		let _ = writeln!(
+			svg,
+			"<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{:.0}%</text>",
+			left - 6.0,
+			y(share) + 4.0,
+			share * 100.0
+		);
+	}
+
+	for (row, (color_index, database, latencies)) in series.iter().enumerate() {
+		let mut sorted = latencies.clone();
+		sorted.sort_by(|a, b| a.total_cmp(b));
+
+		// Steps up by one test at each latency
+		if !sorted.is_empty() {
+			let mut path = format!("M {:.1} {:.1}", left, y(0.0));
+			for (i, latency) in sorted.iter().enumerate() {
+				let _ = write!(
+					path,
+					" H {:.1} V {:.1}",
+					x(*latency),
+					y(((i + 1) as f64) / (sorted.len() as f64))
+				);
+			}
+			let _ = write!(path, " H {:.1}", right);
+			let _ = writeln!(
+				svg,
+				"<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+				path,
+				color(*color_index)
+			);
+		}
+
+		let legend_y = bottom + 36.0 + (row as f64) * 18.0;
+		let _ = writeln!(
+			svg,
+			"<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{}\"/>\
+			<text x=\"{}\" y=\"{}\">{} ({} tests)</text>",
+			left,
+			legend_y - 9.0,
+			color(*color_index),
+			left + 16.0,
+			legend_y,
+			escape(database),
+			sorted.len()
+		);
+	}
+
+	svg.push_str("</svg>\n");
+	svg
+}
+
+// Score every run against the run of `baseline`, named in any case, replacing the scores it had.
+// The runs are left without scores when none of them is of `baseline`.
+pub fn score_runs(runs: &mut [BenchmarkResults], baseline: &str) {
+	let scores: Vec<Option<Scores>> = runs
+		.iter()
+		.map(|run| compute_scores(runs, &run.database, baseline))
+		.collect();
+	for (run, scores) in runs.iter_mut().zip(scores) {
+		run.scores = scores;
+	}
+}
+
+// Scores of `database` against `baseline`, from the most recent measured result of each test of
+// both; `None` when `baseline`, named in any case, has no run
+pub fn compute_scores(runs: &[BenchmarkResults], database: &str, baseline: &str) -> Option<Scores> {
+	let baseline = runs
+		.iter()
+		.map(|run| run.database.as_str())
+		.find(|name| name.eq_ignore_ascii_case(baseline))?;
+
+	let mut overall = Vec::new();
+	let mut categories: [Vec<f64>; 4] = Default::default();
+	for test in test_names(runs) {
+		let throughput = |database| {
+			find_measured_result(runs, database, &test)
+				.map(|result| result.operations_per_second)
+				.filter(|&throughput| throughput > 0.0)
+		};
+		let (Some(throughput), Some(baseline_throughput)) = (throughput(database), throughput(baseline)) else {
+			continue;
+		};
+
+		let ratio = throughput / baseline_throughput;
+		overall.push(ratio);
+		let tags = score_categories(&test);
+		for (tag, ratios) in SCORE_CATEGORIES.iter().zip(&mut categories) {
+			if tags.contains(tag) {
+				ratios.push(ratio);
+			}
+		}
+	}
+
+	let score = |ratios: &[f64]| geometric_mean(ratios).map(|mean| mean * 100.0);
+	let [read, write, update, analytics] = &categories;
+	Some(Scores {
+		baseline: baseline.to_string(),
+		overall: score(&overall),
+		read: score(read),
+		write: score(write),
+		update: score(update),
+		analytics: score(analytics),
+		tests: overall.len(),
+	})
+}
+
+// Tags the results are scored by; the joins are reads too
+const SCORE_CATEGORIES: [TestTag; 4] = [TestTag::Read, TestTag::Write, TestTag::Update, TestTag::Analytics];
+
+// Categories of the result named `test`: the tags of a test, analytics for an analytical query, and
+// none for the workloads, the import and the backup, which only count in the overall score
+fn score_categories(test: &str) -> Vec<TestTag> {
+	if let Ok(test) = test.parse::<BenchmarkTest>() {
+		test_descriptor(test).tags.to_vec()
+	} else if AnalyticsQuery::ALL.iter().any(|query| query.name() == test) {
+		vec![TestTag::Analytics]
+	} else {
+		Vec::new()
+	}
+}
+
+// Geometric mean of positive values, `None` without any
+pub fn geometric_mean(values: &[f64]) -> Option<f64> {
+	if values.is_empty() {
+		return None;
+	}
+	let log_sum: f64 = values.iter().map(|value| value.ln()).sum();
+	Some((log_sum / (values.len() as f64)).exp())
+}
+
+// Mean latency of a single operation in microseconds
+fn mean_latency_us(result: &BenchmarkResult) -> f64 {
+	if result.operations == 0 {
+		return 0.0;
+	}
+	(result.duration().as_secs_f64() * 1e6) / (result.operations as f64)
+}
+
+pub fn format_micros(micros: f64) -> String {
+	if micros >= 1_000_000.0 {
+		format!("{:.2} s", micros / 1_000_000.0)
+	} else if micros >= 1000.0 {
+		format!("{:.2} ms", micros / 1000.0)
+	} else {
+		format!("{:.2} µs", micros)
+	}
+}
+
+fn color(index: usize) -> &'static str {
+	PALETTE[index % PALETTE.len()]
+}
+
+// Databases in order of first appearance
+pub fn database_names(runs: &[BenchmarkResults]) -> Vec<String> {
+	let mut names: Vec<String> = Vec::new();
+	for run in runs {
+		if !names.contains(&run.database) {
+			names.push(run.database.clone());
+		}
+	}
+	names
+}
+
+// Tests in order of first appearance
+pub fn test_names(runs: &[BenchmarkResults]) -> Vec<String> {
+	let mut names: Vec<String> = Vec::new();
+	for result in runs.iter().flat_map(|run| run.results.iter()) {
+		if !names.contains(&result.test_name) {
+			names.push(result.test_name.clone());
+		}
+	}
+	names
+}
+
+// The most recent result for a test on a database
+pub fn find_result<'a>(
+	runs: &'a [BenchmarkResults],
+	database: &str,
+	test: &str
+) -> Option<&'a BenchmarkResult> {
+	runs
+		.iter()
+		.rev()
+		.filter(|run| run.database == database)
+		.flat_map(|run| run.results.iter())
+		.find(|result| result.test_name == test)
+}
+
+// Result of a test that produced a measurement; failed and skipped tests are left out of the charts
+pub fn find_measured_result<'a>(
+	runs: &'a [BenchmarkResults],
+	database: &str,
+	test: &str
+) -> Option<&'a BenchmarkResult> {
+	find_result(runs, database, test).filter(|result| result.status.is_measured())
+}
+
+fn escape(text: &str) -> String {
+	text
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use chrono::Utc;
+	use uuid::Uuid;
+
+	use super::score_runs;
+	use crate::models::{ BenchmarkResult, BenchmarkResults, TestStatus };
+
+	// Run of `database` with a passed result of each test at the given throughput
+	fn run_of(database: &str, throughputs: &[(&str, f64)]) -> BenchmarkResults {
+		let results = throughputs
+			.iter()
+			.map(|&(test, throughput)| BenchmarkResult {
+				operations: 1000,
+				operations_per_second: throughput,
+				..BenchmarkResult::new(database, test, 1, Duration::from_secs(1), TestStatus::Passed)
+			})
+			.collect();
+		BenchmarkResults {
+			run_id: Uuid::new_v4(),
+			database: database.to_string(),
+			results,
+			timestamp: Utc::now(),
+			size_timeline: Vec::new(),
+			calibration: None,
+			scores: None,
+		}
+	}
+
+	#[test]
+	fn races_are_scored_against_a_baseline() {
+		let mut runs = vec![
+			run_of("SQLite", &[("read_by_id_many_times", 100.0), ("insert_many_at_once", 100.0), ("ycsb_a", 100.0)]),
+			run_of("DuckDB", &[("read_by_id_many_times", 400.0), ("insert_many_at_once", 25.0), ("ycsb_a", 100.0)]),
+		];
+		score_runs(&mut runs, "sqlite");
+
+		let baseline = runs[0].scores.clone().unwrap();
+		assert_eq!((baseline.baseline.as_str(), baseline.overall, baseline.tests), ("SQLite", Some(100.0), 3));
+
+		// 4 times faster reads, 4 times slower writes, the same workload
+		let scores = runs[1].scores.clone().unwrap();
+		assert_eq!(scores.tests, 3);
+		assert!((scores.overall.unwrap() - 100.0).abs() < 1e-9);
+		assert!((scores.read.unwrap() - 400.0).abs() < 1e-9);
+		assert!((scores.write.unwrap() - 25.0).abs() < 1e-9);
+		assert_eq!((scores.update, scores.analytics), (None, None));
+
+		// Without a run of the baseline, nothing is scored
+		score_runs(&mut runs, "RocksDB");
+		assert!(runs.iter().all(|run| run.scores.is_none()));
+	}
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::context::RunContext;
+
+// How operations failing with a transient error (a busy or locked database, a write conflict)
+// are retried: up to `max_retries` times, waiting twice as long before each retry, from
+// `initial_backoff` up to `max_backoff`
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+	pub max_retries: u32,
+	pub initial_backoff: Duration,
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_retries: 5,
+			initial_backoff: Duration::from_millis(10),
+			max_backoff: Duration::from_secs(1),
+		}
+	}
+}
+
+impl RetryPolicy {
+	// Time to wait before the given retry, counting from 1
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+		self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+	}
+
+	// Run `operation` until it succeeds, fails with an error `is_transient` rejects or runs out of
+	// retries. Each retry is recorded in the stats of the current test. The backoff blocks the
+	// thread, so this is meant for the blocking closures the databases are queried in
+	pub fn run<T, E>(
+		&self,
+		context: &RunContext,
+		is_transient: impl Fn(&E) -> bool,
+		mut operation: impl FnMut() -> Result<T, E>
+	) -> Result<T, E> {
+		let mut retries = 0;
+
+		loop {
+			match operation() {
+				Err(e) if retries < self.max_retries && is_transient(&e) && context.check_cancelled().is_ok() => {
+					retries += 1;
+					context.record_retry();
+					std::thread::sleep(self.backoff(retries));
+				}
+				result => {
+					return result;
+				}
+			}
+		}
+	}
+}
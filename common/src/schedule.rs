@@ -0,0 +1,166 @@
+use std::fmt;
+use std::str::FromStr;
+use chrono::{ DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc };
+
+// Times of periodic runs, written as a cron expression in UTC: minute, hour, day of the month,
+// month and day of the week (0 or 7 for Sunday). A field is `*`, a value, a range `a-b` or a
+// comma-separated list of them, each optionally stepped with `/n`. `@hourly`, `@daily`, `@weekly`
+// and `@monthly` stand for the usual expressions. Like cron, a time matches when its day matches
+// either the day of the month or the day of the week when both are restricted.
+
+// How far ahead the next time is looked for: every day of the month and week comes back within
+// a few years, so a schedule with no time in this window has none at all (the 30th of February)
+const SEARCH_YEARS: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+	expression: String,
+	// Bit `n` set when value `n` of the field matches
+	minutes: u64,
+	hours: u64,
+	days_of_month: u64,
+	months: u64,
+	days_of_week: u64,
+	// Whether the day fields are `*`, which changes how they combine
+	any_day_of_month: bool,
+	any_day_of_week: bool,
+}
+
+impl Schedule {
+	// First time of the schedule strictly after `time`, to the minute
+	pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+		let start = time.date_naive().and_hms_opt(time.hour(), time.minute(), 0)? + Duration::minutes(1);
+		let limit = start + Duration::days(366 * SEARCH_YEARS);
+
+		let mut candidate = start;
+		while candidate < limit {
+			let date = candidate.date();
+			if !matches(self.months, date.month()) {
+				let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+				candidate = NaiveDate::from_ymd_opt(year, month, 1)?.and_time(NaiveTime::MIN);
+				continue;
+			}
+			if !self.matches_day(date) {
+				candidate = date.succ_opt()?.and_time(NaiveTime::MIN);
+				continue;
+			}
+			if !matches(self.hours, candidate.hour()) {
+				candidate = date.and_hms_opt(candidate.hour(), 0, 0)? + Duration::hours(1);
+				continue;
+			}
+			if !matches(self.minutes, candidate.minute()) {
+				candidate += Duration::minutes(1);
+				continue;
+			}
+			return Some(Utc.from_utc_datetime(&candidate));
+		}
+
+		None
+	}
+
+	fn matches_day(&self, date: NaiveDate) -> bool {
+		let day_of_month = matches(self.days_of_month, date.day());
+		let day_of_week = matches(self.days_of_week, date.weekday().num_days_from_sunday());
+		match (self.any_day_of_month, self.any_day_of_week) {
+			(false, false) => day_of_month || day_of_week,
+			_ => day_of_month && day_of_week,
+		}
+	}
+}
+
+impl fmt::Display for Schedule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.expression)
+	}
+}
+
+impl FromStr for Schedule {
+	type Err = String;
+
+	fn from_str(expression: &str) -> Result<Self, Self::Err> {
+		let expanded = match expression.trim() {
+			"@hourly" => "0 * * * *",
+			"@daily" | "@midnight" => "0 0 * * *",
+			"@weekly" => "0 0 * * 0",
+			"@monthly" => "0 0 1 * *",
+			expression => expression,
+		};
+		let fields: Vec<&str> = expanded.split_whitespace().collect();
+		let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+			return Err(
+				format!(
+					"invalid schedule: {}, expected 5 fields (minute hour day-of-month month day-of-week)",
+					expression
+				)
+			);
+		};
+
+		let field = |text: &str, name: &str, min: u32, max: u32| {
+			parse_field(text, min, max).map_err(|message| {
+				format!("invalid {} field {} in schedule {}: {}", name, text, expression, message)
+			})
+		};
+		let mut days_of_week_bits = field(days_of_week, "day of the week", 0, 7)?;
+		// Sunday is both 0 and 7
+		if days_of_week_bits & (1 << 7) != 0 {
+			days_of_week_bits |= 1;
+		}
+
+		Ok(Schedule {
+			expression: expression.trim().to_string(),
+			minutes: field(minutes, "minute", 0, 59)?,
+			hours: field(hours, "hour", 0, 23)?,
+			days_of_month: field(days_of_month, "day of the month", 1, 31)?,
+			months: field(months, "month", 1, 12)?,
+			days_of_week: days_of_week_bits,
+			any_day_of_month: days_of_month == "*",
+			any_day_of_week: days_of_week == "*",
+		})
+	}
+}
+
+fn matches(bits: u64, value: u32) -> bool {
+	bits & (1 << value) != 0
+}
+
+// Bits of the values of a field between `min` and `max`
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+	let mut bits = 0;
+	for item in field.split(',') {
+		let (range, step) = match item.split_once('/') {
+			Some((range, step)) => {
+				let step: u32 = step.parse().map_err(|_| format!("invalid step {}", step))?;
+				if step == 0 {
+					return Err("the step must be at least 1".to_string());
+				}
+				(range, step)
+			}
+			None => (item, 1),
+		};
+
+		let value = |text: &str| -> Result<u32, String> {
+			let value: u32 = text.parse().map_err(|_| format!("invalid value {}", text))?;
+			if value < min || value > max {
+				return Err(format!("{} is not between {} and {}", value, min, max));
+			}
+			Ok(value)
+		};
+		let (first, last) = match range {
+			"*" => (min, max),
+			range => match range.split_once('-') {
+				Some((first, last)) => (value(first)?, value(last)?),
+				// A stepped value runs to the end of the field, like `5/15`
+				None if step > 1 => (value(range)?, max),
+				None => (value(range)?, value(range)?),
+			},
+		};
+		if first > last {
+			return Err(format!("the range {} is reversed", range));
+		}
+
+		for value in (first..=last).step_by(step as usize) {
+			bits |= 1 << value;
+		}
+	}
+	Ok(bits)
+}
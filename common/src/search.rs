@@ -0,0 +1,61 @@
+// Email domains and the selectivity of read_by_column_search. The emails of the generated users
+// are spread over `email_domains` domains, and a set share of the users get one of the searched
+// domains instead, so a search for one of them matches 0.1%, 1% or 10% of the users whatever the
+// number of domains. The test runs once per selectivity, each result carrying its `selectivity`:
+// an engine scanning the table takes as long for all three, one seeking an index gets faster as
+// fewer rows match.
+use rand::Rng;
+
+// Shares of the users matched by the searches of read_by_column_search
+pub const SEARCH_SELECTIVITIES: [f64; 3] = [0.001, 0.01, 0.1];
+
+// Selectivity searched when none is set, e.g. by a test run alone or explained
+pub const DEFAULT_SELECTIVITY: f64 = 0.1;
+
+// Number of domains of the emails when the plan doesn't say
+pub const DEFAULT_EMAIL_DOMAINS: usize = 10;
+
+// Domains of the emails, in the order they are used; past them, the domains are numbered
+const EMAIL_DOMAINS: [&str; 10] = [
+	"gmail.com",
+	"yahoo.com",
+	"hotmail.com",
+	"outlook.com",
+	"icloud.com",
+	"proton.me",
+	"mail.de",
+	"orange.fr",
+	"acme.io",
+	"aol.com",
+];
+
+// Domain held by the share `selectivity` of the users
+pub fn searched_domain(selectivity: f64) -> String {
+	format!("select-{}.example.com", selectivity)
+}
+
+// End of the emails read_by_column_search looks for at `selectivity`, the default one when unset
+pub fn search_suffix(selectivity: Option<f64>) -> String {
+	format!("@{}", searched_domain(selectivity.unwrap_or(DEFAULT_SELECTIVITY)))
+}
+
+// LIKE pattern of the emails read_by_column_search looks for at `selectivity`
+pub fn search_pattern(selectivity: Option<f64>) -> String {
+	format!("%{}", search_suffix(selectivity))
+}
+
+// Domain of a generated email: a searched domain for its share of the users, one of the first
+// `domains` domains otherwise
+pub fn email_domain<R: Rng>(domains: usize, rng: &mut R) -> String {
+	let mut draw = rng.gen::<f64>();
+	for selectivity in SEARCH_SELECTIVITIES {
+		if draw < selectivity {
+			return searched_domain(selectivity);
+		}
+		draw -= selectivity;
+	}
+	match rng.gen_range(0..domains.max(1)) {
+		index if index < EMAIL_DOMAINS.len() => EMAIL_DOMAINS[index].to_string(),
+		index => format!("mail{}.example.net", index),
+	}
+}
@@ -1,107 +1,1804 @@
-use axum::{ routing::get, Router, Json, http::StatusCode, extract::State };
+use axum::{
+	async_trait,
+	routing::{ get, post, put },
+	Router,
+	Json,
+	http::{ header, HeaderMap, Request, StatusCode },
+	extract::{ ws::{ Message, WebSocket, WebSocketUpgrade }, FromRequest, Path as UrlPath, Query, State },
+	middleware::{ self, Next },
+	response::{ Html, IntoResponse, Response },
+	body::Body,
+};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{ Path, PathBuf };
 use std::sync::{ Arc, Mutex };
 use tokio::net::TcpListener;
+use tokio::sync::{ broadcast, watch, RwLock };
+use std::time::{ Duration, Instant };
 use anyhow::Result;
-use std::net::SocketAddr;
-use tracing::{ info, error };
+use chrono::{ DateTime, Utc };
+use serde::Deserialize;
+use tower_http::{ compression::CompressionLayer, cors::{ Any, CorsLayer } };
+use tracing::{ info, info_span, warn, error, Instrument };
+use utoipa::{ openapi::security::{ HttpAuthScheme, HttpBuilder, SecurityScheme }, Modify, OpenApi };
+use utoipa_swagger_ui::SwaggerUi;
 
-use crate::{ models::BenchmarkResults, benchmark::DatabaseBenchmark };
+use uuid::Uuid;
+
+use crate::{
+	models::{
+		BenchmarkResults,
+		Capabilities,
+		CapabilityReport,
+		CpuConfig,
+		HealthStatus,
+		RunInfo,
+		RunNotification,
+		RunStatus,
+		RunSummary,
+		TestProgress,
+		ThroughputSample,
+	},
+	backup::BACKUP_RESULT,
+	calibration,
+	benchmark::DatabaseBenchmark,
+	connect,
+	context::RunContext,
+	error::{ ApiError, BenchmarkError, ErrorResponse },
+	plan::{ BenchmarkPlan, BenchmarkTest, DEFAULT_DATASET_SIZE },
+	plan_file::{ PlanFormat, RaceFile },
+	workloads::Workload,
+	checkpoint::{ CheckpointStore, RunCheckpoint },
+	fixtures,
+	history::HistoryStore,
+	import,
+	metastore::{ MetaStore, TrendMetric, TrendPoint, TrendQuery, METASTORE_FILE },
+	report::render_html_report,
+	schedule::Schedule,
+	trace::{ self, TraceEntry },
+	oplog,
+	config::ServerConfig,
+	grpc::run_grpc_server,
+};
+
+// Dashboard charting the results, bundled in the binary so no separate frontend is needed
+const DASHBOARD_HTML: &str = include_str!("../static/dashboard.html");
+
+// Maximum time to wait for the database to answer a health check
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Maximum time the probe of /capabilities may take to create, write and drop its table
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Maximum time to wait for the interrupted run to stop when shutting down
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Maximum time to deliver a run notification
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Append-only history of every result, one JSON object per line, in the data directory
+const RESULTS_HISTORY_FILE: &str = "results.jsonl";
+
+// Directory of the data directory holding one JSON file per run, in a subdirectory per database
+const RUNS_HISTORY_DIR: &str = "history";
+
+// Directory of the data directory holding the checkpoint of each unfinished run, in a
+// subdirectory per database
+const CHECKPOINTS_DIR: &str = "checkpoints";
+
+// Directory of the data directory holding the snapshots of the fixture datasets, in a
+// subdirectory per database and run
+const FIXTURES_DIR: &str = "fixtures";
+
+// Directory of the data directory holding the backups of the runs with `backup` set while they
+// run, in a subdirectory per database and run
+const BACKUPS_DIR: &str = "backups";
+
+// Number of runs a trend covers when no `last` is given
+const DEFAULT_TREND_RUNS: usize = 30;
+
+// Directory of the data directory holding the traces recorded or uploaded, one JSON Lines file each
+const TRACES_DIR: &str = "traces";
+
+// Directory of the data directory holding the CSV files uploaded to be imported, one file each
+const IMPORTS_DIR: &str = "imports";
+
+// Directory of the data directory holding the op-logs of the runs, one file each
+const OPLOGS_DIR: &str = "oplogs";
+
+// Number of runs returned by a history page when no limit is given
+const DEFAULT_HISTORY_PAGE_SIZE: usize = 100;
+
+// Largest history page a client can request
+const MAX_HISTORY_PAGE_SIZE: usize = 1_000;
+
+// Environment variable holding the bearer token required by mutating endpoints; auth is disabled when unset
+const API_TOKEN_ENV: &str = "BENCHMARK_API_TOKEN";
+
+// OpenAPI specification of the benchmark server API
+#[derive(OpenApi)]
+#[openapi(
+	info(title = "Database Race Benchmark API"),
+	paths(
+		health_handler,
+		ready_handler,
+		capabilities_handler,
+		results_handler,
+		history_handler,
+		trend_handler,
+		stored_run_handler,
+		run_benchmark_handler,
+		run_test_handler,
+		run_status_handler,
+		run_results_handler,
+		run_oplog_handler,
+		cancel_run_handler,
+		interrupted_runs_handler,
+		resume_run_handler,
+		cleanup_handler,
+		seed_handler,
+		get_cpu_handler,
+		set_cpu_handler,
+		report_handler,
+		get_trace_handler,
+		put_trace_handler,
+		put_import_handler
+	),
+	modifiers(&BearerTokenSecurity)
+)]
+pub struct ApiDoc;
+
+// Declares the bearer token security scheme referenced by the mutating endpoints
+struct BearerTokenSecurity;
+
+impl Modify for BearerTokenSecurity {
+	fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+		if let Some(components) = openapi.components.as_mut() {
+			components.add_security_scheme(
+				"bearer_token",
+				SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build())
+			);
+		}
+	}
+}
 
 // Shared state for the API
 pub struct AppState<T: DatabaseBenchmark + Send + Sync + 'static> {
-	pub benchmark: Arc<T>,
+	pub benchmark: Arc<RwLock<T>>,
 	pub results: Mutex<Option<BenchmarkResults>>,
+	pub history: HistoryStore,
+	pub metastore: Mutex<MetaStore>,
+	// Progress of the unfinished runs, to resume them
+	pub checkpoints: CheckpointStore,
+	pub results_history_path: PathBuf,
+	pub traces_dir: PathBuf,
+	pub imports_dir: PathBuf,
+	pub fixtures_dir: PathBuf,
+	pub backups_dir: PathBuf,
+	pub oplogs_dir: PathBuf,
+	pub runs: Mutex<HashMap<Uuid, RunInfo>>,
+	// Run currently executing, if any
+	pub active_run: Mutex<Option<Uuid>>,
+	pub throughput: broadcast::Sender<ThroughputSample>,
+	// Context of the benchmark, readable while a run holds the benchmark
+	pub context: RunContext,
+	// Bearer token required by mutating endpoints, if any
+	pub api_token: Option<String>,
+	// Set to true once the server starts shutting down
+	pub shutdown: watch::Sender<bool>,
+}
+
+impl<T: DatabaseBenchmark + Send + Sync + 'static> AppState<T> {
+	// State of a server keeping its history in `data_dir`, restoring the latest run of a previous session
+	pub fn new(benchmark: T, data_dir: &Path, api_token: Option<String>) -> Result<Self> {
+		let history = HistoryStore::open(
+			data_dir.join(RUNS_HISTORY_DIR).join(benchmark.database_name().to_lowercase())
+		)?;
+		let runs = history.load_all()?;
+		let latest = runs.last().cloned();
+		if let Some(latest) = &latest {
+			info!("Restored results of run {} from history", latest.run_id);
+		}
+
+		let checkpoints = CheckpointStore::open(
+			data_dir.join(CHECKPOINTS_DIR).join(benchmark.database_name().to_lowercase())
+		)?;
+		let fixtures_dir = data_dir.join(FIXTURES_DIR).join(benchmark.database_name().to_lowercase());
+		let backups_dir = data_dir.join(BACKUPS_DIR).join(benchmark.database_name().to_lowercase());
+		let oplogs_dir = data_dir.join(OPLOGS_DIR).join(benchmark.database_name().to_lowercase());
+
+		// Runs stored before the metastore existed, or while it couldn't be written, are added now
+		let mut metastore = MetaStore::open(data_dir.join(METASTORE_FILE))?;
+		let imported = metastore.import(&runs)?;
+		if imported > 0 {
+			info!("Imported {} runs from history into the metastore", imported);
+		}
+
+		Ok(AppState {
+			context: benchmark.run_context().clone(),
+			benchmark: Arc::new(RwLock::new(benchmark)),
+			results: Mutex::new(latest),
+			history,
+			metastore: Mutex::new(metastore),
+			checkpoints,
+			results_history_path: data_dir.join(RESULTS_HISTORY_FILE),
+			traces_dir: data_dir.join(TRACES_DIR),
+			imports_dir: data_dir.join(IMPORTS_DIR),
+			fixtures_dir,
+			backups_dir,
+			oplogs_dir,
+			runs: Mutex::new(HashMap::new()),
+			active_run: Mutex::new(None),
+			throughput: broadcast::channel(64).0,
+			api_token,
+			shutdown: watch::channel(false).0,
+		})
+	}
 }
 
 // Run the API server with the provided benchmark implementation
 pub async fn run_server<T: DatabaseBenchmark + Send + Sync + 'static>(
 	benchmark: T,
-	port: u16
+	config: &ServerConfig,
+	default_port: u16
 ) -> Result<()> {
-	// Initialize tracing
-	tracing_subscriber::fmt::init();
+	let addr = config.addr(default_port);
+
+	let api_token = std::env::var(API_TOKEN_ENV).ok().filter(|token| !token.is_empty());
+	if api_token.is_some() {
+		info!("Mutating endpoints require a bearer token");
+	}
+
+	let scheduled_plan = config.scheduled_plan()?;
+
+	if let Some(dsn) = &config.connect {
+		if !benchmark.connected() {
+			anyhow::bail!("{} can't attach to an existing database", benchmark.database_name());
+		}
+		info!("Attached to the existing database {}, the runs only read it", dsn);
+	}
 
 	// Create shared state
-	let state = Arc::new(AppState {
-		benchmark: Arc::new(benchmark),
-		results: Mutex::new(None),
-	});
+	let state = Arc::new(AppState::new(benchmark, &config.data_dir, api_token)?);
 
-	// Build our router
-	let app = Router::new()
-		.route("/", get(root_handler))
-		.route("/results", get(results_handler::<T>))
-		.route("/run", get(run_benchmark_handler::<T>))
-		.with_state(state);
+	// Periodic runs of the scheduled plan
+	if let Some((schedule, plan)) = scheduled_plan {
+		info!("Running the scheduled plan on {}", schedule);
+		tokio::spawn(run_schedule(state.clone(), schedule, plan));
+	}
+
+	// Live throughput samples, sent once per second while a test is running
+	tokio::spawn(
+		sample_throughput(
+			state.benchmark.read().await.database_name(),
+			state.context.clone(),
+			state.throughput.clone()
+		)
+	);
+
+	let app = router(state.clone());
+
+	// Serve the gRPC interface alongside the HTTP API
+	if let Some(grpc_addr) = config.grpc_addr() {
+		let grpc_state = state.clone();
+		tokio::spawn(async move {
+			if let Err(e) = run_grpc_server(grpc_state, grpc_addr).await {
+				error!("gRPC server failed: {:?}", e);
+			}
+		});
+	}
 
 	// Run the server
-	let addr = SocketAddr::from(([0, 0, 0, 0], port));
 	info!("Server listening on {}", addr);
 
 	let listener = TcpListener::bind(addr).await?;
-	axum::serve(listener, app).await?;
+	axum::serve(listener, app).with_graceful_shutdown(shutdown_signal(state.clone())).await?;
+
+	// Let the interrupted run store its state, then close the database
+	wait_for_active_run(&state).await;
+	info!("Closing the database");
+	state.benchmark.read().await.close().await?;
 
+	info!("Server stopped");
 	Ok(())
 }
 
+// Routes of the HTTP API
+pub fn router<T: DatabaseBenchmark + Send + Sync + 'static>(state: Arc<AppState<T>>) -> Router {
+	// Endpoints that start runs or change data, guarded by the API token
+	let protected = Router::new()
+		.route("/run", post(run_benchmark_handler::<T>))
+		.route("/run/:test_name", post(run_test_handler::<T>))
+		.route("/runs/:run_id/cancel", post(cancel_run_handler::<T>))
+		.route("/runs/:run_id/resume", post(resume_run_handler::<T>))
+		.route("/cleanup", post(cleanup_handler::<T>))
+		.route("/seed", post(seed_handler::<T>))
+		.route("/config/cpu", put(set_cpu_handler::<T>))
+		.route("/traces/:name", put(put_trace_handler::<T>))
+		.route("/imports/:name", put(put_import_handler::<T>))
+		.route_layer(middleware::from_fn_with_state(state.clone(), require_api_token::<T>));
+
+	Router::new()
+		.route("/", get(root_handler))
+		.route("/health", get(health_handler::<T>))
+		.route("/ready", get(ready_handler::<T>))
+		.route("/capabilities", get(capabilities_handler::<T>))
+		.route("/results", get(results_handler::<T>))
+		.route("/results/history", get(history_handler::<T>))
+		.route("/results/trend", get(trend_handler::<T>))
+		.route("/results/:run_id", get(stored_run_handler::<T>))
+		.route("/runs/interrupted", get(interrupted_runs_handler::<T>))
+		.route("/runs/:run_id/status", get(run_status_handler::<T>))
+		.route("/runs/:run_id/results", get(run_results_handler::<T>))
+		.route("/runs/:run_id/oplog", get(run_oplog_handler::<T>))
+		.route("/config/cpu", get(get_cpu_handler::<T>))
+		.route("/report", get(report_handler::<T>))
+		.route("/traces/:name", get(get_trace_handler::<T>))
+		.route("/ws", get(ws_handler::<T>))
+		.route("/ui", get(dashboard_handler))
+		.route("/ui/", get(dashboard_handler))
+		.merge(protected)
+		.merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", ApiDoc::openapi()))
+		// Compress responses (large histories and reports) for clients sending `Accept-Encoding: gzip`
+		.layer(CompressionLayer::new())
+		// Allow dashboards and tools served from other origins to call the API
+		.layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
+		.with_state(state)
+}
+
+// Resolve on SIGINT or SIGTERM, after cancelling the running benchmark and closing WebSocket streams
+async fn shutdown_signal<T: DatabaseBenchmark + Send + Sync + 'static>(state: Arc<AppState<T>>) {
+	let ctrl_c = async {
+		if let Err(e) = tokio::signal::ctrl_c().await {
+			error!("Failed to listen for SIGINT: {:?}", e);
+			std::future::pending::<()>().await;
+		}
+	};
+
+	#[cfg(unix)]
+	let terminate = async {
+		match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+			Ok(mut signal) => {
+				signal.recv().await;
+			}
+			Err(e) => {
+				error!("Failed to listen for SIGTERM: {:?}", e);
+				std::future::pending::<()>().await;
+			}
+		}
+	};
+
+	#[cfg(not(unix))]
+	let terminate = std::future::pending::<()>();
+
+	tokio::select! {
+		_ = ctrl_c => {}
+		_ = terminate => {}
+	}
+
+	info!("Shutting down");
+	state.shutdown.send_replace(true);
+
+	let active_run = *state.active_run.lock().unwrap();
+	if let Some(run_id) = active_run {
+		info!("Cancelling run {}", run_id);
+		state.benchmark.read().await.run_context().cancel();
+	}
+}
+
+// Wait for the active run, if any, to release the run guard
+async fn wait_for_active_run<T: DatabaseBenchmark + Send + Sync + 'static>(state: &AppState<T>) {
+	let wait = async {
+		while state.active_run.lock().unwrap().is_some() {
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	};
+
+	if tokio::time::timeout(SHUTDOWN_TIMEOUT, wait).await.is_err() {
+		warn!("Run still active after {:?}, shutting down anyway", SHUTDOWN_TIMEOUT);
+	}
+}
+
 // Root handler
 async fn root_handler() -> &'static str {
-	"Database Benchmark API. Use /run to run benchmarks and /results to view results."
+	"Database Benchmark API. Use POST /run to start a benchmark run, /runs/{id}/status to follow it, /results to view results, /report to download an HTML report and /ui to open the dashboard. The API is described at /openapi.json and /swagger-ui."
+}
+
+// Reject requests without the configured bearer token
+async fn require_api_token<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	request: Request<Body>,
+	next: Next
+) -> Result<Response, StatusCode> {
+	let Some(expected) = &state.api_token else {
+		return Ok(next.run(request).await);
+	};
+
+	let token = request
+		.headers()
+		.get(header::AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "));
+
+	match token {
+		Some(token) if tokens_match(token, expected) => Ok(next.run(request).await),
+		_ => {
+			warn!("Rejected unauthenticated request to {}", request.uri().path());
+			Err(StatusCode::UNAUTHORIZED)
+		}
+	}
+}
+
+// Compare tokens in constant time so the comparison doesn't leak how many bytes matched
+pub(crate) fn tokens_match(token: &str, expected: &str) -> bool {
+	token.len() == expected.len() &&
+		token
+			.bytes()
+			.zip(expected.bytes())
+			.fold(0u8, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+// Dashboard handler
+async fn dashboard_handler() -> Html<&'static str> {
+	Html(DASHBOARD_HTML)
+}
+
+// Health handler: the database answers a trivial query
+#[utoipa::path(
+	get, path = "/health", tag = "health",
+	responses(
+		(status = 200, description = "The database answers", body = HealthStatus),
+		(status = 503, description = "The database does not answer", body = HealthStatus)
+	)
+)]
+async fn health_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+	state,
+): State<Arc<AppState<T>>>) -> (StatusCode, Json<HealthStatus>) {
+	let health = check_health(&state).await;
+	let status_code = if health.error.is_none() {
+		StatusCode::OK
+	} else {
+		StatusCode::SERVICE_UNAVAILABLE
+	};
+
+	(status_code, Json(health))
+}
+
+// Readiness handler: the database is healthy and no run is executing, so a new run can start
+#[utoipa::path(
+	get, path = "/ready", tag = "health",
+	responses(
+		(status = 200, description = "A new run can start", body = HealthStatus),
+		(status = 503, description = "The database does not answer or a run is executing", body = HealthStatus)
+	)
+)]
+async fn ready_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+	state,
+): State<Arc<AppState<T>>>) -> (StatusCode, Json<HealthStatus>) {
+	let mut health = check_health(&state).await;
+	let status_code = if health.error.is_some() {
+		StatusCode::SERVICE_UNAVAILABLE
+	} else if health.active_run.is_some() {
+		health.status = "busy".to_string();
+		StatusCode::SERVICE_UNAVAILABLE
+	} else {
+		StatusCode::OK
+	};
+
+	(status_code, Json(health))
 }
 
-// Run benchmarks handler
-async fn run_benchmark_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+// Capabilities handler: the database answers, creates, writes and drops a probe table, and
+// supports these optional features. A check to run before a long run, not during one
+#[utoipa::path(
+	get, path = "/capabilities", tag = "health",
+	responses(
+		(status = 200, description = "The database answers and its probe succeeded", body = CapabilityReport),
+		(status = 403, description = "The database is a connected one, which the probe would write to"),
+		(status = 409, description = "A run is executing"),
+		(status = 503, description = "The database does not answer or its probe failed", body = CapabilityReport)
+	)
+)]
+async fn capabilities_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
 	state,
-): State<Arc<AppState<T>>>) -> Result<Json<BenchmarkResults>, StatusCode> {
+): State<Arc<AppState<T>>>) -> Result<(StatusCode, Json<CapabilityReport>), StatusCode> {
+	reject_connected(&state, "capability probe").await?;
+
+	// The probe writes to the database, which would disturb the measures of a run
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let benchmark = state.benchmark.read().await;
+	let mut report = CapabilityReport {
+		database: benchmark.database_name(),
+		connected: false,
+		probe: false,
+		capabilities: Capabilities::default(),
+		declared: benchmark.capabilities(),
+		error: None,
+	};
+
+	let ping = match tokio::time::timeout(PING_TIMEOUT, benchmark.ping()).await {
+		Ok(result) => result.map_err(BenchmarkError::engine),
+		Err(_) => Err(BenchmarkError::Timeout(PING_TIMEOUT)),
+	};
+	let probe = match ping {
+		Ok(()) => {
+			report.connected = true;
+			match tokio::time::timeout(PROBE_TIMEOUT, benchmark.probe()).await {
+				Ok(result) => result.map_err(BenchmarkError::engine),
+				Err(_) => Err(BenchmarkError::Timeout(PROBE_TIMEOUT)),
+			}
+		}
+		Err(e) => Err(e),
+	};
+
+	let status_code = match probe {
+		Ok(capabilities) => {
+			report.probe = true;
+			report.capabilities = Capabilities { analytics: benchmark.supports_analytics(), ..capabilities };
+			StatusCode::OK
+		}
+		Err(e) => {
+			warn!("Capability check failed: {:?}", e);
+			report.error = Some(e.to_string());
+			StatusCode::SERVICE_UNAVAILABLE
+		}
+	};
+
+	Ok((status_code, Json(report)))
+}
+
+// Ping the database, with a timeout so a stuck connection reports as unavailable
+async fn check_health<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: &AppState<T>
+) -> HealthStatus {
+	let benchmark = state.benchmark.read().await;
+	let active_run = *state.active_run.lock().unwrap();
+
+	let start = Instant::now();
+	let ping = match tokio::time::timeout(PING_TIMEOUT, benchmark.ping()).await {
+		Ok(result) => result.map_err(BenchmarkError::engine),
+		Err(_) => Err(BenchmarkError::Timeout(PING_TIMEOUT)),
+	};
+
+	match ping {
+		Ok(()) =>
+			HealthStatus {
+				status: "ok".to_string(),
+				database: benchmark.database_name(),
+				ping_ms: Some(start.elapsed().as_secs_f64() * 1000.0),
+				error: None,
+				active_run,
+			},
+		Err(e) => {
+			warn!("Database ping failed: {:?}", e);
+			HealthStatus {
+				status: "unavailable".to_string(),
+				database: benchmark.database_name(),
+				ping_ms: None,
+				error: Some(e.to_string()),
+				active_run,
+			}
+		}
+	}
+}
+
+// Plan sent as the body of a run request: JSON, or a race file in TOML or YAML whose plan is kept
+struct PlanBody(BenchmarkPlan);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequest<S> for PlanBody {
+	type Rejection = Response;
+
+	async fn from_request(request: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+		let format = request.headers()
+			.get(header::CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.and_then(PlanFormat::from_content_type);
+
+		match format {
+			Some(format @ (PlanFormat::Toml | PlanFormat::Yaml)) => {
+				let text = String::from_request(request, state).await.map_err(IntoResponse::into_response)?;
+				let file: RaceFile = format
+					.parse(&text)
+					.map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid plan: {:#}", e)).into_response())?;
+				file.into_plan().map(PlanBody).map_err(|message| (StatusCode::BAD_REQUEST, message).into_response())
+			}
+			_ => {
+				let Json(plan) = Json::<BenchmarkPlan>::from_request(request, state).await
+					.map_err(IntoResponse::into_response)?;
+				Ok(PlanBody(plan))
+			}
+		}
+	}
+}
+
+// Run benchmarks handler, configured by the plan sent as the request body.
+// The run is executed in the background; its progress is available from /runs/{id}/status
+#[utoipa::path(
+	post, path = "/run", tag = "runs",
+	security(("bearer_token" = [])),
+	request_body(content(
+		(BenchmarkPlan = "application/json"),
+		(String = "application/toml"),
+		(String = "application/yaml")
+	)),
+	responses(
+		(status = 202, description = "Run started", body = RunInfo),
+		(status = 400, description = "Invalid plan"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "Another run is executing")
+	)
+)]
+async fn run_benchmark_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	PlanBody(plan): PlanBody
+) -> Result<(StatusCode, Json<RunInfo>), StatusCode> {
 	info!("Running benchmark handler");
+	if let Err(message) = plan.validate() {
+		warn!("Rejected invalid plan: {}", message);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	let run = start_run(state, plan).await?;
+	Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+// Run a single benchmark test, workload, the analytical queries (`analytics`), the import of the
+// plan (`import`) or the backup (`backup`), using the rest of the plan sent as the request body
+#[utoipa::path(
+	post, path = "/run/{test_name}", tag = "runs",
+	security(("bearer_token" = [])),
+	params(("test_name" = String, Path, description = "Test, workload, analytics, import or backup to run")),
+	request_body(content(
+		(BenchmarkPlan = "application/json"),
+		(String = "application/toml"),
+		(String = "application/yaml")
+	)),
+	responses(
+		(status = 202, description = "Run started", body = RunInfo),
+		(status = 400, description = "Invalid plan"),
+		(status = 404, description = "Unknown test"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "Another run is executing")
+	)
+)]
+async fn run_test_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(test_name): UrlPath<String>,
+	PlanBody(mut plan): PlanBody
+) -> Result<(StatusCode, Json<RunInfo>), StatusCode> {
+	info!("Running single test handler for {}", test_name);
+	if test_name == "import" && plan.import.is_none() {
+		warn!("Rejected import run without an import in its plan");
+		return Err(StatusCode::BAD_REQUEST);
+	}
+	if test_name == "analytics" || test_name == "import" || test_name == BACKUP_RESULT {
+		plan.tests = Vec::new();
+		plan.workloads = Vec::new();
+		plan.analytics = test_name == "analytics";
+		plan.import = plan.import.filter(|_| test_name == "import");
+		plan.backup = test_name == BACKUP_RESULT;
+	} else if let Ok(workload) = test_name.parse::<Workload>() {
+		plan.tests = Vec::new();
+		plan.workloads = vec![workload];
+		plan.analytics = false;
+		plan.import = None;
+		plan.backup = false;
+	} else {
+		let test = test_name.parse::<BenchmarkTest>().map_err(|message| {
+			warn!("{}", message);
+			StatusCode::NOT_FOUND
+		})?;
+		plan.tests = vec![test];
+		plan.workloads = Vec::new();
+		plan.analytics = false;
+		plan.import = None;
+		plan.backup = false;
+	}
+	if let Err(message) = plan.validate() {
+		warn!("Rejected invalid plan: {}", message);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	let run = start_run(state, plan).await?;
+	Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+// Register a new run and execute its plan on a background task.
+// Only one run may execute at a time, since parallel runs would corrupt each other's measurements
+pub(crate) async fn start_run<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: Arc<AppState<T>>,
+	mut plan: BenchmarkPlan
+) -> Result<RunInfo, StatusCode> {
+	// A connected database only runs the tests leaving its data unchanged
+	if state.benchmark.read().await.connected() {
+		if let Err(message) = connect::restrict_plan(&mut plan) {
+			warn!("Rejected plan for the connected database: {}", message);
+			return Err(StatusCode::BAD_REQUEST);
+		}
+	}
+
+	let run_id = Uuid::new_v4();
+	claim_active_run(&state, run_id)?;
+
+	let database = state.benchmark.read().await.database_name();
+	Ok(spawn_run(state, RunCheckpoint::new(run_id, database, plan)).await)
+}
+
+// Execute the plan of a claimed run on a background task, going on from its checkpoint
+async fn spawn_run<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: Arc<AppState<T>>,
+	checkpoint: RunCheckpoint
+) -> RunInfo {
+	let run_id = checkpoint.run_id;
+	let plan = checkpoint.plan.clone();
+	let run = RunInfo {
+		run_id,
+		database: checkpoint.database.clone(),
+		status: RunStatus::Running,
+		tests: plan.result_names().into_iter().map(str::to_string).collect(),
+		started_at: Utc::now(),
+		finished_at: None,
+		progress: None,
+	};
+	state.runs.lock().unwrap().insert(run.run_id, run.clone());
+
+	let context = state.benchmark.read().await.run_context().clone();
+	context.reset_cancellation();
+	let span = info_span!("run", %run_id, database = %run.database);
+
+	tokio::spawn(async move {
+		// Release the run guard once the run ends, even if it panics
+		let guard = ActiveRunGuard(&state.active_run);
+
+		let (status, results) = match execute_plan(&state, checkpoint).instrument(span).await {
+			Ok(results) => (RunStatus::Completed, Some(results)),
+			Err(BenchmarkError::Cancelled) => {
+				info!("Run {} cancelled", run_id);
+				(RunStatus::Cancelled, None)
+			}
+			Err(_) if context.is_cancelled() => {
+				info!("Run {} cancelled", run_id);
+				(RunStatus::Cancelled, None)
+			}
+			Err(e) => {
+				error!("Run {} failed: {}", run_id, e);
+				(RunStatus::Failed { message: e.to_string(), error: Some(e.kind()) }, None)
+			}
+		};
+
+		let run = {
+			let mut runs = state.runs.lock().unwrap();
+			runs.get_mut(&run_id).map(|run| {
+				run.status = status;
+				run.finished_at = Some(Utc::now());
+				run.clone()
+			})
+		};
+
+		// The next run can start while the notification is delivered
+		drop(guard);
+
+		if let (Some(url), Some(run)) = (&plan.notify_url, run) {
+			if let Err(e) = notify_run_end(url, &RunNotification { run, results }).await {
+				error!("Failed to notify {} of the end of run {}: {:?}", url, run_id, e);
+			}
+		}
+	});
+
+	run
+}
+
+// Start the plan at every time of the schedule until the server shuts down. A time is skipped when
+// another run is still executing then.
+async fn run_schedule<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: Arc<AppState<T>>,
+	schedule: Schedule,
+	plan: BenchmarkPlan
+) {
+	let mut shutdown = state.shutdown.subscribe();
+	while let Some(next) = schedule.next_after(Utc::now()) {
+		info!("Next scheduled run at {}", next);
+		let wait = (next - Utc::now()).to_std().unwrap_or_default();
+		tokio::select! {
+			_ = tokio::time::sleep(wait) => {}
+			_ = shutdown.wait_for(|shutting_down| *shutting_down) => return,
+		}
+
+		match start_run(state.clone(), plan.clone()).await {
+			Ok(run) => info!("Started scheduled run {}", run.run_id),
+			Err(StatusCode::CONFLICT) => warn!("Skipped the scheduled run, another run is executing"),
+			// The server is shutting down
+			Err(_) => return,
+		}
+	}
+}
+
+// POST the outcome of a run to the plan's notify URL
+async fn notify_run_end(url: &str, notification: &RunNotification) -> Result<()> {
+	info!("Notifying {} of the end of run {}", url, notification.run.run_id);
+	reqwest::Client
+		::builder()
+		.timeout(NOTIFY_TIMEOUT)
+		.build()?
+		.post(url)
+		.json(notification)
+		.send().await?
+		.error_for_status()?;
+
+	Ok(())
+}
+
+// Mark a run or dataset operation as active, failing with 409 Conflict if another one is executing
+fn claim_active_run<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: &AppState<T>,
+	run_id: Uuid
+) -> Result<(), StatusCode> {
+	if *state.shutdown.borrow() {
+		warn!("Rejected new run while shutting down");
+		return Err(StatusCode::SERVICE_UNAVAILABLE);
+	}
+
+	let mut active_run = state.active_run.lock().unwrap();
+	if let Some(active_run_id) = *active_run {
+		warn!("Rejected new run while run {} is executing", active_run_id);
+		return Err(StatusCode::CONFLICT);
+	}
+	*active_run = Some(run_id);
+
+	Ok(())
+}
+
+// Refuse an `operation` writing to the database when it is a connected one, with 403 Forbidden
+async fn reject_connected<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: &AppState<T>,
+	operation: &str
+) -> Result<(), StatusCode> {
+	if state.benchmark.read().await.connected() {
+		warn!("Rejected {} of the connected database", operation);
+		return Err(StatusCode::FORBIDDEN);
+	}
+	Ok(())
+}
+
+// Clears the active run when dropped so the next run can start
+struct ActiveRunGuard<'a>(&'a Mutex<Option<Uuid>>);
+
+impl Drop for ActiveRunGuard<'_> {
+	fn drop(&mut self) {
+		if let Ok(mut active_run) = self.0.lock() {
+			*active_run = None;
+		}
+	}
+}
+
+// Prepare the database, run the plan and store its results. The progress of the run is saved
+// in its checkpoint, and a run resumed from a checkpoint skips what it already did.
+async fn execute_plan<T: DatabaseBenchmark + Send + Sync + 'static>(
+	state: &AppState<T>,
+	mut checkpoint: RunCheckpoint
+) -> Result<BenchmarkResults, BenchmarkError> {
+	let run_id = checkpoint.run_id;
+	let plan = &checkpoint.plan.clone();
+	if let Err(e) = state.checkpoints.save(&checkpoint) {
+		error!("Failed to save the checkpoint of run {}: {:?}", run_id, e);
+	}
+
+	// Apply the CPU count before taking the read lock for the run
+	if let Some(cpu_count) = plan.cpu_count {
+		info!("Setting CPU count to {}", cpu_count);
+		state.benchmark
+			.write().await
+			.set_cpu_count(cpu_count).await
+			.map_err(|e| BenchmarkError::engine(e.context("Failed to set the CPU count")))?;
+	}
+
+	// The indexes, the id columns and the timestamp columns are set up by `init()` below
+	state.benchmark.write().await.set_index_mode(plan.indexes);
+	state.benchmark.write().await.set_key_encoding(plan.key_encoding);
+	state.benchmark.write().await.set_timestamp_encoding(plan.timestamp_encoding);
+
+	// Start from new database files, without the bloat and statistics left by previous runs
+	if plan.fresh_database && !checkpoint.seeded {
+		info!("Recreating the database");
+		state.benchmark
+			.write().await
+			.reset().await
+			.map_err(|e| BenchmarkError::init_failed(e.context("Database reset failed")))?;
+	}
+
+	let benchmark = state.benchmark.read().await;
+	let context = benchmark.run_context();
+
 	// Initialize the database
-	state.benchmark.init().await.map_err(|e| {
-		error!("Database initialization failed: {:?}", e);
+	benchmark
+		.init()
+		.instrument(info_span!("init"))
+		.await.map_err(BenchmarkError::init_failed)?;
+
+	context.set_payload(plan.payload);
+	context.set_tenants(plan.tenants);
+	context.set_writers(plan.writers);
+	context.set_key_strategy(plan.key_strategy);
+	context.set_outlier_thresholds(plan.outlier_thresholds());
+	context.set_row_decoding(plan.row_decoding);
+	context.set_trace(plan.trace.as_ref().map(|trace| trace.at(&state.traces_dir)));
+	context.set_import(plan.import.as_ref().map(|import| import.at(&state.imports_dir)));
+	let fixture_dir = state.fixtures_dir.join(run_id.to_string());
+	context.set_fixture(plan.fixtures.then(|| fixture_dir.clone()));
+	let backup_dir = state.backups_dir.join(run_id.to_string());
+	context.set_backup(plan.backup.then(|| backup_dir.clone()));
+	// A resumed run appends to the op-log of the steps it completed before
+	let oplog_path = plan.oplog.then(|| oplog::oplog_path(&state.oplogs_dir, run_id));
+	if let Some(path) = &oplog_path {
+		oplog::create(path).map_err(|e| BenchmarkError::init_failed(e.context("Failed to create the op-log")))?;
+	}
+	context.set_oplog(oplog_path);
+
+	// A resumed run goes on with the data the interrupted one generated and changed
+	if checkpoint.seeded {
+		info!("Resuming run {} after {} completed steps", run_id, checkpoint.steps);
+	} else if plan.prebuilt {
+		// The tests of the plan leave the dataset as it is, so a resumed run finds it unchanged
+		context.check_cancelled()?;
+		let counts = benchmark.count_records().await.map_err(BenchmarkError::engine)?;
+		if counts.users == 0 {
+			return Err(
+				BenchmarkError::data_generation(
+					anyhow::anyhow!("The database holds no prebuilt dataset, build one with `race seed` or POST /seed")
+				)
+			);
+		}
+		info!("Running over the prebuilt dataset of {} users", counts.users);
+	} else {
+		// Clean up previous data
+		context.check_cancelled()?;
+		info!("Cleaning up previous data");
+		benchmark.cleanup().await.map_err(|e| BenchmarkError::engine(e.context("Cleanup failed")))?;
+
+		// Generate test data
+		context.check_cancelled()?;
+		info!("Generating {} records of test data", plan.dataset_size);
+		let seeded = fixtures::seed_dataset(plan.seed);
+		benchmark
+			.generate_test_data(plan.dataset_size)
+			.instrument(info_span!("seed", records = plan.dataset_size))
+			.await.map_err(BenchmarkError::data_generation)?;
+		drop(seeded);
+
+		if plan.fixtures {
+			info!("Saving the fixture dataset");
+			benchmark
+				.snapshot(&fixture_dir)
+				.instrument(info_span!("snapshot"))
+				.await.map_err(|e| BenchmarkError::data_generation(e.context("Snapshot failed")))?;
+		}
+
+		checkpoint.seeded = true;
+		checkpoint.updated_at = Utc::now();
+		if let Err(e) = state.checkpoints.save(&checkpoint) {
+			error!("Failed to save the checkpoint of run {}: {:?}", run_id, e);
+		}
+	}
+
+	// The calibration is a diagnostic: failing to run it leaves the results without it
+	let calibration = if plan.calibrate {
+		context.check_cancelled()?;
+		info!("Calibrating the harness");
+		match calibration::calibrate(plan.payload, plan.tenants, plan.key_strategy).await {
+			Ok(stats) => Some(stats),
+			Err(e) => {
+				warn!("Calibration of the harness failed: {:#}", e);
+				None
+			}
+		}
+	} else {
+		None
+	};
+
+	// Run the selected benchmarks, saving the checkpoint after each step
+	info!("Running benchmarks");
+	context.set_checkpoint(Some((state.checkpoints.clone(), checkpoint)));
+	let results = benchmark.run_all_benchmarks(plan).await;
+	context.set_checkpoint(None);
+	context.set_fixture(None);
+	context.set_import(None);
+	context.set_backup(None);
+	context.set_oplog(None);
+	// The backup is only kept long enough to be restored
+	if plan.backup {
+		if let Err(e) = std::fs::remove_dir_all(&backup_dir) {
+			if e.kind() != std::io::ErrorKind::NotFound {
+				error!("Failed to remove the backup of run {}: {:?}", run_id, e);
+			}
+		}
+	}
+	let mut results = results?;
+	results.run_id = run_id;
+	results.calibration = calibration;
+
+	// Store the results
+	info!("Storing results");
+	{
+		let mut results_lock = state.results.lock().unwrap();
+		*results_lock = Some(results.clone());
+	}
+
+	// Persist the run so it survives restarts
+	if let Err(e) = state.history.save(&results) {
+		error!("Failed to save run to history: {:?}", e);
+	}
+
+	if let Err(e) = state.metastore.lock().unwrap().insert(&results) {
+		error!("Failed to store run in the metastore: {:?}", e);
+	}
+
+	// Append the results to the on-disk history
+	if let Err(e) = append_results_history(&state.results_history_path, &results) {
+		error!("Failed to append results history: {:?}", e);
+	}
+
+	if let Err(e) = state.checkpoints.remove(run_id) {
+		error!("Failed to remove the checkpoint of run {}: {:?}", run_id, e);
+	}
+	if plan.fixtures {
+		if let Err(e) = std::fs::remove_dir_all(&fixture_dir) {
+			error!("Failed to remove the fixture dataset of run {}: {:?}", run_id, e);
+		}
+	}
+
+	info!("Results stored");
+	Ok(results)
+}
+
+// Cleanup handler removing all benchmark data from the database
+#[utoipa::path(
+	post, path = "/cleanup", tag = "dataset",
+	security(("bearer_token" = [])),
+	responses(
+		(status = 200, description = "Data removed", body = String),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 403, description = "The database is a connected one, which is only read"),
+		(status = 409, description = "A run is executing"),
+		(status = 500, description = "The database failed to remove the data", body = ErrorResponse)
+	)
+)]
+async fn cleanup_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+	state,
+): State<Arc<AppState<T>>>) -> Result<String, ApiError> {
+	info!("Cleanup handler");
+	reject_connected(&state, "cleanup").await?;
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let benchmark = state.benchmark.read().await;
+	benchmark.cleanup().await.map_err(|e| {
+		error!("Cleanup failed: {:?}", e);
+		BenchmarkError::engine(e.context("Cleanup failed"))
+	})?;
+
+	Ok(format!("{} data cleaned up", benchmark.database_name()))
+}
+
+#[derive(Deserialize)]
+struct SeedParams {
+	count: Option<usize>,
+}
+
+// Seed handler generating `count` records of test data on top of the existing data
+#[utoipa::path(
+	post, path = "/seed", tag = "dataset",
+	security(("bearer_token" = [])),
+	params(("count" = Option<usize>, Query, description = "Number of records to generate (1000 by default)")),
+	responses(
+		(status = 200, description = "Data generated", body = String),
+		(status = 400, description = "Invalid count"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 403, description = "The database is a connected one, which is only read"),
+		(status = 409, description = "A run is executing"),
+		(status = 500, description = "Initialization or data generation failed", body = ErrorResponse)
+	)
+)]
+async fn seed_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	Query(params): Query<SeedParams>
+) -> Result<String, ApiError> {
+	let count = params.count.unwrap_or(DEFAULT_DATASET_SIZE);
+	info!("Seed handler for {} records", count);
+	if count == 0 {
+		warn!("Rejected seed of 0 records");
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+
+	reject_connected(&state, "seed").await?;
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let benchmark = state.benchmark.read().await;
+	let seeded = async {
+		benchmark.init().await.map_err(BenchmarkError::init_failed)?;
+		benchmark.generate_test_data(count).await.map_err(BenchmarkError::data_generation)
+	};
+	seeded.await.map_err(|e| {
+		error!("Seeding failed: {}", e);
+		e
+	})?;
+
+	Ok(format!("Generated {} records of {} test data", count, benchmark.database_name()))
+}
+
+// Current CPU count used by the benchmarks
+#[utoipa::path(
+	get, path = "/config/cpu", tag = "config",
+	responses((status = 200, description = "Current CPU count", body = CpuConfig))
+)]
+async fn get_cpu_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+	state,
+): State<Arc<AppState<T>>>) -> Json<CpuConfig> {
+	info!("Get CPU count handler");
+	let cpu_count = state.benchmark.read().await.get_cpu_count();
+
+	Json(CpuConfig { cpu_count })
+}
+
+// Change the CPU count used by the following runs
+#[utoipa::path(
+	put, path = "/config/cpu", tag = "config",
+	security(("bearer_token" = [])),
+	request_body = CpuConfig,
+	responses(
+		(status = 200, description = "CPU count changed", body = CpuConfig),
+		(status = 400, description = "Invalid CPU count"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "A run is executing"),
+		(status = 500, description = "The database rejected the setting", body = ErrorResponse)
+	)
+)]
+async fn set_cpu_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	Json(config): Json<CpuConfig>
+) -> Result<Json<CpuConfig>, ApiError> {
+	info!("Set CPU count handler to {}", config.cpu_count);
+	if config.cpu_count == 0 {
+		warn!("Rejected CPU count of 0");
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+
+	// Changing the CPU count in the middle of a run would skew its measurements
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let mut benchmark = state.benchmark.write().await;
+	benchmark.set_cpu_count(config.cpu_count).await.map_err(|e| {
+		error!("Failed to set the CPU count: {:?}", e);
+		BenchmarkError::engine(e.context("Failed to set the CPU count"))
+	})?;
+
+	Ok(Json(CpuConfig { cpu_count: benchmark.get_cpu_count() }))
+}
+
+// Status of a background run
+#[utoipa::path(
+	get, path = "/runs/{run_id}/status", tag = "runs",
+	params(("run_id" = Uuid, Path, description = "Run identifier")),
+	responses(
+		(status = 200, description = "Run status", body = RunInfo),
+		(status = 404, description = "Unknown run")
+	)
+)]
+async fn run_status_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>
+) -> Result<Json<RunInfo>, StatusCode> {
+	info!("Run status handler for {}", run_id);
+	let mut run = state.runs.lock().unwrap().get(&run_id).cloned().ok_or(StatusCode::NOT_FOUND)?;
+
+	// Only one run executes at a time, so the test in progress is the one of this run
+	if run.status == RunStatus::Running {
+		run.progress = state.context
+			.snapshot()
+			.map(|(test_name, operations)| TestProgress { test_name, operations });
+	}
+
+	Ok(Json(run))
+}
+
+// Cancel a background run; the running test stops at its next operation
+#[utoipa::path(
+	post, path = "/runs/{run_id}/cancel", tag = "runs",
+	security(("bearer_token" = [])),
+	params(("run_id" = Uuid, Path, description = "Run identifier")),
+	responses(
+		(status = 202, description = "Cancellation requested", body = RunInfo),
+		(status = 404, description = "Unknown run"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "The run is not running")
+	)
+)]
+async fn cancel_run_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>
+) -> Result<(StatusCode, Json<RunInfo>), StatusCode> {
+	info!("Cancel run handler for {}", run_id);
+	let run = state.runs.lock().unwrap().get(&run_id).cloned().ok_or(StatusCode::NOT_FOUND)?;
+
+	if run.status != RunStatus::Running {
+		warn!("Run {} is not running, nothing to cancel", run_id);
+		return Err(StatusCode::CONFLICT);
+	}
+
+	state.benchmark.read().await.run_context().cancel();
+	Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+// Runs interrupted by a crash, a failure or a cancellation that can be resumed, the most recently
+// updated first
+#[utoipa::path(
+	get, path = "/runs/interrupted", tag = "runs",
+	responses(
+		(status = 200, description = "Checkpoints of the interrupted runs", body = Vec<RunCheckpoint>),
+		(status = 500, description = "The checkpoints could not be read")
+	)
+)]
+async fn interrupted_runs_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+	state,
+): State<Arc<AppState<T>>>) -> Result<Json<Vec<RunCheckpoint>>, StatusCode> {
+	info!("Interrupted runs handler");
+	let mut checkpoints = state.checkpoints.list().map_err(|e| {
+		error!("Failed to list checkpoints: {:?}", e);
 		StatusCode::INTERNAL_SERVER_ERROR
 	})?;
 
-	// Clean up previous data
-	info!("Cleaning up previous data");
-	state.benchmark.cleanup().await.map_err(|e| {
-		error!("Cleanup failed: {:?}", e);
+	// The checkpoint of the executing run is its progress, not an interruption
+	let active_run = *state.active_run.lock().unwrap();
+	checkpoints.retain(|checkpoint| Some(checkpoint.run_id) != active_run);
+
+	Ok(Json(checkpoints))
+}
+
+// Resume an interrupted run from its checkpoint: the completed tests keep their results and the
+// run goes on from the next one, with the data the interrupted run left in the database
+#[utoipa::path(
+	post, path = "/runs/{run_id}/resume", tag = "runs",
+	security(("bearer_token" = [])),
+	params(("run_id" = Uuid, Path, description = "Run identifier")),
+	responses(
+		(status = 202, description = "Run resumed", body = RunInfo),
+		(status = 404, description = "No interrupted run with this id"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "A run is executing")
+	)
+)]
+async fn resume_run_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>
+) -> Result<(StatusCode, Json<RunInfo>), StatusCode> {
+	info!("Resume run handler for {}", run_id);
+	// Claimed before reading the checkpoint, which the run of this id may still be updating
+	claim_active_run(&state, run_id)?;
+	let checkpoint = match state.checkpoints.get(run_id) {
+		Ok(Some(checkpoint)) => checkpoint,
+		outcome => {
+			*state.active_run.lock().unwrap() = None;
+			if let Err(e) = outcome {
+				error!("Failed to read the checkpoint of run {}: {:?}", run_id, e);
+				return Err(StatusCode::INTERNAL_SERVER_ERROR);
+			}
+			warn!("No interrupted run {} to resume", run_id);
+			return Err(StatusCode::NOT_FOUND);
+		}
+	};
+
+	let run = spawn_run(state, checkpoint).await;
+	Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+// Results of a background run; 202 with the run status while it is still running
+#[utoipa::path(
+	get, path = "/runs/{run_id}/results", tag = "runs",
+	params(("run_id" = Uuid, Path, description = "Run identifier")),
+	responses(
+		(status = 200, description = "Results of the completed run", body = BenchmarkResults),
+		(status = 202, description = "The run is still running", body = RunInfo),
+		(status = 404, description = "Unknown run"),
+		(status = 410, description = "The run was cancelled", body = RunInfo),
+		(status = 500, description = "The run failed", body = RunInfo)
+	)
+)]
+async fn run_results_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>
+) -> Result<Response, StatusCode> {
+	info!("Run results handler for {}", run_id);
+	let run = state.runs.lock().unwrap().get(&run_id).cloned();
+
+	if let Some(run) = run {
+		match run.status {
+			RunStatus::Running => {
+				return Ok((StatusCode::ACCEPTED, Json(run)).into_response());
+			}
+			RunStatus::Failed { .. } => {
+				return Ok((StatusCode::INTERNAL_SERVER_ERROR, Json(run)).into_response());
+			}
+			RunStatus::Cancelled => {
+				return Ok((StatusCode::GONE, Json(run)).into_response());
+			}
+			RunStatus::Completed => {}
+		}
+	}
+
+	// Completed runs (including those from previous sessions) are read from the history
+	let results = state.history.get(run_id).map_err(|e| {
+		error!("Failed to read run {} from history: {:?}", run_id, e);
+		StatusCode::INTERNAL_SERVER_ERROR
+	})?;
+
+	results
+		.map(|results| Json(results).into_response())
+		.ok_or(StatusCode::NOT_FOUND)
+}
+
+// Op-log of a run made with `oplog` set; that of a run still executing, failed or cancelled holds
+// the operations of the tests it finished
+#[utoipa::path(
+	get, path = "/runs/{run_id}/oplog", tag = "runs",
+	params(("run_id" = Uuid, Path, description = "Run identifier")),
+	responses(
+		(status = 200, description = "Operations of the run, in the binary format of `common::oplog`", body = Vec<u8>, content_type = "application/octet-stream"),
+		(status = 404, description = "Unknown run, or a run without an op-log")
+	)
+)]
+async fn run_oplog_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>
+) -> Result<impl IntoResponse, StatusCode> {
+	info!("Run op-log handler for {}", run_id);
+	match std::fs::read(oplog::oplog_path(&state.oplogs_dir, run_id)) {
+		Ok(contents) => Ok(([(header::CONTENT_TYPE, "application/octet-stream")], contents)),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(StatusCode::NOT_FOUND),
+		Err(e) => {
+			error!("Failed to read the op-log of run {}: {:?}", run_id, e);
+			Err(StatusCode::INTERNAL_SERVER_ERROR)
+		}
+	}
+}
+
+// Results handler, returning JSON by default or CSV / JSON Lines based on the Accept header
+#[utoipa::path(
+	get, path = "/results", tag = "results",
+	responses(
+		(status = 200, description = "Latest results, as JSON, CSV or JSON Lines depending on the Accept header", content(
+			(BenchmarkResults = "application/json"),
+			(String = "text/csv"),
+			(String = "application/jsonl")
+		)),
+		(status = 404, description = "No results yet")
+	)
+)]
+async fn results_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	headers: HeaderMap
+) -> Result<Response, StatusCode> {
+	info!("Results handler");
+	let results_lock = state.results.lock().unwrap();
+
+	let Some(results) = &*results_lock else {
+		return Err(StatusCode::NOT_FOUND);
+	};
+
+	let accept = headers
+		.get(header::ACCEPT)
+		.and_then(|value| value.to_str().ok())
+		.unwrap_or("");
+
+	if accept.contains("text/csv") {
+		Ok(([(header::CONTENT_TYPE, "text/csv; charset=utf-8")], results.to_csv()).into_response())
+	} else if accept.contains("application/jsonl") || accept.contains("application/x-ndjson") {
+		let jsonl = results.to_jsonl().map_err(|e| {
+			error!("JSON Lines serialization failed: {:?}", e);
+			StatusCode::INTERNAL_SERVER_ERROR
+		})?;
+		Ok(([(header::CONTENT_TYPE, "application/jsonl")], jsonl).into_response())
+	} else {
+		Ok(Json(results.clone()).into_response())
+	}
+}
+
+#[derive(Deserialize)]
+struct HistoryParams {
+	test: Option<String>,
+	since: Option<DateTime<Utc>>,
+	limit: Option<usize>,
+	offset: Option<usize>,
+}
+
+// History handler listing the stored runs, oldest first, one page at a time
+#[utoipa::path(
+	get, path = "/results/history", tag = "results",
+	params(
+		("test" = Option<String>, Query, description = "Only list runs that include this test"),
+		("since" = Option<DateTime<Utc>>, Query, description = "Only list runs recorded at or after this time (RFC 3339)"),
+		("limit" = Option<usize>, Query, description = "Maximum number of runs to return (100 by default, at most 1000)"),
+		("offset" = Option<usize>, Query, description = "Number of matching runs to skip")
+	),
+	responses(
+		(status = 200, description = "A page of the stored runs, with the number of matching runs in the `X-Total-Count` header", body = Vec<RunSummary>),
+		(status = 400, description = "Invalid limit")
+	)
+)]
+async fn history_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	Query(params): Query<HistoryParams>
+) -> Result<Response, StatusCode> {
+	info!("History handler");
+	let limit = params.limit.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE);
+	if limit == 0 || limit > MAX_HISTORY_PAGE_SIZE {
+		warn!("Rejected history page of {} runs", limit);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	let runs = state.history.list(params.test.as_deref(), params.since).map_err(|e| {
+		error!("Failed to list history: {:?}", e);
 		StatusCode::INTERNAL_SERVER_ERROR
 	})?;
 
-	// Generate test data - 1000 records of each type
-	info!("Generating test data");
-	state.benchmark.generate_test_data(1000).await.map_err(|e| {
-		error!("Test data generation failed: {:?}", e);
+	let total = runs.len();
+	let page: Vec<RunSummary> = runs
+		.into_iter()
+		.skip(params.offset.unwrap_or(0))
+		.take(limit)
+		.collect();
+
+	Ok(([("x-total-count", total.to_string())], Json(page)).into_response())
+}
+
+#[derive(Deserialize)]
+struct TrendParams {
+	test: String,
+	database: Option<String>,
+	#[serde(default)]
+	metric: TrendMetric,
+	last: Option<usize>,
+	since: Option<DateTime<Utc>>,
+}
+
+// Trend handler following a test across the stored runs
+#[utoipa::path(
+	get, path = "/results/trend", tag = "results",
+	params(
+		("test" = String, Query, description = "Test, workload or analytical query to follow"),
+		("database" = Option<String>, Query, description = "Only the runs of this database, ignoring case"),
+		("metric" = Option<TrendMetric>, Query, description = "Value of each result (operations_per_second by default)"),
+		("last" = Option<usize>, Query, description = "Number of the most recent runs of the test (30 by default, at most 1000)"),
+		("since" = Option<DateTime<Utc>>, Query, description = "Only the runs recorded at or after this time (RFC 3339)")
+	),
+	responses(
+		(status = 200, description = "Measured results of the test, oldest first", body = Vec<TrendPoint>),
+		(status = 400, description = "Missing test, unknown metric or invalid number of runs")
+	)
+)]
+async fn trend_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	Query(params): Query<TrendParams>
+) -> Result<Json<Vec<TrendPoint>>, StatusCode> {
+	info!("Trend handler for {}", params.test);
+	let last = params.last.unwrap_or(DEFAULT_TREND_RUNS);
+	if last == 0 || last > MAX_HISTORY_PAGE_SIZE {
+		warn!("Rejected trend of {} runs", last);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	let query = TrendQuery {
+		test: params.test,
+		database: params.database,
+		metric: params.metric,
+		last,
+		since: params.since,
+	};
+	let points = state.metastore.lock().unwrap().trend(&query).map_err(|e| {
+		error!("Failed to query the metastore: {:?}", e);
 		StatusCode::INTERNAL_SERVER_ERROR
 	})?;
 
-	// Run all benchmarks with 1000 operations each
-	info!("Running all benchmarks");
-	let results = state.benchmark.run_all_benchmarks().await.map_err(|e| {
-		error!("Benchmark execution failed: {:?}", e);
+	Ok(Json(points))
+}
+
+#[derive(Deserialize)]
+struct StoredRunParams {
+	test: Option<String>,
+}
+
+// Results of a single stored run, optionally restricted to one test
+#[utoipa::path(
+	get, path = "/results/{run_id}", tag = "results",
+	params(
+		("run_id" = Uuid, Path, description = "Run identifier"),
+		("test" = Option<String>, Query, description = "Only return the results of this test")
+	),
+	responses(
+		(status = 200, description = "Results of the stored run", body = BenchmarkResults),
+		(status = 404, description = "Unknown run")
+	)
+)]
+async fn stored_run_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(run_id): UrlPath<Uuid>,
+	Query(params): Query<StoredRunParams>
+) -> Result<Json<BenchmarkResults>, StatusCode> {
+	info!("Run results handler for {}", run_id);
+	let results = state.history.get(run_id).map_err(|e| {
+		error!("Failed to read run {} from history: {:?}", run_id, e);
 		StatusCode::INTERNAL_SERVER_ERROR
 	})?;
 
-	// Store the results
-	info!("Storing results");
-	{
-		let mut results_lock = state.results.lock().unwrap();
-		*results_lock = Some(results.clone());
+	let Some(mut results) = results else {
+		return Err(StatusCode::NOT_FOUND);
+	};
+	if let Some(test) = &params.test {
+		results.results.retain(|result| &result.test_name == test);
 	}
 
-	info!("Results stored");
 	Ok(Json(results))
 }
 
-// Results handler
-async fn results_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
+// WebSocket handler streaming live throughput samples as JSON messages
+async fn ws_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	ws: WebSocketUpgrade
+) -> Response {
+	info!("WebSocket handler");
+	let samples = state.throughput.subscribe();
+	let shutdown = state.shutdown.subscribe();
+	ws.on_upgrade(move |socket| stream_throughput(socket, samples, shutdown))
+}
+
+// Forward throughput samples to a WebSocket client until it disconnects or the server shuts down
+async fn stream_throughput(
+	mut socket: WebSocket,
+	mut samples: broadcast::Receiver<ThroughputSample>,
+	mut shutdown: watch::Receiver<bool>
+) {
+	loop {
+		let received = tokio::select! {
+			received = samples.recv() => received,
+			_ = async { shutdown.wait_for(|shutting_down| *shutting_down).await.ok(); } => {
+				let _ = socket.send(Message::Close(None)).await;
+				break;
+			}
+		};
+
+		let sample = match received {
+			Ok(sample) => sample,
+			// A slow client only misses samples, it keeps receiving the next ones
+			Err(broadcast::error::RecvError::Lagged(_)) => {
+				continue;
+			}
+			Err(broadcast::error::RecvError::Closed) => {
+				break;
+			}
+		};
+
+		let Ok(json) = serde_json::to_string(&sample) else {
+			continue;
+		};
+		if socket.send(Message::Text(json)).await.is_err() {
+			break;
+		}
+	}
+}
+
+// Sample the operation counter of the running test every second
+async fn sample_throughput(
+	database: String,
+	context: RunContext,
+	sender: broadcast::Sender<ThroughputSample>
+) {
+	let mut interval = tokio::time::interval(Duration::from_secs(1));
+	let mut previous: Option<(String, u64, Instant)> = None;
+
+	loop {
+		interval.tick().await;
+
+		let Some((test_name, operations)) = context.snapshot() else {
+			previous = None;
+			continue;
+		};
+		let now = Instant::now();
+
+		// Only compare with the previous sample when it belongs to the same test
+		let operations_per_second = match &previous {
+			Some((previous_test, previous_operations, previous_time)) if
+				*previous_test == test_name &&
+				operations >= *previous_operations
+			=> {
+				let elapsed = now.duration_since(*previous_time).as_secs_f64();
+				((operations - previous_operations) as f64) / elapsed
+			}
+			_ => 0.0,
+		};
+
+		// Nobody listening is not an error
+		let _ = sender.send(ThroughputSample {
+			database: database.clone(),
+			test_name: test_name.clone(),
+			operations,
+			operations_per_second,
+			timestamp: Utc::now(),
+		});
+
+		previous = Some((test_name, operations, now));
+	}
+}
+
+// Append results to the JSON Lines history file, creating it if needed
+fn append_results_history(path: &Path, results: &BenchmarkResults) -> Result<()> {
+	if let Some(dir) = path.parent() {
+		std::fs::create_dir_all(dir)?;
+	}
+
+	let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+	file.write_all(results.to_jsonl()?.as_bytes())?;
+
+	Ok(())
+}
+
+// HTML report handler
+#[utoipa::path(
+	get, path = "/report", tag = "results",
+	responses(
+		(status = 200, description = "Standalone HTML report of the latest results", body = String, content_type = "text/html"),
+		(status = 404, description = "No results yet")
+	)
+)]
+async fn report_handler<T: DatabaseBenchmark + Send + Sync + 'static>(State(
 	state,
-): State<Arc<AppState<T>>>) -> Result<Json<BenchmarkResults>, StatusCode> {
-	info!("Results handler");
+): State<Arc<AppState<T>>>) -> Result<impl IntoResponse, StatusCode> {
+	info!("Report handler");
 	let results_lock = state.results.lock().unwrap();
 
 	if let Some(results) = &*results_lock {
-		Ok(Json(results.clone()))
+		let html = render_html_report(std::slice::from_ref(results));
+		Ok((
+			[
+				(header::CONTENT_TYPE, "text/html; charset=utf-8"),
+				(header::CONTENT_DISPOSITION, "attachment; filename=\"benchmark-report.html\""),
+			],
+			html,
+		))
 	} else {
 		Err(StatusCode::NOT_FOUND)
 	}
 }
+
+// Download a trace, to replay it on the server of another database
+#[utoipa::path(
+	get, path = "/traces/{name}", tag = "traces",
+	params(("name" = String, Path, description = "Name of the trace")),
+	responses(
+		(status = 200, description = "Operations of the trace, one JSON object per line", body = String, content_type = "application/x-ndjson"),
+		(status = 400, description = "Invalid trace name"),
+		(status = 404, description = "No trace of that name")
+	)
+)]
+async fn get_trace_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(name): UrlPath<String>
+) -> Result<impl IntoResponse, StatusCode> {
+	info!("Get trace handler for {}", name);
+	if !trace::is_valid_name(&name) {
+		warn!("Rejected invalid trace name {:?}", name);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	match std::fs::read(trace::trace_path(&state.traces_dir, &name)) {
+		Ok(contents) => Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], contents)),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(StatusCode::NOT_FOUND),
+		Err(e) => {
+			error!("Failed to read trace {}: {:?}", name, e);
+			Err(StatusCode::INTERNAL_SERVER_ERROR)
+		}
+	}
+}
+
+// Upload a trace recorded by another server, replacing any trace of the same name
+#[utoipa::path(
+	put, path = "/traces/{name}", tag = "traces",
+	security(("bearer_token" = [])),
+	params(("name" = String, Path, description = "Name of the trace")),
+	request_body(content = String, description = "Operations of the trace, one JSON object per line", content_type = "application/x-ndjson"),
+	responses(
+		(status = 204, description = "Trace stored"),
+		(status = 400, description = "Invalid trace name or operations"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "A run is executing")
+	)
+)]
+async fn put_trace_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(name): UrlPath<String>,
+	body: String
+) -> Result<StatusCode, ApiError> {
+	info!("Put trace handler for {}", name);
+	if !trace::is_valid_name(&name) {
+		warn!("Rejected invalid trace name {:?}", name);
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+	let invalid = body
+		.lines()
+		.filter(|line| !line.trim().is_empty())
+		.position(|line| serde_json::from_str::<TraceEntry>(line).is_err());
+	if let Some(line) = invalid {
+		warn!("Rejected trace {} with an invalid operation on line {}", name, line + 1);
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+
+	// A run may be replaying the trace
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let stored = std::fs::create_dir_all(&state.traces_dir).and_then(|_| {
+		// Write to a temporary file first so a failed upload never leaves a truncated trace behind
+		let path = trace::trace_path(&state.traces_dir, &name);
+		let tmp_path = path.with_extension("jsonl.tmp");
+		std::fs::write(&tmp_path, body)?;
+		std::fs::rename(&tmp_path, path)
+	});
+	stored.map_err(|e| {
+		error!("Failed to store trace {}: {:?}", name, e);
+		StatusCode::INTERNAL_SERVER_ERROR
+	})?;
+
+	Ok(StatusCode::NO_CONTENT)
+}
+
+// Upload a CSV file of the user's data, imported by the runs whose plan names it, replacing any
+// file of the same name
+#[utoipa::path(
+	put, path = "/imports/{name}", tag = "imports",
+	security(("bearer_token" = [])),
+	params(("name" = String, Path, description = "Name of the import")),
+	request_body(content = String, description = "Records of the import, a header row then one row per record", content_type = "text/csv"),
+	responses(
+		(status = 204, description = "CSV file stored"),
+		(status = 400, description = "Invalid import name or CSV file"),
+		(status = 401, description = "Missing or invalid API token"),
+		(status = 409, description = "A run is executing")
+	)
+)]
+async fn put_import_handler<T: DatabaseBenchmark + Send + Sync + 'static>(
+	State(state): State<Arc<AppState<T>>>,
+	UrlPath(name): UrlPath<String>,
+	body: String
+) -> Result<StatusCode, ApiError> {
+	info!("Put import handler for {}", name);
+	if !trace::is_valid_name(&name) {
+		warn!("Rejected invalid import name {:?}", name);
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+	if let Err(e) = import::check_csv(body.as_bytes()) {
+		warn!("Rejected import {} with an invalid CSV file: {:#}", name, e);
+		return Err(StatusCode::BAD_REQUEST.into());
+	}
+
+	// A run may be importing the file
+	claim_active_run(&state, Uuid::new_v4())?;
+	let _guard = ActiveRunGuard(&state.active_run);
+
+	let stored = std::fs::create_dir_all(&state.imports_dir).and_then(|_| {
+		// Write to a temporary file first so a failed upload never leaves a truncated file behind
+		let path = import::import_path(&state.imports_dir, &name);
+		let tmp_path = path.with_extension("csv.tmp");
+		std::fs::write(&tmp_path, body)?;
+		std::fs::rename(&tmp_path, path)
+	});
+	stored.map_err(|e| {
+		error!("Failed to store import {}: {:?}", name, e);
+		StatusCode::INTERNAL_SERVER_ERROR
+	})?;
+
+	Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::context::RunContext;
+
+// SQL texts a connection already prepared through its driver's statement cache. The drivers
+// don't tell whether `prepare_cached` found the statement, so a lookup counts as a hit when the
+// connection prepared the same text before. This holds as long as a connection prepares fewer
+// distinct statements than its cache capacity (16 by default), which the benchmarks do
+pub struct PreparedStatements {
+	prepared: Mutex<HashSet<&'static str>>,
+	context: RunContext,
+}
+
+impl PreparedStatements {
+	pub fn new(context: RunContext) -> Self {
+		Self { prepared: Mutex::new(HashSet::new()), context }
+	}
+
+	// Record a lookup of `sql` in the statement cache stats of the current test
+	pub fn record_lookup(&self, sql: &'static str) {
+		let hit = !self.prepared.lock().unwrap().insert(sql);
+		self.context.record_statement_cache_lookup(hit);
+	}
+}
@@ -0,0 +1,29 @@
+// Tenants sharing the database, like the customers of a SaaS: every user and order belongs to
+// one of them, and the tenant-scoped queries filter on its `tenant_id`. The SQL is the same in
+// SQLite and DuckDB.
+use uuid::Uuid;
+
+// Tenants the users and orders are spread across when the plan doesn't say
+pub const DEFAULT_TENANTS: usize = 10;
+
+// Orders read by each query of read_tenant_orders
+pub const TENANT_PAGE_LEN: usize = 20;
+
+// Tenant of a user, derived from its id so the orders of the user get the same tenant without
+// looking the user up
+pub fn tenant_of(user_id: &Uuid, tenants: usize) -> u32 {
+	(user_id.as_u128() % (tenants.max(1) as u128)) as u32
+}
+
+// A page of the orders of one tenant
+pub const READ_TENANT_ORDERS_QUERY: &str =
+	"SELECT id, user_id, product_id, quantity, total_price, created_at FROM orders WHERE tenant_id = ? LIMIT ?";
+
+// Orders, customers and revenue of every tenant
+pub const AGGREGATE_ORDERS_BY_TENANT_QUERY: &str =
+	"
+	SELECT tenant_id, COUNT(*) AS orders, COUNT(DISTINCT user_id) AS customers, SUM(total_price) AS revenue
+	FROM orders
+	GROUP BY tenant_id
+	ORDER BY tenant_id
+";
@@ -0,0 +1,624 @@
+use std::path::PathBuf;
+use anyhow::{ ensure, Context, Result };
+use uuid::Uuid;
+
+use crate::benchmark::DatabaseBenchmark;
+use crate::import::{ ImportColumn, ImportColumnType, ImportConfig };
+use crate::models::{ RecordCounts, TestStatus };
+use crate::plan::{ BenchmarkTest, KeyStrategy, NullRates, PayloadProfile, TextMode };
+
+// Conformance checks every `DatabaseBenchmark` implementation should pass. Each backend crate
+// runs them against its own implementation with `conformance_tests!`, so a new backend can be
+// validated with `cargo test` instead of a live race.
+
+// Records of each table generated before the checks needing existing data
+pub const SUITE_DATASET_SIZE: usize = 200;
+
+// Operations of each test run by the checks, below the dataset size so every test finds its records
+pub const SUITE_TEST_COUNT: usize = 50;
+
+// Writers of concurrent_inserts in the checks
+pub const SUITE_WRITERS: usize = 4;
+
+// Inserted records are stored, counted and read back by id
+pub async fn insert_read_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+
+	run_passing(benchmark, BenchmarkTest::InsertSingleManyTimes, SUITE_TEST_COUNT).await?;
+	run_passing(benchmark, BenchmarkTest::InsertManyAtOnce, SUITE_TEST_COUNT).await?;
+
+	let counts = benchmark.count_records().await?;
+	ensure!(
+		counts.total() == 2 * SUITE_TEST_COUNT,
+		"expected {} records after the insert tests, found {:?}",
+		2 * SUITE_TEST_COUNT,
+		counts
+	);
+	benchmark.verify_test(BenchmarkTest::InsertManyAtOnce, SUITE_TEST_COUNT).await?;
+
+	for test in [BenchmarkTest::ReadByIdManyTimes, BenchmarkTest::ReadManyByIds] {
+		let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+		ensure!(operations > 0, "{} read none of the inserted records", test);
+	}
+
+	Ok(())
+}
+
+// Writers inserting at the same time store every record between them, each reporting its throughput
+pub async fn concurrent_inserts_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	context.set_writers(SUITE_WRITERS);
+	let checked = async {
+		reset_data(benchmark).await?;
+
+		let operations = run_passing(benchmark, BenchmarkTest::ConcurrentInserts, SUITE_TEST_COUNT).await?;
+		ensure!(operations == SUITE_TEST_COUNT as u64, "the writers recorded {} inserts", operations);
+		let counts = benchmark.count_records().await?;
+		ensure!(
+			counts.users == SUITE_TEST_COUNT,
+			"expected {} users after the concurrent inserts, found {:?}",
+			SUITE_TEST_COUNT,
+			counts
+		);
+		benchmark.verify_test(BenchmarkTest::ConcurrentInserts, SUITE_TEST_COUNT).await?;
+
+		let result = benchmark.run_test(BenchmarkTest::ConcurrentInserts, SUITE_TEST_COUNT).await?;
+		let rates = result.writer_ops_per_second.unwrap_or_default();
+		ensure!(
+			rates.len() == SUITE_WRITERS && rates.iter().all(|rate| *rate > 0.0),
+			"expected the throughput of {} writers, found {:?}",
+			SUITE_WRITERS,
+			rates
+		);
+		Ok(())
+	}.await;
+	context.set_writers(0);
+	checked
+}
+
+// Connections contending with a long write transaction count every operation they ran, and the
+// transaction commits once they are done, leaving the records as they were and the database
+// writable. Nothing to check for a backend without transactions
+pub async fn long_transaction_contention_settles<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	if !benchmark.capabilities().transactions {
+		return Ok(());
+	}
+	let context = benchmark.run_context();
+	context.set_writers(SUITE_WRITERS);
+	let checked = async {
+		reset_data(benchmark).await?;
+		benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+		let before = benchmark.count_records().await?;
+
+		let result = benchmark.run_test(BenchmarkTest::LongTransactionContention, SUITE_TEST_COUNT).await?;
+		ensure!(result.status == TestStatus::Passed, "the test ended with status {:?}", result.status);
+		let stats = result.contention.context("the test reported no contention")?;
+		let writes = (SUITE_TEST_COUNT / 2) as u64;
+		ensure!(
+			stats.reads == (SUITE_TEST_COUNT as u64) - writes && stats.writes == writes,
+			"expected {} operations, half of them writes, found {:?}",
+			SUITE_TEST_COUNT,
+			stats
+		);
+		ensure!(
+			stats.failed_reads <= stats.reads && stats.failed_writes <= stats.writes,
+			"more operations failed than ran: {:?}",
+			stats
+		);
+
+		let after = benchmark.count_records().await?;
+		ensure!(after == before, "the test changed the records from {:?} to {:?}", before, after);
+		// Nothing is left locked once the transaction committed
+		run_passing(benchmark, BenchmarkTest::UpdateSingleFieldOneEntry, SUITE_TEST_COUNT).await?;
+		Ok(())
+	}.await;
+	context.set_writers(0);
+	checked
+}
+
+// Writers incrementing a counter at the same time account for every increment, committed or
+// aborted, and the counter holds those that weren't lost
+pub async fn consistency_probe_accounts<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	context.set_writers(SUITE_WRITERS);
+	let checked = async {
+		reset_data(benchmark).await?;
+		benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+		let before = benchmark.count_records().await?;
+
+		let result = benchmark.run_test(BenchmarkTest::ConsistencyProbe, SUITE_TEST_COUNT).await?;
+		ensure!(result.status == TestStatus::Passed, "the test ended with status {:?}", result.status);
+		let stats = result.consistency.context("the test reported no consistency")?;
+		ensure!(
+			stats.committed + stats.aborted == SUITE_TEST_COUNT as u64,
+			"expected {} increments, found {:?}",
+			SUITE_TEST_COUNT,
+			stats
+		);
+		ensure!(
+			stats.applied <= stats.committed && stats.lost_updates == stats.committed - stats.applied,
+			"the counter doesn't match the committed increments: {:?}",
+			stats
+		);
+
+		let after = benchmark.count_records().await?;
+		ensure!(after == before, "the test changed the records from {:?} to {:?}", before, after);
+		Ok(())
+	}.await;
+	context.set_writers(0);
+	checked
+}
+
+// The values written by the update tests are visible afterwards, and no record is added or lost
+pub async fn update_visibility<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+	let before = benchmark.count_records().await?;
+
+	for test in [
+		BenchmarkTest::UpdateSingleFieldOneEntry,
+		BenchmarkTest::UpdateSingleFieldManyEntries,
+		BenchmarkTest::UpdateMultipleFieldsOneEntry,
+		BenchmarkTest::UpdateMultipleFieldsManyEntries,
+	] {
+		run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+		benchmark
+			.verify_test(test, SUITE_TEST_COUNT).await
+			.with_context(|| format!("the writes of {} are not visible", test))?;
+	}
+
+	let after = benchmark.count_records().await?;
+	ensure!(after == before, "the update tests changed the records from {:?} to {:?}", before, after);
+
+	Ok(())
+}
+
+// Generated data fills every table, and cleanup removes all of it
+pub async fn cleanup_empties_everything<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	let counts = benchmark.count_records().await?;
+	let expected = RecordCounts {
+		users: SUITE_DATASET_SIZE,
+		products: SUITE_DATASET_SIZE,
+		orders: SUITE_DATASET_SIZE,
+		order_items: counts.order_items,
+		wide_records: SUITE_DATASET_SIZE,
+	};
+	ensure!(counts.order_items >= SUITE_DATASET_SIZE, "generated orders have no items: {:?}", counts);
+	ensure!(counts == expected, "expected {:?} after generation, found {:?}", expected, counts);
+
+	benchmark.cleanup().await?;
+	let counts = benchmark.count_records().await?;
+	ensure!(counts == RecordCounts::default(), "{:?} records left after cleanup", counts);
+
+	Ok(())
+}
+
+// Every order joins its user and product, and the join tests read the joined records, for a
+// backend with joins
+pub async fn join_correctness<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	if !benchmark.capabilities().joins {
+		return Ok(());
+	}
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	for test in [BenchmarkTest::ReadWithOneJoin, BenchmarkTest::ReadWithTwoJoins] {
+		let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+		ensure!(operations > 0, "{} joined no orders", test);
+
+		// Reports orders referencing a missing user or product, which a join would silently drop
+		benchmark
+			.verify_test(test, SUITE_TEST_COUNT).await
+			.with_context(|| format!("the data read by {} is inconsistent", test))?;
+	}
+
+	Ok(())
+}
+
+// Orders inserted with their items are stored in one piece, and read back with them by a backend
+// with joins
+pub async fn order_items_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	let before = benchmark.count_records().await?;
+	run_passing(benchmark, BenchmarkTest::InsertOrdersWithItems, SUITE_TEST_COUNT).await?;
+	benchmark
+		.verify_after_test(BenchmarkTest::InsertOrdersWithItems, SUITE_TEST_COUNT, before).await
+		.context("the orders were not inserted with their items")?;
+
+	// Reading them back joins their items and products, for a backend with joins
+	if benchmark.capabilities().joins {
+		let operations = run_passing(benchmark, BenchmarkTest::ReadOrdersWithItems, SUITE_TEST_COUNT).await?;
+		ensure!(operations > 0, "read_orders_with_items read no orders");
+	}
+
+	Ok(())
+}
+
+// Wide records are inserted whole, and read back whole by id or a few columns at a time
+pub async fn wide_records_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	let before = benchmark.count_records().await?;
+	run_passing(benchmark, BenchmarkTest::InsertWideRecords, SUITE_TEST_COUNT).await?;
+	benchmark
+		.verify_after_test(BenchmarkTest::InsertWideRecords, SUITE_TEST_COUNT, before).await
+		.context("the wide records were not inserted")?;
+
+	for test in [BenchmarkTest::ReadWideRecordsById, BenchmarkTest::ReadWideColumnSubset] {
+		let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+		ensure!(operations > 0, "{} read no wide records", test);
+	}
+
+	Ok(())
+}
+
+// The orders of each tenant are read on their own, and aggregated across tenants
+pub async fn tenant_queries<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	for test in [BenchmarkTest::ReadTenantOrders, BenchmarkTest::AggregateOrdersByTenant] {
+		let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+		ensure!(operations > 0, "{} read no orders", test);
+	}
+
+	Ok(())
+}
+
+// Records generated with time-ordered and sequential ids are inserted, read back and joined like
+// the ones with random ids
+pub async fn ordered_keys_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	for strategy in [KeyStrategy::UuidV7, KeyStrategy::Ulid, KeyStrategy::Sequential] {
+		context.set_key_strategy(strategy);
+		let checked = async {
+			reset_data(benchmark).await?;
+			benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+			run_passing(benchmark, BenchmarkTest::InsertSingleManyTimes, SUITE_TEST_COUNT).await?;
+			benchmark.verify_test(BenchmarkTest::InsertSingleManyTimes, SUITE_TEST_COUNT).await?;
+			for test in [BenchmarkTest::ReadByIdManyTimes, BenchmarkTest::ReadWithTwoJoins] {
+				let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+				ensure!(operations > 0, "{} read no records", test);
+			}
+			Ok(())
+		}.await;
+		context.set_key_strategy(KeyStrategy::default());
+		checked.with_context(|| format!("with {} keys", strategy))?;
+	}
+
+	Ok(())
+}
+
+// Names holding multi-byte Unicode, emoji, quotes, SQL metacharacters and very long strings are
+// stored and read back unchanged, and searched and joined like ASCII ones
+pub async fn adversarial_text_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	context.set_payload(PayloadProfile { text: TextMode::Adversarial, ..PayloadProfile::default() });
+	let checked = async {
+		reset_data(benchmark).await?;
+		benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+		benchmark.verify_test(BenchmarkTest::ReadByIdManyTimes, SUITE_TEST_COUNT).await?;
+
+		for test in [BenchmarkTest::InsertSingleManyTimes, BenchmarkTest::InsertManyAtOnce] {
+			run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+			benchmark
+				.verify_test(test, SUITE_TEST_COUNT).await
+				.with_context(|| format!("the names written by {} changed", test))?;
+		}
+		for test in [BenchmarkTest::ReadByColumnSearch, BenchmarkTest::ReadWithTwoJoins] {
+			let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+			ensure!(operations > 0, "{} read no records", test);
+		}
+		Ok(())
+	}.await;
+	context.set_payload(PayloadProfile::default());
+	checked
+}
+
+// The projection and the full rows read the same products, and only the projection counts as
+// the test's operations
+pub async fn projection_reads_every_row<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+	let context = benchmark.run_context();
+	context.start_test(BenchmarkTest::ReadProjection.name());
+	let result = benchmark.run_test(BenchmarkTest::ReadProjection, SUITE_TEST_COUNT).await;
+	let operations = context.snapshot().map_or(0, |(_, operations)| operations);
+	context.finish_test();
+
+	let result = result.context("read_projection failed")?;
+	ensure!(result.status == TestStatus::Passed, "the test ended with status {:?}", result.status);
+	ensure!(operations == SUITE_TEST_COUNT as u64, "the projection read {} products", operations);
+	ensure!(
+		result.all_columns_ops_per_second.is_some_and(|rate| rate > 0.0),
+		"the test reported no throughput with every column"
+	);
+	Ok(())
+}
+
+// Optional fields are stored as NULL when not set, and only when not set: the filter tests read
+// every record when none of the fields are set and none when all of them are
+pub async fn null_filters<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	let tests = [BenchmarkTest::ReadUsersNotDeleted, BenchmarkTest::ReadDiscountedProducts];
+
+	for (null_rates, expect_rows) in [
+		(NullRates { deleted_at: 1.0, discount: 0.0 }, true),
+		(NullRates { deleted_at: 0.0, discount: 1.0 }, false),
+	] {
+		context.set_payload(PayloadProfile { null_rates, ..PayloadProfile::default() });
+		let checked = async {
+			reset_data(benchmark).await?;
+			benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+			for test in tests {
+				let operations = run_passing(benchmark, test, SUITE_TEST_COUNT).await?;
+				if expect_rows {
+					ensure!(operations == SUITE_TEST_COUNT as u64, "{} read {} records", test, operations);
+				} else {
+					ensure!(operations == 0, "{} read {} records its filter excludes", test, operations);
+				}
+			}
+			Ok(())
+		}.await;
+		context.set_payload(PayloadProfile::default());
+		checked?;
+	}
+
+	Ok(())
+}
+
+// Soft-deleted users leave the live ones: read_live_users reads only the half of the users not
+// deleted, including after soft_delete_users marked some of them
+pub async fn soft_delete<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	let context = benchmark.run_context();
+	let null_rates = NullRates { deleted_at: 1.0, ..NullRates::default() };
+	context.set_payload(PayloadProfile { null_rates, ..PayloadProfile::default() });
+	let checked = async {
+		reset_data(benchmark).await?;
+		benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+
+		let deleted = run_passing(benchmark, BenchmarkTest::SoftDeleteUsers, SUITE_TEST_COUNT).await?;
+		ensure!(deleted == SUITE_TEST_COUNT as u64, "soft_delete_users deleted {} users", deleted);
+		let live = run_passing(benchmark, BenchmarkTest::ReadUsersNotDeleted, SUITE_DATASET_SIZE).await?;
+		ensure!(
+			live == (SUITE_DATASET_SIZE - SUITE_TEST_COUNT) as u64,
+			"read {} users not deleted after deleting {} of {}",
+			live,
+			SUITE_TEST_COUNT,
+			SUITE_DATASET_SIZE
+		);
+
+		let live = run_passing(benchmark, BenchmarkTest::ReadLiveUsers, SUITE_DATASET_SIZE).await?;
+		ensure!(
+			live == (SUITE_DATASET_SIZE / 2) as u64,
+			"read_live_users read {} of {} users, half of them soft-deleted",
+			live,
+			SUITE_DATASET_SIZE
+		);
+		Ok(())
+	}.await;
+	context.set_payload(PayloadProfile::default());
+	checked
+}
+
+// The probe reads its blob back and drops its table: it can run again, and leaves the records as
+// they were
+pub async fn probe_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+	let before = benchmark.count_records().await?;
+
+	for _ in 0..2 {
+		let capabilities = benchmark.probe().await?;
+		ensure!(capabilities.blobs, "the probe blob didn't read back unchanged");
+	}
+
+	let after = benchmark.count_records().await?;
+	ensure!(after == before, "{:?} records after the probes, {:?} before", after, before);
+	Ok(())
+}
+
+// A restored snapshot holds the records of the snapshot, whatever was written or deleted since
+pub async fn snapshot_restore_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+	let snapshot = benchmark.count_records().await?;
+
+	let dir = scratch_dir("snapshot");
+	let checked = async {
+		benchmark.snapshot(&dir).await.context("snapshot failed")?;
+
+		benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+		benchmark.restore(&dir).await.context("restore failed")?;
+		let counts = benchmark.count_records().await?;
+		ensure!(
+			counts == snapshot,
+			"{:?} records after restoring over new ones, {:?} in the snapshot",
+			counts,
+			snapshot
+		);
+
+		benchmark.cleanup().await?;
+		benchmark.restore(&dir).await.context("restore failed")?;
+		let counts = benchmark.count_records().await?;
+		ensure!(
+			counts == snapshot,
+			"{:?} records after restoring an empty database, {:?} in the snapshot",
+			counts,
+			snapshot
+		);
+		Ok(())
+	}.await;
+
+	let _ = std::fs::remove_dir_all(&dir);
+	checked
+}
+
+// An import loads every row of its CSV file, empty cells included, reads and updates each record
+// and drops its table, leaving the records of the dataset as they were. It can run again
+pub async fn import_roundtrip<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	reset_data(benchmark).await?;
+	benchmark.generate_test_data(SUITE_DATASET_SIZE).await?;
+	let before = benchmark.count_records().await?;
+
+	let dir = scratch_dir("import");
+	let checked = async {
+		let column = |name: &str, column_type, anonymize| ImportColumn { name: name.to_string(), column_type, anonymize };
+		let import = ImportConfig {
+			name: "suite".to_string(),
+			columns: vec![
+				column("quantity", ImportColumnType::Integer, false),
+				column("email", ImportColumnType::Text, true),
+				column("score", ImportColumnType::Real, false),
+				column("vip", ImportColumnType::Boolean, false),
+			],
+		}.at(&dir);
+		// Every fifth score is left empty
+		let rows: String = (0..SUITE_TEST_COUNT)
+			.map(|i| {
+				let score = if i % 5 == 0 { String::new() } else { format!("{}.5", i) };
+				format!("{},{},user{}@example.com,{}\n", i % 2 == 0, score, i, i)
+			})
+			.collect();
+		std::fs::create_dir_all(&dir)?;
+		std::fs::write(&import.path, format!("vip,score,email,quantity\n{}", rows))?;
+
+		for _ in 0..2 {
+			let results = benchmark.run_import_suite(&import, KeyStrategy::default()).await?;
+			for result in &results {
+				ensure!(
+					result.status == TestStatus::Passed,
+					"{} ended with status {:?}",
+					result.test_name,
+					result.status
+				);
+				ensure!(
+					result.operations == SUITE_TEST_COUNT,
+					"{} ran {} operations over {} records",
+					result.test_name,
+					result.operations,
+					SUITE_TEST_COUNT
+				);
+			}
+		}
+
+		let after = benchmark.count_records().await?;
+		ensure!(after == before, "{:?} records after the import, {:?} before", after, before);
+		Ok(())
+	}.await;
+
+	let _ = std::fs::remove_dir_all(&dir);
+	checked
+}
+
+// Run every conformance check, stopping at the first failure
+pub async fn run_all<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	insert_read_roundtrip(benchmark).await.context("insert_read_roundtrip")?;
+	concurrent_inserts_roundtrip(benchmark).await.context("concurrent_inserts_roundtrip")?;
+	long_transaction_contention_settles(benchmark).await.context("long_transaction_contention_settles")?;
+	consistency_probe_accounts(benchmark).await.context("consistency_probe_accounts")?;
+	update_visibility(benchmark).await.context("update_visibility")?;
+	cleanup_empties_everything(benchmark).await.context("cleanup_empties_everything")?;
+	join_correctness(benchmark).await.context("join_correctness")?;
+	order_items_roundtrip(benchmark).await.context("order_items_roundtrip")?;
+	wide_records_roundtrip(benchmark).await.context("wide_records_roundtrip")?;
+	tenant_queries(benchmark).await.context("tenant_queries")?;
+	ordered_keys_roundtrip(benchmark).await.context("ordered_keys_roundtrip")?;
+	adversarial_text_roundtrip(benchmark).await.context("adversarial_text_roundtrip")?;
+	null_filters(benchmark).await.context("null_filters")?;
+	projection_reads_every_row(benchmark).await.context("projection_reads_every_row")?;
+	soft_delete(benchmark).await.context("soft_delete")?;
+	probe_roundtrip(benchmark).await.context("probe_roundtrip")?;
+	snapshot_restore_roundtrip(benchmark).await.context("snapshot_restore_roundtrip")?;
+	import_roundtrip(benchmark).await.context("import_roundtrip")?;
+	Ok(())
+}
+
+// New directory under the system temporary directory for the database files of one check
+pub fn scratch_dir(name: &str) -> PathBuf {
+	std::env::temp_dir().join(format!("database-race-{}-{}", name, Uuid::new_v4()))
+}
+
+// Start from an initialized and empty database
+async fn reset_data<T: DatabaseBenchmark + Sync>(benchmark: &T) -> Result<()> {
+	benchmark.init().await?;
+	benchmark.cleanup().await?;
+
+	let counts = benchmark.count_records().await?;
+	ensure!(counts == RecordCounts::default(), "{:?} records left after cleanup", counts);
+	Ok(())
+}
+
+// Run a test the way `run_all_benchmarks` does, failing unless it passed,
+// and return the number of operations it recorded
+async fn run_passing<T: DatabaseBenchmark + Sync>(
+	benchmark: &T,
+	test: BenchmarkTest,
+	count: usize
+) -> Result<u64> {
+	let context = benchmark.run_context();
+	context.start_test(test.name());
+	let result = benchmark.run_test(test, count).await;
+	let operations = context.snapshot().map_or(0, |(_, operations)| operations);
+	context.finish_test();
+
+	let result = result.with_context(|| format!("{} failed", test))?;
+	ensure!(result.status == TestStatus::Passed, "{} ended with status {:?}", test, result.status);
+	Ok(operations)
+}
+
+// Declare one `#[tokio::test]` per conformance check in a backend crate. The expression builds
+// the benchmark from `$dir`, a new scratch directory removed once the check is done:
+//
+//     common::conformance_tests!(dir => SqliteBenchmark::new(1, &dir));
+#[macro_export]
+macro_rules! conformance_tests {
+	($dir:ident => $new:expr) => {
+		$crate::conformance_tests!(
+			@checks $dir => $new;
+			insert_read_roundtrip,
+			concurrent_inserts_roundtrip,
+			long_transaction_contention_settles,
+			consistency_probe_accounts,
+			update_visibility,
+			cleanup_empties_everything,
+			join_correctness,
+			order_items_roundtrip,
+			wide_records_roundtrip,
+			tenant_queries,
+			ordered_keys_roundtrip,
+			adversarial_text_roundtrip,
+			null_filters,
+			projection_reads_every_row,
+			soft_delete,
+			probe_roundtrip,
+			snapshot_restore_roundtrip,
+			import_roundtrip
+		);
+	};
+	(@checks $dir:ident => $new:expr; $($check:ident),+) => {
+		$(
+			#[tokio::test]
+			async fn $check() {
+				let $dir = $crate::testsuite::scratch_dir(stringify!($check));
+				let benchmark = $new.await.expect("failed to create the benchmark");
+				let outcome = $crate::testsuite::$check(&benchmark).await;
+
+				drop(benchmark);
+				let _ = std::fs::remove_dir_all(&$dir);
+				if let Err(e) = outcome {
+					panic!("{:#}", e);
+				}
+			}
+		)+
+	};
+}
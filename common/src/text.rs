@@ -0,0 +1,61 @@
+// Adversarial text appended to the generated names in the `adversarial` text mode: multi-byte
+// scripts, emoji sequences, combining and invisible characters, quotes, SQL and LIKE
+// metacharacters, and now and then a very long string. The text of a record is picked from its
+// id, so the verification can tell whether the names were stored and read back unchanged.
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::plan::TextMode;
+
+const ADVERSARIAL_TEXTS: [&str; 16] = [
+	"Zoë Saldaña Ångström",
+	"李小龍 東京都 서울특별시",
+	"Владимир Достоевский",
+	"مرحبا بالعالم שלום",
+	"👩‍👩‍👧‍👦 🏳️‍🌈 👍🏽",
+	"e\u{301}le\u{300}ve cafe\u{301} n\u{303}",
+	"𝕌𝕟𝕚𝕔𝕠𝕕𝕖 𐍈 𝄞",
+	"O'Brien's \"quoted\" `backticked`",
+	"'; DROP TABLE users; --",
+	"Robert'); DELETE FROM products WHERE ('1'='1",
+	"100% _under_score_ \\back\\slash\\",
+	"tab\tnew\nline\r\nend",
+	"\u{FEFF}bom \u{200B}zero\u{200B}width \u{202E}override",
+	"<script>alert('x')</script> &amp;",
+	"ﷺ ﷽ ꧅",
+	"NULL",
+];
+
+// Characters of the long text, which one record in `ADVERSARIAL_TEXTS.len() + 1` gets
+const LONG_TEXT_CHARS: usize = 10_000;
+
+static LONG_TEXT: LazyLock<String> = LazyLock::new(|| {
+	"Ωß😀 ".chars().cycle().take(LONG_TEXT_CHARS).collect()
+});
+
+// Average bytes added to a name by the adversarial text, its separating space included
+pub static ADVERSARIAL_TEXT_BYTES: LazyLock<u64> = LazyLock::new(|| {
+	let total: usize = ADVERSARIAL_TEXTS.iter().map(|text| text.len()).sum::<usize>() + LONG_TEXT.len();
+	1 + (total as u64) / ((ADVERSARIAL_TEXTS.len() + 1) as u64)
+});
+
+// Adversarial text of the record with `id`
+pub fn adversarial_text(id: &Uuid) -> &'static str {
+	match (id.as_u128() % ((ADVERSARIAL_TEXTS.len() + 1) as u128)) as usize {
+		index if index < ADVERSARIAL_TEXTS.len() => ADVERSARIAL_TEXTS[index],
+		_ => LONG_TEXT.as_str(),
+	}
+}
+
+// `value` followed by the adversarial text of `id` in the adversarial mode, as it is otherwise
+pub fn with_text(mode: TextMode, id: &Uuid, value: String) -> String {
+	match mode {
+		TextMode::Ascii => value,
+		TextMode::Adversarial => value + " " + adversarial_text(id),
+	}
+}
+
+// Whether a name generated in the adversarial mode still holds the text of its record
+pub fn holds_text(id: &Uuid, value: &str) -> bool {
+	value.contains(adversarial_text(id))
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+use std::fs::{ self, File, OpenOptions };
+use std::io::{ BufRead, BufReader, BufWriter, Write };
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+use anyhow::{ Context, Result };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::models::User;
+use crate::plan::BenchmarkTest;
+use crate::workloads::Workload;
+
+// Traces of the workloads: every operation a workload runs, with its key and the record it writes,
+// saved as JSON Lines while recording and run again from the file when replaying. A replay runs
+// the same operations on the same records on every database, instead of drawing them again.
+//
+// A recorded workload first loads users of its own, then reads, updates and scans them, so the
+// trace doesn't depend on the keys of the seeded data, which differ from one database to another.
+
+// Users a recorded workload loads before its operations
+pub const TRACE_LOADED_USERS: usize = 1_000;
+
+// Whether a run writes the operations of its workloads to a trace or runs those of a trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceMode {
+	Record,
+	Replay,
+}
+
+impl fmt::Display for TraceMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			TraceMode::Record => "record",
+			TraceMode::Replay => "replay",
+		})
+	}
+}
+
+impl FromStr for TraceMode {
+	type Err = String;
+
+	fn from_str(mode: &str) -> Result<Self, Self::Err> {
+		match mode {
+			"record" => Ok(TraceMode::Record),
+			"replay" => Ok(TraceMode::Replay),
+			_ => Err(format!("unknown trace mode: {}, expected record or replay", mode)),
+		}
+	}
+}
+
+// Trace of a plan, stored in the traces directory of the server under its name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TraceConfig {
+	pub mode: TraceMode,
+	pub name: String,
+}
+
+impl TraceConfig {
+	// Trace file of the config in `dir`
+	pub fn at(&self, dir: &Path) -> Trace {
+		Trace { mode: self.mode, path: trace_path(dir, &self.name) }
+	}
+}
+
+// Trace a run records or replays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+	pub mode: TraceMode,
+	pub path: PathBuf,
+}
+
+// Names are used as file names, so they can't leave the traces directory
+pub fn is_valid_name(name: &str) -> bool {
+	!name.is_empty() &&
+		name.len() <= 64 &&
+		name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// File of the trace `name` in `dir`
+pub fn trace_path(dir: &Path, name: &str) -> PathBuf {
+	dir.join(format!("{}.jsonl", name))
+}
+
+// Kind of a traced operation. `load` inserts the users of the workload before it starts, untimed;
+// a read-modify-write reads then updates the same records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceOp {
+	Load,
+	Read,
+	Update,
+	Insert,
+	Scan,
+	ReadModifyWrite,
+}
+
+impl TraceOp {
+	// Operations run, one after the other, for an operation of this kind: reads, updates, inserts
+	// or scans of users
+	pub fn steps(&self) -> &'static [TraceOp] {
+		match self {
+			TraceOp::Load | TraceOp::Insert => &[TraceOp::Insert],
+			TraceOp::Read => &[TraceOp::Read],
+			TraceOp::Update => &[TraceOp::Update],
+			TraceOp::Scan => &[TraceOp::Scan],
+			TraceOp::ReadModifyWrite => &[TraceOp::Read, TraceOp::Update],
+		}
+	}
+
+	// Test a step runs with, the same one as in the workloads that aren't traced
+	pub fn test(&self) -> BenchmarkTest {
+		match self.steps()[0] {
+			TraceOp::Read => BenchmarkTest::ReadByIdManyTimes,
+			TraceOp::Update => BenchmarkTest::UpdateSingleFieldOneEntry,
+			TraceOp::Scan => BenchmarkTest::ReadManyByIds,
+			_ => BenchmarkTest::InsertSingleManyTimes,
+		}
+	}
+}
+
+// An operation of a trace, one line of its file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+	pub workload: Workload,
+	// Operations of the same chunk run together, as a single test
+	pub chunk: usize,
+	pub op: TraceOp,
+	pub key: Uuid,
+	// User written by a load or insert
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub payload: Option<User>,
+}
+
+// Operations of a kind run together: the keys they access, and the users they write for inserts
+#[derive(Debug, Clone)]
+pub struct TraceChunk {
+	pub op: TraceOp,
+	pub keys: Vec<Uuid>,
+	pub users: Vec<User>,
+}
+
+impl TraceChunk {
+	// Chunk writing `users`
+	pub fn writing(op: TraceOp, users: Vec<User>) -> Self {
+		TraceChunk { op, keys: users.iter().map(|user| user.id).collect(), users }
+	}
+
+	// Chunk accessing the records of `keys`
+	pub fn accessing(op: TraceOp, keys: Vec<Uuid>) -> Self {
+		TraceChunk { op, keys, users: Vec::new() }
+	}
+
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+}
+
+// Start a recording, replacing any previous trace of the same name
+pub fn create(path: &Path) -> Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	File::create(path).with_context(|| format!("Could not create trace {}", path.display()))?;
+	Ok(())
+}
+
+// Append the operations of a workload to a trace
+pub fn append(path: &Path, workload: Workload, chunks: &[TraceChunk]) -> Result<()> {
+	let file = OpenOptions::new()
+		.append(true)
+		.open(path)
+		.with_context(|| format!("Could not open trace {}", path.display()))?;
+	let mut writer = BufWriter::new(file);
+
+	for (chunk, operations) in chunks.iter().enumerate() {
+		for (i, &key) in operations.keys.iter().enumerate() {
+			let entry = TraceEntry {
+				workload,
+				chunk,
+				op: operations.op,
+				key,
+				payload: operations.users.get(i).cloned(),
+			};
+			serde_json::to_writer(&mut writer, &entry)?;
+			writer.write_all(b"\n")?;
+		}
+	}
+
+	writer.flush()?;
+	Ok(())
+}
+
+// Operations of a workload in a trace, grouped in their chunks
+pub fn read(path: &Path, workload: Workload) -> Result<Vec<TraceChunk>> {
+	let file = File::open(path).with_context(|| format!("Could not open trace {}", path.display()))?;
+	let mut chunks: Vec<TraceChunk> = Vec::new();
+	let mut current = None;
+
+	for (number, line) in BufReader::new(file).lines().enumerate() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		let entry: TraceEntry = serde_json::from_str(&line)
+			.with_context(|| format!("Invalid operation on line {} of trace {}", number + 1, path.display()))?;
+		if entry.workload != workload {
+			continue;
+		}
+
+		if current != Some(entry.chunk) {
+			current = Some(entry.chunk);
+			chunks.push(TraceChunk::accessing(entry.op, Vec::new()));
+		}
+		let chunk = chunks.last_mut().unwrap();
+		chunk.keys.push(entry.key);
+		chunk.users.extend(entry.payload);
+	}
+
+	anyhow::ensure!(!chunks.is_empty(), "Trace {} has no operations of {}", path.display(), workload);
+	Ok(chunks)
+}
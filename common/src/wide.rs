@@ -0,0 +1,87 @@
+// A table of many columns of mixed types, read whole by id or a few columns at a time. The row
+// stores read every column of the rows they go through, while DuckDB's columnar layout only
+// reads the columns a query selects. The SQL is the same in SQLite and DuckDB.
+use std::sync::LazyLock;
+use rand::{ distributions::Alphanumeric, Rng };
+
+use crate::fixtures::DataRng;
+use crate::keys::new_key;
+use crate::models::{ WideRecord, WideValue };
+use crate::plan::KeyStrategy;
+
+// Columns besides the id, named c00 to c98, so the table has 100 columns
+pub const WIDE_COLUMN_COUNT: usize = 99;
+
+// Columns read by read_wide_column_subset: an integer, a real and a text column
+pub const WIDE_SUBSET_COLUMNS: [usize; 3] = [0, 41, 82];
+
+// Length of the generated text values
+const WIDE_TEXT_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WideColumnType {
+	Integer,
+	Real,
+	Text,
+	Boolean,
+}
+
+// Type of a column, cycling through the four types
+pub fn column_type(index: usize) -> WideColumnType {
+	match index % 4 {
+		0 => WideColumnType::Integer,
+		1 => WideColumnType::Real,
+		2 => WideColumnType::Text,
+		_ => WideColumnType::Boolean,
+	}
+}
+
+pub fn column_name(index: usize) -> String {
+	format!("c{:02}", index)
+}
+
+pub fn generate_wide_record(keys: KeyStrategy) -> WideRecord {
+	let mut rng = DataRng;
+	let values = (0..WIDE_COLUMN_COUNT)
+		.map(|index| match column_type(index) {
+			WideColumnType::Integer => WideValue::Integer(rng.gen_range(0..1_000_000)),
+			WideColumnType::Real => WideValue::Real((rng.gen_range(0..1_000_000) as f64) / 100.0),
+			WideColumnType::Text => {
+				let text = (&mut rng).sample_iter(Alphanumeric).take(WIDE_TEXT_LEN).map(char::from).collect();
+				WideValue::Text(text)
+			}
+			WideColumnType::Boolean => WideValue::Boolean(rng.gen_bool(0.5)),
+		})
+		.collect();
+
+	WideRecord { id: new_key(keys), values }
+}
+
+// CREATE TABLE statement of the wide table, `sql_type` naming the type of each column
+pub fn create_table_query(id_type: &str, sql_type: impl Fn(WideColumnType) -> &'static str) -> String {
+	let columns: Vec<String> = (0..WIDE_COLUMN_COUNT)
+		.map(|index| format!("{} {} NOT NULL", column_name(index), sql_type(column_type(index))))
+		.collect();
+	format!("CREATE TABLE IF NOT EXISTS wide_records (id {} PRIMARY KEY, {})", id_type, columns.join(", "))
+}
+
+fn column_list(columns: impl Iterator<Item = usize>) -> String {
+	columns.map(column_name).collect::<Vec<_>>().join(", ")
+}
+
+pub static INSERT_WIDE_RECORD_QUERY: LazyLock<String> = LazyLock::new(|| {
+	format!(
+		"INSERT INTO wide_records (id, {}) VALUES (?{})",
+		column_list(0..WIDE_COLUMN_COUNT),
+		", ?".repeat(WIDE_COLUMN_COUNT)
+	)
+});
+
+pub const READ_WIDE_RECORD_QUERY: &str = "SELECT * FROM wide_records WHERE id = ?";
+
+pub static READ_WIDE_COLUMN_SUBSET_QUERY: LazyLock<String> = LazyLock::new(|| {
+	format!(
+		"SELECT {} FROM wide_records LIMIT ? OFFSET ?",
+		column_list(WIDE_SUBSET_COLUMNS.into_iter())
+	)
+});
@@ -0,0 +1,328 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{ Duration, Instant };
+use anyhow::Result;
+use rand::Rng;
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+use crate::benchmark::{ generate_random_user, operations_per_second, DatabaseBenchmark };
+use crate::context::RunContext;
+use crate::distribution::KeyChooser;
+use crate::models::{ BenchmarkResult, TestStatus };
+use crate::plan::{
+	AccessDistribution,
+	BenchmarkTest,
+	DEFAULT_ZIPFIAN_THETA,
+};
+use crate::trace::{ self, TraceChunk, TraceMode, TraceOp, TRACE_LOADED_USERS };
+
+// Operations run by a workload when the plan doesn't say
+pub const DEFAULT_WORKLOAD_OPERATIONS: usize = 10_000;
+
+// Operations of the same kind run back to back. Each chunk is prepared before it is timed, like a
+// test, so smaller chunks interleave the kinds more finely but prepare more often.
+const WORKLOAD_CHUNK_SIZE: usize = 100;
+
+// The YCSB core workloads, mixing reads, updates, inserts and scans of records picked by id.
+// Their operations are mapped to the tests of the benchmark:
+// - read: `read_by_id_many_times`
+// - update: `update_single_field_one_entry`
+// - insert: `insert_single_many_times`
+// - scan: `read_many_by_ids`, each scanned record counting as an operation
+// - read-modify-write: a read followed by an update, of records drawn independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Workload {
+	// Update heavy: 50% reads, 50% updates
+	YcsbA,
+	// Read mostly: 95% reads, 5% updates
+	YcsbB,
+	// Read only
+	YcsbC,
+	// Read latest: 95% reads, 5% inserts
+	YcsbD,
+	// Short ranges: 95% scans, 5% inserts
+	YcsbE,
+	// Read-modify-write: 50% reads, 50% read-modify-writes
+	YcsbF,
+}
+
+// Share of each kind of operation in a workload, summing to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkloadMix {
+	pub read: f64,
+	pub update: f64,
+	pub insert: f64,
+	pub scan: f64,
+	pub read_modify_write: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+	Read,
+	Update,
+	Insert,
+	Scan,
+	ReadModifyWrite,
+}
+
+impl Workload {
+	pub const ALL: [Workload; 6] = [
+		Workload::YcsbA,
+		Workload::YcsbB,
+		Workload::YcsbC,
+		Workload::YcsbD,
+		Workload::YcsbE,
+		Workload::YcsbF,
+	];
+
+	// Name used in URLs, plans and results
+	pub fn name(&self) -> &'static str {
+		match self {
+			Workload::YcsbA => "ycsb_a",
+			Workload::YcsbB => "ycsb_b",
+			Workload::YcsbC => "ycsb_c",
+			Workload::YcsbD => "ycsb_d",
+			Workload::YcsbE => "ycsb_e",
+			Workload::YcsbF => "ycsb_f",
+		}
+	}
+
+	pub fn mix(&self) -> WorkloadMix {
+		let none = WorkloadMix { read: 0.0, update: 0.0, insert: 0.0, scan: 0.0, read_modify_write: 0.0 };
+		match self {
+			Workload::YcsbA => WorkloadMix { read: 0.5, update: 0.5, ..none },
+			Workload::YcsbB => WorkloadMix { read: 0.95, update: 0.05, ..none },
+			Workload::YcsbC => WorkloadMix { read: 1.0, ..none },
+			Workload::YcsbD => WorkloadMix { read: 0.95, insert: 0.05, ..none },
+			Workload::YcsbE => WorkloadMix { scan: 0.95, insert: 0.05, ..none },
+			Workload::YcsbF => WorkloadMix { read: 0.5, read_modify_write: 0.5, ..none },
+		}
+	}
+}
+
+impl fmt::Display for Workload {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Workload {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Workload::ALL
+			.into_iter()
+			.find(|workload| workload.name() == name)
+			.ok_or_else(|| format!("unknown workload: {}", name))
+	}
+}
+
+impl WorkloadMix {
+	// Kind of the next operations, drawn following the shares of the mix
+	fn pick<R: Rng>(&self, rng: &mut R) -> Operation {
+		let shares = [
+			(Operation::Read, self.read),
+			(Operation::Update, self.update),
+			(Operation::Insert, self.insert),
+			(Operation::Scan, self.scan),
+			(Operation::ReadModifyWrite, self.read_modify_write),
+		];
+		let mut draw = rng.gen::<f64>() * shares.iter().map(|(_, share)| share).sum::<f64>();
+		for (operation, share) in shares {
+			if draw < share {
+				return operation;
+			}
+			draw -= share;
+		}
+		Operation::Read
+	}
+}
+
+impl Operation {
+	// Tests run, one after the other, for operations of this kind
+	fn tests(&self) -> &'static [BenchmarkTest] {
+		match self {
+			Operation::Read => &[BenchmarkTest::ReadByIdManyTimes],
+			Operation::Update => &[BenchmarkTest::UpdateSingleFieldOneEntry],
+			Operation::Insert => &[BenchmarkTest::InsertSingleManyTimes],
+			Operation::Scan => &[BenchmarkTest::ReadManyByIds],
+			Operation::ReadModifyWrite => {
+				&[BenchmarkTest::ReadByIdManyTimes, BenchmarkTest::UpdateSingleFieldOneEntry]
+			}
+		}
+	}
+
+	fn trace_op(&self) -> TraceOp {
+		match self {
+			Operation::Read => TraceOp::Read,
+			Operation::Update => TraceOp::Update,
+			Operation::Insert => TraceOp::Insert,
+			Operation::Scan => TraceOp::Scan,
+			Operation::ReadModifyWrite => TraceOp::ReadModifyWrite,
+		}
+	}
+}
+
+// Operations run by a workload, the time they took and whether it stopped at the deadline
+#[derive(Default)]
+struct Progress {
+	done: usize,
+	duration: Duration,
+	timed_out: bool,
+}
+
+// Run `operations` operations of a workload in chunks of a kind drawn from its mix, stopping at the
+// deadline. Records are picked with `distribution`, Zipfian like in YCSB when not set; YCSB's
+// "latest" distribution of workload D isn't available, so it is Zipfian as well. When the run
+// records a trace, the operations are drawn first and written to it; when it replays one, those
+// of the trace are run instead, whatever `operations` and `distribution` are.
+pub async fn run_workload<T: DatabaseBenchmark + Sync + ?Sized>(
+	benchmark: &T,
+	workload: Workload,
+	operations: usize,
+	distribution: Option<AccessDistribution>,
+	deadline: Option<Instant>
+) -> Result<BenchmarkResult> {
+	let context = benchmark.run_context();
+	let zipfian = AccessDistribution::Zipfian { theta: DEFAULT_ZIPFIAN_THETA };
+	let distribution = distribution.unwrap_or(zipfian);
+	context.set_distribution(Some(distribution));
+
+	let progress = match context.trace() {
+		None => run_mix(benchmark, workload, operations, deadline).await?,
+		Some(trace) => {
+			let chunks = match trace.mode {
+				TraceMode::Record => {
+					let chunks = draw_trace(context, workload, operations, distribution);
+					trace::append(&trace.path, workload, &chunks)?;
+					chunks
+				}
+				TraceMode::Replay => trace::read(&trace.path, workload)?,
+			};
+			run_trace(benchmark, chunks, deadline).await?
+		}
+	};
+
+	let Progress { done, duration, timed_out } = progress;
+	let status = if timed_out { TestStatus::TimedOut } else { TestStatus::Passed };
+	Ok(BenchmarkResult {
+		operations: done,
+		operations_per_second: operations_per_second(done, duration),
+		..BenchmarkResult::new(&benchmark.database_name(), workload.name(), benchmark.get_cpu_count(), duration, status)
+	})
+}
+
+// Run the operations of a workload, each chunk prepared by the tests its kind maps to
+async fn run_mix<T: DatabaseBenchmark + Sync + ?Sized>(
+	benchmark: &T,
+	workload: Workload,
+	operations: usize,
+	deadline: Option<Instant>
+) -> Result<Progress> {
+	let context = benchmark.run_context();
+	let mix = workload.mix();
+	let mut progress = Progress::default();
+
+	while progress.done < operations {
+		context.check_cancelled()?;
+		if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+			progress.timed_out = true;
+			break;
+		}
+
+		let operation = mix.pick(&mut rand::thread_rng());
+		let count = WORKLOAD_CHUNK_SIZE.min(operations - progress.done);
+		for &test in operation.tests() {
+			let fixture = benchmark.prepare(test, count).await?;
+			let start = Instant::now();
+			benchmark.execute(test, count, fixture).await?;
+			progress.duration += start.elapsed();
+		}
+		progress.done += count;
+	}
+
+	Ok(progress)
+}
+
+// Operations of a workload to record: users of its own to load, then chunks of a kind drawn from
+// its mix, over the loaded users picked with `distribution`. Inserts write new users, and scans read
+// the loaded users following the one drawn.
+fn draw_trace(
+	context: &RunContext,
+	workload: Workload,
+	operations: usize,
+	distribution: AccessDistribution
+) -> Vec<TraceChunk> {
+	let (payload, tenants, keys) = (context.payload(), context.tenants(), context.key_strategy());
+	let loaded: Vec<_> = (0..TRACE_LOADED_USERS).map(|_| generate_random_user(payload, tenants, keys)).collect();
+	let loaded_keys: Vec<_> = loaded.iter().map(|user| user.id).collect();
+	let chooser = KeyChooser::new(distribution, loaded_keys.len());
+	let mut rng = rand::thread_rng();
+
+	let mix = workload.mix();
+	let mut chunks = vec![TraceChunk::writing(TraceOp::Load, loaded)];
+	let mut done = 0;
+	while done < operations {
+		let operation = mix.pick(&mut rng);
+		let count = WORKLOAD_CHUNK_SIZE.min(operations - done);
+		chunks.push(match operation {
+			Operation::Insert => {
+				let users = (0..count).map(|_| generate_random_user(payload, tenants, keys)).collect();
+				TraceChunk::writing(TraceOp::Insert, users)
+			}
+			Operation::Scan => {
+				let start = chooser.next_index(&mut rng);
+				let keys = (start..start + count).map(|i| loaded_keys[i % loaded_keys.len()]).collect();
+				TraceChunk::accessing(TraceOp::Scan, keys)
+			}
+			_ => {
+				let keys = (0..count).map(|_| loaded_keys[chooser.next_index(&mut rng)]).collect();
+				TraceChunk::accessing(operation.trace_op(), keys)
+			}
+		});
+		done += count;
+	}
+
+	chunks
+}
+
+// Run the chunks of a trace, each step of a chunk by the test the database maps it to. The users
+// are loaded first, outside of the timing and of the operations.
+async fn run_trace<T: DatabaseBenchmark + Sync + ?Sized>(
+	benchmark: &T,
+	chunks: Vec<TraceChunk>,
+	deadline: Option<Instant>
+) -> Result<Progress> {
+	let context = benchmark.run_context();
+	let mut progress = Progress::default();
+
+	for chunk in chunks {
+		context.check_cancelled()?;
+		if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+			progress.timed_out = true;
+			break;
+		}
+		let writes = matches!(chunk.op, TraceOp::Load | TraceOp::Insert);
+		anyhow::ensure!(
+			!writes || chunk.users.len() == chunk.len(),
+			"Trace chunk of {:?} misses the users it writes",
+			chunk.op
+		);
+
+		let mut duration = Duration::ZERO;
+		for &step in chunk.op.steps() {
+			let (test, fixture) = benchmark.traced_fixture(step, &chunk)?;
+			let start = Instant::now();
+			benchmark.execute(test, chunk.len(), fixture).await?;
+			duration += start.elapsed();
+		}
+		if chunk.op != TraceOp::Load {
+			progress.duration += duration;
+			progress.done += chunk.len();
+		}
+	}
+
+	Ok(progress)
+}
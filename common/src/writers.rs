@@ -0,0 +1,65 @@
+// Writers inserting at the same time, for concurrent_inserts: the users of the test are split
+// into one contiguous share per writer, and every writer inserts its share on its own connection
+// (SQLite, DuckDB) or blocking thread (RocksDB) while the others do. The result reports the
+// throughput of all the writers together and of each one, since a database serializing its
+// writes shows a single-writer rate split between them.
+use std::future::Future;
+use std::ops::Range;
+use std::time::Instant;
+use anyhow::Result;
+
+use crate::benchmark::measure_execution;
+use crate::models::BenchmarkResult;
+use crate::plan::BenchmarkTest;
+
+// Writers of concurrent_inserts when the plan doesn't say
+pub const DEFAULT_WRITERS: usize = 4;
+
+// Share of each writer among `count` operations, in order; no writer gets an empty share
+pub fn writer_ranges(count: usize, writers: usize) -> Vec<Range<usize>> {
+	let chunk = count.div_ceil(writers.max(1)).max(1);
+	(0..count)
+		.step_by(chunk)
+		.map(|start| start..(start + chunk).min(count))
+		.collect()
+}
+
+// Run concurrent_inserts with `writers` writers, `write` inserting the records of a writer's
+// range. The test is timed as a whole and each writer on its own
+pub async fn measure_concurrent_inserts<F, Fut>(
+	database_name: &str,
+	count: usize,
+	writers: usize,
+	cpu_count: usize,
+	write: F
+)
+	-> Result<BenchmarkResult>
+	where F: Fn(usize, Range<usize>) -> Fut, Fut: Future<Output = Result<()>>
+{
+	let mut writer_rates = Vec::new();
+	let mut result = measure_execution(
+		database_name,
+		BenchmarkTest::ConcurrentInserts.name(),
+		count,
+		cpu_count,
+		|| async {
+			let tasks = writer_ranges(count, writers)
+				.into_iter()
+				.enumerate()
+				.map(|(writer, range)| {
+					let operations = range.len() as f64;
+					let task = write(writer, range);
+					async move {
+						let start = Instant::now();
+						task.await?;
+						Ok::<_, anyhow::Error>(operations / start.elapsed().as_secs_f64().max(f64::EPSILON))
+					}
+				});
+			writer_rates = futures::future::try_join_all(tasks).await?;
+			Ok(())
+		}
+	).await?;
+
+	result.writer_ops_per_second = Some(writer_rates);
+	Ok(result)
+}
@@ -0,0 +1,41 @@
+use common::{
+	benchmark::{ generate_orders_with_items, generate_random_user },
+	fixtures::{ seed_dataset, FIXTURE_EPOCH },
+	plan::{ KeyStrategy, PayloadProfile },
+};
+use serde_json::Value;
+
+// Records generated while a dataset is seeded with `seed`, as JSON to compare them
+fn seeded_records(seed: u64) -> Value {
+	let _seeded = seed_dataset(Some(seed));
+	let users: Vec<_> = (0..10)
+		.map(|_| generate_random_user(PayloadProfile::default(), 4, KeyStrategy::UuidV4))
+		.collect();
+	let user_ids: Vec<_> = users.iter().map(|user| user.id).collect();
+	let orders = generate_orders_with_items(
+		10,
+		PayloadProfile::default(),
+		&user_ids,
+		&user_ids,
+		4,
+		KeyStrategy::UuidV4
+	);
+
+	serde_json::json!({ "users": users, "orders": orders })
+}
+
+// Fixtures are generated in a process of their own: the seeded generator is shared by every
+// thread, so tests generating data at the same time would draw from it
+#[test]
+fn seeded_datasets_are_identical() {
+	let first = seeded_records(42);
+	assert_eq!(first, seeded_records(42));
+	assert_ne!(first, seeded_records(43));
+
+	let created_at = first["users"][0]["created_at"].as_str().unwrap();
+	assert_eq!(chrono::DateTime::parse_from_rfc3339(created_at).unwrap().timestamp(), FIXTURE_EPOCH);
+
+	// Without a seed the data is random again
+	let user = generate_random_user(PayloadProfile::default(), 4, KeyStrategy::UuidV4);
+	assert_ne!(serde_json::to_value(user).unwrap()["id"], first["users"][0]["id"]);
+}
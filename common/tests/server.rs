@@ -0,0 +1,1140 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+use chrono::{ DateTime, Utc };
+use axum::{ body::Body, http::{ header, Method, Request, StatusCode }, Router };
+use clap::Parser;
+use serde_json::{ json, Value };
+use tower::ServiceExt;
+
+use common::{
+	benchmark::{ measure_execution, operations_per_second },
+	codec::{ RawValue, Row, RowCodec, RowDecoding },
+	context::RunContext,
+	mock::MockBenchmark,
+	models::{ BackendCapabilities, BatchStats, OrderWithDetails, OutlierStats, Product, User },
+	oplog,
+	outliers::{ self, OutlierThresholds },
+	config::ServerConfig,
+	plan::{ BenchmarkPlan, BenchmarkTest },
+	registry::{ register_backend, registered_backends, serve_backend },
+	schedule::Schedule,
+	server::{ router, AppState },
+	testsuite::scratch_dir,
+};
+
+// Longest time a run of the mock may take before a test gives up on it
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Router of a server over `benchmark`, with its history in a new scratch directory
+fn app(benchmark: MockBenchmark, api_token: Option<&str>) -> (Router, PathBuf) {
+	let dir = scratch_dir("server");
+	let state = AppState::new(benchmark, &dir, api_token.map(str::to_string)).unwrap();
+	(router(Arc::new(state)), dir)
+}
+
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+	send_with_token(app, method, uri, body, None).await
+}
+
+// Send a request, returning its status and its JSON body (`Null` when the body isn't JSON)
+async fn send_with_token(
+	app: &Router,
+	method: Method,
+	uri: &str,
+	body: Option<Value>,
+	token: Option<&str>
+) -> (StatusCode, Value) {
+	let mut request = Request::builder()
+		.method(method)
+		.uri(uri)
+		.header(header::CONTENT_TYPE, "application/json");
+	if let Some(token) = token {
+		request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+	}
+	let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+	let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+	let status = response.status();
+	let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	(status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+// Send a request with a raw body, returning its status and its body as text
+async fn send_text(app: &Router, method: Method, uri: &str, body: String) -> (StatusCode, String) {
+	let request = Request::builder().method(method).uri(uri).body(Body::from(body)).unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+	let status = response.status();
+	let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	(status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+// Send a run request with a plan written in `content_type`, returning its status and its body
+async fn post_plan(app: &Router, content_type: &str, plan: &str) -> (StatusCode, Value) {
+	let request = Request::builder()
+		.method(Method::POST)
+		.uri("/run")
+		.header(header::CONTENT_TYPE, content_type)
+		.body(Body::from(plan.to_string()))
+		.unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+	let status = response.status();
+	let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	(status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+// Start a run and return its id
+async fn start_run(app: &Router, plan: Value) -> String {
+	let (status, run) = send(app, Method::POST, "/run", Some(plan)).await;
+	assert_eq!(status, StatusCode::ACCEPTED, "{}", run);
+	run["run_id"].as_str().unwrap().to_string()
+}
+
+// Poll the status of a run until it stops running
+async fn wait_for_run(app: &Router, run_id: &str) -> Value {
+	let start = Instant::now();
+	loop {
+		let (status, run) = send(app, Method::GET, &format!("/runs/{}/status", run_id), None).await;
+		assert_eq!(status, StatusCode::OK);
+		if run["status"]["state"] != "running" {
+			return run;
+		}
+
+		assert!(start.elapsed() < RUN_TIMEOUT, "run {} is still running", run_id);
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+}
+
+#[tokio::test]
+async fn run_completes_with_deterministic_results() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_many_by_ids"], "counts": { "read_many_by_ids": 25 } })
+	).await;
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "completed");
+	assert_eq!(run["tests"], json!(["read_many_by_ids"]));
+
+	let (status, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	assert_eq!(status, StatusCode::OK);
+	let result = &results["results"][0];
+	assert_eq!(results["results"].as_array().unwrap().len(), 1);
+	assert_eq!(result["database"], "Mock");
+	assert_eq!(result["test_name"], "read_many_by_ids");
+	assert_eq!(result["operations"], 25);
+	assert_eq!(result["duration_ms"], 25);
+	assert_eq!(result["duration_ns"], 25_000_000);
+	assert_eq!(result["status"]["state"], "passed");
+	// The mock records its operations at once, after the same pause as ever
+	assert_eq!(result["outliers"], json!({ "slow_operations": 0, "stalls": 0, "longest_stall_ms": 0.0 }));
+	// It reads its 25 ids in a single batch
+	assert_eq!(result["batches"]["count"], 1);
+	assert_eq!(result["batches"]["mean_size"], 25.0);
+
+	// The latest results are those of the run
+	let (status, latest) = send(&app, Method::GET, "/results", None).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(latest["run_id"], run_id);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn single_test_runs_alone() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let (status, run) = send(&app, Method::POST, "/run/insert_many_at_once", Some(json!({}))).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["insert_many_at_once"]));
+	let run = wait_for_run(&app, run["run_id"].as_str().unwrap()).await;
+	assert_eq!(run["status"]["state"], "completed");
+
+	let (status, _) = send(&app, Method::POST, "/run/unknown_test", Some(json!({}))).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn concurrent_inserts_report_each_writer() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let plan = json!({ "writers": 3, "counts": { "concurrent_inserts": 300 } });
+	let (status, run) = send(&app, Method::POST, "/run/concurrent_inserts", Some(plan)).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	let run_id = run["run_id"].as_str().unwrap();
+	assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let result = &results["results"][0];
+	assert_eq!(result["operations"], 300);
+	assert_eq!(result["writer_ops_per_second"].as_array().unwrap().len(), 3);
+
+	let (status, _) = send(&app, Method::POST, "/run", Some(json!({ "writers": 0 }))).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn contention_is_reported() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let plan = json!({ "writers": 2, "counts": { "long_transaction_contention": 100 } });
+	let (status, run) = send(&app, Method::POST, "/run/long_transaction_contention", Some(plan)).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	let run_id = run["run_id"].as_str().unwrap();
+	assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let contention = &results["results"][0]["contention"];
+	assert_eq!(contention["reads"], 50);
+	assert_eq!(contention["writes"], 50);
+	assert_eq!(contention["error_rate"], 0.0);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn lost_updates_are_reported() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let plan = json!({ "writers": 2, "counts": { "consistency_probe": 100 } });
+	let (status, run) = send(&app, Method::POST, "/run/consistency_probe", Some(plan)).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	let run_id = run["run_id"].as_str().unwrap();
+	assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let consistency = &results["results"][0]["consistency"];
+	assert_eq!(consistency["committed"], 100);
+	assert_eq!(consistency["applied"], 100);
+	assert_eq!(consistency["lost_updates"], 0);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn column_search_runs_once_per_selectivity() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let run_id = start_run(&app, json!({ "dataset_size": 10, "tests": ["read_by_column_search"] })).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let selectivities: Vec<_> = results["results"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|result| result["selectivity"].as_f64().unwrap())
+		.collect();
+	assert_eq!(selectivities, [0.001, 0.01, 0.1]);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn connected_database_is_only_read() {
+	let (app, dir) = app(MockBenchmark::new().with_connected_database(500), None);
+
+	// Every run goes over the data as it is, with only the tests that don't write
+	let run_id = start_run(&app, json!({ "dataset_size": 10 })).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert!(!results.is_empty());
+	assert!(
+		results.iter().all(|result| {
+			let test = result["test_name"].as_str().unwrap().parse::<BenchmarkTest>().unwrap();
+			!test.is_write()
+		})
+	);
+
+	for plan in [
+		json!({ "tests": ["insert_many_at_once"] }),
+		json!({ "fresh_database": true }),
+		json!({ "backup": true }),
+	] {
+		let (status, _) = send(&app, Method::POST, "/run", Some(plan)).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+	}
+	for (method, uri) in [(Method::POST, "/seed"), (Method::POST, "/cleanup"), (Method::GET, "/capabilities")] {
+		let (status, _) = send(&app, method, uri, None).await;
+		assert_eq!(status, StatusCode::FORBIDDEN);
+	}
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn compare_cache_runs_read_tests_cold_then_warm() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_many_by_ids", "insert_single_many_times"], "compare_cache": true })
+	).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let runs: Vec<_> = results["results"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|result| (result["test_name"].as_str().unwrap(), result["cache_state"].as_str()))
+		.collect();
+	assert_eq!(
+		runs,
+		[("insert_single_many_times", None), ("read_many_by_ids", Some("cold")), ("read_many_by_ids", Some("warm"))]
+	);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn calibration_is_reported_with_the_results() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let run_id = start_run(&app, json!({ "dataset_size": 10, "tests": ["read_many_by_ids"], "calibrate": true })).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let calibration = &results["calibration"];
+	assert_eq!(calibration["operations"], 10_000);
+	for step in ["generate_user_ns", "rfc3339_ns", "bincode_ns", "async_call_ns", "spawn_blocking_ns", "record_operation_ns"] {
+		assert!(calibration[step].as_f64().unwrap() > 0.0, "{} should take some time", step);
+	}
+	let (_, report) = send_text(&app, Method::GET, "/report", String::new()).await;
+	assert!(report.contains("Harness overhead"));
+
+	// Only the runs asking for it calibrate the harness
+	let run_id = start_run(&app, json!({ "dataset_size": 10, "tests": ["read_many_by_ids"] })).await;
+	wait_for_run(&app, &run_id).await;
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	assert!(results.get("calibration").is_none());
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn backup_is_timed_and_removed() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let (status, run) = send(&app, Method::POST, "/run/backup", Some(json!({}))).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["backup", "restore_backup"]));
+	let run_id = run["run_id"].as_str().unwrap();
+	assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 2);
+	assert_eq!(results[0]["test_name"], "backup");
+	assert!(results[0]["backup_bytes"].as_u64().unwrap() > 0);
+	assert_eq!(results[1]["test_name"], "restore_backup");
+	assert_eq!(results[1]["status"]["state"], "passed");
+	assert!(!dir.join("backups").join("mock").join(run_id).exists());
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn import_loads_the_uploaded_csv() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let csv = "id,quantity,email\n7,3,ann@example.com\n8,,bob@example.com\n9,12,cid@example.com\n";
+	let (status, _) = send_text(&app, Method::PUT, "/imports/orders", csv.to_string()).await;
+	assert_eq!(status, StatusCode::NO_CONTENT);
+	assert!(dir.join("imports").join("orders.csv").exists());
+	// Rows of a CSV file have as many cells as its header
+	let (status, _) = send_text(&app, Method::PUT, "/imports/broken", "a,b\n1\n".to_string()).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	let (status, _) = send(&app, Method::POST, "/run/import", Some(json!({}))).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	let import = json!({
+		"name": "orders",
+		"columns": [{ "name": "quantity", "type": "integer" }, { "name": "email", "type": "text", "anonymize": true }],
+	});
+	let (status, run) = send(&app, Method::POST, "/run/import", Some(json!({ "import": import }))).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["import_records", "import_read_by_id", "import_update_field"]));
+	let run_id = run["run_id"].as_str().unwrap();
+	assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 3);
+	for result in results {
+		assert_eq!(result["status"]["state"], "passed");
+		assert_eq!(result["operations"], 3);
+	}
+
+	// A column missing from the CSV file fails the run
+	let import = json!({ "name": "orders", "columns": [{ "name": "price", "type": "real" }] });
+	let run_id = start_run(&app, json!({ "import": import })).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "failed");
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn missing_index_fails_the_test() {
+	let plan = json!({
+		"tests": ["read_by_id_many_times", "read_many_by_ids"],
+		"assert_indexes": true
+	});
+
+	let (indexed, dir) = app(MockBenchmark::new(), None);
+	let run_id = start_run(&indexed, plan.clone()).await;
+	assert_eq!(wait_for_run(&indexed, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&indexed, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	assert!(results["results"].as_array().unwrap().iter().all(|result| result["status"]["state"] == "passed"));
+	let _ = std::fs::remove_dir_all(dir);
+
+	// Only the test expecting an index fails, without running
+	let (unindexed, dir) = app(MockBenchmark::new().with_missing_index(), None);
+	let run_id = start_run(&unindexed, plan).await;
+	assert_eq!(wait_for_run(&unindexed, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&unindexed, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let read_by_id = &results["results"][0];
+	assert_eq!(read_by_id["status"]["state"], "failed");
+	assert_eq!(read_by_id["operations"], 0);
+	let message = read_by_id["status"]["message"].as_str().unwrap();
+	assert!(message.contains("doesn't use the index mock_users_id"), "{}", message);
+	assert!(message.contains("SCAN users"), "{}", message);
+	assert_eq!(results["results"][1]["status"]["state"], "passed");
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn database_size_and_engine_stats_are_read_after_each_test() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let plan = json!({
+		"dataset_size": 10,
+		"tests": ["insert_single_many_times", "read_by_id_many_times"],
+		"counts": { "insert_single_many_times": 5, "read_by_id_many_times": 5 }
+	});
+	let run_id = start_run(&app, plan).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	// The mock counts 100 bytes per record, the 5 inserted users stay for the read
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let timeline = results["size_timeline"].as_array().unwrap();
+	assert_eq!(timeline.len(), 2);
+	assert_eq!(timeline[0]["test_name"], "insert_single_many_times");
+	assert_eq!(timeline[1]["test_name"], "read_by_id_many_times");
+	assert_eq!(timeline[0]["bytes"], 5500);
+	assert_eq!(timeline[1]["bytes"], 5500);
+
+	// The engine statistics of the mock are its record counts after each test
+	assert_eq!(results["results"][0]["engine_stats"]["users"], 15);
+	assert_eq!(results["results"][1]["engine_stats"]["users"], 15);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn prebuilt_dataset_runs_the_read_tests() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	// Nothing to run over before the dataset is built
+	let run_id = start_run(&app, json!({ "prebuilt": true })).await;
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "failed");
+	assert_eq!(run["status"]["error"], "data_generation");
+
+	let (status, _) = send(&app, Method::POST, "/seed?count=500", None).await;
+	assert_eq!(status, StatusCode::OK);
+	let run_id = start_run(&app, json!({ "prebuilt": true })).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let names: Vec<&str> = results["results"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|result| result["test_name"].as_str().unwrap())
+		.collect();
+	// read_by_column_search runs once per selectivity
+	let read_tests: Vec<&str> = BenchmarkTest::ALL.iter()
+		.filter(|test| !test.is_write())
+		.flat_map(|test| test.selectivities().into_iter().map(|_| test.name()))
+		.collect();
+	assert_eq!(names, read_tests);
+
+	for plan in [
+		json!({ "prebuilt": true, "tests": ["insert_many_at_once"] }),
+		json!({ "prebuilt": true, "workloads": ["ycsb_a"] }),
+		json!({ "prebuilt": true, "fresh_database": true }),
+	] {
+		let (status, _) = send(&app, Method::POST, "/run", Some(plan)).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+	}
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn tests_are_selected_by_tag() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let (status, run) = send(&app, Method::POST, "/run", Some(json!({ "tags": ["join"] }))).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["read_with_one_join", "read_with_two_joins", "read_orders_with_items"]));
+	wait_for_run(&app, run["run_id"].as_str().unwrap()).await;
+
+	// Tags narrow down the listed tests
+	let plan = json!({ "tests": ["insert_many_at_once", "soft_delete_users"], "tags": ["update"] });
+	let (status, run) = send(&app, Method::POST, "/run", Some(plan)).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["soft_delete_users"]));
+	wait_for_run(&app, run["run_id"].as_str().unwrap()).await;
+
+	let plan = json!({ "tests": ["insert_many_at_once"], "tags": ["read"] });
+	let (status, _) = send(&app, Method::POST, "/run", Some(plan)).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn workload_runs_by_name() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let (status, run) = send(&app, Method::POST, "/run/ycsb_a", Some(json!({ "workload_operations": 250 }))).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["tests"], json!(["ycsb_a"]));
+	let run_id = run["run_id"].as_str().unwrap();
+	let run = wait_for_run(&app, run_id).await;
+	assert_eq!(run["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0]["test_name"], "ycsb_a");
+	assert_eq!(results[0]["operations"], 250);
+	assert_eq!(results[0]["status"]["state"], "passed");
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn fixture_dataset_is_snapshotted_for_the_run() {
+	let (app, dir) = app(MockBenchmark::new().with_test_duration(Duration::from_millis(100)), None);
+
+	let plan = json!({
+		"dataset_size": 10,
+		"tests": ["insert_single_many_times", "read_by_id_many_times"],
+		"workloads": ["ycsb_a"],
+		"workload_operations": 100,
+		"seed": 7,
+		"fixtures": true
+	});
+	let run_id = start_run(&app, plan).await;
+	let snapshot = dir.join("fixtures").join("mock").join(&run_id).join("mock.json");
+	while !snapshot.exists() {
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	// The snapshot only lives as long as its run
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert!(results.iter().all(|result| result["status"]["state"] == "passed"));
+	assert!(!snapshot.exists());
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn recorded_trace_replays_on_another_server() {
+	let (recorder, recorder_dir) = app(MockBenchmark::new(), None);
+
+	let run_id = start_run(&recorder, json!({
+		"workloads": ["ycsb_a"],
+		"workload_operations": 250,
+		"trace": { "mode": "record", "name": "ycsb-a" }
+	})).await;
+	assert_eq!(wait_for_run(&recorder, &run_id).await["status"]["state"], "completed");
+
+	let (status, trace) = send_text(&recorder, Method::GET, "/traces/ycsb-a", String::new()).await;
+	assert_eq!(status, StatusCode::OK);
+	let entries: Vec<Value> = trace.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+	let loads = entries.iter().filter(|entry| entry["op"] == "load").count();
+	assert_eq!(loads, 1_000);
+	assert_eq!(entries.len(), loads + 250);
+	assert!(entries.iter().all(|entry| entry["workload"] == "ycsb_a"));
+
+	// The trace replays the same operations on a server that didn't record it
+	let (replayer, replayer_dir) = app(MockBenchmark::new(), None);
+	let (status, _) = send_text(&replayer, Method::PUT, "/traces/ycsb-a", trace).await;
+	assert_eq!(status, StatusCode::NO_CONTENT);
+
+	let run_id = start_run(&replayer, json!({
+		"workloads": ["ycsb_a"],
+		"trace": { "mode": "replay", "name": "ycsb-a" }
+	})).await;
+	assert_eq!(wait_for_run(&replayer, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&replayer, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	assert_eq!(results["results"][0]["operations"], 250);
+	assert_eq!(results["results"][0]["status"]["state"], "passed");
+
+	// Traces are named like files of the traces directory, and only hold operations
+	let (status, _) = send_text(&replayer, Method::GET, "/traces/missing", String::new()).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+	let (status, _) = send_text(&replayer, Method::GET, "/traces/..%2Fplan", String::new()).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	let (status, _) = send_text(&replayer, Method::PUT, "/traces/broken", "{}".to_string()).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+	let (status, _) = send(&replayer, Method::POST, "/run", Some(json!({
+		"trace": { "mode": "replay", "name": "ycsb-a" }
+	}))).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	let _ = std::fs::remove_dir_all(recorder_dir);
+	let _ = std::fs::remove_dir_all(replayer_dir);
+}
+
+#[tokio::test]
+async fn plans_are_accepted_as_toml_or_yaml() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	// A race file keeps its plan, the rest only matters to the race runner
+	let toml = r#"
+		databases = ["sqlite", "duckdb"]
+		dataset_size = 10
+		tests = ["read_many_by_ids"]
+
+		[counts]
+		read_many_by_ids = 30
+
+		[output]
+		formats = ["csv"]
+	"#;
+	let yaml = "dataset_sizes: [10]\ntests: [insert_many_at_once]\ncounts:\n  insert_many_at_once: 20\n";
+	for (content_type, plan, test, operations) in [
+		("application/toml", toml, "read_many_by_ids", 30),
+		("application/yaml", yaml, "insert_many_at_once", 20),
+	] {
+		let (status, run) = post_plan(&app, content_type, plan).await;
+		assert_eq!(status, StatusCode::ACCEPTED, "{}", content_type);
+		let run_id = run["run_id"].as_str().unwrap();
+		assert_eq!(wait_for_run(&app, run_id).await["status"]["state"], "completed");
+
+		let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+		assert_eq!(results["results"][0]["test_name"], test);
+		assert_eq!(results["results"][0]["operations"], operations);
+	}
+
+	for (content_type, plan) in [
+		("application/toml", "dataset_sizes = [10, 20]"),
+		("application/toml", "tests = 3"),
+		("application/yaml", "repetitions: 0"),
+	] {
+		let (status, _) = post_plan(&app, content_type, plan).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST, "{}", plan);
+	}
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn trend_follows_a_test_across_runs() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	for count in [10, 20, 30] {
+		let plan = json!({ "dataset_size": 10, "tests": ["read_many_by_ids"], "counts": { "read_many_by_ids": count } });
+		let run_id = start_run(&app, plan).await;
+		assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+	}
+
+	let values = |points: Value| -> Vec<f64> {
+		points.as_array().unwrap().iter().map(|point| point["value"].as_f64().unwrap()).collect()
+	};
+	let uri = "/results/trend?test=read_many_by_ids&metric=operations";
+	let (status, points) = send(&app, Method::GET, uri, None).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(values(points), [10.0, 20.0, 30.0]);
+
+	let (_, points) = send(&app, Method::GET, &format!("{}&database=mock&last=2", uri), None).await;
+	assert_eq!(values(points), [20.0, 30.0]);
+
+	let (_, points) = send(&app, Method::GET, "/results/trend?test=read_many_by_ids&database=duckdb", None).await;
+	assert_eq!(points, json!([]));
+
+	// A new server on the same data directory imports the runs of its history
+	let state = AppState::new(MockBenchmark::new(), &dir, None).unwrap();
+	let restarted = router(Arc::new(state));
+	let (_, points) = send(&restarted, Method::GET, "/results/trend?test=read_many_by_ids", None).await;
+	assert_eq!(points.as_array().unwrap().len(), 3);
+
+	for uri in [
+		"/results/trend",
+		"/results/trend?test=read_many_by_ids&metric=p42",
+		"/results/trend?test=read_many_by_ids&last=0",
+	] {
+		let (status, _) = send(&app, Method::GET, uri, None).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+	}
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn invalid_requests_are_rejected() {
+	let (app, dir) = app(MockBenchmark::new(), None);
+
+	let (status, _) = send(&app, Method::POST, "/run", Some(json!({ "repetitions": 0 }))).await;
+	assert_eq!(status, StatusCode::BAD_REQUEST);
+
+	let unknown_run = "00000000-0000-0000-0000-000000000000";
+	let (status, _) = send(&app, Method::GET, &format!("/runs/{}/status", unknown_run), None).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+	let (status, _) = send(&app, Method::GET, &format!("/runs/{}/results", unknown_run), None).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn backends_are_registered_by_name() {
+	register_backend("mock-registered", |_config| async { Ok(MockBenchmark::new()) }).unwrap();
+	assert!(registered_backends().contains(&"mock-registered".to_string()));
+
+	assert!(register_backend("mock-registered", |_config| async { Ok(MockBenchmark::new()) }).is_err());
+	assert!(register_backend("Mock Registered", |_config| async { Ok(MockBenchmark::new()) }).is_err());
+
+	let config = ServerConfig::parse_from(["server"]);
+	let error = serve_backend("unknown", &config).await.unwrap_err();
+	assert!(error.to_string().contains("mock-registered"), "{}", error);
+}
+
+#[test]
+fn schedules_give_the_next_run_times() {
+	let time = |text: &str| text.parse::<DateTime<Utc>>().unwrap();
+	let next = |expression: &str, after: &str| {
+		expression.parse::<Schedule>().unwrap().next_after(time(after)).map(|next| next.to_rfc3339())
+	};
+
+	assert_eq!(next("*/15 * * * *", "2024-05-01T10:07:30Z").unwrap(), "2024-05-01T10:15:00+00:00");
+	assert_eq!(next("0 3 * * *", "2024-05-01T03:00:00Z").unwrap(), "2024-05-02T03:00:00+00:00");
+	assert_eq!(next("@weekly", "2024-05-01T12:00:00Z").unwrap(), "2024-05-05T00:00:00+00:00");
+	assert_eq!(next("30 8 * * 1-5", "2024-05-03T09:00:00Z").unwrap(), "2024-05-06T08:30:00+00:00");
+	assert_eq!(next("0 0 29 2 *", "2024-03-01T00:00:00Z").unwrap(), "2028-02-29T00:00:00+00:00");
+	// Either day matches when both are restricted
+	assert_eq!(next("0 0 1 * 0", "2024-05-01T00:00:00Z").unwrap(), "2024-05-05T00:00:00+00:00");
+	assert_eq!(next("0 0 30 2 *", "2024-01-01T00:00:00Z"), None);
+
+	for invalid in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "@yearly"] {
+		assert!(invalid.parse::<Schedule>().is_err(), "{} should be rejected", invalid);
+	}
+}
+
+#[tokio::test]
+async fn sub_millisecond_tests_keep_their_throughput() {
+	assert_eq!(operations_per_second(1_000, Duration::from_micros(500)), 2_000_000.0);
+	assert_eq!(operations_per_second(1_000, Duration::from_millis(2)), 500_000.0);
+	assert_eq!(operations_per_second(1_000, Duration::ZERO), 0.0);
+
+	let result = measure_execution("Mock", "read_by_id_many_times", 1_000, 1, || async { Ok(()) }).await.unwrap();
+	assert_eq!(result.duration_ms, 0);
+	assert!(result.duration_ns > 0);
+	assert_eq!(result.duration(), Duration::from_nanos(result.duration_ns));
+	assert_eq!(result.operations_per_second, operations_per_second(1_000, result.duration()));
+}
+
+#[test]
+fn plan_cases_follow_the_registry() {
+	let plan: BenchmarkPlan = serde_json::from_value(json!({
+		"tests": ["read_by_column_search", "insert_many_at_once"],
+		"batch_size_sweep": [10, 100],
+		"repetitions": 2,
+		"verify": true,
+	})).unwrap();
+	let cases = plan.cases();
+	let steps: Vec<_> = cases
+		.iter()
+		.map(|case| (case.test(), case.variant.batch_size, case.variant.selectivity))
+		.collect();
+
+	// Each batch size of the insert, then each selectivity of the search, all twice
+	assert_eq!(steps.len(), 10);
+	assert_eq!(steps[0], (BenchmarkTest::InsertManyAtOnce, Some(10), None));
+	assert_eq!(steps[1], steps[0]);
+	assert_eq!(steps[2], (BenchmarkTest::InsertManyAtOnce, Some(100), None));
+	assert_eq!(steps[4], (BenchmarkTest::ReadByColumnSearch, None, Some(0.001)));
+	assert_eq!(steps[9], (BenchmarkTest::ReadByColumnSearch, None, Some(0.1)));
+	assert!(cases.iter().all(|case| case.verify && case.count == plan.count_for(case.test())));
+}
+
+#[test]
+fn batches_are_timed_each_on_their_own() {
+	let context = RunContext::default();
+	context.start_test("insert_many_at_once");
+	context.record_operations(5);
+	assert_eq!(context.batch_stats(), None);
+
+	// 99 batches of 100 records in 1 ms each, then a giant one of 10,000 in 50 ms
+	for _ in 0..99 {
+		context.record_batch(100, Duration::from_millis(1));
+	}
+	context.record_batch(10_000, Duration::from_millis(50));
+	let stats = context.batch_stats().unwrap();
+	assert_eq!(stats, BatchStats {
+		count: 100,
+		mean_size: 199.0,
+		max_size: 10_000,
+		latency_p50_us: 1_000.0,
+		latency_p99_us: 1_000.0,
+		latency_max_us: 50_000.0,
+	});
+	assert_eq!(context.snapshot(), Some(("insert_many_at_once".to_string(), 19_905)));
+
+	// The next test starts without batches
+	context.start_test("read_many_by_ids");
+	assert_eq!(context.batch_stats(), None);
+}
+
+#[test]
+fn outliers_and_stalls_are_flagged() {
+	let thresholds = OutlierThresholds::default();
+	let ms = |millis: u64| millis * 1_000_000;
+
+	// Operations of 1 ms, one of 50 ms, then a batch of 10 completing after 2 s without any
+	let mut samples = vec![(ms(1), 1); 10_000];
+	samples.push((ms(50), 1));
+	samples.push((ms(200), 10));
+	let stats = outliers::analyze(&samples, thresholds).unwrap();
+	assert_eq!(stats, OutlierStats { slow_operations: 11, stalls: 1, longest_stall_ms: 2_000.0 });
+
+	// Operations all as slow don't stall, however long they take
+	let stats = outliers::analyze(&[(ms(500), 1); 20], thresholds).unwrap();
+	assert_eq!(stats, OutlierStats::default());
+
+	assert_eq!(outliers::analyze(&[], thresholds), None);
+}
+
+#[test]
+fn row_codec_decodes_rows_when_full() {
+	let context = RunContext::default();
+	context.start_test("read_by_id_many_times");
+	let decode = |row: Row| Ok((row.key(0)?, row.timestamp(1)?, row.boolean(2)?));
+	let rows = vec![
+		Row(vec![
+			RawValue::Text("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()),
+			RawValue::Text("2024-01-01T00:00:00+00:00".to_string()),
+			RawValue::Integer(1),
+		]),
+		Row(vec![RawValue::Blob(vec![7; 16]), RawValue::Integer(1_704_067_200_000_000), RawValue::Boolean(false)]),
+	];
+	let invalid = || [Row(vec![RawValue::Text("not an id".to_string()), RawValue::Null, RawValue::Null])];
+
+	// The raw codec leaves the rows as they were fetched
+	let codec = RowCodec::new(&context);
+	codec.decode(invalid(), decode).unwrap();
+	assert_eq!(context.decoding_ms(), None);
+
+	context.set_row_decoding(RowDecoding::Full);
+	let codec = RowCodec::new(&context);
+	codec.decode(rows, decode).unwrap();
+	assert!(context.decoding_ms().is_some());
+	let error = codec.decode(invalid(), decode).unwrap_err();
+	assert!(error.to_string().contains("column 0"), "{}", error);
+	assert!("partial".parse::<RowDecoding>().is_err());
+}
+
+#[test]
+fn models_decode_from_rows_without_panicking() {
+	let text = |text: &str| RawValue::Text(text.to_string());
+	let user = vec![
+		text("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+		RawValue::Integer(3),
+		text("Ada"),
+		text("ada@example.com"),
+		RawValue::Timestamp(1_704_067_200_000_000),
+		RawValue::Boolean(true),
+		RawValue::Null,
+	];
+	let product = vec![
+		RawValue::Blob(vec![7; 16]),
+		text("Lamp"),
+		text("A lamp"),
+		RawValue::Real(19.5),
+		RawValue::Null,
+		RawValue::Integer(12),
+		text("2024-01-01T00:00:00+00:00"),
+	];
+	let order = vec![
+		text("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"),
+		RawValue::Integer(2),
+		RawValue::Real(39.0),
+		RawValue::Integer(0),
+	];
+
+	let decoded = User::try_from(Row(user.clone())).unwrap();
+	assert_eq!((decoded.tenant_id, decoded.active, decoded.deleted_at), (3, true, None));
+	assert_eq!(decoded.created_at, "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+	let decoded = OrderWithDetails::try_from(Row([order.clone(), user.clone(), product.clone()].concat())).unwrap();
+	assert_eq!((decoded.quantity, decoded.user.name.as_str(), decoded.product.stock), (2, "Ada", 12));
+
+	// A malformed value fails the decoding instead of the server
+	let mut malformed = product.clone();
+	malformed[6] = text("yesterday");
+	assert!(Product::try_from(Row(malformed.clone())).is_err());
+	assert!(OrderWithDetails::try_from(Row([order.clone(), user.clone(), malformed].concat())).is_err());
+	assert!(OrderWithDetails::try_from(Row([order, user].concat())).is_err());
+}
+
+#[tokio::test]
+async fn capabilities_are_probed_outside_of_runs() {
+	let (app, dir) = app(MockBenchmark::new().with_test_duration(Duration::from_secs(30)), None);
+
+	let (status, report) = send(&app, Method::GET, "/capabilities", None).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(report["database"], "Mock");
+	assert_eq!(report["connected"], true);
+	assert_eq!(report["probe"], true);
+	assert_eq!(report["capabilities"]["blobs"], true);
+	assert_eq!(report["capabilities"]["window_functions"], false);
+
+	// The probe would disturb a run
+	let run_id = start_run(&app, json!({ "dataset_size": 10 })).await;
+	let (status, _) = send(&app, Method::GET, "/capabilities", None).await;
+	assert_eq!(status, StatusCode::CONFLICT);
+	send(&app, Method::POST, &format!("/runs/{}/cancel", run_id), None).await;
+	wait_for_run(&app, &run_id).await;
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn running_run_can_be_cancelled() {
+	let (app, dir) = app(MockBenchmark::new().with_test_duration(Duration::from_secs(30)), None);
+
+	let run_id = start_run(&app, json!({ "dataset_size": 10 })).await;
+
+	// Only one run executes at a time
+	let (status, _) = send(&app, Method::POST, "/run", Some(json!({}))).await;
+	assert_eq!(status, StatusCode::CONFLICT);
+
+	let (status, _) = send(&app, Method::POST, &format!("/runs/{}/cancel", run_id), None).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "cancelled");
+
+	let (status, _) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	assert_eq!(status, StatusCode::GONE);
+	let (status, _) = send(&app, Method::POST, &format!("/runs/{}/cancel", run_id), None).await;
+	assert_eq!(status, StatusCode::CONFLICT);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn interrupted_run_resumes_after_its_completed_tests() {
+	let (app, dir) = app(MockBenchmark::new().with_test_duration(Duration::from_millis(300)), None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_by_id_many_times", "read_many_by_ids"] })
+	).await;
+	loop {
+		let (_, run) = send(&app, Method::GET, &format!("/runs/{}/status", run_id), None).await;
+		if run["progress"]["test_name"] == "read_many_by_ids" {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+	send(&app, Method::POST, &format!("/runs/{}/cancel", run_id), None).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "cancelled");
+
+	// The checkpoint survives a restart of the server
+	let state = AppState::new(MockBenchmark::new(), &dir, None).unwrap();
+	let app = router(Arc::new(state));
+	let (status, interrupted) = send(&app, Method::GET, "/runs/interrupted", None).await;
+	assert_eq!(status, StatusCode::OK);
+	assert_eq!(interrupted.as_array().unwrap().len(), 1);
+	assert_eq!(interrupted[0]["run_id"], run_id);
+	assert_eq!(interrupted[0]["steps"], 1);
+
+	let resumed_at = Utc::now();
+	let (status, run) = send(&app, Method::POST, &format!("/runs/{}/resume", run_id), None).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	assert_eq!(run["run_id"], run_id);
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+
+	// Only the test the cancellation interrupted ran again
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 2);
+	let timestamp = |result: &Value| result["timestamp"].as_str().unwrap().parse::<DateTime<Utc>>().unwrap();
+	assert!(timestamp(&results[0]) < resumed_at);
+	assert!(timestamp(&results[1]) > resumed_at);
+
+	// A completed run has nothing left to resume
+	let (_, interrupted) = send(&app, Method::GET, "/runs/interrupted", None).await;
+	assert_eq!(interrupted, json!([]));
+	let (status, _) = send(&app, Method::POST, &format!("/runs/{}/resume", run_id), None).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn running_run_reports_its_progress_and_latency() {
+	let (app, dir) = app(MockBenchmark::new().with_test_duration(Duration::from_millis(500)), None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_many_by_ids"], "counts": { "read_many_by_ids": 50 } })
+	).await;
+	let progress = loop {
+		let (_, run) = send(&app, Method::GET, &format!("/runs/{}/status", run_id), None).await;
+		if !run["progress"].is_null() {
+			break run["progress"].clone();
+		}
+		assert_eq!(run["status"]["state"], "running");
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	};
+	assert_eq!(progress["test_name"], "read_many_by_ids");
+	assert_eq!(progress["operations"], 0);
+
+	let run = wait_for_run(&app, &run_id).await;
+	assert!(run["progress"].is_null());
+
+	// The mock records its operations at once after running for the whole test
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let p99 = results["results"][0]["latency_p99_us"].as_f64().unwrap();
+	assert!(p99 >= 10_000.0, "{}", p99);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn failing_test_is_recorded_and_the_run_goes_on() {
+	let benchmark = MockBenchmark::new().with_failing_test(BenchmarkTest::ReadManyByIds);
+	let (app, dir) = app(benchmark, None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_many_by_ids", "read_with_one_join"] })
+	).await;
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 2);
+	assert_eq!(results[0]["status"]["state"], "failed");
+	assert_eq!(results[0]["status"]["message"], "mock failure of read_many_by_ids");
+	assert_eq!(results[1]["status"]["state"], "passed");
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn tests_needing_a_missing_capability_are_skipped() {
+	let capabilities = BackendCapabilities { joins: false, transactions: false };
+	let (app, dir) = app(MockBenchmark::new().with_capabilities(capabilities), None);
+
+	let (_, report) = send(&app, Method::GET, "/capabilities", None).await;
+	assert_eq!(report["declared"], json!({ "joins": false, "transactions": false }));
+
+	let run_id = start_run(
+		&app,
+		json!({
+			"dataset_size": 10,
+			"tests": ["read_by_id_many_times", "read_with_one_join", "long_transaction_contention"]
+		})
+	).await;
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "completed");
+
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let results = results["results"].as_array().unwrap();
+	assert_eq!(results.len(), 3);
+	assert_eq!(results[0]["status"]["state"], "passed");
+	assert_eq!(results[1]["status"], json!({ "state": "skipped", "reason": "Mock doesn't support joins" }));
+	assert_eq!(results[1]["operations"], 0);
+	assert_eq!(results[2]["status"]["reason"], "Mock doesn't support transactions");
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn oplog_holds_every_operation_of_the_run() {
+	let benchmark = MockBenchmark::new().with_failing_test(BenchmarkTest::ReadManyByIds);
+	let (app, dir) = app(benchmark, None);
+
+	let run_id = start_run(
+		&app,
+		json!({ "dataset_size": 10, "tests": ["read_many_by_ids", "read_with_one_join"], "oplog": true })
+	).await;
+	assert_eq!(wait_for_run(&app, &run_id).await["status"]["state"], "completed");
+	let (_, results) = send(&app, Method::GET, &format!("/runs/{}/results", run_id), None).await;
+	let joins = results["results"][1]["operations"].as_u64().unwrap();
+
+	let request = Request::builder().uri(format!("/runs/{}/oplog", run_id)).body(Body::empty()).unwrap();
+	let response = app.clone().oneshot(request).await.unwrap();
+	assert_eq!(response.status(), StatusCode::OK);
+	let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let entries = oplog::decode(&bytes).unwrap();
+
+	// The failed test logs the operation it failed at, the other one each of its operations
+	assert_eq!(entries.len() as u64, 1 + joins);
+	assert_eq!((entries[0].test.as_str(), entries[0].index, entries[0].success), ("read_many_by_ids", 0, false));
+	for (i, entry) in entries[1..].iter().enumerate() {
+		assert_eq!((entry.test.as_str(), entry.index, entry.success), ("read_with_one_join", i as u64, true));
+	}
+
+	// A run without `oplog` has none
+	let run_id = start_run(&app, json!({ "dataset_size": 10, "tests": ["read_with_one_join"] })).await;
+	wait_for_run(&app, &run_id).await;
+	let (status, _) = send(&app, Method::GET, &format!("/runs/{}/oplog", run_id), None).await;
+	assert_eq!(status, StatusCode::NOT_FOUND);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn failures_report_their_kind() {
+	let (app, dir) = app(MockBenchmark::new().with_failing_setup(), None);
+
+	let run_id = start_run(&app, json!({})).await;
+	let run = wait_for_run(&app, &run_id).await;
+	assert_eq!(run["status"]["state"], "failed");
+	assert_eq!(run["status"]["error"], "init_failed");
+
+	let (status, error) = send(&app, Method::POST, "/cleanup", None).await;
+	assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+	assert_eq!(error["error"], "engine");
+
+	let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn mutating_endpoints_require_the_api_token() {
+	let (app, dir) = app(MockBenchmark::new(), Some("secret"));
+
+	let plan = json!({ "dataset_size": 10, "tests": ["read_many_by_ids"] });
+	let (status, _) = send(&app, Method::POST, "/run", Some(plan.clone())).await;
+	assert_eq!(status, StatusCode::UNAUTHORIZED);
+	let (status, _) = send_with_token(&app, Method::POST, "/run", Some(plan.clone()), Some("wrong")).await;
+	assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+	let (status, run) = send_with_token(&app, Method::POST, "/run", Some(plan), Some("secret")).await;
+	assert_eq!(status, StatusCode::ACCEPTED);
+	wait_for_run(&app, run["run_id"].as_str().unwrap()).await;
+
+	// Read-only endpoints stay public
+	let (status, _) = send(&app, Method::GET, "/results", None).await;
+	assert_eq!(status, StatusCode::OK);
+
+	let _ = std::fs::remove_dir_all(dir);
+}
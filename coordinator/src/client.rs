@@ -0,0 +1,234 @@
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+use anyhow::{ bail, Context, Result };
+use reqwest::StatusCode;
+use tracing::info;
+use uuid::Uuid;
+
+use common::{
+	checkpoint::RunCheckpoint,
+	error::ErrorResponse,
+	models::{ BenchmarkResults, CapabilityReport, HealthStatus, RunInfo, RunStatus },
+	plan::BenchmarkPlan,
+};
+
+// Delay between two status requests while a run is executing, short enough to follow its tests
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Delay between two readiness checks of a server starting up
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Called with the status of a run each time the client gets it, to follow its progress
+pub type ProgressCallback = Arc<dyn Fn(&RunInfo) + Send + Sync>;
+
+// HTTP client for a single benchmark server
+#[derive(Clone)]
+pub struct BackendClient {
+	url: String,
+	http: reqwest::Client,
+	// Bearer token sent to servers that protect their mutating endpoints
+	token: Option<String>,
+	progress: Option<ProgressCallback>,
+	// Whether a run resumes the interrupted run of the same plan rather than starting over
+	resume: bool,
+}
+
+impl BackendClient {
+	pub fn new(url: &str, http: reqwest::Client, token: Option<String>) -> Self {
+		Self {
+			url: url.trim_end_matches('/').to_string(),
+			http,
+			token,
+			progress: None,
+			resume: false,
+		}
+	}
+
+	// Resume the interrupted run of the same plan, when the server has one, instead of starting a
+	// new run
+	pub fn resuming(mut self) -> Self {
+		self.resume = true;
+		self
+	}
+
+	// Report the status of the runs of this client to `callback` while they execute
+	pub fn on_progress(mut self, callback: impl Fn(&RunInfo) + Send + Sync + 'static) -> Self {
+		self.progress = Some(Arc::new(callback));
+		self
+	}
+
+	fn report_progress(&self, run: &RunInfo) {
+		if let Some(progress) = &self.progress {
+			progress(run);
+		}
+	}
+
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	// Wait until the server can start a run: it answers and isn't executing one
+	pub async fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			let ready = self.http
+				.get(format!("{}/ready", self.url))
+				.send().await
+				.is_ok_and(|response| response.status().is_success());
+			if ready {
+				return Ok(());
+			}
+			if Instant::now() >= deadline {
+				bail!("{} is not ready after {}s", self.url, timeout.as_secs());
+			}
+			tokio::time::sleep(READY_POLL_INTERVAL).await;
+		}
+	}
+
+	// Health of the server, naming its database even when the database doesn't answer. Read-only,
+	// so it can be asked while the server executes a run
+	pub async fn health(&self) -> Result<HealthStatus> {
+		let response = self.http
+			.get(format!("{}/health", self.url))
+			.send().await
+			.with_context(|| format!("Failed to reach {}", self.url))?;
+
+		match response.status() {
+			StatusCode::SERVICE_UNAVAILABLE => Ok(response.json().await?),
+			_ => Ok(response.error_for_status()?.json().await?),
+		}
+	}
+
+	// Probe the database of the server for its optional features. The report holds an error when
+	// the database doesn't answer or its probe failed
+	pub async fn capabilities(&self) -> Result<CapabilityReport> {
+		let response = self.http
+			.get(format!("{}/capabilities", self.url))
+			.send().await
+			.with_context(|| format!("Failed to reach {}", self.url))?;
+
+		match response.status() {
+			StatusCode::CONFLICT => bail!("{} is executing a run", self.url),
+			StatusCode::SERVICE_UNAVAILABLE => Ok(response.json().await?),
+			_ => Ok(response.error_for_status()?.json().await?),
+		}
+	}
+
+	// Remove every record of the database
+	pub async fn cleanup(&self) -> Result<()> {
+		self.dataset_request("cleanup").await
+	}
+
+	// Generate `count` records of each table on top of those the database holds
+	pub async fn seed(&self, count: usize) -> Result<()> {
+		self.dataset_request(&format!("seed?count={}", count)).await
+	}
+
+	// Send a request changing the dataset, which the server answers once it is done
+	async fn dataset_request(&self, path: &str) -> Result<()> {
+		let mut request = self.http.post(format!("{}/{}", self.url, path));
+		if let Some(token) = &self.token {
+			request = request.bearer_auth(token);
+		}
+
+		let response = request
+			.send().await
+			.with_context(|| format!("Failed to reach {}", self.url))?;
+		if response.status() == StatusCode::CONFLICT {
+			bail!("{} is executing a run", self.url);
+		}
+		response.error_for_status().with_context(|| format!("{} failed to change its dataset", self.url))?;
+
+		Ok(())
+	}
+
+	// Latest run of the plan the server didn't finish, if any
+	pub async fn interrupted_run(&self, plan: &BenchmarkPlan) -> Result<Option<Uuid>> {
+		let checkpoints: Vec<RunCheckpoint> = self.http
+			.get(format!("{}/runs/interrupted", self.url))
+			.send().await
+			.with_context(|| format!("Failed to reach {}", self.url))?
+			.error_for_status()?
+			.json().await?;
+
+		let plan = serde_json::to_value(plan)?;
+		Ok(
+			checkpoints
+				.into_iter()
+				.find(|checkpoint| serde_json::to_value(&checkpoint.plan).is_ok_and(|other| other == plan))
+				.map(|checkpoint| checkpoint.run_id)
+		)
+	}
+
+	// Start a run with the given plan, or resume its interrupted run when resuming, wait for it to
+	// end and return its results
+	pub async fn run(&self, plan: &BenchmarkPlan) -> Result<BenchmarkResults> {
+		let interrupted = if self.resume { self.interrupted_run(plan).await? } else { None };
+		let mut request = match interrupted {
+			Some(run_id) => {
+				info!("Resuming run {} on {}", run_id, self.url);
+				self.http.post(format!("{}/runs/{}/resume", self.url, run_id))
+			}
+			None => self.http.post(format!("{}/run", self.url)).json(plan),
+		};
+		if let Some(token) = &self.token {
+			request = request.bearer_auth(token);
+		}
+
+		let response = request
+			.send().await
+			.with_context(|| format!("Failed to reach {}", self.url))?;
+
+		if response.status() == StatusCode::CONFLICT {
+			bail!("{} is already executing a run", self.url);
+		}
+		let run: RunInfo = response
+			.error_for_status()
+			.with_context(|| format!("{} rejected the run", self.url))?
+			.json().await?;
+		info!("Started run {} on {}", run.run_id, self.url);
+		self.report_progress(&run);
+
+		// Wait for the run to end
+		let run = loop {
+			tokio::time::sleep(POLL_INTERVAL).await;
+
+			let run: RunInfo = self.http
+				.get(format!("{}/runs/{}/status", self.url, run.run_id))
+				.send().await?
+				.error_for_status()?
+				.json().await?;
+			self.report_progress(&run);
+
+			if run.status != RunStatus::Running {
+				break run;
+			}
+		};
+
+		match run.status {
+			RunStatus::Completed => {}
+			// Keep the kind of failure so the race can report it
+			RunStatus::Failed { message, error: Some(kind) } => {
+				return Err(
+					anyhow::Error
+						::new(ErrorResponse { error: kind, message })
+						.context(format!("Run {} on {} failed", run.run_id, self.url))
+				);
+			}
+			RunStatus::Failed { message, error: None } => {
+				bail!("Run {} on {} failed: {}", run.run_id, self.url, message)
+			}
+			RunStatus::Cancelled => bail!("Run {} on {} was cancelled", run.run_id, self.url),
+			RunStatus::Running => unreachable!(),
+		}
+
+		let results = self.http
+			.get(format!("{}/runs/{}/results", self.url, run.run_id))
+			.send().await?
+			.error_for_status()?
+			.json().await?;
+
+		info!("Collected results of run {} from {}", run.run_id, self.url);
+		Ok(results)
+	}
+}
@@ -0,0 +1,3 @@
+pub mod client;
+pub mod race;
+pub mod server;
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use clap::Parser;
+use std::net::{ IpAddr, Ipv4Addr, SocketAddr };
+
+use coordinator::{ client::BackendClient, server::run_server };
+
+// Settings of the coordinator, read from command-line flags or environment variables
+#[derive(Debug, Parser)]
+struct CoordinatorConfig {
+	/// Address the coordinator listens on
+	#[arg(long, env = "RACE_BIND", default_value_t = IpAddr::V4(Ipv4Addr::UNSPECIFIED))]
+	bind: IpAddr,
+
+	/// Port the coordinator listens on
+	#[arg(long, env = "RACE_PORT", default_value_t = 3000)]
+	port: u16,
+
+	/// Comma-separated URLs of the benchmark servers to race
+	#[arg(
+		long,
+		env = "RACE_BACKENDS",
+		value_delimiter = ',',
+		default_value = "http://localhost:3001,http://localhost:3002,http://localhost:3003"
+	)]
+	backends: Vec<String>,
+
+	/// Token of the benchmark servers, when they require one
+	#[arg(long, env = "BENCHMARK_API_TOKEN")]
+	api_token: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	println!("Starting race coordinator");
+	let config = CoordinatorConfig::parse();
+	let token = config.api_token.filter(|token| !token.is_empty());
+
+	let http = reqwest::Client::new();
+	let backends: Vec<BackendClient> = config.backends
+		.iter()
+		.map(|url| url.trim())
+		.filter(|url| !url.is_empty())
+		.map(|url| BackendClient::new(url, http.clone(), token.clone()))
+		.collect();
+	println!("Racing {} databases", backends.len());
+
+	run_server(backends, SocketAddr::new(config.bind, config.port)).await?;
+
+	Ok(())
+}
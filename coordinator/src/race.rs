@@ -0,0 +1,137 @@
+use chrono::{ DateTime, Utc };
+use serde::{ Deserialize, Serialize };
+use tracing::{ error, info, warn };
+use uuid::Uuid;
+
+use common::{
+	error::{ ErrorKind, ErrorResponse },
+	models::{ BenchmarkResults, RunStatus },
+	plan::BenchmarkPlan,
+	report::score_runs,
+};
+
+use crate::client::BackendClient;
+
+// A race runs the same plan on every benchmark server and gathers their results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Race {
+	pub race_id: Uuid,
+	pub status: RunStatus,
+	pub parallel: bool,
+	pub backends: Vec<String>,
+	pub results: Vec<BenchmarkResults>,
+	pub errors: Vec<BackendError>,
+	// Database the results are scored against, the first one that completed its run when not given
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub baseline: Option<String>,
+	pub started_at: DateTime<Utc>,
+	pub finished_at: Option<DateTime<Utc>>,
+}
+
+// A benchmark server whose run could not complete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendError {
+	pub backend: String,
+	pub message: String,
+	// Kind of failure reported by the server, absent when it could not be reached
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<ErrorKind>,
+}
+
+impl Race {
+	// New race of the given servers, running until `finish` records its outcome
+	pub fn start(backends: &[BackendClient], parallel: bool) -> Self {
+		Race {
+			race_id: Uuid::new_v4(),
+			status: RunStatus::Running,
+			parallel,
+			backends: backends
+				.iter()
+				.map(|backend| backend.url().to_string())
+				.collect(),
+			results: Vec::new(),
+			errors: Vec::new(),
+			baseline: None,
+			started_at: Utc::now(),
+			finished_at: None,
+		}
+	}
+
+	// Record the results and errors of the servers, scored against the baseline; the race fails
+	// when none of them completed
+	pub fn finish(&mut self, mut results: Vec<BenchmarkResults>, errors: Vec<BackendError>) {
+		self.status = if results.is_empty() && !errors.is_empty() {
+			RunStatus::Failed { message: "No database completed its run".to_string(), error: None }
+		} else {
+			RunStatus::Completed
+		};
+		let baseline = self.baseline.take().or_else(|| results.first().map(|run| run.database.clone()));
+		// A baseline without results scores nothing, and isn't kept as the baseline of the race
+		self.baseline = baseline.filter(|baseline| {
+			let found = results.iter().any(|run| run.database.eq_ignore_ascii_case(baseline));
+			if !found {
+				warn!("Baseline {} has no results, the race isn't scored", baseline);
+			}
+			found
+		});
+		if let Some(baseline) = &self.baseline {
+			score_runs(&mut results, baseline);
+		}
+		self.results = results;
+		self.errors = errors;
+		self.finished_at = Some(Utc::now());
+		info!("Race {} finished", self.race_id);
+	}
+}
+
+// Run the plan on every server and gather the results of those that completed it, along with
+// the errors of the others
+pub async fn run_race(
+	backends: &[BackendClient],
+	plan: &BenchmarkPlan,
+	parallel: bool
+) -> (Vec<BenchmarkResults>, Vec<BackendError>) {
+	let outcomes = if parallel {
+		let handles: Vec<_> = backends
+			.iter()
+			.cloned()
+			.map(|backend| {
+				let plan = plan.clone();
+				tokio::spawn(async move { backend.run(&plan).await })
+			})
+			.collect();
+
+		let mut outcomes = Vec::new();
+		for handle in handles {
+			outcomes.push(
+				handle.await.unwrap_or_else(|e| Err(anyhow::anyhow!("Run task failed: {}", e)))
+			);
+		}
+		outcomes
+	} else {
+		// Sequential runs keep the databases from competing for CPU and disk
+		let mut outcomes = Vec::new();
+		for backend in backends {
+			outcomes.push(backend.run(plan).await);
+		}
+		outcomes
+	};
+
+	let mut results = Vec::new();
+	let mut errors = Vec::new();
+	for (backend, outcome) in backends.iter().zip(outcomes) {
+		match outcome {
+			Ok(backend_results) => results.push(backend_results),
+			Err(e) => {
+				error!("Run on {} failed: {:?}", backend.url(), e);
+				errors.push(BackendError {
+					backend: backend.url().to_string(),
+					message: format!("{:#}", e),
+					error: e.downcast_ref::<ErrorResponse>().map(|response| response.error),
+				});
+			}
+		}
+	}
+
+	(results, errors)
+}
@@ -0,0 +1,189 @@
+use axum::{
+	routing::{ get, post },
+	Router,
+	Json,
+	http::{ header, StatusCode },
+	extract::{ Query, State },
+	response::{ IntoResponse, Response },
+};
+use std::net::SocketAddr;
+use std::sync::{ Arc, Mutex };
+use anyhow::Result;
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tracing::{ info, warn };
+
+use common::{
+	leaderboard::render_markdown_leaderboard,
+	models::RunStatus,
+	plan::BenchmarkPlan,
+	report::render_html_report,
+};
+
+use crate::{ client::BackendClient, race::{ self, Race } };
+
+// Shared state for the coordinator API
+pub struct AppState {
+	pub backends: Vec<BackendClient>,
+	// Latest race, running or finished
+	pub race: Mutex<Option<Race>>,
+}
+
+// Run the coordinator API for the given benchmark servers
+pub async fn run_server(backends: Vec<BackendClient>, addr: SocketAddr) -> Result<()> {
+	// Initialize tracing
+	tracing_subscriber::fmt::init();
+
+	let state = Arc::new(AppState {
+		backends,
+		race: Mutex::new(None),
+	});
+
+	let app = Router::new()
+		.route("/", get(root_handler))
+		.route("/race", post(start_race_handler).get(race_handler))
+		.route("/race/report", get(report_handler))
+		.route("/leaderboard", get(leaderboard_handler))
+		.with_state(state);
+
+	info!("Coordinator listening on {}", addr);
+
+	let listener = TcpListener::bind(addr).await?;
+	axum::serve(listener, app).await?;
+
+	Ok(())
+}
+
+// Root handler
+async fn root_handler() -> &'static str {
+	"Database Race Coordinator. Use POST /race to run the benchmarks on every database, /race to follow the race, /race/report to download the merged HTML report and /leaderboard the Markdown leaderboard."
+}
+
+#[derive(Deserialize)]
+struct RaceParams {
+	#[serde(default)]
+	parallel: bool,
+	// Database the results are scored against
+	baseline: Option<String>,
+}
+
+// Start a race in the background, running the plan sent as the request body on every server
+async fn start_race_handler(
+	State(state): State<Arc<AppState>>,
+	Query(params): Query<RaceParams>,
+	Json(plan): Json<BenchmarkPlan>
+) -> Result<(StatusCode, Json<Race>), StatusCode> {
+	info!("Start race handler");
+	if let Err(message) = plan.validate() {
+		warn!("Rejected invalid plan: {}", message);
+		return Err(StatusCode::BAD_REQUEST);
+	}
+
+	ensure_no_race_running(&state.race.lock().unwrap())?;
+
+	// The baseline is one of the databases of the servers, as named by their /health
+	if let Some(baseline) = &params.baseline {
+		let mut databases = Vec::new();
+		for backend in &state.backends {
+			match backend.health().await {
+				Ok(health) => databases.push(health.database),
+				Err(e) => warn!("Could not get the database of {}: {}", backend.url(), e),
+			}
+		}
+		if !databases.iter().any(|database| database.eq_ignore_ascii_case(baseline)) {
+			warn!("Rejected unknown baseline {}, the servers run {}", baseline, databases.join(", "));
+			return Err(StatusCode::BAD_REQUEST);
+		}
+	}
+
+	let mut race = Race::start(&state.backends, params.parallel);
+	race.baseline = params.baseline;
+
+	{
+		// Checked again, as another race may have started while the servers were asked
+		let mut current = state.race.lock().unwrap();
+		ensure_no_race_running(&current)?;
+		*current = Some(race.clone());
+	}
+
+	tokio::spawn(run_race(state, plan, params.parallel));
+
+	Ok((StatusCode::ACCEPTED, Json(race)))
+}
+
+// Reject a new race while the current one is running
+fn ensure_no_race_running(current: &Option<Race>) -> Result<(), StatusCode> {
+	match current {
+		Some(current) if current.status == RunStatus::Running => {
+			warn!("Rejected new race while race {} is running", current.race_id);
+			Err(StatusCode::CONFLICT)
+		}
+		_ => Ok(()),
+	}
+}
+
+// Run the plan on every server and record the outcome in the current race
+async fn run_race(state: Arc<AppState>, plan: BenchmarkPlan, parallel: bool) {
+	let (results, errors) = race::run_race(&state.backends, &plan, parallel).await;
+
+	let mut race = state.race.lock().unwrap();
+	if let Some(race) = race.as_mut() {
+		race.finish(results, errors);
+	}
+}
+
+// Latest race with the results collected so far
+async fn race_handler(State(state): State<Arc<AppState>>) -> Result<Json<Race>, StatusCode> {
+	info!("Race handler");
+	let race = state.race.lock().unwrap();
+
+	race.clone().map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+// Merged HTML report comparing every database of the latest race
+async fn report_handler(State(state): State<Arc<AppState>>) -> Result<Response, StatusCode> {
+	info!("Race report handler");
+	let race = state.race.lock().unwrap();
+
+	let Some(race) = &*race else {
+		return Err(StatusCode::NOT_FOUND);
+	};
+	if race.results.is_empty() {
+		return Err(StatusCode::NOT_FOUND);
+	}
+
+	let html = render_html_report(&race.results);
+	Ok(
+		(
+			[
+				(header::CONTENT_TYPE, "text/html; charset=utf-8"),
+				(header::CONTENT_DISPOSITION, "attachment; filename=\"race-report.html\""),
+			],
+			html,
+		).into_response()
+	)
+}
+
+// Markdown leaderboard of the latest race, ranking the databases test by test
+async fn leaderboard_handler(State(state): State<Arc<AppState>>) -> Result<Response, StatusCode> {
+	info!("Leaderboard handler");
+	let race = state.race.lock().unwrap();
+
+	let Some(race) = &*race else {
+		return Err(StatusCode::NOT_FOUND);
+	};
+	if race.results.is_empty() {
+		return Err(StatusCode::NOT_FOUND);
+	}
+
+	let markdown = render_markdown_leaderboard(&race.results);
+	Ok(
+		(
+			[
+				(header::CONTENT_TYPE, "text/markdown; charset=utf-8"),
+				(header::CONTENT_DISPOSITION, "attachment; filename=\"leaderboard.md\""),
+			],
+			markdown,
+		).into_response()
+	)
+}
@@ -0,0 +1,143 @@
+use comfy_table::{ presets::UTF8_FULL_CONDENSED, CellAlignment, ContentArrangement, Table };
+
+use common::{
+	models::{ BenchmarkResult, BenchmarkResults, TestStatus },
+	report::{ database_names, find_result, format_micros, test_names },
+};
+
+// Results of two races set side by side, test by test and database by database, so a change can
+// be checked for regressions. A result regresses when its throughput drops by more than the
+// threshold, or when it fails where it used to pass.
+
+// A test of a database in both races, or in only one of them
+pub struct Change<'a> {
+	pub database: String,
+	pub test: String,
+	pub old: Option<&'a BenchmarkResult>,
+	pub new: Option<&'a BenchmarkResult>,
+}
+
+impl Change<'_> {
+	// Relative change of the throughput in percent, when both results were measured
+	pub fn throughput_change(&self) -> Option<f64> {
+		let (old, new) = (measured(self.old)?, measured(self.new)?);
+		percent_change(old.operations_per_second, new.operations_per_second)
+	}
+
+	pub fn latency_change(&self) -> Option<f64> {
+		let (old, new) = (measured(self.old)?, measured(self.new)?);
+		percent_change(old.latency_p99_us?, new.latency_p99_us?)
+	}
+
+	// Whether the test got slower by more than `threshold` percent, or fails where it passed
+	pub fn is_regression(&self, threshold: f64) -> bool {
+		match (self.old, self.new) {
+			(Some(old), Some(new)) if is_failed(new) => !is_failed(old),
+			_ => self.throughput_change().is_some_and(|change| change < -threshold),
+		}
+	}
+}
+
+// Every test of every database of the two races, in the order of the new one, then those only
+// the old one ran
+pub fn compare<'a>(old: &'a [BenchmarkResults], new: &'a [BenchmarkResults]) -> Vec<Change<'a>> {
+	let mut databases = database_names(new);
+	for database in database_names(old) {
+		if !databases.contains(&database) {
+			databases.push(database);
+		}
+	}
+	let mut tests = test_names(new);
+	for test in test_names(old) {
+		if !tests.contains(&test) {
+			tests.push(test);
+		}
+	}
+
+	let mut changes = Vec::new();
+	for database in &databases {
+		for test in &tests {
+			let (old, new) = (find_result(old, database, test), find_result(new, database, test));
+			if old.is_some() || new.is_some() {
+				changes.push(Change { database: database.clone(), test: test.clone(), old, new });
+			}
+		}
+	}
+	changes
+}
+
+// Table of the throughput and the 99th percentile latency of each test in both races, with the
+// regressions marked
+pub fn render_table(changes: &[Change], threshold: f64) -> Table {
+	let mut table = Table::new();
+	table.load_preset(UTF8_FULL_CONDENSED).set_content_arrangement(ContentArrangement::Dynamic);
+	table.set_header(vec![
+		"test",
+		"database",
+		"old ops/s",
+		"new ops/s",
+		"change",
+		"old p99",
+		"new p99",
+		"change",
+		"",
+	]);
+
+	for change in changes {
+		let verdict = if change.is_regression(threshold) {
+			"regression"
+		} else if change.old.is_none() {
+			"new"
+		} else if change.new.is_none() {
+			"missing"
+		} else {
+			""
+		};
+		table.add_row(vec![
+			change.test.clone(),
+			change.database.clone(),
+			throughput(change.old),
+			throughput(change.new),
+			change.throughput_change().map_or(String::new(), format_change),
+			latency(change.old),
+			latency(change.new),
+			change.latency_change().map_or(String::new(), format_change),
+			verdict.to_string(),
+		]);
+	}
+
+	for i in 2..=7 {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	table
+}
+
+fn is_failed(result: &BenchmarkResult) -> bool {
+	matches!(result.status, TestStatus::Failed { .. })
+}
+
+fn measured(result: Option<&BenchmarkResult>) -> Option<&BenchmarkResult> {
+	result.filter(|result| result.status.is_measured())
+}
+
+fn percent_change(old: f64, new: f64) -> Option<f64> {
+	(old > 0.0).then(|| ((new - old) / old) * 100.0)
+}
+
+fn format_change(change: f64) -> String {
+	format!("{:+.1}%", change)
+}
+
+fn throughput(result: Option<&BenchmarkResult>) -> String {
+	match result {
+		None => String::new(),
+		Some(result) if !result.status.is_measured() => result.status.as_str().to_string(),
+		Some(result) => format!("{:.0}", result.operations_per_second),
+	}
+}
+
+fn latency(result: Option<&BenchmarkResult>) -> String {
+	measured(result).and_then(|result| result.latency_p99_us).map_or(String::new(), format_micros)
+}
@@ -0,0 +1,654 @@
+use anyhow::{ bail, Context, Result };
+use clap::{ Args, Parser, Subcommand };
+use comfy_table::{ presets::UTF8_FULL_CONDENSED, CellAlignment, ContentArrangement, Table };
+use std::fs;
+use std::sync::Arc;
+use std::path::{ Path, PathBuf };
+use std::time::Duration;
+mod compare;
+mod progress;
+mod results;
+mod spawn;
+
+use common::{
+	leaderboard::render_markdown_leaderboard,
+	metastore::{ MetaStore, TrendMetric, TrendQuery, METASTORE_FILE },
+	models::{ BenchmarkResults, TestStatus },
+	plan_file::{ OutputFormat, RaceFile },
+	report::{
+		database_names,
+		find_result,
+		format_micros,
+		render_html_report,
+		render_svg_charts,
+		score_runs,
+		test_names,
+	},
+};
+use coordinator::{ client::BackendClient, race::{ self, Race } };
+
+use crate::{ progress::RaceProgress, spawn::SpawnedServer };
+
+// Time a server has to open its database and answer once started
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Race file loaded from the working directory when `--config` isn't given
+const DEFAULT_CONFIG: &str = "race.toml";
+
+// Databases raced when neither the command line nor the race file name them
+const DEFAULT_DATABASES: [&str; 3] = ["sqlite", "duckdb", "rocksdb"];
+
+// Records of each table generated per request by `race seed`, so a large dataset is built in
+// steps that show its progress rather than in a single request lasting hours
+const SEED_CHUNK_ROWS: usize = 1_000_000;
+
+// Runs a race from the command line, starting the benchmark servers or using running ones
+#[derive(Debug, Parser)]
+#[command(name = "race")]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Run a plan on every database, then write the merged results and the comparison report
+	Run(RunArgs),
+	/// Check that every database answers and can create a table, and list its optional features
+	Doctor(DoctorArgs),
+	/// Build a dataset in every database once, to race it later with `race run --prebuilt`
+	Seed(SeedArgs),
+	/// Show the results of a test across the races stored in a results directory
+	Trend(TrendArgs),
+	/// Compare the results of two races and fail when a test regressed
+	Compare(CompareArgs),
+	/// Draw the charts of a race as SVG images
+	Chart(ChartArgs),
+	/// Rank the databases of one or more result files in a Markdown leaderboard
+	Leaderboard(LeaderboardArgs),
+}
+
+// Servers of the databases: started from their binaries, or already running
+#[derive(Debug, Args)]
+struct ServerArgs {
+	/// Comma-separated databases to race, each started from its `benchmarks-<database>` binary,
+	/// overriding those of the race file (default sqlite,duckdb,rocksdb)
+	#[arg(long, value_delimiter = ',')]
+	databases: Vec<String>,
+
+	/// Comma-separated URLs of running benchmark servers to race instead of starting them
+	#[arg(long, value_delimiter = ',', conflicts_with = "databases")]
+	connect: Vec<String>,
+
+	/// Directory holding the server binaries, the one of this executable by default
+	#[arg(long)]
+	bin_dir: Option<PathBuf>,
+
+	/// Directory holding the data of the started servers, a directory per database, `data` in the
+	/// output directory by default
+	#[arg(long)]
+	data_dir: Option<PathBuf>,
+
+	/// Port of the first started server, the next ones taking the following ports
+	#[arg(long, default_value_t = 4001)]
+	base_port: u16,
+
+	/// Token of the benchmark servers, when they require one
+	#[arg(long, env = "BENCHMARK_API_TOKEN")]
+	api_token: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+	#[command(flatten)]
+	servers: ServerArgs,
+
+	/// Records of each table generated before the tests, overriding the dataset sizes of the plan
+	#[arg(long)]
+	rows: Option<usize>,
+
+	/// Race file in TOML, YAML or JSON holding the plan, `race.toml` when it exists, the default
+	/// plan otherwise
+	#[arg(long)]
+	config: Option<PathBuf>,
+
+	/// Directory receiving the results, the report, and the data and logs of the started servers,
+	/// overriding the output directory of the race file (default results)
+	#[arg(long)]
+	out: Option<PathBuf>,
+
+	/// Run the databases at the same time rather than one after the other
+	#[arg(long)]
+	parallel: bool,
+
+	/// Resume the runs a previous race of the same plan left unfinished, skipping the tests they
+	/// completed
+	#[arg(long)]
+	resume: bool,
+
+	/// Race the datasets the databases already hold, built by `race seed` in the same data
+	/// directory, running only the tests that leave them unchanged
+	#[arg(long, conflicts_with = "rows")]
+	prebuilt: bool,
+
+	/// Database the results are scored against, the first one that completes its run when not given
+	#[arg(long)]
+	baseline: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct DoctorArgs {
+	#[command(flatten)]
+	servers: ServerArgs,
+
+	/// Directory receiving the data and logs of the started servers
+	#[arg(long, default_value = "results")]
+	out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct SeedArgs {
+	#[command(flatten)]
+	servers: ServerArgs,
+
+	/// Records generated in each table
+	#[arg(long)]
+	rows: usize,
+
+	/// Directory receiving the logs of the started servers, and their data unless --data-dir is given
+	#[arg(long, default_value = "results")]
+	out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct TrendArgs {
+	/// Test, workload or analytical query to follow
+	test: String,
+
+	/// Only the runs of this database, ignoring case
+	#[arg(long)]
+	database: Option<String>,
+
+	/// Value of each result: operations_per_second, latency_p99_us, duration_ms or operations
+	#[arg(long, default_value_t = TrendMetric::OperationsPerSecond)]
+	metric: TrendMetric,
+
+	/// Number of the most recent runs of the test
+	#[arg(long, default_value_t = 30)]
+	last: usize,
+
+	/// Results directory of the races
+	#[arg(long, default_value = "results")]
+	out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct CompareArgs {
+	/// Results to compare against: a race.json, the results of a run, or a list of runs
+	old: PathBuf,
+
+	/// Results of the change, in the same formats
+	new: PathBuf,
+
+	/// Drop of throughput in percent above which a test regressed
+	#[arg(long, default_value_t = 10.0)]
+	threshold: f64,
+}
+
+#[derive(Debug, Args)]
+struct ChartArgs {
+	/// Results to draw: a race.json, the results of a run, or a list of runs
+	results: PathBuf,
+
+	/// Directory receiving the images
+	#[arg(long, default_value = "charts")]
+	out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct LeaderboardArgs {
+	/// Results to rank: race.json files, the results of runs, or lists of runs
+	#[arg(required = true)]
+	results: Vec<PathBuf>,
+
+	/// File receiving the leaderboard, printed when not given
+	#[arg(long)]
+	out: Option<PathBuf>,
+
+	/// Database to score the results against, replacing the scores of their races
+	#[arg(long)]
+	baseline: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	// Only warnings, the progress bars tell how the race goes
+	tracing_subscriber::fmt().with_max_level(tracing_subscriber::filter::LevelFilter::WARN).init();
+
+	match Cli::parse().command {
+		Command::Run(args) => run(args).await,
+		Command::Doctor(args) => doctor(args).await,
+		Command::Seed(args) => seed(args).await,
+		Command::Trend(args) => trend(args),
+		Command::Compare(args) => compare(args),
+		Command::Chart(args) => chart(args),
+		Command::Leaderboard(args) => leaderboard(args),
+	}
+}
+
+async fn run(args: RunArgs) -> Result<()> {
+	let config = args.config.clone().or_else(|| {
+		let default = PathBuf::from(DEFAULT_CONFIG);
+		default.exists().then_some(default)
+	});
+	let mut file = match &config {
+		Some(path) => RaceFile::load(path)?,
+		None => RaceFile::default(),
+	};
+	if let Some(rows) = args.rows {
+		file.dataset_sizes.clear();
+		file.plan.dataset_size = rows;
+	}
+	// The size of a prebuilt dataset is the one it was built with
+	if args.prebuilt {
+		file.dataset_sizes.clear();
+		file.plan.prebuilt = true;
+	}
+	let plans = file.plans();
+	for plan in &plans {
+		plan.validate().map_err(anyhow::Error::msg)?;
+	}
+
+	let out = args.out.clone().or(file.output.dir.clone()).unwrap_or_else(|| PathBuf::from("results"));
+	fs::create_dir_all(&out).with_context(|| format!("Could not create {}", out.display()))?;
+	// Every race of the directory is kept, to follow the tests across races
+	let mut metastore = MetaStore::open(out.join(METASTORE_FILE))?;
+	let databases = if !file.databases.is_empty() { file.databases.clone() } else { default_databases() };
+
+	// Started servers stop when dropped, once the race is over or has failed
+	let (servers, backends) = start_servers(&args.servers, &databases, &out)?;
+	wait_until_ready(&servers, &backends).await?;
+
+	// A sweep over several dataset sizes races each size in turn on the same servers, and writes
+	// each race to a directory of its own
+	let mut failed = 0;
+	for plan in &plans {
+		let dir = if plans.len() > 1 { out.join(format!("{}-rows", plan.dataset_size)) } else { out.clone() };
+		if plan.prebuilt {
+			println!("Racing {} databases on their prebuilt datasets", backends.len());
+		} else {
+			println!("Racing {} databases on {} rows", backends.len(), plan.dataset_size);
+		}
+		let progress = RaceProgress::new();
+		let followed: Vec<BackendClient> = backends
+			.iter()
+			.enumerate()
+			.map(|(i, backend)| {
+				let name = servers.get(i).map_or(backend.url(), |server| server.database.as_str());
+				let database = Arc::new(progress.database(name));
+				let backend = backend.clone().on_progress(move |run| database.update(run));
+				if args.resume { backend.resuming() } else { backend }
+			})
+			.collect();
+		let mut race = Race::start(&followed, args.parallel);
+		race.baseline = args.baseline.clone();
+		let (results, errors) = race::run_race(&followed, plan, args.parallel).await;
+		race.finish(results, errors);
+		drop(followed);
+		drop(progress);
+
+		write_outputs(&race, &dir, &file.output.formats)?;
+		for results in &race.results {
+			metastore.insert(results)?;
+		}
+		print_summary(&race);
+		for error in &race.errors {
+			eprintln!("{}: {}", error.backend, error.message);
+		}
+		if !race.errors.is_empty() {
+			failed += 1;
+		}
+	}
+	drop(servers);
+
+	if failed > 0 {
+		bail!("{} of {} races had databases failing their run", failed, plans.len());
+	}
+
+	Ok(())
+}
+
+// Probe the database of every server, printing whether it answers and can create a table, and
+// the optional features it supports. Fails when a database didn't pass its check
+async fn doctor(args: DoctorArgs) -> Result<()> {
+	let (servers, backends) = start_servers(&args.servers, &default_databases(), &args.out)?;
+	// Running servers are checked as they are, a busy or unreachable one reported below
+	if !servers.is_empty() {
+		wait_until_ready(&servers, &backends).await?;
+	}
+
+	let yes_no = |value: bool| (if value { "yes" } else { "no" }).to_string();
+	let mut table = Table::new();
+	table.load_preset(UTF8_FULL_CONDENSED).set_content_arrangement(ContentArrangement::Dynamic);
+	table.set_header(
+		vec!["server", "database", "connected", "probe table", "window functions", "full-text search", "blobs", "analytics"]
+	);
+
+	let mut errors = Vec::new();
+	for backend in &backends {
+		match backend.capabilities().await {
+			Ok(report) => {
+				let capabilities = report.capabilities;
+				table.add_row(vec![
+					backend.url().to_string(),
+					report.database.clone(),
+					yes_no(report.connected),
+					yes_no(report.probe),
+					yes_no(capabilities.window_functions),
+					yes_no(capabilities.full_text_search),
+					yes_no(capabilities.blobs),
+					yes_no(capabilities.analytics),
+				]);
+				if let Some(error) = report.error {
+					errors.push(format!("{}: {}", report.database, error));
+				}
+			}
+			Err(e) => {
+				table.add_row(vec![backend.url().to_string(), String::new(), yes_no(false)]);
+				errors.push(format!("{:#}", e));
+			}
+		}
+	}
+	drop(servers);
+
+	println!("{}", table);
+	for error in &errors {
+		eprintln!("{}", error);
+	}
+	if !errors.is_empty() {
+		bail!("{} of {} databases failed their check", errors.len(), backends.len());
+	}
+
+	Ok(())
+}
+
+// Empty the database of every server and generate `rows` records of each table in it, a chunk at
+// a time, for races over prebuilt datasets
+async fn seed(args: SeedArgs) -> Result<()> {
+	if args.rows == 0 {
+		bail!("--rows must be at least 1");
+	}
+	fs::create_dir_all(&args.out).with_context(|| format!("Could not create {}", args.out.display()))?;
+	let (servers, backends) = start_servers(&args.servers, &default_databases(), &args.out)?;
+	wait_until_ready(&servers, &backends).await?;
+
+	for (i, backend) in backends.iter().enumerate() {
+		let name = servers.get(i).map_or(backend.url(), |server| server.database.as_str());
+		backend.cleanup().await?;
+
+		let mut seeded = 0;
+		while seeded < args.rows {
+			let rows = SEED_CHUNK_ROWS.min(args.rows - seeded);
+			backend.seed(rows).await.with_context(|| format!("Seeding {} failed after {} rows", name, seeded))?;
+			seeded += rows;
+			println!("{}: {} of {} rows", name, seeded, args.rows);
+		}
+	}
+	drop(servers);
+
+	Ok(())
+}
+
+// Names of `DEFAULT_DATABASES`
+fn default_databases() -> Vec<String> {
+	DEFAULT_DATABASES.iter().map(|database| database.to_string()).collect()
+}
+
+// Start the server of each database, writing its data and log to `out`, or connect to the running
+// ones. The databases of the command line take precedence over `databases`
+fn start_servers(
+	args: &ServerArgs,
+	databases: &[String],
+	out: &Path
+) -> Result<(Vec<SpawnedServer>, Vec<BackendClient>)> {
+	let databases = if args.databases.is_empty() { databases } else { args.databases.as_slice() };
+
+	let mut servers = Vec::new();
+	if args.connect.is_empty() {
+		let bin_dir = match &args.bin_dir {
+			Some(dir) => dir.clone(),
+			None => spawn::default_bin_dir()?,
+		};
+		let data_dir = args.data_dir.clone().unwrap_or_else(|| out.join("data"));
+		for (i, database) in databases.iter().map(|database| database.trim()).enumerate() {
+			let port = u16::try_from(i)
+				.ok()
+				.and_then(|i| args.base_port.checked_add(i))
+				.context("Not enough ports above the base port")?;
+			servers.push(SpawnedServer::start(&bin_dir, database, port, &data_dir, out)?);
+		}
+	}
+
+	let http = reqwest::Client::new();
+	let token = args.api_token.clone().filter(|token| !token.is_empty());
+	let urls: Vec<&str> = if servers.is_empty() {
+		args.connect.iter().map(|url| url.trim()).filter(|url| !url.is_empty()).collect()
+	} else {
+		servers.iter().map(|server| server.url.as_str()).collect()
+	};
+	let backends: Vec<BackendClient> = urls
+		.into_iter()
+		.map(|url| BackendClient::new(url, http.clone(), token.clone()))
+		.collect();
+	if backends.is_empty() {
+		bail!("No database to race");
+	}
+
+	Ok((servers, backends))
+}
+
+async fn wait_until_ready(servers: &[SpawnedServer], backends: &[BackendClient]) -> Result<()> {
+	for (i, backend) in backends.iter().enumerate() {
+		let ready = backend.wait_until_ready(STARTUP_TIMEOUT).await;
+		if let Some(server) = servers.get(i) {
+			ready.with_context(|| format!("{} didn't start, see {}", server.database, server.log.display()))?;
+		} else {
+			ready?;
+		}
+	}
+	Ok(())
+}
+
+// Print the results of a test in the last runs stored in the results directory, oldest first
+fn trend(args: TrendArgs) -> Result<()> {
+	let path = args.out.join(METASTORE_FILE);
+	if !path.exists() {
+		bail!("No race stored in {}", args.out.display());
+	}
+	if args.last == 0 {
+		bail!("--last must be at least 1");
+	}
+
+	let points = MetaStore::open(&path)?.trend(
+		&(TrendQuery {
+			test: args.test.clone(),
+			database: args.database,
+			metric: args.metric,
+			last: args.last,
+			since: None,
+		})
+	)?;
+	if points.is_empty() {
+		bail!("No measured result of {} in {}", args.test, args.out.display());
+	}
+
+	let mut table = Table::new();
+	table.load_preset(UTF8_FULL_CONDENSED).set_content_arrangement(ContentArrangement::Dynamic);
+	table.set_header(vec!["run", "database", "batch size", args.metric.name()]);
+	for point in points {
+		let value = match (point.value, args.metric) {
+			(None, _) => String::new(),
+			(Some(micros), TrendMetric::LatencyP99Us) => format_micros(micros),
+			(Some(value), _) => format!("{:.0}", value),
+		};
+		table.add_row(vec![
+			point.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+			point.database,
+			point.batch_size.map_or(String::new(), |size| size.to_string()),
+			value,
+		]);
+	}
+	for i in [2, 3] {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	println!("{}", table);
+
+	Ok(())
+}
+
+// Print the changes between two races, failing when any test regressed beyond the threshold
+fn compare(args: CompareArgs) -> Result<()> {
+	if args.threshold.is_nan() || args.threshold < 0.0 {
+		bail!("--threshold must be a positive percentage");
+	}
+	let old = results::load_runs(&args.old)?;
+	let new = results::load_runs(&args.new)?;
+
+	let changes = compare::compare(&old, &new);
+	println!("{}", compare::render_table(&changes, args.threshold));
+
+	let regressions = changes.iter().filter(|change| change.is_regression(args.threshold)).count();
+	if regressions > 0 {
+		bail!(
+			"{} of {} results regressed by more than {}%",
+			regressions,
+			changes.len(),
+			args.threshold
+		);
+	}
+
+	Ok(())
+}
+
+// Write a throughput chart per test and the latency distribution of the race to the output directory
+fn chart(args: ChartArgs) -> Result<()> {
+	let runs = results::load_runs(&args.results)?;
+	let charts = render_svg_charts(&runs);
+	if charts.is_empty() {
+		bail!("No measured result to draw in {}", args.results.display());
+	}
+
+	fs::create_dir_all(&args.out).with_context(|| format!("Could not create {}", args.out.display()))?;
+	for (name, svg) in charts {
+		let path = args.out.join(name);
+		fs::write(&path, svg).with_context(|| format!("Could not write {}", path.display()))?;
+		println!("Written {}", path.display());
+	}
+
+	Ok(())
+}
+
+// Rank the databases of every given file together
+fn leaderboard(args: LeaderboardArgs) -> Result<()> {
+	let mut runs = Vec::new();
+	for path in &args.results {
+		runs.extend(results::load_runs(path)?);
+	}
+	if let Some(baseline) = &args.baseline {
+		score_runs(&mut runs, baseline);
+	}
+
+	let markdown = render_markdown_leaderboard(&runs);
+	match &args.out {
+		Some(path) => {
+			fs::write(path, markdown).with_context(|| format!("Could not write {}", path.display()))?;
+			println!("Written {}", path.display());
+		}
+		None => print!("{}", markdown),
+	}
+
+	Ok(())
+}
+
+// Write the race to `dir` in every format of the race file
+fn write_outputs(race: &Race, dir: &Path, formats: &[OutputFormat]) -> Result<()> {
+	fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+	for format in formats {
+		let (name, content) = match format {
+			OutputFormat::Json => ("race.json", serde_json::to_string_pretty(race)?),
+			OutputFormat::Jsonl => {
+				let mut jsonl = String::new();
+				for results in &race.results {
+					jsonl.push_str(&results.to_jsonl()?);
+				}
+				("results.jsonl", jsonl)
+			}
+			OutputFormat::Csv => {
+				let mut csv = format!("{}\n", BenchmarkResults::CSV_HEADER);
+				for result in race.results.iter().flat_map(|results| &results.results) {
+					csv.push_str(&result.to_csv_row());
+					csv.push('\n');
+				}
+				("results.csv", csv)
+			}
+			// The report compares results, there is none without any
+			OutputFormat::Html if race.results.is_empty() => continue,
+			OutputFormat::Html => ("report.html", render_html_report(&race.results)),
+			OutputFormat::Markdown if race.results.is_empty() => continue,
+			OutputFormat::Markdown => ("leaderboard.md", render_markdown_leaderboard(&race.results)),
+		};
+		let path = dir.join(name);
+		fs::write(&path, content).with_context(|| format!("Could not write {}", path.display()))?;
+		println!("Written {}", path.display());
+	}
+
+	Ok(())
+}
+
+// Table of the throughput and the 99th percentile latency of every test on every database
+fn print_summary(race: &Race) {
+	if race.results.is_empty() {
+		return;
+	}
+	let databases = database_names(&race.results);
+
+	let mut table = Table::new();
+	table.load_preset(UTF8_FULL_CONDENSED).set_content_arrangement(ContentArrangement::Dynamic);
+	let mut header = vec!["test".to_string()];
+	for database in &databases {
+		header.push(format!("{} ops/s", database));
+		header.push(format!("{} p99", database));
+	}
+	table.set_header(header);
+
+	for test in test_names(&race.results) {
+		let mut row = vec![test.clone()];
+		for database in &databases {
+			let (throughput, latency) = match find_result(&race.results, database, &test) {
+				None => (String::new(), String::new()),
+				Some(result) if !result.status.is_measured() => {
+					(result.status.as_str().to_string(), String::new())
+				}
+				Some(result) => {
+					let mut throughput = format!("{:.0}", result.operations_per_second);
+					if result.status == TestStatus::TimedOut {
+						throughput.push_str(" (timed out)");
+					}
+					(throughput, result.latency_p99_us.map_or(String::new(), format_micros))
+				}
+			};
+			row.push(throughput);
+			row.push(latency);
+		}
+		table.add_row(row);
+	}
+
+	for i in 1..=databases.len() * 2 {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	println!("\n{}", table);
+}
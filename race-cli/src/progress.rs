@@ -0,0 +1,111 @@
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+use indicatif::{ MultiProgress, ProgressBar, ProgressState, ProgressStyle };
+
+use common::models::{ RunInfo, RunStatus };
+
+// Redraws of the spinner of the test in progress
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+// Live progress of a race in the terminal: a bar per database counting the tests of its run, above
+// a line with the operations the test in progress completed. The bars are cleared once the race
+// is over, and nothing is drawn when the output isn't a terminal.
+pub struct RaceProgress {
+	bars: MultiProgress,
+}
+
+impl RaceProgress {
+	pub fn new() -> Self {
+		Self { bars: MultiProgress::new() }
+	}
+
+	// Progress of the run of a database, shown until the race is dropped
+	pub fn database(&self, database: &str) -> DatabaseProgress {
+		let run = self.bars.add(ProgressBar::new(0));
+		run.set_style(
+			ProgressStyle::with_template("{prefix:<10} [{bar:30}] {pos}/{len} tests {msg}")
+				.unwrap()
+				.progress_chars("=> ")
+		);
+		run.set_prefix(database.to_string());
+
+		DatabaseProgress {
+			bars: self.bars.clone(),
+			database: database.to_string(),
+			run,
+			test: Mutex::new(None),
+		}
+	}
+}
+
+pub struct DatabaseProgress {
+	bars: MultiProgress,
+	database: String,
+	// Tests of the run done so far
+	run: ProgressBar,
+	// Test in progress and its line
+	test: Mutex<Option<(String, ProgressBar)>>,
+}
+
+impl DatabaseProgress {
+	// Follow the status of the run, as the client gets it
+	pub fn update(&self, run: &RunInfo) {
+		self.run.set_length(run.tests.len() as u64);
+		let mut test = self.test.lock().unwrap();
+
+		let Some(progress) = &run.progress else {
+			// Between two tests, or once the run is over
+			if run.status != RunStatus::Running {
+				if let Some((_, bar)) = test.take() {
+					bar.finish_and_clear();
+				}
+				self.run.set_position(run.tests.len() as u64);
+				self.run.finish_with_message(run_state(&run.status));
+			}
+			return;
+		};
+
+		if test.as_ref().is_some_and(|(test_name, _)| *test_name != progress.test_name) {
+			if let Some((_, bar)) = test.take() {
+				bar.finish_and_clear();
+			}
+		}
+		let (_, bar) = test.get_or_insert_with(|| {
+			let bar = self.bars.insert_before(&self.run, ProgressBar::new_spinner());
+			bar.set_style(
+				ProgressStyle::with_template(
+					"{spinner} {prefix:<10} {msg:<40} {human_pos:>12} ops {rate:>14} ops/s"
+				)
+					.unwrap()
+					.with_key("rate", |state: &ProgressState, w: &mut dyn Write| {
+						let _ = write!(w, "{:.0}", state.per_sec());
+					})
+			);
+			bar.enable_steady_tick(TICK_INTERVAL);
+			bar.set_prefix(self.database.clone());
+			bar.set_message(progress.test_name.clone());
+			(progress.test_name.clone(), bar)
+		});
+		bar.set_position(progress.operations);
+
+		let done = run.tests.iter().position(|test_name| *test_name == progress.test_name);
+		self.run.set_position(done.unwrap_or(0) as u64);
+		self.run.set_message(progress.test_name.clone());
+	}
+}
+
+impl Drop for RaceProgress {
+	fn drop(&mut self) {
+		let _ = self.bars.clear();
+	}
+}
+
+fn run_state(status: &RunStatus) -> &'static str {
+	match status {
+		RunStatus::Running => "running",
+		RunStatus::Completed => "completed",
+		RunStatus::Cancelled => "cancelled",
+		RunStatus::Failed { .. } => "failed",
+	}
+}
@@ -0,0 +1,34 @@
+use anyhow::{ bail, Context, Result };
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use common::models::BenchmarkResults;
+use coordinator::race::Race;
+
+// Result files the commands read: a race written by `race run` or the coordinator, the results of
+// a run as returned by a server, or a list of runs
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ResultsFile {
+	Race(Race),
+	Run(BenchmarkResults),
+	Runs(Vec<BenchmarkResults>),
+}
+
+pub fn load_runs(path: &Path) -> Result<Vec<BenchmarkResults>> {
+	let text = fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+	let file: ResultsFile = serde_json::from_str(&text).with_context(|| {
+		format!("{} holds neither a race nor the results of runs", path.display())
+	})?;
+
+	let runs = match file {
+		ResultsFile::Race(race) => race.results,
+		ResultsFile::Run(run) => vec![run],
+		ResultsFile::Runs(runs) => runs,
+	};
+	if runs.is_empty() {
+		bail!("No results in {}", path.display());
+	}
+	Ok(runs)
+}
@@ -0,0 +1,82 @@
+use std::fs::{ self, File };
+use std::path::{ Path, PathBuf };
+use std::process::{ Child, Command };
+use anyhow::{ bail, Context, Result };
+
+// A benchmark server started by the runner, stopped once dropped
+pub struct SpawnedServer {
+	pub database: String,
+	pub url: String,
+	// File receiving the output of the server
+	pub log: PathBuf,
+	child: Child,
+}
+
+impl SpawnedServer {
+	// Start the server of `database` from `bin_dir` on `port`, with its data in a directory of
+	// `data_dir` named after it and its log under `out`. The server is the
+	// `benchmarks-<database>` binary, or else the `benchmarks` binary serving the backends
+	// registered by other crates
+	pub fn start(bin_dir: &Path, database: &str, port: u16, data_dir: &Path, out: &Path) -> Result<Self> {
+		let binary = bin_dir.join(format!("benchmarks-{}{}", database, std::env::consts::EXE_SUFFIX));
+		let shared = bin_dir.join(format!("benchmarks{}", std::env::consts::EXE_SUFFIX));
+		let (binary, backend) = if binary.is_file() {
+			(binary, None)
+		} else if shared.is_file() {
+			(shared, Some(database))
+		} else {
+			bail!(
+				"No server for {} at {}, build it with `cargo build --release -p benchmarks-{}`",
+				database,
+				binary.display(),
+				database
+			);
+		};
+
+		let data_dir = data_dir.join(database);
+		fs::create_dir_all(&data_dir)?;
+		let log = out.join(format!("{}.log", database));
+		let output = File::create(&log).with_context(|| format!("Could not create {}", log.display()))?;
+
+		// The flags override the settings of the environment, except the gRPC port, which would
+		// be shared by every server
+		let mut command = Command::new(&binary);
+		if let Some(backend) = backend {
+			command.arg("--backend").arg(backend);
+		}
+		let child = command
+			.arg("--bind")
+			.arg("127.0.0.1")
+			.arg("--port")
+			.arg(port.to_string())
+			.arg("--data-dir")
+			.arg(&data_dir)
+			.env_remove("BENCHMARK_GRPC_PORT")
+			.stdout(output.try_clone()?)
+			.stderr(output)
+			.spawn()
+			.with_context(|| format!("Could not start {}", binary.display()))?;
+		println!("Started {} on port {}, logging to {}", database, port, log.display());
+
+		Ok(Self {
+			database: database.to_string(),
+			url: format!("http://127.0.0.1:{}", port),
+			log,
+			child,
+		})
+	}
+}
+
+impl Drop for SpawnedServer {
+	fn drop(&mut self) {
+		let _ = self.child.kill();
+		let _ = self.child.wait();
+	}
+}
+
+// Directory of the runner's executable, where cargo also builds the servers of the workspace
+pub fn default_bin_dir() -> Result<PathBuf> {
+	let exe = std::env::current_exe()?;
+	let dir = exe.parent().context("The runner's executable has no directory")?;
+	Ok(dir.to_path_buf())
+}
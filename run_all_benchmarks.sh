@@ -10,7 +10,7 @@ sleep 10
 
 # Run all benchmark services in Docker
 echo "Running all benchmarks..."
-docker-compose up -d sqlite-benchmark duckdb-benchmark rocksdb-benchmark
+docker-compose up -d sqlite-benchmark duckdb-benchmark rocksdb-benchmark coordinator
 
 # Wait for benchmarks to complete
 echo "All benchmark services are running. Access results at:"
@@ -20,6 +20,7 @@ echo "- RocksDB: http://localhost:3003/results"
 echo "- PostgreSQL: http://localhost:3004/results (run with cargo run -p benchmarks-postgres)"
 echo "- SurrealDB: http://localhost:3005/results (run with cargo run -p benchmarks-surrealdb)"
 echo "- KuZu: http://localhost:3006/results (run with cargo run -p benchmarks-kuzu)"
+echo "- Race all databases: curl -X POST http://localhost:3000/race -H 'Content-Type: application/json' -d '{}'"
 
 # Instructions for running locally
 echo ""